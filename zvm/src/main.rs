@@ -1,21 +1,221 @@
-use std::env;
+use std::{
+    env,
+    io::{self, Write},
+    path::Path,
+    process, thread,
+};
 
-use crate::{parser::reader::Reader, vm::vm::Vm};
+use crate::{
+    parser::{class_file::ClassFile, reader::Reader},
+    vm::{
+        call_stack::{self, DEFAULT_STACK_SIZE, MAX_STACK_SIZE, MIN_STACK_SIZE},
+        error::VmError,
+        vm::Vm,
+    },
+};
 mod parser;
 mod vm;
 
+const USAGE: &str = "Usage: zvm [options] <mainclass> [args...]
+           (to execute the class file <mainclass>.class)
+   or  zvm check <mainclass>
+           (to list the instructions of <mainclass>.class and of the classes
+            it references that zvm cannot execute yet)
+
+ where options include:
+    -Xmx<size>        set the maximum heap size
+    -Xss<size>        set the thread stack size
+    -XX:ThreadQuantum=<n>
+                      set the number of instructions of a thread time slice
+    -XX:ThreadSeed=<n>
+                      schedule the threads in a random order reproducible from the seed
+    -XX:+Strict       throw an error when the program uses a part of the JDK or of
+                      the class file format zvm does not implement ( default )
+    -XX:-Strict       skip the unsupported instructions with a warning instead";
+
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    let class_file_path = &args[1];
+    // The virtual machine runs on a host thread whose stack is sized from the thread stack
+    // size, so that the recursions going through nested runs of the dispatch loop overflow
+    // the stack of their thread before the host one
+    let stack_size = args[1..]
+        .iter()
+        .take_while(|arg| arg.starts_with('-'))
+        .filter_map(|arg| arg.strip_prefix("-Xss").and_then(parse_memory_size))
+        .last()
+        .unwrap_or(DEFAULT_STACK_SIZE)
+        .min(MAX_STACK_SIZE);
+
+    let host = thread::Builder::new()
+        .name("main".to_string())
+        .stack_size(call_stack::host_stack_size(stack_size))
+        .spawn(move || run(args));
+
+    match host.map(|host| host.join()) {
+        Ok(Ok(())) => {}
+        // The panic was reported by the host thread
+        Ok(Err(_)) => process::exit(101),
+        Err(error) => {
+            eprintln!("Could not start the virtual machine: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+/// Parses the options and runs the class, or scans it with `check`, then exits the process
+fn run(args: Vec<String>) {
+    // VM options come before the class, e.g. `zvm -Xmx16m Main arg0 arg1`
+    let options: Vec<&String> = args[1..]
+        .iter()
+        .take_while(|arg| arg.starts_with('-'))
+        .collect();
+    let mut class_index = options.len() + 1;
+
+    // `zvm check Main` scans the class instead of running it
+    let check = args.get(class_index).is_some_and(|arg| arg == "check");
+    if check {
+        class_index += 1;
+    }
+
+    let Some(class_file_path) = args.get(class_index) else {
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    };
 
     let mut reader = Reader::new(class_file_path.clone());
     reader.read();
 
     let class_file = reader.get_class_file();
 
-    let env_args: Vec<String> = args[2..].to_vec();
+    let env_args: Vec<String> = args[class_index + 1..].to_vec();
 
     let mut jvm = Vm::new();
-    jvm.run(class_file, env_args);
+
+    for option in options {
+        let applied = if let Some(value) = option.strip_prefix("-Xmx") {
+            parse_memory_size(value)
+                .map(|max_heap_size| jvm.set_max_heap_size(max_heap_size))
+                .ok_or_else(|| format!("Invalid maximum heap size: {}", option))
+        } else if let Some(value) = option.strip_prefix("-Xss") {
+            // Like HotSpot, a size of 0 keeps the default one
+            match parse_memory_size(value) {
+                Some(stack_size) if stack_size < MIN_STACK_SIZE => Err(format!(
+                    "\nThe Java thread stack size specified is too small. Specify at least {}k",
+                    MIN_STACK_SIZE / 1024
+                )),
+                Some(stack_size) if stack_size > MAX_STACK_SIZE => Err(format!(
+                    "Invalid thread stack size: {}\nThe specified size exceeds the maximum representable size.",
+                    option
+                )),
+                Some(stack_size) => {
+                    jvm.set_thread_stack_size(stack_size);
+                    Ok(())
+                }
+                None if value.parse::<usize>() == Ok(0) => Ok(()),
+                None => Err(format!("Invalid thread stack size: {}", option)),
+            }
+        } else if let Some(value) = option.strip_prefix("-XX:ThreadQuantum=") {
+            value
+                .parse::<usize>()
+                .ok()
+                .filter(|quantum| *quantum > 0)
+                .map(|quantum| jvm.set_thread_quantum(quantum))
+                .ok_or_else(|| format!("Invalid thread quantum: {}", option))
+        } else if let Some(value) = option.strip_prefix("-XX:ThreadSeed=") {
+            value
+                .parse::<u64>()
+                .map(|seed| jvm.set_thread_seed(seed))
+                .map_err(|_| format!("Invalid thread seed: {}", option))
+        } else if option.as_str() == "-XX:+Strict" {
+            jvm.set_strict(true);
+            Ok(())
+        } else if option.as_str() == "-XX:-Strict" {
+            jvm.set_strict(false);
+            Ok(())
+        } else {
+            Err(format!("Unrecognized option: {}", option))
+        };
+
+        if let Err(message) = applied {
+            eprintln!("{}", message);
+            eprintln!("Error: Could not create the Java Virtual Machine.");
+            process::exit(1);
+        }
+    }
+
+    // Classes referenced by the main class are loaded from its directory
+    let class_path = Path::new(class_file_path)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    jvm.add_class_path(class_path);
+
+    if check {
+        process::exit(check_class(&mut jvm, class_file));
+    }
+
+    // Like HotSpot, the status is 1 when the main thread ends with an exception,
+    // `System.exit` terminates the process with its own status
+    let exit_code = match jvm.run(class_file, env_args) {
+        Ok(()) => 0,
+        // The default uncaught exception handler printed it already
+        Err(VmError::UncaughtException { .. }) => 1,
+        Err(error) => {
+            let _ = io::stdout().flush();
+            eprintln!("{}", error);
+            1
+        }
+    };
+
+    // process::exit skips destructors, flush whatever is still buffered first
+    let _ = io::stdout().flush();
+    process::exit(exit_code);
+}
+
+/// Prints the instructions zvm cannot execute yet, one per line, the status is 1 if there is any
+fn check_class(jvm: &mut Vm, class_file: ClassFile) -> i32 {
+    let gaps = match jvm.check(class_file) {
+        Ok(gaps) => gaps,
+        Err(error) => {
+            eprintln!("{}", error);
+            return 1;
+        }
+    };
+
+    for gap in &gaps {
+        println!("{}: {}", gap.context, gap.description);
+    }
+
+    match gaps.len() {
+        0 => {
+            println!("zvm can execute every instruction");
+            0
+        }
+        count => {
+            println!(
+                "{} unsupported instruction{} found",
+                count,
+                if count == 1 { "" } else { "s" }
+            );
+            1
+        }
+    }
+}
+
+/// Parses a memory size option value in bytes, with an optional `k`, `m` or `g` unit suffix
+/// like `-Xmx512m` or `-Xmx2G`
+fn parse_memory_size(value: &str) -> Option<usize> {
+    let (digits, unit) = match value.char_indices().last()? {
+        (index, 'k' | 'K') => (&value[..index], 1024),
+        (index, 'm' | 'M') => (&value[..index], 1024 * 1024),
+        (index, 'g' | 'G') => (&value[..index], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+
+    digits
+        .parse::<usize>()
+        .ok()
+        .and_then(|size| size.checked_mul(unit))
+        .filter(|size| *size > 0)
 }
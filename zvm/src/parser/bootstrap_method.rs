@@ -0,0 +1,44 @@
+use crate::parser::buffer::Buffer;
+
+/// A single entry of a `BootstrapMethods` attribute, referenced by `CONSTANT_InvokeDynamic`
+/// entries of the constant pool
+#[derive(Debug, Clone)]
+pub struct BootstrapMethod {
+    /// Constant pool index of the `CONSTANT_MethodHandle` of the bootstrap method
+    pub method_ref: u16,
+    /// Constant pool indices of the static arguments of the bootstrap method, in order
+    pub arguments: Vec<u16>,
+}
+
+impl BootstrapMethod {
+    /// Parses the raw `info` bytes of a `BootstrapMethods` attribute
+    ///
+    /// BootstrapMethods_attribute {
+    ///     u2 num_bootstrap_methods;
+    ///     {   u2 bootstrap_method_ref;
+    ///         u2 num_bootstrap_arguments;
+    ///         u2 bootstrap_arguments[num_bootstrap_arguments];
+    ///     } bootstrap_methods[num_bootstrap_methods];
+    /// }
+    pub fn parse_all(info: &[u8]) -> Option<Vec<Self>> {
+        let mut buffer = Buffer::new(info.to_vec());
+
+        let num_bootstrap_methods = buffer.read_u16()?;
+        let mut bootstrap_methods = Vec::with_capacity(num_bootstrap_methods as usize);
+
+        for _ in 0..num_bootstrap_methods {
+            let method_ref = buffer.read_u16()?;
+            let num_bootstrap_arguments = buffer.read_u16()?;
+            let arguments = (0..num_bootstrap_arguments)
+                .map(|_| buffer.read_u16())
+                .collect::<Option<Vec<u16>>>()?;
+
+            bootstrap_methods.push(Self {
+                method_ref,
+                arguments,
+            });
+        }
+
+        Some(bootstrap_methods)
+    }
+}
@@ -1,5 +1,6 @@
 use crate::parser::{
-    attribute_info::AttributeInfo, constant_pool_info::CpInfo, field_info::FieldInfo,
+    attribute_info::AttributeInfo, bootstrap_method::BootstrapMethod,
+    code_attribute::CodeAttribute, constant_pool_info::CpInfo, field_info::FieldInfo,
     method_info::MethodInfo,
 };
 
@@ -57,7 +58,7 @@ impl ClassFile {
     }
 
     /// Retrieves the class name corresponding to the given constant pool index.
-    fn get_class_name(&self, index: u16) -> Option<String> {
+    pub fn get_class_name(&self, index: u16) -> Option<String> {
         if let Some(CpInfo::Class { name_index }) = self.constant_pool.get(index as usize) {
             self.get_utf8(*name_index)
         } else {
@@ -126,28 +127,146 @@ impl ClassFile {
         }
     }
 
-    pub fn find_method(&self, name: &str) -> Option<MethodInfo> {
-        for method_info in self.methods.iter() {
-            let name_index = method_info.name_index;
-            if let Some(method_name) = self.get_utf8(name_index) {
-                if method_name == name {
-                    return Some(method_info.clone());
-                }
-            }
+    /// Retrieves detailed method information from an `InterfaceMethodref` entry in the constant pool.
+    pub fn get_interface_method_info(&self, index: u16) -> Option<(String, String, String)> {
+        if let Some(CpInfo::InterfaceMethodref {
+            class_index,
+            name_and_type_index,
+        }) = self.constant_pool.get(index as usize)
+        {
+            let class_name = self.get_class_name(*class_index)?;
+            let method_name = self.get_field_or_method_name(*name_and_type_index)?;
+            let method_descriptor = self.get_field_or_method_descriptor(*name_and_type_index)?;
+
+            Some((class_name, method_name, method_descriptor))
+        } else {
+            None
+        }
+    }
+
+    /// Retrieves the bootstrap method index, name and descriptor of an `InvokeDynamic` entry
+    /// in the constant pool.
+    pub fn get_invoke_dynamic_info(&self, index: u16) -> Option<(u16, String, String)> {
+        if let Some(CpInfo::InvokeDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        }) = self.constant_pool.get(index as usize)
+        {
+            let name = self.get_field_or_method_name(*name_and_type_index)?;
+            let descriptor = self.get_field_or_method_descriptor(*name_and_type_index)?;
+
+            Some((*bootstrap_method_attr_index, name, descriptor))
+        } else {
+            None
         }
+    }
+
+    /// Retrieves the kind and the referenced class, name and descriptor of a `MethodHandle` entry
+    /// in the constant pool.
+    pub fn get_method_handle_info(&self, index: u16) -> Option<(u8, String, String, String)> {
+        let Some(CpInfo::MethodHandle {
+            reference_kind,
+            reference_index,
+        }) = self.constant_pool.get(index as usize)
+        else {
+            return None;
+        };
+
+        let (class_name, name, descriptor) =
+            match self.constant_pool.get(*reference_index as usize)? {
+                CpInfo::Fieldref { .. } => self.get_field_info(*reference_index)?,
+                CpInfo::Methodref { .. } => self.get_method_info(*reference_index)?,
+                CpInfo::InterfaceMethodref { .. } => {
+                    self.get_interface_method_info(*reference_index)?
+                }
+                _ => return None,
+            };
 
-        return None;
+        Some((*reference_kind, class_name, name, descriptor))
     }
 
-    /// Retrieves a string constant from the constant pool.
-    pub fn get_string(&self, index: u16) -> Option<String> {
-        if let Some(CpInfo::String { string_index }) = self.constant_pool.get(index as usize) {
-            self.get_utf8(*string_index)
+    /// Retrieves the method descriptor of a `MethodType` entry in the constant pool.
+    pub fn get_method_type(&self, index: u16) -> Option<String> {
+        if let Some(CpInfo::MethodType { descriptor_index }) =
+            self.constant_pool.get(index as usize)
+        {
+            self.get_utf8(*descriptor_index)
         } else {
             None
         }
     }
 
+    /// Retrieves an entry of the `BootstrapMethods` attribute.
+    pub fn get_bootstrap_method(&self, index: u16) -> Option<BootstrapMethod> {
+        let attribute = self.attributes.iter().find(|attribute| {
+            self.get_utf8(attribute.attribute_name_index).as_deref() == Some("BootstrapMethods")
+        })?;
+
+        BootstrapMethod::parse_all(&attribute.info)?
+            .into_iter()
+            .nth(index as usize)
+    }
+
+    /// Retrieves the binary names of the direct superinterfaces, in declaration order.
+    pub fn interface_names(&self) -> Option<Vec<String>> {
+        self.interfaces
+            .iter()
+            .map(|index| self.get_class_name(*index))
+            .collect()
+    }
+
+    /// Retrieves the binary name of the class defined by this class file.
+    pub fn this_class_name(&self) -> Option<String> {
+        self.get_class_name(self.this_class)
+    }
+
+    /// Retrieves the binary name of the direct superclass, or `None` for `java/lang/Object`.
+    pub fn super_class_name(&self) -> Option<String> {
+        if self.super_class == 0 {
+            return None;
+        }
+
+        self.get_class_name(self.super_class)
+    }
+
+    /// Finds the `Code` attribute of the given method and parses it.
+    /// Abstract and native methods have no `Code` attribute.
+    pub fn get_code_attribute(&self, method_info: &MethodInfo) -> Option<CodeAttribute> {
+        method_info
+            .attributes
+            .iter()
+            .find(|attribute| {
+                self.get_utf8(attribute.attribute_name_index).as_deref() == Some("Code")
+            })
+            .and_then(|attribute| {
+                CodeAttribute::parse(&attribute.info, |index| self.get_utf8(index))
+            })
+    }
+
+    /// Retrieves the name of the source file from the `SourceFile` attribute, if present.
+    pub fn source_file(&self) -> Option<String> {
+        let attribute = self.attributes.iter().find(|attribute| {
+            self.get_utf8(attribute.attribute_name_index).as_deref() == Some("SourceFile")
+        })?;
+
+        let sourcefile_index =
+            u16::from_be_bytes([*attribute.info.first()?, *attribute.info.get(1)?]);
+        self.get_utf8(sourcefile_index)
+    }
+
+    /// Retrieves the UTF-16 code units of a string constant from the constant pool,
+    /// decoded from the modified UTF-8 of its `CONSTANT_Utf8` entry.
+    pub fn get_string_utf16(&self, index: u16) -> Option<Vec<u16>> {
+        let Some(CpInfo::String { string_index }) = self.constant_pool.get(index as usize) else {
+            return None;
+        };
+
+        match self.constant_pool.get(*string_index as usize) {
+            Some(CpInfo::Utf8 { bytes, .. }) => decode_modified_utf8(bytes),
+            _ => None,
+        }
+    }
+
     pub fn get_integer(&self, index: u16) -> Option<i32> {
         if let Some(CpInfo::Integer { bytes }) = self.constant_pool.get(index as usize) {
             Some(*bytes as i32)
@@ -195,3 +314,35 @@ impl ClassFile {
         }
     }
 }
+
+/// Decodes modified UTF-8 bytes to UTF-16 code units.
+///
+/// AS SPECIFIED BY THE SPECS: §4.4.7, code points from U+0001 to U+007F take one byte, the null
+/// code point and code points up to U+07FF take two bytes, other code units take three bytes,
+/// supplementary characters being encoded as their two surrogate code units.
+fn decode_modified_utf8(bytes: &[u8]) -> Option<Vec<u16>> {
+    let mut chars = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let x = bytes[i] as u16;
+
+        if x & 0x80 == 0 {
+            chars.push(x);
+            i += 1;
+        } else if x & 0xE0 == 0xC0 {
+            let y = *bytes.get(i + 1)? as u16;
+            chars.push(((x & 0x1F) << 6) | (y & 0x3F));
+            i += 2;
+        } else if x & 0xF0 == 0xE0 {
+            let y = *bytes.get(i + 1)? as u16;
+            let z = *bytes.get(i + 2)? as u16;
+            chars.push(((x & 0x0F) << 12) | ((y & 0x3F) << 6) | (z & 0x3F));
+            i += 3;
+        } else {
+            return None;
+        }
+    }
+
+    Some(chars)
+}
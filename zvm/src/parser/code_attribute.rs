@@ -0,0 +1,168 @@
+use crate::parser::buffer::Buffer;
+
+/// Parsed contents of a method's `Code` attribute
+#[derive(Debug, Clone)]
+pub struct CodeAttribute {
+    /// Deepest the operand stack gets, only used to estimate the size of a frame since the
+    /// operand stack grows on demand
+    pub max_stack: u16,
+    pub max_locals: u16,
+    pub code: Vec<u8>,
+    /// Exception handlers, in the order they must be searched
+    pub exception_table: Vec<ExceptionTableEntry>,
+    /// ( start_pc, line_number ) pairs of the `LineNumberTable` attribute, if present
+    pub line_numbers: Vec<(u16, u16)>,
+    /// Entries of the `LocalVariableTable` attribute, only present when compiled with `-g`
+    pub local_variables: Vec<LocalVariable>,
+}
+
+/// A single entry of a `LocalVariableTable` attribute
+#[derive(Debug, Clone)]
+pub struct LocalVariable {
+    /// The variable is live for pcs in [ start_pc, start_pc + length )
+    pub start_pc: u16,
+    pub length: u16,
+    pub name: String,
+    /// Slot of the variable in the local variables array
+    pub index: u16,
+}
+
+/// A single entry of a `Code` attribute's exception table
+#[derive(Debug, Clone)]
+pub struct ExceptionTableEntry {
+    /// First pc covered by the handler ( inclusive )
+    pub start_pc: u16,
+    /// Last pc covered by the handler ( exclusive )
+    pub end_pc: u16,
+    pub handler_pc: u16,
+    /// Constant pool index of the caught class, 0 catches everything ( used by `finally` )
+    pub catch_type: u16,
+}
+
+impl CodeAttribute {
+    /// Parses the raw `info` bytes of a `Code` attribute, `get_utf8` resolves the names
+    /// of its nested attributes and local variables from the constant pool
+    ///
+    /// Code_attribute {
+    ///     u2 max_stack;
+    ///     u2 max_locals;
+    ///     u4 code_length;
+    ///     u1 code[code_length];
+    ///     u2 exception_table_length;
+    ///     {   u2 start_pc;
+    ///         u2 end_pc;
+    ///         u2 handler_pc;
+    ///         u2 catch_type;
+    ///     } exception_table[exception_table_length];
+    ///     u2 attributes_count;
+    ///     attribute_info attributes[attributes_count];
+    /// }
+    pub fn parse(info: &[u8], get_utf8: impl Fn(u16) -> Option<String>) -> Option<Self> {
+        let mut buffer = Buffer::new(info.to_vec());
+
+        let max_stack = buffer.read_u16()?;
+        let max_locals = buffer.read_u16()?;
+        let code_length = buffer.read_u32()? as usize;
+        let code = buffer.read_un(code_length)?;
+
+        let exception_table_length = buffer.read_u16()?;
+        let mut exception_table = Vec::with_capacity(exception_table_length as usize);
+
+        for _ in 0..exception_table_length {
+            exception_table.push(ExceptionTableEntry {
+                start_pc: buffer.read_u16()?,
+                end_pc: buffer.read_u16()?,
+                handler_pc: buffer.read_u16()?,
+                catch_type: buffer.read_u16()?,
+            });
+        }
+
+        let mut line_numbers = Vec::new();
+        let mut local_variables = Vec::new();
+        let attributes_count = buffer.read_u16()?;
+
+        for _ in 0..attributes_count {
+            let name_index = buffer.read_u16()?;
+            let length = buffer.read_u32()? as usize;
+            let attribute = buffer.read_un(length)?;
+
+            // LineNumberTable_attribute {
+            //     u2 line_number_table_length;
+            //     {   u2 start_pc;
+            //         u2 line_number;
+            //     } line_number_table[line_number_table_length];
+            // }
+            //
+            // LocalVariableTable_attribute {
+            //     u2 local_variable_table_length;
+            //     {   u2 start_pc;
+            //         u2 length;
+            //         u2 name_index;
+            //         u2 descriptor_index;
+            //         u2 index;
+            //     } local_variable_table[local_variable_table_length];
+            // }
+            match get_utf8(name_index).as_deref() {
+                Some("LineNumberTable") => {
+                    let mut table = Buffer::new(attribute);
+                    let table_length = table.read_u16()?;
+
+                    for _ in 0..table_length {
+                        line_numbers.push((table.read_u16()?, table.read_u16()?));
+                    }
+                }
+                Some("LocalVariableTable") => {
+                    let mut table = Buffer::new(attribute);
+                    let table_length = table.read_u16()?;
+
+                    for _ in 0..table_length {
+                        let start_pc = table.read_u16()?;
+                        let length = table.read_u16()?;
+                        let name = get_utf8(table.read_u16()?)?;
+                        // The descriptor is not needed
+                        table.read_u16()?;
+                        let index = table.read_u16()?;
+
+                        local_variables.push(LocalVariable {
+                            start_pc,
+                            length,
+                            name,
+                            index,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            max_stack,
+            max_locals,
+            code,
+            exception_table,
+            line_numbers,
+            local_variables,
+        })
+    }
+
+    /// Finds the name of the local variable stored in the given slot at the given pc
+    pub fn local_variable_name(&self, index: usize, pc: usize) -> Option<&str> {
+        self.local_variables
+            .iter()
+            .find(|variable| {
+                variable.index as usize == index
+                    && variable.start_pc as usize <= pc
+                    && pc < variable.start_pc as usize + variable.length as usize
+            })
+            .map(|variable| variable.name.as_str())
+    }
+
+    /// Finds the source line of the instruction at the given pc
+    pub fn line_number(&self, pc: usize) -> Option<u16> {
+        self.line_numbers
+            .iter()
+            .filter(|(start_pc, _)| *start_pc as usize <= pc)
+            .max_by_key(|(start_pc, _)| *start_pc)
+            .map(|(_, line_number)| *line_number)
+    }
+}
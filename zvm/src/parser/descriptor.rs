@@ -0,0 +1,44 @@
+/// Splits a method descriptor into the descriptors of its parameters,
+/// e.g. `(I[CLjava/lang/String;)V` into `I`, `[C` and `Ljava/lang/String;`
+/// AS SPECIFIED BY THE SPECS: §4.3.3
+pub fn parameter_types(descriptor: &str) -> Vec<&str> {
+    let Some(parameters) = descriptor
+        .strip_prefix('(')
+        .and_then(|descriptor| descriptor.split(')').next())
+    else {
+        return Vec::new();
+    };
+
+    let mut types = Vec::new();
+    let mut start = 0;
+    let bytes = parameters.as_bytes();
+
+    while start < bytes.len() {
+        let mut end = start;
+
+        // An array type is its dimensions followed by the type of its elements
+        while end + 1 < bytes.len() && bytes[end] == b'[' {
+            end += 1;
+        }
+
+        if bytes[end] == b'L' {
+            end += parameters[end..].find(';').unwrap_or(bytes.len() - 1 - end);
+        }
+
+        types.push(&parameters[start..=end]);
+        start = end + 1;
+    }
+
+    types
+}
+
+/// Return descriptor of a method descriptor, `V` for void methods
+pub fn return_type(descriptor: &str) -> &str {
+    descriptor.rsplit(')').next().unwrap_or("V")
+}
+
+/// Whether a value of the given field type is a long or a double, which take two local
+/// variables and count as two entries of the operand stack for the verifier
+pub fn is_wide(descriptor: &str) -> bool {
+    descriptor == "J" || descriptor == "D"
+}
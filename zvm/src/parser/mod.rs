@@ -1,7 +1,11 @@
+pub mod access_flags;
 pub mod attribute_info;
+pub mod bootstrap_method;
 pub mod buffer;
 pub mod class_file;
+pub mod code_attribute;
 pub mod constant_pool_info;
+pub mod descriptor;
 pub mod field_info;
 pub mod method_info;
 pub mod opcode;
@@ -2,194 +2,215 @@
 #[derive(Debug, Clone, Copy)]
 #[allow(non_camel_case_types)]
 pub enum Opcode {
-    Nop = 0x00,           // 0
-    Aconst_null = 0x01,   // 1
-    Iconstm1 = 0x02,      // 2
-    Iconst0 = 0x03,       // 3
-    Iconst1 = 0x04,       // 4
-    Iconst2 = 0x05,       // 5
-    Iconst3 = 0x06,       // 6
-    Iconst4 = 0x07,       // 7
-    Iconst5 = 0x08,       // 8
-    Lconst0 = 0x09,       // 9
-    Lconst1 = 0x0A,       // 10
-    Fconst0 = 0x0B,       // 11
-    Fconst1 = 0x0C,       // 12
-    Fconst2 = 0x0D,       // 13
-    Dconst0 = 0x0E,       // 14
-    Dconst1 = 0x0F,       // 15
-    Bipush = 0x10,        // 16
-    Sipush = 0x11,        // 17
-    Ldc = 0x12,           // 18
-    Ldc_w = 0x13,         // 19
-    Ldc2_w = 0x14,        // 20
-    Iload = 0x15,         // 21
-    Lload = 0x16,         // 22
-    Fload = 0x17,         // 23
-    Dload = 0x18,         // 24
-    Aload = 0x19,         // 25
-    Iload0 = 0x1A,        // 26
-    Iload1 = 0x1B,        // 27
-    Iload2 = 0x1C,        // 28
-    Iload3 = 0x1D,        // 29
-    Lload0 = 0x1E,        // 30
-    Lload1 = 0x1F,        // 31
-    Lload2 = 0x20,        // 32
-    Lload3 = 0x21,        // 33
-    Fload0 = 0x22,        // 34
-    Fload1 = 0x23,        // 35
-    Fload2 = 0x24,        // 36
-    Fload3 = 0x25,        // 37
-    Dload0 = 0x26,        // 38
-    Dload1 = 0x27,        // 39
-    Dload2 = 0x28,        // 40
-    Dload3 = 0x29,        // 41
-    Aload_0 = 0x2A,       // 42
-    Aload_1 = 0x2B,       // 43
-    Aload_2 = 0x2C,       // 44
-    Aload_3 = 0x2D,       // 45
-    Iaload = 0x2E,        // 46
-    Laload = 0x2F,        // 47
-    Faload = 0x30,        // 48
-    Daload = 0x31,        // 49
-    Aaload = 0x32,        // 50
-    Baload = 0x33,        // 51
-    Caload = 0x34,        // 52
-    Saload = 0x35,        // 53
-    Istore = 0x36,        // 54
-    Lstore = 0x37,        // 55
-    Fstore = 0x38,        // 56
-    Dstore = 0x39,        // 57
-    Astore = 0x3A,        // 58
-    Istore_0 = 0x3B,      // 59
-    Istore_1 = 0x3C,      // 60
-    Istore_2 = 0x3D,      // 61
-    Istore_3 = 0x3E,      // 62
-    Lstore_0 = 0x3F,      // 63
-    Lstore_1 = 0x40,      // 64
-    Lstore_2 = 0x41,      // 65
-    Lstore_3 = 0x42,      // 66
-    Fstore_0 = 0x43,      // 67
-    Fstore_1 = 0x44,      // 68
-    Fstore_2 = 0x45,      // 69
-    Fstore_3 = 0x46,      // 70
-    Dstore_0 = 0x47,      // 71
-    Dstore_1 = 0x48,      // 72
-    Dstore_2 = 0x49,      // 73
-    Dstore_3 = 0x4A,      // 74
-    Astore_0 = 0x4B,      // 75
-    Astore_1 = 0x4C,      // 76
-    Astore_2 = 0x4D,      // 77
-    Astore_3 = 0x4E,      // 78
-    Iastore = 0x4F,       // 79
-    Lastore = 0x50,       // 80
-    Fastore = 0x51,       // 81
-    Dastore = 0x52,       // 82
-    Bastore = 0x54,       // 84
-    Castore = 0x55,       // 85
-    Sastore = 0x56,       // 86
-    Pop = 0x57,           // 87
-    Pop2 = 0x58,          // 88
-    Dup = 0x59,           // 89
-    Dup_x1 = 0x5A,        // 90
-    Dup_x2 = 0x5B,        // 91
-    Dup2 = 0x5C,          // 92
-    Dup2_x1 = 0x5D,       // 93
-    Dup2_x2 = 0x5E,       // 94
-    Swap = 0x5F,          // 95
-    Iadd = 0x60,          // 96
-    Ladd = 0x61,          // 97
-    Fadd = 0x62,          // 98
-    Dadd = 0x63,          // 99
-    Isub = 0x64,          // 100
-    Lsub = 0x65,          // 101
-    Fsub = 0x66,          // 102
-    Dsub = 0x67,          // 103
-    Imul = 0x68,          // 104
-    Lmul = 0x69,          // 105
-    Fmul = 0x6A,          // 106
-    Dmul = 0x6B,          // 107
-    Idiv = 0x6C,          // 108
-    Ldiv = 0x6D,          // 109
-    Fdiv = 0x6E,          // 110
-    Ddiv = 0x6F,          // 111
-    Irem = 0x70,          // 112
-    Lrem = 0x71,          // 113
-    Frem = 0x72,          // 114
-    Drem = 0x73,          // 115
-    Ineg = 0x74,          // 116
-    Lneg = 0x75,          // 117
-    Fneg = 0x76,          // 118
-    Dneg = 0x77,          // 119
-    Ishl = 0x78,          // 120
-    Lshl = 0x79,          // 121
-    Ishr = 0x7A,          // 122
-    Lshr = 0x7B,          // 123
-    Iushr = 0x7C,         // 124
-    Lushr = 0x7D,         // 125
-    Iand = 0x7E,          // 126
-    Land = 0x7F,          // 127
-    Ior = 0x80,           // 128
-    Lor = 0x81,           // 129
-    Ixor = 0x82,          // 130
-    Lxor = 0x83,          // 131
-    Iinc = 0x84,          // 132
-    I2l = 0x85,           // 133
-    I2f = 0x86,           // 134
-    I2d = 0x87,           // 135
-    L2i = 0x88,           // 136
-    L2f = 0x89,           // 137
-    L2d = 0x8A,           // 138
-    F2i = 0x8B,           // 139
-    F2l = 0x8C,           // 140
-    F2d = 0x8D,           // 141
-    D2i = 0x8E,           // 142
-    D2l = 0x8F,           // 143
-    D2f = 0x90,           // 144
-    I2b = 0x91,           // 145
-    I2c = 0x92,           // 146
-    I2s = 0x93,           // 147
-    Lcmp = 0x94,          // 148
-    Fcmpl = 0x95,         // 149
-    Fcmpg = 0x96,         // 150
-    Dcmpl = 0x97,         // 151
-    Dcmpg = 0x98,         // 152
-    Ifeq = 0x99,          // 153
-    Ifne = 0x9A,          // 154
-    Iflt = 0x9B,          // 155
-    Ifge = 0x9C,          // 156
-    Ifgt = 0x9D,          // 157
-    Ifle = 0x9E,          // 158
-    If_icmpeq = 0x9F,     // 159
-    If_icmpne = 0xA0,     // 160
-    If_icmplt = 0xA1,     // 161
-    If_icmpge = 0xA2,     // 162
-    If_icmpgt = 0xA3,     // 163
-    If_icmple = 0xA4,     // 164
-    Goto = 0xA7,          // 167
-    Tableswitch = 0xAA,   // 170
-    Lookupswitch = 0xAB,  // 171
-    Ireturn = 0xAC,       // 172
-    Lreturn = 0xAD,       // 173
-    Freturn = 0xAE,       // 174
-    Dreturn = 0xAF,       // 175
-    Areturn = 0xB0,       // 176
-    Return = 0xB1,        // 177
-    Getstatic = 0xB2,     // 178
-    Putstatic = 0xB3,     // 179
-    Invokevirtual = 0xB6, // 182
-    Invokespecial = 0xB7, // 183
-    Invokestatic = 0xB8,  // 184
-    Newarray = 0xBC,      // 188
-    Arraylength = 0xBE,   // 190
-    Ifnull = 0xC6,        // 198
-    Ifnonnull = 0xC7,     // 199
-    Goto_w = 0xC8,        // 200
+    Nop = 0x00,             // 0
+    Aconst_null = 0x01,     // 1
+    Iconstm1 = 0x02,        // 2
+    Iconst0 = 0x03,         // 3
+    Iconst1 = 0x04,         // 4
+    Iconst2 = 0x05,         // 5
+    Iconst3 = 0x06,         // 6
+    Iconst4 = 0x07,         // 7
+    Iconst5 = 0x08,         // 8
+    Lconst0 = 0x09,         // 9
+    Lconst1 = 0x0A,         // 10
+    Fconst0 = 0x0B,         // 11
+    Fconst1 = 0x0C,         // 12
+    Fconst2 = 0x0D,         // 13
+    Dconst0 = 0x0E,         // 14
+    Dconst1 = 0x0F,         // 15
+    Bipush = 0x10,          // 16
+    Sipush = 0x11,          // 17
+    Ldc = 0x12,             // 18
+    Ldc_w = 0x13,           // 19
+    Ldc2_w = 0x14,          // 20
+    Iload = 0x15,           // 21
+    Lload = 0x16,           // 22
+    Fload = 0x17,           // 23
+    Dload = 0x18,           // 24
+    Aload = 0x19,           // 25
+    Iload0 = 0x1A,          // 26
+    Iload1 = 0x1B,          // 27
+    Iload2 = 0x1C,          // 28
+    Iload3 = 0x1D,          // 29
+    Lload0 = 0x1E,          // 30
+    Lload1 = 0x1F,          // 31
+    Lload2 = 0x20,          // 32
+    Lload3 = 0x21,          // 33
+    Fload0 = 0x22,          // 34
+    Fload1 = 0x23,          // 35
+    Fload2 = 0x24,          // 36
+    Fload3 = 0x25,          // 37
+    Dload0 = 0x26,          // 38
+    Dload1 = 0x27,          // 39
+    Dload2 = 0x28,          // 40
+    Dload3 = 0x29,          // 41
+    Aload_0 = 0x2A,         // 42
+    Aload_1 = 0x2B,         // 43
+    Aload_2 = 0x2C,         // 44
+    Aload_3 = 0x2D,         // 45
+    Iaload = 0x2E,          // 46
+    Laload = 0x2F,          // 47
+    Faload = 0x30,          // 48
+    Daload = 0x31,          // 49
+    Aaload = 0x32,          // 50
+    Baload = 0x33,          // 51
+    Caload = 0x34,          // 52
+    Saload = 0x35,          // 53
+    Istore = 0x36,          // 54
+    Lstore = 0x37,          // 55
+    Fstore = 0x38,          // 56
+    Dstore = 0x39,          // 57
+    Astore = 0x3A,          // 58
+    Istore_0 = 0x3B,        // 59
+    Istore_1 = 0x3C,        // 60
+    Istore_2 = 0x3D,        // 61
+    Istore_3 = 0x3E,        // 62
+    Lstore_0 = 0x3F,        // 63
+    Lstore_1 = 0x40,        // 64
+    Lstore_2 = 0x41,        // 65
+    Lstore_3 = 0x42,        // 66
+    Fstore_0 = 0x43,        // 67
+    Fstore_1 = 0x44,        // 68
+    Fstore_2 = 0x45,        // 69
+    Fstore_3 = 0x46,        // 70
+    Dstore_0 = 0x47,        // 71
+    Dstore_1 = 0x48,        // 72
+    Dstore_2 = 0x49,        // 73
+    Dstore_3 = 0x4A,        // 74
+    Astore_0 = 0x4B,        // 75
+    Astore_1 = 0x4C,        // 76
+    Astore_2 = 0x4D,        // 77
+    Astore_3 = 0x4E,        // 78
+    Iastore = 0x4F,         // 79
+    Lastore = 0x50,         // 80
+    Fastore = 0x51,         // 81
+    Dastore = 0x52,         // 82
+    Aastore = 0x53,         // 83
+    Bastore = 0x54,         // 84
+    Castore = 0x55,         // 85
+    Sastore = 0x56,         // 86
+    Pop = 0x57,             // 87
+    Pop2 = 0x58,            // 88
+    Dup = 0x59,             // 89
+    Dup_x1 = 0x5A,          // 90
+    Dup_x2 = 0x5B,          // 91
+    Dup2 = 0x5C,            // 92
+    Dup2_x1 = 0x5D,         // 93
+    Dup2_x2 = 0x5E,         // 94
+    Swap = 0x5F,            // 95
+    Iadd = 0x60,            // 96
+    Ladd = 0x61,            // 97
+    Fadd = 0x62,            // 98
+    Dadd = 0x63,            // 99
+    Isub = 0x64,            // 100
+    Lsub = 0x65,            // 101
+    Fsub = 0x66,            // 102
+    Dsub = 0x67,            // 103
+    Imul = 0x68,            // 104
+    Lmul = 0x69,            // 105
+    Fmul = 0x6A,            // 106
+    Dmul = 0x6B,            // 107
+    Idiv = 0x6C,            // 108
+    Ldiv = 0x6D,            // 109
+    Fdiv = 0x6E,            // 110
+    Ddiv = 0x6F,            // 111
+    Irem = 0x70,            // 112
+    Lrem = 0x71,            // 113
+    Frem = 0x72,            // 114
+    Drem = 0x73,            // 115
+    Ineg = 0x74,            // 116
+    Lneg = 0x75,            // 117
+    Fneg = 0x76,            // 118
+    Dneg = 0x77,            // 119
+    Ishl = 0x78,            // 120
+    Lshl = 0x79,            // 121
+    Ishr = 0x7A,            // 122
+    Lshr = 0x7B,            // 123
+    Iushr = 0x7C,           // 124
+    Lushr = 0x7D,           // 125
+    Iand = 0x7E,            // 126
+    Land = 0x7F,            // 127
+    Ior = 0x80,             // 128
+    Lor = 0x81,             // 129
+    Ixor = 0x82,            // 130
+    Lxor = 0x83,            // 131
+    Iinc = 0x84,            // 132
+    I2l = 0x85,             // 133
+    I2f = 0x86,             // 134
+    I2d = 0x87,             // 135
+    L2i = 0x88,             // 136
+    L2f = 0x89,             // 137
+    L2d = 0x8A,             // 138
+    F2i = 0x8B,             // 139
+    F2l = 0x8C,             // 140
+    F2d = 0x8D,             // 141
+    D2i = 0x8E,             // 142
+    D2l = 0x8F,             // 143
+    D2f = 0x90,             // 144
+    I2b = 0x91,             // 145
+    I2c = 0x92,             // 146
+    I2s = 0x93,             // 147
+    Lcmp = 0x94,            // 148
+    Fcmpl = 0x95,           // 149
+    Fcmpg = 0x96,           // 150
+    Dcmpl = 0x97,           // 151
+    Dcmpg = 0x98,           // 152
+    Ifeq = 0x99,            // 153
+    Ifne = 0x9A,            // 154
+    Iflt = 0x9B,            // 155
+    Ifge = 0x9C,            // 156
+    Ifgt = 0x9D,            // 157
+    Ifle = 0x9E,            // 158
+    If_icmpeq = 0x9F,       // 159
+    If_icmpne = 0xA0,       // 160
+    If_icmplt = 0xA1,       // 161
+    If_icmpge = 0xA2,       // 162
+    If_icmpgt = 0xA3,       // 163
+    If_icmple = 0xA4,       // 164
+    If_acmpeq = 0xA5,       // 165
+    If_acmpne = 0xA6,       // 166
+    Goto = 0xA7,            // 167
+    Jsr = 0xA8,             // 168
+    Ret = 0xA9,             // 169
+    Tableswitch = 0xAA,     // 170
+    Lookupswitch = 0xAB,    // 171
+    Ireturn = 0xAC,         // 172
+    Lreturn = 0xAD,         // 173
+    Freturn = 0xAE,         // 174
+    Dreturn = 0xAF,         // 175
+    Areturn = 0xB0,         // 176
+    Return = 0xB1,          // 177
+    Getstatic = 0xB2,       // 178
+    Putstatic = 0xB3,       // 179
+    Getfield = 0xB4,        // 180
+    Putfield = 0xB5,        // 181
+    Invokevirtual = 0xB6,   // 182
+    Invokespecial = 0xB7,   // 183
+    Invokestatic = 0xB8,    // 184
+    Invokeinterface = 0xB9, // 185
+    Invokedynamic = 0xBA,   // 186
+    New = 0xBB,             // 187
+    Newarray = 0xBC,        // 188
+    Anewarray = 0xBD,       // 189
+    Arraylength = 0xBE,     // 190
+    Athrow = 0xBF,          // 191
+    Checkcast = 0xC0,       // 192
+    Instanceof = 0xC1,      // 193
+    Monitorenter = 0xC2,    // 194
+    Monitorexit = 0xC3,     // 195
+    Wide = 0xC4,            // 196
+    Multianewarray = 0xC5,  // 197
+    Ifnull = 0xC6,          // 198
+    Ifnonnull = 0xC7,       // 199
+    Goto_w = 0xC8,          // 200
+    Jsr_w = 0xC9,           // 201
 }
 
-impl From<u8> for Opcode {
-    fn from(byte: u8) -> Self {
-        match byte {
+impl Opcode {
+    /// Decodes an opcode, `None` for the reserved opcodes ( breakpoint, impdep1 and impdep2 )
+    /// and the bytes that are not opcodes
+    pub fn decode(byte: u8) -> Option<Self> {
+        let opcode = match byte {
             0x00 => Opcode::Nop,
             0x01 => Opcode::Aconst_null,
             0x02 => Opcode::Iconstm1,
@@ -273,6 +294,7 @@ impl From<u8> for Opcode {
             0x50 => Opcode::Lastore,
             0x51 => Opcode::Fastore,
             0x52 => Opcode::Dastore,
+            0x53 => Opcode::Aastore,
             0x54 => Opcode::Bastore,
             0x55 => Opcode::Castore,
             0x56 => Opcode::Sastore,
@@ -354,7 +376,11 @@ impl From<u8> for Opcode {
             0xA2 => Opcode::If_icmpge,
             0xA3 => Opcode::If_icmpgt,
             0xA4 => Opcode::If_icmple,
+            0xA5 => Opcode::If_acmpeq,
+            0xA6 => Opcode::If_acmpne,
             0xA7 => Opcode::Goto,
+            0xA8 => Opcode::Jsr,
+            0xA9 => Opcode::Ret,
             0xAA => Opcode::Tableswitch,
             0xAB => Opcode::Lookupswitch,
             0xAC => Opcode::Ireturn,
@@ -365,15 +391,122 @@ impl From<u8> for Opcode {
             0xB1 => Opcode::Return,
             0xB2 => Opcode::Getstatic,
             0xB3 => Opcode::Putstatic,
+            0xB4 => Opcode::Getfield,
+            0xB5 => Opcode::Putfield,
             0xB6 => Opcode::Invokevirtual,
             0xB7 => Opcode::Invokespecial,
             0xB8 => Opcode::Invokestatic,
+            0xB9 => Opcode::Invokeinterface,
+            0xBA => Opcode::Invokedynamic,
+            0xBB => Opcode::New,
             0xBC => Opcode::Newarray,
+            0xBD => Opcode::Anewarray,
             0xBE => Opcode::Arraylength,
+            0xBF => Opcode::Athrow,
+            0xC0 => Opcode::Checkcast,
+            0xC1 => Opcode::Instanceof,
+            0xC2 => Opcode::Monitorenter,
+            0xC3 => Opcode::Monitorexit,
+            0xC4 => Opcode::Wide,
+            0xC5 => Opcode::Multianewarray,
             0xC6 => Opcode::Ifnull,
             0xC7 => Opcode::Ifnonnull,
             0xC8 => Opcode::Goto_w,
-            _ => panic!("Unknown opcode: 0x{:02X}", byte),
-        }
+            0xC9 => Opcode::Jsr_w,
+            _ => return None,
+        };
+
+        Some(opcode)
+    }
+}
+
+impl From<u8> for Opcode {
+    fn from(byte: u8) -> Self {
+        Self::decode(byte).unwrap_or_else(|| panic!("Unknown opcode: 0x{:02X}", byte))
     }
 }
+
+/// Decodes the opcode at the given pc, `None` for bytes that are not opcodes
+pub fn opcode_at(code: &[u8], pc: usize) -> Option<Opcode> {
+    Opcode::decode(*code.get(pc)?)
+}
+
+/// Computes the length in bytes of the instruction at the given pc, including its operands
+pub fn instruction_length(code: &[u8], pc: usize) -> Option<usize> {
+    // AS SPECIFIED BY THE SPECS: the operands of tableswitch and lookupswitch start at the
+    // next address that is a multiple of four
+    let padding = 3 - pc % 4;
+
+    let length = match opcode_at(code, pc)? {
+        Opcode::Bipush
+        | Opcode::Ldc
+        | Opcode::Iload
+        | Opcode::Lload
+        | Opcode::Fload
+        | Opcode::Dload
+        | Opcode::Aload
+        | Opcode::Istore
+        | Opcode::Lstore
+        | Opcode::Fstore
+        | Opcode::Dstore
+        | Opcode::Astore
+        | Opcode::Newarray
+        | Opcode::Ret => 2,
+        Opcode::Sipush
+        | Opcode::Ldc_w
+        | Opcode::Ldc2_w
+        | Opcode::Iinc
+        | Opcode::Ifeq
+        | Opcode::Ifne
+        | Opcode::Iflt
+        | Opcode::Ifge
+        | Opcode::Ifgt
+        | Opcode::Ifle
+        | Opcode::If_icmpeq
+        | Opcode::If_icmpne
+        | Opcode::If_icmplt
+        | Opcode::If_icmpge
+        | Opcode::If_icmpgt
+        | Opcode::If_icmple
+        | Opcode::If_acmpeq
+        | Opcode::If_acmpne
+        | Opcode::Goto
+        | Opcode::Jsr
+        | Opcode::Ifnull
+        | Opcode::Ifnonnull
+        | Opcode::Getstatic
+        | Opcode::Putstatic
+        | Opcode::Getfield
+        | Opcode::Putfield
+        | Opcode::Invokevirtual
+        | Opcode::Invokespecial
+        | Opcode::Invokestatic
+        | Opcode::New
+        | Opcode::Anewarray
+        | Opcode::Checkcast
+        | Opcode::Instanceof => 3,
+        Opcode::Multianewarray => 4,
+        Opcode::Invokeinterface | Opcode::Invokedynamic | Opcode::Goto_w | Opcode::Jsr_w => 5,
+        Opcode::Wide => match opcode_at(code, pc + 1)? {
+            Opcode::Iinc => 6,
+            _ => 4,
+        },
+        Opcode::Tableswitch => {
+            let low = read_i32(code, pc + 1 + padding + 4)?;
+            let high = read_i32(code, pc + 1 + padding + 8)?;
+            let count = usize::try_from(high as i64 - low as i64 + 1).ok()?;
+            1 + padding + 12 + 4 * count
+        }
+        Opcode::Lookupswitch => {
+            let npairs = usize::try_from(read_i32(code, pc + 1 + padding + 4)?).ok()?;
+            1 + padding + 8 + 8 * npairs
+        }
+        _ => 1,
+    };
+
+    Some(length)
+}
+
+fn read_i32(code: &[u8], at: usize) -> Option<i32> {
+    Some(i32::from_be_bytes(code.get(at..at + 4)?.try_into().ok()?))
+}
@@ -47,6 +47,14 @@ impl Reader {
         }
     }
 
+    /// Creates a new `Reader` instance from the bytes of an already loaded class file
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Reader {
+            buffer: Buffer::new(bytes),
+            class_file: ClassFile::new(),
+        }
+    }
+
     /// Reads the bytes from the buffer sequentially and parse them
     /// into the class file instance in memory
     pub fn read(&mut self) {
@@ -103,7 +111,8 @@ impl Reader {
     fn test_parsing_fields_and_methods(&self) {
         let str = self
             .class_file
-            .get_string(28u16)
+            .get_string_utf16(28u16)
+            .map(|chars| String::from_utf16_lossy(&chars))
             .expect("Failed to read String");
         println!("Parsed String on index #28 from the class file: {}", str);
 
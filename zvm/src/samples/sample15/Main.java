@@ -0,0 +1,81 @@
+abstract class Shape {
+    static int created;
+
+    int sides;
+
+    Shape(int sides) {
+        this.sides = sides;
+        created++;
+    }
+
+    abstract int area();
+
+    int perimeter() {
+        return 0;
+    }
+
+    void describe() {
+        System.out.println(sides);
+        System.out.println(area());
+        System.out.println(perimeter());
+    }
+}
+
+class Rectangle extends Shape {
+    int width;
+    int height;
+
+    Rectangle(int width, int height) {
+        super(4);
+        this.width = width;
+        this.height = height;
+    }
+
+    int area() {
+        return width * height;
+    }
+
+    int perimeter() {
+        return 2 * (width + height);
+    }
+}
+
+class Square extends Rectangle {
+    Square(int side) {
+        super(side, side);
+    }
+
+    int perimeter() {
+        return super.perimeter() + 1000;
+    }
+}
+
+class Triangle extends Shape {
+    int base;
+    int height;
+
+    Triangle(int base, int height) {
+        super(3);
+        this.base = base;
+        this.height = height;
+    }
+
+    int area() {
+        return base * height / 2;
+    }
+}
+
+public class Main {
+    public static void main(String[] args) {
+        Shape rectangle = new Rectangle(3, 4);
+        rectangle.describe();
+
+        Shape square = new Square(5);
+        square.describe();
+
+        Shape triangle = new Triangle(6, 7);
+        triangle.describe();
+
+        System.out.println(Shape.created);
+    }
+}
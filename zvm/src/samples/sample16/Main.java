@@ -0,0 +1,101 @@
+interface Sized {
+    int size();
+
+    default int isEmpty() {
+        return size() == 0 ? 1 : 0;
+    }
+}
+
+interface IntList extends Sized {
+    int LIMIT = 8;
+
+    int get(int index);
+
+    void add(int value);
+
+    default int sum() {
+        int total = 0;
+        for (int i = 0; i < size(); i++) {
+            total += get(i);
+        }
+        return total;
+    }
+
+    static int first(IntList list) {
+        return list.get(0);
+    }
+}
+
+interface Named {
+    default int id() {
+        return 1;
+    }
+}
+
+interface Tagged {
+    default int id() {
+        return 2;
+    }
+}
+
+class ArrayIntList implements IntList {
+    int[] values = new int[LIMIT];
+    int count;
+
+    public int size() {
+        return count;
+    }
+
+    public int get(int index) {
+        return values[index];
+    }
+
+    public void add(int value) {
+        values[count++] = value;
+    }
+}
+
+class SquaresList extends ArrayIntList implements Named, Tagged {
+    public void add(int value) {
+        super.add(value * value);
+    }
+
+    public int sum() {
+        return super.sum() + 1000;
+    }
+
+    public int id() {
+        return Named.super.id() + Tagged.super.id();
+    }
+}
+
+public class Main {
+    public static void main(String[] args) {
+        IntList plain = new ArrayIntList();
+        System.out.println(plain.isEmpty());
+        plain.add(3);
+        plain.add(4);
+        plain.add(5);
+        System.out.println(plain.size());
+        System.out.println(plain.sum());
+        System.out.println(IntList.first(plain));
+        System.out.println(plain.isEmpty());
+
+        IntList squares = new SquaresList();
+        squares.add(3);
+        squares.add(4);
+        System.out.println(squares.sum());
+        System.out.println(IntList.first(squares));
+
+        Sized sized = squares;
+        System.out.println(sized.size());
+
+        Named named = new SquaresList();
+        System.out.println(named.id());
+
+        ArrayIntList list = new SquaresList();
+        list.add(6);
+        System.out.println(list.sum());
+        System.out.println(IntList.LIMIT);
+    }
+}
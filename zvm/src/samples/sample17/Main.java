@@ -0,0 +1,117 @@
+class InsufficientFundsException extends Exception {
+    int missing;
+
+    InsufficientFundsException(String message, int missing) {
+        super(message);
+        this.missing = missing;
+    }
+}
+
+class Account {
+    int balance;
+
+    Account(int balance) {
+        this.balance = balance;
+    }
+
+    void withdraw(int amount) throws InsufficientFundsException {
+        if (amount > balance) {
+            throw new InsufficientFundsException("Insufficient funds", amount - balance);
+        }
+        balance -= amount;
+    }
+}
+
+public class Main {
+    static int attempts;
+
+    public static void main(String[] args) {
+        Account account = new Account(100);
+
+        try {
+            account.withdraw(30);
+            account.withdraw(100);
+            System.out.println("unreachable");
+        } catch (InsufficientFundsException e) {
+            System.out.println(e.getMessage());
+            System.out.println(e.missing);
+        }
+        System.out.println(account.balance);
+
+        System.out.println(withFinally(5));
+        System.out.println(withFinally(-5));
+        System.out.println(attempts);
+
+        try {
+            deep(3);
+        } catch (RuntimeException e) {
+            System.out.println(e.getMessage());
+        }
+
+        try {
+            rethrow();
+        } catch (Exception e) {
+            System.out.println(e.getMessage());
+            System.out.println(e.getCause().getMessage());
+        }
+
+        try {
+            throw null;
+        } catch (NullPointerException e) {
+            System.out.println("caught null");
+        }
+
+        try {
+            Failing.value++;
+        } catch (ExceptionInInitializerError e) {
+            System.out.println(e.getCause().getMessage());
+        }
+
+        // A class whose initialization failed stays unusable
+        try {
+            Failing.value++;
+        } catch (NoClassDefFoundError e) {
+            System.out.println(e.getMessage());
+            System.out.println(e.getCause().getMessage());
+        }
+
+        deep(2);
+        System.out.println("unreachable");
+    }
+
+    static int withFinally(int value) {
+        try {
+            if (value < 0) {
+                throw new IllegalArgumentException("negative");
+            }
+            return value * 2;
+        } catch (IllegalArgumentException e) {
+            return -1;
+        } finally {
+            attempts++;
+        }
+    }
+
+    static void deep(int depth) {
+        if (depth == 0) {
+            throw new IllegalStateException("bottom reached");
+        }
+        deep(depth - 1);
+    }
+
+    static void rethrow() throws Exception {
+        try {
+            deep(1);
+        } catch (IllegalStateException e) {
+            throw new Exception("wrapped", e);
+        }
+    }
+}
+
+class Failing {
+    static int value = compute();
+
+    static int compute() {
+        throw new UnsupportedOperationException("no static value");
+    }
+}
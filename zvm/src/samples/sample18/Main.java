@@ -0,0 +1,105 @@
+class Node {
+    int value;
+    Node next;
+
+    Node(int value) {
+        this.value = value;
+    }
+
+    int sum() {
+        return value + next.sum();
+    }
+}
+
+public class Main {
+    static int[] table;
+
+    static int divide(int a, int b) {
+        return a / b;
+    }
+
+    static long remainder(long a, long b) {
+        return a % b;
+    }
+
+    static int average(int[] values) {
+        int total = 0;
+        for (int i = 0; i <= values.length; i++) {
+            total += values[i];
+        }
+        return total / values.length;
+    }
+
+    static int safeDivide(int a, int b) {
+        try {
+            return divide(a, b);
+        } catch (ArithmeticException e) {
+            System.out.println(e.getMessage());
+            return 0;
+        }
+    }
+
+    public static void main(String[] args) {
+        System.out.println(safeDivide(10, 2));
+        System.out.println(safeDivide(10, 0));
+
+        try {
+            System.out.println(remainder(7L, 0L));
+        } catch (ArithmeticException e) {
+            System.out.println(e.getMessage());
+        }
+
+        int[] values = new int[3];
+        values[0] = 4;
+        values[1] = 5;
+        values[2] = 6;
+
+        try {
+            System.out.println(average(values));
+        } catch (ArrayIndexOutOfBoundsException e) {
+            System.out.println(e.getMessage());
+        }
+
+        try {
+            values[-1] = 1;
+        } catch (IndexOutOfBoundsException e) {
+            System.out.println(e.getMessage());
+        }
+
+        try {
+            int[] negative = new int[values[0] - 10];
+            System.out.println(negative.length);
+        } catch (NegativeArraySizeException e) {
+            System.out.println(e.getMessage());
+        }
+
+        try {
+            System.out.println(table[0]);
+        } catch (NullPointerException e) {
+            System.out.println(e.getMessage());
+        }
+
+        Node head = new Node(1);
+        head.next = new Node(2);
+        try {
+            System.out.println(head.sum());
+        } catch (NullPointerException e) {
+            System.out.println(e.getMessage());
+        }
+
+        try {
+            System.out.println(head.next.next.value);
+        } catch (RuntimeException e) {
+            System.out.println(e.getMessage());
+        }
+
+        int[] missing = null;
+        try {
+            System.out.println(missing.length);
+        } catch (Exception e) {
+            System.out.println(e.getMessage());
+        }
+
+        System.out.println(divide(1, 0));
+    }
+}
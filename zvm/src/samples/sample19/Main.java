@@ -0,0 +1,63 @@
+class Cell {
+    Cell next;
+    Cell prev;
+    int[] payload;
+
+    Cell(int size) {
+        payload = new int[size];
+    }
+}
+
+public class Main {
+    static Cell survivors;
+
+    // Every iteration creates a cycle that becomes garbage right away
+    static long churn(int iterations) {
+        long total = 0;
+        for (int i = 0; i < iterations; i++) {
+            Cell a = new Cell(32);
+            Cell b = new Cell(32);
+            a.next = b;
+            b.prev = a;
+            b.next = a;
+            a.payload[0] = i;
+            total += b.next.payload[0];
+        }
+        return total;
+    }
+
+    // A long list reachable from a static field survives every collection
+    static int retain(int count) {
+        for (int i = 0; i < count; i++) {
+            Cell cell = new Cell(4);
+            cell.payload[0] = i;
+            cell.next = survivors;
+            survivors = cell;
+        }
+
+        int sum = 0;
+        for (Cell cell = survivors; cell != null; cell = cell.next) {
+            sum += cell.payload[0];
+        }
+        return sum;
+    }
+
+    public static void main(String[] args) {
+        Object marker = new Object();
+        int hash = marker.hashCode();
+
+        System.out.println(churn(20000));
+        System.out.println(retain(5000));
+        System.out.println(churn(20000));
+
+        survivors = null;
+        System.out.println(churn(10000));
+
+        // Identity hash codes do not change when collections run
+        if (marker.hashCode() == hash) {
+            System.out.println(1);
+        } else {
+            System.out.println(0);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+class Shape {
+    int sides;
+
+    Shape(int sides) {
+        this.sides = sides;
+    }
+}
+
+class Square extends Shape implements Cloneable {
+    Square() {
+        super(4);
+    }
+
+    Object duplicate() throws CloneNotSupportedException {
+        return clone();
+    }
+}
+
+class Circle extends Shape {
+    Circle() {
+        super(0);
+    }
+
+    Object duplicate() throws CloneNotSupportedException {
+        return clone();
+    }
+}
+
+public class Main {
+    static int totalSides(Shape[] shapes) {
+        int total = 0;
+        for (int i = 0; i < shapes.length; i++) {
+            if (shapes[i] != null) {
+                total += shapes[i].sides;
+            }
+        }
+        return total;
+    }
+
+    public static void main(String[] args) throws Exception {
+        String[] names = new String[3];
+        names[0] = "first";
+        names[2] = "third";
+        for (int i = 0; i < names.length; i++) {
+            if (names[i] != null) {
+                System.out.println(names[i]);
+            }
+        }
+
+        int[][] grid = new int[4][5];
+        for (int row = 0; row < grid.length; row++) {
+            for (int column = 0; column < grid[row].length; column++) {
+                grid[row][column] = row * column;
+            }
+        }
+        System.out.println(grid[3][4]);
+
+        double[][][] cube = new double[2][3][];
+        System.out.println(cube[1].length);
+        if (cube[1][2] == null) {
+            System.out.println(0);
+        }
+
+        Shape[] shapes = new Square[3];
+        shapes[0] = new Square();
+        shapes[1] = new Square();
+        System.out.println(totalSides(shapes));
+
+        // The array is a Square[], it can not hold a Circle
+        try {
+            shapes[2] = new Circle();
+        } catch (ArrayStoreException e) {
+            System.out.println(e.getMessage());
+        }
+
+        Object[][] matrix = new String[2][];
+        try {
+            matrix[0] = new Object[1];
+        } catch (ArrayStoreException e) {
+            System.out.println(e.getMessage());
+        }
+
+        Object[] anything = new Object[2];
+        anything[0] = grid;
+        anything[1] = new Circle();
+
+        Object copy = new Square().duplicate();
+        Object gridCopy = grid.clone();
+        if (copy.hashCode() != gridCopy.hashCode()) {
+            System.out.println(1);
+        }
+
+        try {
+            new Circle().duplicate();
+        } catch (CloneNotSupportedException e) {
+            System.out.println(e.getMessage());
+        }
+
+        Cloneable[] cloneables = new Cloneable[2];
+        cloneables[0] = new Square();
+        cloneables[1] = grid;
+
+        try {
+            int[][] negative = new int[2][-3];
+        } catch (NegativeArraySizeException e) {
+            System.out.println(e.getMessage());
+        }
+    }
+}
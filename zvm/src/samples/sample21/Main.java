@@ -0,0 +1,63 @@
+public class Main {
+    // Adler-32 checksum of a byte buffer
+    static long adler32(byte[] data) {
+        int a = 1;
+        int b = 0;
+        for (int i = 0; i < data.length; i++) {
+            a = (a + (data[i] & 0xFF)) % 65521;
+            b = (b + a) % 65521;
+        }
+        return ((long) b << 16) | a;
+    }
+
+    // Count the primes below the limit with a sieve of Eratosthenes
+    static int sieve(int limit) {
+        boolean[] composite = new boolean[limit];
+        int primes = 0;
+        for (int i = 2; i < limit; i++) {
+            if (!composite[i]) {
+                primes++;
+                for (int j = i * 2; j < limit; j += i) {
+                    composite[j] = true;
+                }
+            }
+        }
+        return primes;
+    }
+
+    public static void main(String[] args) {
+        byte[] data = new byte[100000];
+        for (int i = 0; i < data.length; i++) {
+            data[i] = (byte) (i * 31 + 7);
+        }
+        System.out.println(adler32(data));
+        System.out.println(sieve(100000));
+
+        // Stores truncate to the component type, loads sign or zero extend it
+        byte[] bytes = new byte[2];
+        bytes[0] = (byte) 200;
+        bytes[1] = 127;
+        bytes[1]++;
+        System.out.println(bytes[0]);
+        System.out.println(bytes[1]);
+
+        char[] chars = new char[2];
+        chars[0] = 'A';
+        chars[0] += 2;
+        chars[1] = (char) -1;
+        System.out.println(chars[0] + 0);
+        System.out.println(chars[1] + 0);
+
+        short[] shorts = new short[2];
+        shorts[0] = (short) 40000;
+        shorts[1] = Short.MIN_VALUE;
+        shorts[1]--;
+        System.out.println(shorts[0]);
+        System.out.println(shorts[1]);
+
+        long[] longs = new long[2];
+        longs[0] = Long.MAX_VALUE;
+        longs[1] = longs[0] + 1;
+        System.out.println(longs[1]);
+    }
+}
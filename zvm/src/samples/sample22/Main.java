@@ -0,0 +1,73 @@
+public class Main {
+    static final String GREETING = "hello";
+    static String stored;
+
+    static void check(String label, boolean condition) {
+        System.out.println(label);
+        if (condition) {
+            System.out.println("  yes");
+        } else {
+            System.out.println("  no");
+        }
+    }
+
+    static String divisionMessage() {
+        try {
+            int zero = 0;
+            System.out.println(1 / zero);
+        } catch (ArithmeticException e) {
+            return e.getMessage();
+        }
+        return null;
+    }
+
+    public static void main(String[] args) {
+        String a = "hello";
+        String b = "hello";
+        stored = "hello";
+
+        check("literals are the same object", a == b);
+        check("literal and static constant", a == GREETING);
+        check("literal from another class", a == Other.greeting());
+        check("field and literal", stored == a);
+        check("different literals", a != "world");
+
+        String message = divisionMessage();
+        System.out.println(message);
+        check("runtime string is not the literal", message != "/ by zero");
+        check("runtime string equals the literal", message.equals("/ by zero"));
+        check("interned runtime string is the literal", message.intern() == "/ by zero");
+        check("equals with a non string", !a.equals(new Object()));
+        check("equals with null", !a.equals(null));
+        check("toString returns itself", a.toString() == a);
+
+        System.out.println("hello".hashCode());
+        System.out.println("".hashCode());
+        System.out.println(message.hashCode());
+        System.out.println("The quick brown fox jumps over the lazy dog".hashCode());
+
+        String accents = "h\u00e9llo \u20ac";
+        String emoji = "smile \ud83d\ude00";
+        String nul = "nul\u0000char";
+        System.out.println(accents);
+        System.out.println(emoji);
+        System.out.println(accents.hashCode());
+        System.out.println(emoji.hashCode());
+        System.out.println(nul.hashCode());
+        check("non ASCII literals are interned", accents == Other.accents());
+
+        Object o = a;
+        check("string as object", o.equals("hello"));
+        System.out.println(o.hashCode());
+    }
+}
+
+class Other {
+    static String greeting() {
+        return "hello";
+    }
+
+    static String accents() {
+        return "h\u00e9llo \u20ac";
+    }
+}
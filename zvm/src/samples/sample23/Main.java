@@ -0,0 +1,163 @@
+public class Main {
+    static class Point {
+        int x;
+        int y;
+
+        Point(int x, int y) {
+            this.x = x;
+            this.y = y;
+        }
+
+        public String toString() {
+            return new StringBuilder("(").append(x).append(", ").append(y).append(")").toString();
+        }
+    }
+
+    static class Broken {
+        public String toString() {
+            throw new IllegalStateException("no description");
+        }
+    }
+
+    static void printParts(String label, String[] parts) {
+        StringBuilder line = new StringBuilder(label).append(" ").append(parts.length).append(":");
+        for (int i = 0; i < parts.length; i++) {
+            line.append(" [").append(parts[i]).append("]");
+        }
+        System.out.println(line.toString());
+    }
+
+    static void charAt(String s, int index) {
+        try {
+            System.out.println(String.valueOf(s.charAt(index)));
+        } catch (StringIndexOutOfBoundsException e) {
+            System.out.println(e.getMessage());
+        }
+    }
+
+    static void substring(String s, int begin, int end) {
+        try {
+            System.out.println(s.substring(begin, end));
+        } catch (IndexOutOfBoundsException e) {
+            System.out.println(e.getMessage());
+        }
+    }
+
+    public static void main(String[] args) {
+        String s = "Hello, World";
+
+        System.out.println(s.length());
+        System.out.println(String.valueOf(s.isEmpty()));
+        System.out.println(String.valueOf("".isEmpty()));
+        charAt(s, 0);
+        charAt(s, 11);
+        charAt(s, 12);
+        charAt(s, -1);
+
+        System.out.println(s.substring(7));
+        System.out.println(s.substring(0, 5));
+        System.out.println(String.valueOf(s.substring(0) == s));
+        substring(s, 3, 3);
+        substring(s, 5, 20);
+        substring(s, 6, 2);
+        substring(s, -2, 2);
+        try {
+            System.out.println(s.substring(13));
+        } catch (StringIndexOutOfBoundsException e) {
+            System.out.println(e.getMessage());
+        }
+
+        System.out.println(s.indexOf('o'));
+        System.out.println(s.indexOf('o', 5));
+        System.out.println(s.indexOf('z'));
+        System.out.println(s.indexOf("World"));
+        System.out.println(s.indexOf("o", 9));
+        System.out.println(s.indexOf(""));
+        System.out.println(s.indexOf("", 40));
+        System.out.println(s.indexOf("l", -3));
+
+        System.out.println("apple".compareTo("banana"));
+        System.out.println("apple".compareTo("app"));
+        System.out.println("apple".compareTo("apple"));
+        System.out.println("Zebra".compareTo("apple"));
+        System.out.println("hello".hashCode());
+        System.out.println(String.valueOf(s.equals("Hello, World")));
+
+        printParts("comma", "a,b,,c,,".split(","));
+        printParts("limit", "a,b,,c,,".split(",", -1));
+        printParts("limit2", "a,b,,c,,".split(",", 2));
+        printParts("spaces", "  the quick   brown fox ".split("\\s+"));
+        printParts("class", "k1=v1;k2=v2|k3=v3".split("[;|]"));
+        printParts("chars", "abc".split(""));
+        printParts("none", "abc".split("x"));
+        printParts("empty", "".split(","));
+        printParts("only", ",,,".split(","));
+        printParts("alt", "one and two or three".split(" (and|or) "));
+        printParts("dot", "1.2.3".split("\\."));
+        printParts("digits", "a1b22c333d".split("\\d+"));
+        printParts("lazy", "aXbXXc".split("X+?"));
+        printParts("counted", "aaaabaaab".split("a{2,3}"));
+
+        System.out.println("Hello, World! stra\u00dfe".toUpperCase());
+        System.out.println("MiXeD CaSe".toLowerCase());
+        String upper = "ALREADY";
+        System.out.println(String.valueOf(upper.toUpperCase() == upper));
+
+        System.out.println(String.valueOf(42));
+        System.out.println(String.valueOf(-7L));
+        System.out.println(String.valueOf('x'));
+        System.out.println(String.valueOf(true));
+        System.out.println(String.valueOf(1.5));
+        System.out.println(String.valueOf(2.25f));
+        System.out.println(String.valueOf(new char[] {'c', 'h', 'a', 'r', 's'}));
+        System.out.println(String.valueOf(new Point(3, 4)));
+        Object nothing = null;
+        System.out.println(String.valueOf(nothing));
+
+        StringBuilder sb = new StringBuilder();
+        for (int i = 0; i < 40; i++) {
+            sb.append(i % 10);
+        }
+        System.out.println(sb.toString());
+        System.out.println(sb.length());
+
+        StringBuilder b = new StringBuilder("world");
+        b.insert(0, "hello ").append('!').append(true).append(7L).append(nothing);
+        b.insert(5, ',');
+        b.insert(b.length(), 99);
+        System.out.println(b.toString());
+        System.out.println(String.valueOf(b.charAt(4)));
+        System.out.println(new StringBuilder("stressed").reverse().toString());
+        System.out.println(new StringBuilder().append(new Point(1, 2)).append(b.length()).toString());
+
+        try {
+            b.insert(100, "x");
+        } catch (StringIndexOutOfBoundsException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            b.charAt(100);
+        } catch (StringIndexOutOfBoundsException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            new StringBuilder().append(new Broken());
+        } catch (IllegalStateException e) {
+            System.out.println(e.getMessage());
+        }
+
+        // Supplementary characters are two UTF-16 code units
+        String emoji = "a\ud83d\ude00b";
+        System.out.println(emoji.length());
+        System.out.println(emoji.indexOf(0x1F600));
+        System.out.println(emoji.indexOf('b'));
+        System.out.println((int) emoji.charAt(1));
+        System.out.println((int) emoji.charAt(2));
+        String reversed = new StringBuilder(emoji).reverse().toString();
+        System.out.println(reversed.length());
+        System.out.println(reversed.indexOf(0x1F600));
+        System.out.println((int) reversed.charAt(1));
+        printParts("surrogates", "x\ud83d\ude00y\ud83d\ude00z".split("\ud83d\ude00"));
+        System.out.println(emoji.toUpperCase().indexOf(0x1F600));
+    }
+}
@@ -0,0 +1,74 @@
+public class Main {
+    static class Account {
+        String owner;
+        long cents;
+
+        Account(String owner, long cents) {
+            this.owner = owner;
+            this.cents = cents;
+        }
+
+        public String toString() {
+            return owner + ": " + cents / 100 + "." + cents % 100 / 10 + cents % 10;
+        }
+    }
+
+    static class Faulty {
+        public String toString() {
+            throw new UnsupportedOperationException("toString of " + "Faulty");
+        }
+    }
+
+    public static void main(String[] args) {
+        int i = 42;
+        long l = -9000000000L;
+        char c = 'Z';
+        boolean b = true;
+        byte by = -8;
+        short sh = 1234;
+        double d = 0.5;
+        float f = 2.25f;
+        String s = "text";
+        String none = null;
+        Object o = new Account("alice", 12345);
+        int[] numbers = {1, 2, 3};
+
+        System.out.println("i = " + i);
+        System.out.println("l = " + l + ", c = " + c + ", b = " + b);
+        System.out.println(by + sh + " is numeric but " + by + sh + " is not");
+        System.out.println("d = " + d + ", f = " + f);
+        System.out.println(s + none + s);
+        System.out.println("account " + o);
+        System.out.println("" + numbers.length + numbers[2]);
+        System.out.println(c + "" + c);
+        System.out.println((char) (c + 1) + "!");
+        System.out.println("tags \u0001 and \u0002 are kept: " + i);
+        System.out.println('[' + s + ']');
+
+        String joined = "";
+        for (int n = 0; n < 5; n++) {
+            joined += n + (n < 4 ? "," : "");
+        }
+        System.out.println(joined);
+
+        String a1 = "con" + "stant";
+        String a2 = "constant";
+        System.out.println("interned: " + (a1 == a2));
+        String runtime = s + "";
+        System.out.println("same: " + (runtime == s));
+        System.out.println("equal: " + runtime.equals(s));
+
+        try {
+            System.out.println("value: " + new Faulty());
+        } catch (UnsupportedOperationException e) {
+            System.out.println("caught " + e.getMessage());
+        }
+
+        StringBuilder many = new StringBuilder();
+        for (int n = 0; n < 3; n++) {
+            many.append(n + ":" + (n * n) + ";");
+        }
+        System.out.println(many.toString());
+        System.out.println(Other.describe("x", 1, 2L));
+    }
+}
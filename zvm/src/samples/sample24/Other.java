@@ -0,0 +1,6 @@
+public class Other {
+    // Compiled with -XDstringConcat=indy, which links makeConcat instead of makeConcatWithConstants
+    static String describe(String name, int a, long b) {
+        return name + a + b;
+    }
+}
@@ -0,0 +1,185 @@
+import java.io.Serializable;
+import java.util.Comparator;
+import java.util.function.BiFunction;
+import java.util.function.Function;
+import java.util.function.IntBinaryOperator;
+import java.util.function.IntSupplier;
+import java.util.function.IntUnaryOperator;
+import java.util.function.Predicate;
+import java.util.function.Supplier;
+
+public class Main {
+    interface Greeter {
+        String greet(String name);
+    }
+
+    interface Slicer {
+        String slice(String text, int begin);
+    }
+
+    interface SerializableTask extends Runnable, Serializable {
+    }
+
+    interface LongOperation {
+        long apply(int value);
+    }
+
+    static class Counter {
+        int count;
+
+        Counter() {
+            count = 100;
+        }
+
+        Counter(int start) {
+            count = start;
+        }
+
+        int next() {
+            return ++count;
+        }
+
+        public String toString() {
+            return "Counter(" + count + ")";
+        }
+    }
+
+    int base;
+
+    Main(int base) {
+        this.base = base;
+    }
+
+    static int twice(int value) {
+        return value * 2;
+    }
+
+    int plusBase(int value) {
+        return value + base;
+    }
+
+    static Integer nothing() {
+        return null;
+    }
+
+    IntUnaryOperator adder() {
+        return value -> value + base;
+    }
+
+    public static void main(String[] args) {
+        // Non capturing lambdas
+        IntBinaryOperator add = (a, b) -> a + b;
+        System.out.println(add.applyAsInt(3, 4));
+
+        Runnable hello = () -> System.out.println("hello from a runnable");
+        hello.run();
+
+        // Capturing local variables
+        int offset = 10;
+        String prefix = "Dear ";
+        Greeter greeter = name -> prefix + name + " #" + offset;
+        System.out.println(greeter.greet("Ada"));
+
+        // Capturing this
+        Main main = new Main(5);
+        System.out.println(main.adder().applyAsInt(1));
+
+        // Static, bound, unbound and constructor method references
+        IntUnaryOperator doubler = Main::twice;
+        System.out.println(doubler.applyAsInt(21));
+
+        IntUnaryOperator bound = main::plusBase;
+        System.out.println(bound.applyAsInt(37));
+
+        String text = "method reference";
+        IntSupplier length = text::length;
+        System.out.println(length.getAsInt());
+
+        Function<String, String> upper = String::toUpperCase;
+        Object shout = upper.apply("quiet");
+        System.out.println("upper: " + shout);
+
+        Slicer slicer = String::substring;
+        System.out.println(slicer.slice("unbound receiver", 8));
+
+        Supplier<Counter> fresh = Counter::new;
+        Object counter = fresh.get();
+        System.out.println("supplied: " + counter);
+
+        Counter counted = new Counter(7);
+        IntSupplier next = counted::next;
+        next.getAsInt();
+        System.out.println(next.getAsInt() + " " + counted.count);
+
+        Comparator<String> order = String::compareTo;
+        System.out.println("apple before banana: " + (order.compare("apple", "banana") < 0));
+        System.out.println("cherry before banana: " + (order.compare("cherry", "banana") < 0));
+
+        Predicate<String> empty = String::isEmpty;
+        System.out.println("empty: " + empty.test("") + " " + empty.test("full"));
+
+        // Widening of the result of the implementation method
+        LongOperation square = value -> value * value;
+        System.out.println(square.apply(100000));
+
+        // Serializable functional interfaces are linked through altMetafactory
+        SerializableTask task = () -> System.out.println("serializable task");
+        task.run();
+        Runnable runnable = task;
+        runnable.run();
+
+        // Every evaluation captures its own values
+        IntSupplier[] suppliers = new IntSupplier[3];
+        for (int i = 0; i < suppliers.length; i++) {
+            int captured = i * i;
+            suppliers[i] = () -> captured;
+        }
+        for (IntSupplier supplier : suppliers) {
+            System.out.println(supplier.getAsInt());
+        }
+
+        // A null bound receiver fails when the method reference is evaluated
+        String missing = null;
+        try {
+            IntSupplier broken = missing::length;
+            System.out.println(broken.getAsInt());
+        } catch (NullPointerException e) {
+            System.out.println("caught NullPointerException");
+        }
+
+        // Exceptions thrown by the implementation propagate through the lambda
+        IntUnaryOperator divide = value -> 100 / value;
+        try {
+            divide.applyAsInt(0);
+        } catch (ArithmeticException e) {
+            System.out.println("caught " + e.getMessage());
+        }
+
+        // Primitives are boxed and unboxed to the types of the instantiated method type
+        Function<String, Integer> stringLength = String::length;
+        System.out.println(stringLength.apply("boxed") + 1);
+
+        Function<Integer, Integer> boxedTwice = Main::twice;
+        System.out.println(boxedTwice.apply(21));
+
+        BiFunction<Integer, Integer, Integer> sum = Integer::sum;
+        System.out.println(sum.apply(2, 3));
+
+        Function<Integer, Counter> startAt = Counter::new;
+        System.out.println(startAt.apply(41).next());
+
+        // Unboxing null throws
+        try {
+            boxedTwice.apply(null);
+        } catch (NullPointerException e) {
+            System.out.println("caught NullPointerException unboxing an argument");
+        }
+
+        IntSupplier none = Main::nothing;
+        try {
+            none.getAsInt();
+        } catch (NullPointerException e) {
+            System.out.println("caught NullPointerException unboxing a result");
+        }
+    }
+}
@@ -0,0 +1,190 @@
+import java.io.Serializable;
+
+public class Main {
+    interface Shape {
+        int sides();
+    }
+
+    interface Named {
+        String name();
+    }
+
+    interface Marker {
+    }
+
+    static class Polygon implements Shape {
+        public int sides() {
+            return 0;
+        }
+    }
+
+    static class Square extends Polygon implements Named {
+        public int sides() {
+            return 4;
+        }
+
+        public String name() {
+            return "square";
+        }
+    }
+
+    static class Triangle extends Polygon {
+        public int sides() {
+            return 3;
+        }
+    }
+
+    // A tiny generic list, its elements are erased to Object and cast back on access
+    static class Bag<T> {
+        private Object[] items = new Object[2];
+        private int size;
+
+        void add(T item) {
+            if (size == items.length) {
+                Object[] grown = new Object[size * 2];
+                for (int i = 0; i < size; i++) {
+                    grown[i] = items[i];
+                }
+                items = grown;
+            }
+            items[size++] = item;
+        }
+
+        @SuppressWarnings("unchecked")
+        T get(int index) {
+            return (T) items[index];
+        }
+
+        int size() {
+            return size;
+        }
+    }
+
+    static void cast(String label, Runnable cast) {
+        try {
+            cast.run();
+            System.out.println(label + ": ok");
+        } catch (ClassCastException e) {
+            System.out.println(label + ": " + e.getMessage());
+        }
+    }
+
+    public static void main(String[] args) {
+        Bag<String> words = new Bag<>();
+        words.add("erased");
+        words.add("generic");
+        words.add("access");
+        String sentence = "";
+        for (int i = 0; i < words.size(); i++) {
+            String word = words.get(i);
+            sentence = sentence + word.toUpperCase() + " ";
+        }
+        System.out.println(sentence + words.get(0).length());
+
+        Bag<Shape> shapes = new Bag<>();
+        shapes.add(new Square());
+        shapes.add(new Triangle());
+        shapes.add(new Square());
+        int sides = 0;
+        int named = 0;
+        for (int i = 0; i < shapes.size(); i++) {
+            Shape shape = shapes.get(i);
+            sides += shape.sides();
+            if (shape instanceof Named) {
+                named++;
+                System.out.println("named " + ((Named) shape).name());
+            }
+        }
+        System.out.println("sides " + sides + ", named " + named);
+
+        Object square = new Square();
+        Object triangle = new Triangle();
+        Object text = "text";
+        Object nothing = null;
+        Object ints = new int[] {1, 2};
+        Object squares = new Square[1];
+        Object strings = new String[] {"a"};
+        Object matrix = new int[2][2];
+
+        System.out.println("square instanceof Polygon " + (square instanceof Polygon));
+        System.out.println("square instanceof Shape " + (square instanceof Shape));
+        System.out.println("triangle instanceof Named " + (triangle instanceof Named));
+        System.out.println("text instanceof Comparable " + (text instanceof Comparable));
+        System.out.println("text instanceof CharSequence " + (text instanceof CharSequence));
+        System.out.println("null instanceof Object " + (nothing instanceof Object));
+        System.out.println("int[] instanceof Object " + (ints instanceof Object));
+        System.out.println("int[] instanceof Cloneable " + (ints instanceof Cloneable));
+        System.out.println("int[] instanceof Serializable " + (ints instanceof Serializable));
+        System.out.println("int[] instanceof long[] " + (ints instanceof long[]));
+        System.out.println("Square[] instanceof Polygon[] " + (squares instanceof Polygon[]));
+        System.out.println("Square[] instanceof Shape[] " + (squares instanceof Shape[]));
+        System.out.println("Square[] instanceof Triangle[] " + (squares instanceof Triangle[]));
+        System.out.println("String[] instanceof Object[] " + (strings instanceof Object[]));
+        System.out.println("int[][] instanceof Object[] " + (matrix instanceof Object[]));
+        System.out.println("int[][] instanceof Cloneable[] " + (matrix instanceof Cloneable[]));
+        System.out.println("int[][] instanceof long[][] " + (matrix instanceof long[][]));
+
+        // A null reference can be cast to any type
+        String none = (String) nothing;
+        System.out.println("null cast " + none);
+
+        cast("Square to Shape", () -> {
+            Shape shape = (Shape) square;
+        });
+        cast("Triangle to Square", () -> {
+            Square wrong = (Square) triangle;
+        });
+        cast("String to Shape", () -> {
+            Shape wrong = (Shape) text;
+        });
+        cast("Square to String", () -> {
+            String wrong = (String) square;
+        });
+        cast("int[] to long[]", () -> {
+            long[] wrong = (long[]) ints;
+        });
+        cast("Square[] to Triangle[]", () -> {
+            Triangle[] wrong = (Triangle[]) squares;
+        });
+        cast("String[] to Square[]", () -> {
+            Square[] wrong = (Square[]) strings;
+        });
+        cast("int[] to Runnable", () -> {
+            Runnable wrong = (Runnable) ints;
+        });
+        cast("int[][] to Object[]", () -> {
+            Object[] rows = (Object[]) matrix;
+        });
+
+        // The same instruction sees different types, a cached success must not hide a failure
+        Object[] mixed = {new Square(), new Square(), new Triangle(), new Square()};
+        int squaresSeen = 0;
+        for (Object shape : mixed) {
+            try {
+                Square checked = (Square) shape;
+                squaresSeen++;
+            } catch (ClassCastException e) {
+                System.out.println("not a square: " + e.getMessage());
+            }
+        }
+        System.out.println("squares seen " + squaresSeen);
+
+        int polygons = 0;
+        for (int i = 0; i < 1000; i++) {
+            Object shape = i % 3 == 0 ? triangle : square;
+            if (shape instanceof Polygon) {
+                polygons++;
+            }
+        }
+        System.out.println("polygons " + polygons);
+
+        // Lambdas implement their functional interface and the marker interfaces
+        Runnable marked = (Runnable & Marker) () -> System.out.println("marked lambda");
+        marked.run();
+        System.out.println("marked instanceof Marker " + (marked instanceof Marker));
+        Runnable serializable = (Runnable & Serializable) () -> System.out.println("serializable lambda");
+        serializable.run();
+        System.out.println("serializable instanceof Serializable " + (serializable instanceof Serializable));
+        System.out.println("marked instanceof Serializable " + (marked instanceof Serializable));
+    }
+}
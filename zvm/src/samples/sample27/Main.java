@@ -0,0 +1,149 @@
+public class Main {
+    static int[] results = new int[4];
+    static StringBuilder log = new StringBuilder();
+
+    static class Summer extends Thread {
+        private final int index;
+        private final int limit;
+
+        Summer(int index, int limit) {
+            super("summer-" + index);
+            this.index = index;
+            this.limit = limit;
+        }
+
+        public void run() {
+            int sum = 0;
+            for (int i = 1; i <= limit; i++) {
+                sum += i;
+                if (i % 1000 == 0) {
+                    Thread.yield();
+                }
+            }
+            results[index] = sum;
+        }
+    }
+
+    static class Failing extends Thread {
+        public void run() {
+            throw new IllegalStateException("worker failed");
+        }
+    }
+
+    static class Sleeper implements Runnable {
+        private final String name;
+        private final long millis;
+
+        Sleeper(String name, long millis) {
+            this.name = name;
+            this.millis = millis;
+        }
+
+        public void run() {
+            try {
+                Thread.sleep(millis);
+            } catch (InterruptedException e) {
+                return;
+            }
+            record(name);
+        }
+    }
+
+    static void record(String text) {
+        log.append(text).append(' ');
+    }
+
+    public static void main(String[] args) throws InterruptedException {
+        Thread main = Thread.currentThread();
+        System.out.println("current " + main.getName());
+        System.out.println("main alive " + main.isAlive());
+
+        // Workers summing in parallel, joined before reading their results
+        Summer[] summers = new Summer[4];
+        for (int i = 0; i < summers.length; i++) {
+            summers[i] = new Summer(i, 5000 * (i + 1));
+            summers[i].start();
+        }
+        for (Summer summer : summers) {
+            summer.join();
+            System.out.println(summer.getName() + " alive " + summer.isAlive());
+        }
+        for (int i = 0; i < results.length; i++) {
+            System.out.println("sum " + i + " = " + results[i]);
+        }
+
+        // Sleeping threads wake up in the order of their deadlines
+        Thread slow = new Thread(new Sleeper("slow", 300));
+        Thread fast = new Thread(new Sleeper("fast", 20), "fast-thread");
+        Thread medium = new Thread(new Sleeper("medium", 150));
+        slow.start();
+        fast.start();
+        medium.start();
+        System.out.println("names " + slow.getName() + " " + fast.getName() + " " + medium.getName());
+        slow.join();
+        fast.join();
+        medium.join();
+        System.out.println("woke " + log);
+
+        // Lambdas are runnables too, currentThread() is the thread running them
+        String[] seen = new String[1];
+        Thread lambda = new Thread(() -> seen[0] = Thread.currentThread().getName());
+        lambda.setName("renamed");
+        lambda.start();
+        lambda.join();
+        System.out.println("lambda ran in " + seen[0]);
+
+        // join with a timeout gives up on a thread that sleeps longer
+        Thread sleepy = new Thread(new Sleeper("sleepy", 500));
+        sleepy.start();
+        sleepy.join(50);
+        System.out.println("sleepy alive after timeout " + sleepy.isAlive());
+        sleepy.join();
+        System.out.println("sleepy alive after join " + sleepy.isAlive());
+
+        // A thread is started only once
+        try {
+            lambda.start();
+        } catch (IllegalThreadStateException e) {
+            System.out.println("restart " + e);
+        }
+
+        // Daemons are set before starting
+        Thread daemon = new Thread(new Sleeper("daemon", 10));
+        daemon.setDaemon(true);
+        System.out.println("daemon " + daemon.isDaemon() + " priority " + daemon.getPriority());
+        Thread busy = new Thread(new Sleeper("busy", 10));
+        busy.start();
+        try {
+            busy.setDaemon(true);
+        } catch (IllegalThreadStateException e) {
+            System.out.println("late daemon " + e);
+        }
+        busy.join();
+
+        try {
+            Thread.sleep(-1);
+        } catch (IllegalArgumentException e) {
+            System.out.println("negative sleep " + e.getMessage());
+        }
+
+        // An exception escaping run() only ends its own thread
+        Failing failing = new Failing();
+        failing.setName("failing-worker");
+        failing.start();
+        failing.join();
+        System.out.println("main still running");
+
+        // main returns first, the VM waits for the non daemon thread
+        Thread last = new Thread(() -> {
+            try {
+                Thread.sleep(100);
+            } catch (InterruptedException e) {
+                return;
+            }
+            System.out.println("last thread done");
+        });
+        last.start();
+        System.out.println("main done");
+    }
+}
@@ -0,0 +1,197 @@
+public class Main {
+    static int shared = 0;
+    static int staticShared = 0;
+
+    static class Counter {
+        private int count = 0;
+
+        synchronized void increment() {
+            count++;
+        }
+
+        synchronized int get() {
+            return count;
+        }
+    }
+
+    static class Incrementer extends Thread {
+        private final Counter counter;
+        private final Object lock;
+
+        Incrementer(String name, Counter counter, Object lock) {
+            super(name);
+            this.counter = counter;
+            this.lock = lock;
+        }
+
+        public void run() {
+            for (int i = 0; i < 2000; i++) {
+                counter.increment();
+                synchronized (lock) {
+                    int read = shared;
+                    Thread.yield();
+                    shared = read + 1;
+                }
+                incrementStatic();
+            }
+        }
+    }
+
+    static synchronized void incrementStatic() {
+        int read = staticShared;
+        Thread.yield();
+        staticShared = read + 1;
+    }
+
+    static class Buffer {
+        private final int[] items = new int[3];
+        private int size = 0;
+
+        synchronized void put(int item) throws InterruptedException {
+            while (size == items.length) {
+                wait();
+            }
+            items[size++] = item;
+            notifyAll();
+        }
+
+        synchronized int take() throws InterruptedException {
+            while (size == 0) {
+                wait();
+            }
+            int item = items[0];
+            for (int i = 1; i < size; i++) {
+                items[i - 1] = items[i];
+            }
+            size--;
+            notifyAll();
+            return item;
+        }
+    }
+
+    static class Producer extends Thread {
+        private final Buffer buffer;
+
+        Producer(Buffer buffer) {
+            super("producer");
+            this.buffer = buffer;
+        }
+
+        public void run() {
+            try {
+                for (int i = 1; i <= 20; i++) {
+                    buffer.put(i);
+                }
+                buffer.put(-1);
+            } catch (InterruptedException e) {
+                System.out.println("producer interrupted");
+            }
+        }
+    }
+
+    static int depth(Object lock, int n) {
+        synchronized (lock) {
+            if (n == 0) {
+                return 0;
+            }
+            return 1 + depth(lock, n - 1);
+        }
+    }
+
+    static synchronized void throwInside() {
+        throw new IllegalStateException("thrown while holding the class lock");
+    }
+
+    public static void main(String[] args) throws Exception {
+        // Synchronized methods and blocks keep the read-modify-write updates atomic
+        Counter counter = new Counter();
+        Object lock = new Object();
+        Thread[] threads = new Thread[3];
+        for (int i = 0; i < threads.length; i++) {
+            threads[i] = new Incrementer("incrementer-" + i, counter, lock);
+            threads[i].start();
+        }
+        for (Thread thread : threads) {
+            thread.join();
+        }
+        System.out.println("counter: " + counter.get());
+        System.out.println("shared: " + shared);
+        System.out.println("static shared: " + staticShared);
+
+        // Producer and consumer coordinate with wait and notifyAll
+        Buffer buffer = new Buffer();
+        new Producer(buffer).start();
+        int sum = 0;
+        int taken = 0;
+        while (true) {
+            int item = buffer.take();
+            if (item < 0) {
+                break;
+            }
+            sum += item;
+            taken++;
+        }
+        System.out.println("consumed " + taken + " items, sum " + sum);
+
+        // Monitors are reentrant
+        System.out.println("reentrant depth: " + depth(lock, 5));
+
+        // A timed wait returns once the timeout elapses without any notification
+        synchronized (lock) {
+            lock.wait(20);
+            System.out.println("timed wait over");
+        }
+
+        // The monitor is released when an exception leaves a synchronized block or method
+        try {
+            synchronized (lock) {
+                throw new RuntimeException("thrown inside a synchronized block");
+            }
+        } catch (RuntimeException e) {
+            System.out.println("caught: " + e.getMessage());
+        }
+        try {
+            throwInside();
+        } catch (IllegalStateException e) {
+            System.out.println("caught: " + e.getMessage());
+        }
+        Thread other = new Thread(() -> {
+            synchronized (lock) {
+                System.out.println(Thread.currentThread().getName() + " entered the lock");
+            }
+            incrementStatic();
+            System.out.println(Thread.currentThread().getName() + " entered the class lock");
+        }, "other");
+        other.start();
+        other.join();
+
+        // Only the owner of a monitor may wait on or notify its object
+        try {
+            lock.notify();
+        } catch (IllegalMonitorStateException e) {
+            System.out.println("notify: " + e);
+        }
+        try {
+            lock.wait();
+        } catch (IllegalMonitorStateException e) {
+            System.out.println("wait: " + e);
+        }
+        try {
+            synchronized (lock) {
+                lock.wait(-1);
+            }
+        } catch (IllegalArgumentException e) {
+            System.out.println("wait(-1): " + e);
+        }
+
+        // Entering the monitor of null throws a NullPointerException
+        Object missing = null;
+        try {
+            synchronized (missing) {
+                System.out.println("unreachable");
+            }
+        } catch (NullPointerException e) {
+            System.out.println("caught NullPointerException");
+        }
+    }
+}
@@ -0,0 +1,113 @@
+public class Main {
+    static class Point {
+        private final int x;
+        private final int y;
+
+        Point(int x, int y) {
+            this.x = x;
+            this.y = y;
+        }
+
+        public String toString() {
+            return "(" + x + ", " + y + ")";
+        }
+    }
+
+    static void printOverloads() {
+        char[] letters = {'z', 'v', 'm'};
+
+        System.out.println('A');
+        System.out.println((char) 66);
+        System.out.println(true);
+        System.out.println(42);
+        System.out.println(-7L);
+        System.out.println(2.5f);
+        System.out.println(0.125);
+        System.out.println(letters);
+        System.out.println("text");
+        System.out.println(new Point(1, 2));
+        System.out.println((Object) null);
+        System.out.println((String) null);
+        System.out.println((Object) "object");
+
+        System.out.print('c');
+        System.out.print(false);
+        System.out.print(' ');
+        System.out.print(letters);
+        System.out.print(1);
+        System.out.print(2L);
+        System.out.print(3.0f);
+        System.out.print(4.0);
+        System.out.print(new Point(3, 4));
+        System.out.println();
+
+        try {
+            System.out.println((char[]) null);
+        } catch (NullPointerException e) {
+            System.out.println("println(char[]) of null throws NullPointerException");
+        }
+    }
+
+    static void printf() {
+        System.out.printf("%s|%5d|%-5s|%05.1f|%x|%c|%b|%,d%n", "s", 42, "ab", 3.14159, 255, 'z', false, 1234567);
+        System.out.printf("%08.3f|%+d|%(d|% d|%e|%g|%.0f|%.2f|%.2f%n", -3.5, 5, -7, 8, 12345.678, 0.0001234, 2.5, 0.125, 1.005);
+        System.out.printf("%2$s %1$s %<s|%S|%.3s|%10s|%-10s|%n", "a", "b", "upper", "truncated", "right", "left");
+        System.out.printf("%x %X %o %#x %08X %d %,d%n", -1, 255L, 8, 255, 48879, Long.MIN_VALUE, -1234567890123L);
+        System.out.printf("%h %b %c %C %s %d%%%n", "hi", null, 0x1F600, 'q', new Point(5, 6), 100);
+        System.out.format("%e %.3e %g %f %.1f%n", 0.0, 6.02214076e23, 1e-10, 1e20, 0.05);
+
+        String formatted = String.format("%-6s|%6.2f|%06d", "name", Math.PI, 42);
+        System.out.println(formatted);
+
+        String[] bad = {"%q", "%d", "%-s", "%#d", "%s %s", "%.2d", "%+ d"};
+        for (String format : bad) {
+            try {
+                System.out.println(String.format(format, "x"));
+            } catch (IllegalArgumentException e) {
+                System.out.println(e);
+            }
+        }
+
+        // The text before the failing specifier is already written
+        try {
+            System.out.printf("partial %d %d%n", 1, "two");
+        } catch (IllegalArgumentException e) {
+            System.out.println();
+            System.out.println(e);
+        }
+    }
+
+    static void streams() {
+        System.out.println(System.out == System.err);
+
+        // write(int) is buffered until a line feed or a flush, print and println are not
+        System.out.write('1');
+        System.err.println("A");
+        System.out.write('2');
+        System.out.write('\n');
+        System.err.println("B");
+        System.out.write('3');
+        System.out.flush();
+        System.err.println("C");
+        System.out.write('4');
+        System.out.print('5');
+        System.err.println("D");
+        System.out.write(new byte[] {'6', '7', '8'}, 1, 2);
+        System.out.write(new byte[] {'9', '\n'}, 0, 2);
+        System.err.printf("%s%n", "E");
+
+        try {
+            System.out.write(new byte[3], 2, 5);
+        } catch (IndexOutOfBoundsException e) {
+            System.err.println(e);
+        }
+
+        new IllegalStateException("printed to System.err").printStackTrace();
+    }
+
+    public static void main(String[] args) {
+        printOverloads();
+        printf();
+        streams();
+    }
+}
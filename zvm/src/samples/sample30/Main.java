@@ -0,0 +1,26 @@
+public class Main {
+    public static void main(String[] args) {
+        double[] doubles = {
+            0.0, -0.0, 1.0, 100.0, 0.1, 0.001, 0.0001, 1234567.0, 1.0E7, 1.0E-5,
+            123123123.12312312, 1.0 / 3.0, 2.0 / 3.0, 2.0E-3, Math.PI, Math.E,
+            Double.MAX_VALUE, Double.MIN_VALUE, Double.MIN_NORMAL, 8.339840063750242E14,
+            Double.NaN, Double.POSITIVE_INFINITY, Double.NEGATIVE_INFINITY
+        };
+        for (double d : doubles) {
+            System.out.println(d);
+        }
+
+        float[] floats = {
+            0.0f, 1.0f, 0.1f, 1.0f / 3.0f, 16777216.0f, 9.223372E18f, 8.999999977277184E-6f,
+            2247983.25f, Float.MAX_VALUE, Float.MIN_VALUE, Float.NaN
+        };
+        for (float f : floats) {
+            System.out.println(f);
+        }
+
+        float third = 1.0f / 3.0f;
+        double widened = third;
+        System.out.println("float " + third + " widened to double " + widened);
+        System.out.println(String.valueOf(-1.5e-300) + " " + String.valueOf(6.02214076e23f));
+    }
+}
@@ -0,0 +1,51 @@
+public class Main {
+    static void parse(String text) {
+        try {
+            int value = Integer.parseInt(text);
+            System.out.println(text + " -> int " + value + ", hex " + Integer.toHexString(value)
+                + ", binary " + Integer.toBinaryString(value));
+            return;
+        } catch (NumberFormatException e) {
+            System.out.println(e.getMessage());
+        }
+
+        try {
+            long value = Long.parseLong(text);
+            System.out.println(text + " -> long " + value + ", base 36 " + Long.toString(value, 36));
+            return;
+        } catch (NumberFormatException e) {
+            System.out.println(e.getMessage());
+        }
+
+        try {
+            System.out.println(text + " -> double " + Double.parseDouble(text));
+        } catch (NumberFormatException e) {
+            System.out.println(e);
+        }
+    }
+
+    public static void main(String[] args) {
+        String[] inputs = args.length > 0
+            ? args
+            : new String[] {"42", "-7", "3000000000", "0x1.8p1", "1e-3", " 2.5 ", "12abc", ""};
+        for (String input : inputs) {
+            parse(input);
+        }
+
+        System.out.println(Integer.parseInt("-ff", 16) + " " + Integer.parseInt("1010", 2)
+            + " " + Long.parseLong("zzzzzz", 36) + " " + Integer.valueOf("123").equals(123));
+        System.out.println(Long.toHexString(-1L) + " " + Long.toBinaryString(5L)
+            + " " + Integer.toOctalString(8) + " " + Integer.toString(-255, 16));
+
+        try {
+            Integer.parseInt("2147483648");
+        } catch (NumberFormatException e) {
+            System.out.println(e);
+        }
+        try {
+            Integer.parseInt("zz", 16);
+        } catch (NumberFormatException e) {
+            System.out.println(e);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+// Compiled with -source 1.4 -target 1.4, the class file is version 49.0 and its
+// finally blocks are subroutines called with jsr and left with ret
+public class Main {
+    static int cleanups;
+
+    static int divide(int a, int b) {
+        try {
+            return a / b;
+        } finally {
+            cleanups++;
+        }
+    }
+
+    // The subroutine throws on every iteration, the handler catching it leaves the
+    // subroutine so the next iteration can call it again
+    static void retry() {
+        for (int i = 0; i < 2; i++) {
+            try {
+                try {
+                    cleanups++;
+                } finally {
+                    throw new RuntimeException();
+                }
+            } catch (RuntimeException e) {
+                System.out.println("caught");
+            }
+        }
+    }
+
+    public static void main(String[] args) {
+        System.out.println(divide(10, 2));
+        try {
+            System.out.println(divide(1, 0));
+        } catch (ArithmeticException e) {
+            System.out.println(e.getMessage());
+        }
+        System.out.println(cleanups);
+        retry();
+        System.out.println(cleanups);
+    }
+}
@@ -0,0 +1,71 @@
+import java.util.function.IntUnaryOperator;
+
+public class Main {
+    static final int DEPTH = 5000;
+
+    static IntUnaryOperator countDown;
+
+    static int depth(int n) {
+        return n == 0 ? 0 : 1 + depth(n - 1);
+    }
+
+    int virtualDepth(int n) {
+        return n == 0 ? 0 : 1 + virtualDepth(n - 1);
+    }
+
+    static synchronized int synchronizedDepth(int n) {
+        return n == 0 ? 0 : 1 + synchronizedDepth(n - 1);
+    }
+
+    static int throwAtBottom(int n) {
+        if (n == 0) {
+            throw new IllegalStateException("bottom reached");
+        }
+        return throwAtBottom(n - 1) + 1;
+    }
+
+    // Each level catches what the levels below threw and throws it again
+    static int rethrowAtEachLevel(int n) {
+        if (n == 0) {
+            throw new RuntimeException("0");
+        }
+        try {
+            return rethrowAtEachLevel(n - 1);
+        } catch (RuntimeException e) {
+            throw new RuntimeException(String.valueOf(Integer.parseInt(e.getMessage()) + 1));
+        }
+    }
+
+    static long ackermann(long m, long n) {
+        if (m == 0) {
+            return n + 1;
+        }
+        if (n == 0) {
+            return ackermann(m - 1, 1);
+        }
+        return ackermann(m - 1, ackermann(m, n - 1));
+    }
+
+    public static void main(String[] args) {
+        System.out.println("static: " + depth(DEPTH));
+        System.out.println("virtual: " + new Main().virtualDepth(DEPTH));
+        System.out.println("synchronized: " + synchronizedDepth(DEPTH));
+
+        countDown = n -> n == 0 ? 0 : 1 + countDown.applyAsInt(n - 1);
+        System.out.println("lambda: " + countDown.applyAsInt(2000));
+
+        try {
+            throwAtBottom(DEPTH);
+        } catch (IllegalStateException e) {
+            System.out.println("caught: " + e.getMessage());
+        }
+
+        try {
+            rethrowAtEachLevel(300);
+        } catch (RuntimeException e) {
+            System.out.println("rethrown: " + e.getMessage());
+        }
+
+        System.out.println("ackermann(2, 200): " + ackermann(2, 200));
+    }
+}
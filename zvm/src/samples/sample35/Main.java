@@ -0,0 +1,75 @@
+public class Main {
+    static int depth;
+
+    static final Main LOCK = new Main();
+
+    static void recurse() {
+        depth++;
+        recurse();
+    }
+
+    synchronized void synchronizedRecurse() {
+        synchronizedRecurse();
+    }
+
+    static int sum(int n) {
+        return n == 0 ? 0 : n + sum(n - 1);
+    }
+
+    public static void main(String[] args) throws InterruptedException {
+        // A runaway recursion ends with an error the program can catch
+        try {
+            recurse();
+        } catch (StackOverflowError e) {
+            System.out.println("Caught: " + e + ", deeper than 1000: " + (depth > 1000));
+        }
+
+        // The stack is usable again once the error unwound it
+        System.out.println("sum(1000) = " + sum(1000));
+
+        // It is a VirtualMachineError, so an Error but not an Exception
+        try {
+            recurse();
+        } catch (Exception e) {
+            System.out.println("Not reached");
+        } catch (VirtualMachineError e) {
+            System.out.println("Caught as VirtualMachineError");
+        }
+
+        // The monitors of the synchronized frames are released while unwinding
+        try {
+            LOCK.synchronizedRecurse();
+        } catch (StackOverflowError e) {
+            System.out.println("Caught in synchronized recursion");
+        }
+        Thread other = new Thread(() -> {
+            synchronized (LOCK) {
+                System.out.println("Monitor released");
+            }
+        });
+        other.start();
+        other.join();
+
+        // Every thread has a stack of its own
+        Thread thread = new Thread(() -> {
+            try {
+                recurse();
+            } catch (StackOverflowError e) {
+                System.out.println("Caught in " + Thread.currentThread().getName());
+            }
+        });
+        thread.start();
+        thread.join();
+
+        // finally blocks run while the error unwinds the frames
+        try {
+            try {
+                recurse();
+            } finally {
+                System.out.println("Finally ran");
+            }
+        } catch (StackOverflowError e) {
+            System.out.println("Caught after finally");
+        }
+    }
+}
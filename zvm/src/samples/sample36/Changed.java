@@ -0,0 +1,24 @@
+// The library classes shipped with this sample, compiled after Main.java
+
+interface B {
+    default String hello() {
+        return "B";
+    }
+}
+
+abstract class Base {
+    abstract String tag();
+}
+
+// Compiled against the previous I and a Base without methods
+class Impl extends Base implements A, B, I {
+    public String name() {
+        return "impl";
+    }
+}
+
+class Holder {
+    static Object named() {
+        return new Unnamed();
+    }
+}
@@ -0,0 +1,98 @@
+// The classes below are the ones Main was compiled against. Changed.java holds the versions
+// of the library classes compiled on their own afterwards, the ones shipped with this sample,
+// so every invocation of Main links against a method that changed since.
+public class Main {
+    public static void main(String[] args) {
+        Impl impl = new Impl();
+
+        // B gained a default method conflicting with the one of A
+        try {
+            System.out.println(impl.hello());
+        } catch (IncompatibleClassChangeError e) {
+            System.out.println(e);
+        }
+
+        // I gained a method Impl does not implement
+        try {
+            System.out.println(((I) impl).extra());
+        } catch (AbstractMethodError e) {
+            System.out.println("extra is abstract");
+        }
+
+        // Impl no longer overrides the abstract tag method of Base
+        try {
+            System.out.println(impl.tag());
+        } catch (AbstractMethodError e) {
+            System.out.println("tag is abstract");
+        }
+
+        // Base no longer declares id
+        try {
+            System.out.println(impl.id());
+        } catch (NoSuchMethodError e) {
+            System.out.println("id is gone");
+        }
+
+        // Holder now returns an object that does not implement I
+        try {
+            System.out.println(Probe.call(Holder.named()));
+        } catch (IncompatibleClassChangeError e) {
+            System.out.println(e);
+        }
+
+        System.out.println("done");
+    }
+}
+
+interface A {
+    default String hello() {
+        return "A";
+    }
+}
+
+interface B {
+}
+
+interface I {
+    String name();
+
+    String extra();
+}
+
+abstract class Base {
+    abstract String tag();
+
+    String id() {
+        return "id";
+    }
+}
+
+class Impl extends Base implements A, B, I {
+    public String name() {
+        return "impl";
+    }
+
+    public String extra() {
+        return "extra";
+    }
+
+    String tag() {
+        return "tag";
+    }
+}
+
+class Unnamed {
+}
+
+class Holder {
+    static Object named() {
+        return new Impl();
+    }
+}
+
+class Probe {
+    // Probe.class is assembled without the checkcast javac emits, invokeinterface checks the receiver
+    static String call(Object named) {
+        return ((I) named).name();
+    }
+}
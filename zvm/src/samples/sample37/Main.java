@@ -0,0 +1,65 @@
+// Every recursion below overflows the stack of its thread with the default stack size,
+// the program catches the StackOverflowError and carries on
+public class Main {
+    static int depth;
+
+    static int recurse(int n) {
+        depth++;
+        return recurse(n + 1) + 1;
+    }
+
+    // A list whose last node links back to the first one, printing it never ends
+    static class Node {
+        Node next;
+        int visits;
+
+        public String toString() {
+            visits++;
+            return "(" + next + ")";
+        }
+    }
+
+    static class Appender {
+        Appender next;
+
+        public String toString() {
+            return new StringBuilder().append('[').append(next).append(']').toString();
+        }
+    }
+
+    static String overflow(String what, Runnable recursion) {
+        try {
+            recursion.run();
+            return what + ": completed";
+        } catch (StackOverflowError e) {
+            return what + ": " + e;
+        }
+    }
+
+    public static void main(String[] args) throws InterruptedException {
+        System.out.println(overflow("plain recursion", () -> recurse(0)));
+        System.out.println("deep enough: " + (depth > 1000));
+
+        Node first = new Node();
+        Node second = new Node();
+        first.next = second;
+        second.next = first;
+        System.out.println(overflow("toString in a concatenation", () -> first.toString()));
+        System.out.println("visited both: " + (first.visits > 10 && second.visits > 10));
+
+        Appender appender = new Appender();
+        appender.next = appender;
+        System.out.println(overflow("toString in StringBuilder.append", () -> appender.toString()));
+
+        String[] result = new String[1];
+        Thread worker = new Thread(() -> result[0] = overflow("in another thread", () -> first.toString()));
+        worker.start();
+        worker.join();
+        System.out.println(result[0]);
+
+        // The stack is back to its size once the frames are gone
+        depth = 0;
+        System.out.println(overflow("plain recursion again", () -> recurse(0)));
+        System.out.println("deep enough: " + (depth > 1000));
+    }
+}
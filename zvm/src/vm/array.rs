@@ -0,0 +1,136 @@
+use std::fmt;
+
+use crate::vm::value::Value;
+
+/// An array instance created by `newarray`, `anewarray` or `multianewarray`
+pub struct Array {
+    /// Descriptor of the array type, e.g. `[I`, `[Ljava/lang/String;` or `[[D`
+    pub descriptor: String,
+    pub elements: ArrayElements,
+}
+
+/// Backing store of an array, primitive components are stored unboxed at their own width
+/// instead of as a `Value` each
+#[derive(Clone)]
+pub enum ArrayElements {
+    /// `byte[]` and `boolean[]`, both accessed with `baload` and `bastore`
+    Byte(Vec<i8>),
+    Char(Vec<u16>),
+    Short(Vec<i16>),
+    Int(Vec<i32>),
+    Long(Vec<i64>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    /// Arrays of objects, strings and arrays
+    Reference(Vec<Value>),
+}
+
+impl Array {
+    /// Creates an array of the given type with every component set to its default value
+    pub fn new(descriptor: String, length: usize) -> Self {
+        let elements = match descriptor[1..].chars().next() {
+            Some('Z') | Some('B') => ArrayElements::Byte(vec![0; length]),
+            Some('C') => ArrayElements::Char(vec![0; length]),
+            Some('S') => ArrayElements::Short(vec![0; length]),
+            Some('I') => ArrayElements::Int(vec![0; length]),
+            Some('J') => ArrayElements::Long(vec![0; length]),
+            Some('F') => ArrayElements::Float(vec![0.0; length]),
+            Some('D') => ArrayElements::Double(vec![0.0; length]),
+            _ => ArrayElements::Reference(vec![Value::Null; length]),
+        };
+
+        Self {
+            descriptor,
+            elements,
+        }
+    }
+
+    /// Creates an array of references of the given type holding the given elements
+    pub fn from_elements(descriptor: String, elements: Vec<Value>) -> Self {
+        Self {
+            descriptor,
+            elements: ArrayElements::Reference(elements),
+        }
+    }
+
+    /// Creates a `char[]` holding the given UTF-16 code units
+    pub fn from_chars(chars: Vec<u16>) -> Self {
+        Self {
+            descriptor: "[C".to_string(),
+            elements: ArrayElements::Char(chars),
+        }
+    }
+
+    /// Descriptor of the components, e.g. `I` for `[I` or `[I` for `[[I`
+    pub fn component_type(&self) -> &str {
+        &self.descriptor[1..]
+    }
+
+    /// Number of components of the array
+    pub fn length(&self) -> usize {
+        match &self.elements {
+            ArrayElements::Byte(values) => values.len(),
+            ArrayElements::Char(values) => values.len(),
+            ArrayElements::Short(values) => values.len(),
+            ArrayElements::Int(values) => values.len(),
+            ArrayElements::Long(values) => values.len(),
+            ArrayElements::Float(values) => values.len(),
+            ArrayElements::Double(values) => values.len(),
+            ArrayElements::Reference(values) => values.len(),
+        }
+    }
+
+    /// References held by the array, empty for arrays of primitives
+    pub fn references(&self) -> &[Value] {
+        match &self.elements {
+            ArrayElements::Reference(values) => values,
+            _ => &[],
+        }
+    }
+}
+
+impl fmt::Debug for Array {
+    // Only print the type and length, printing the elements could recurse forever on cyclic arrays
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Array({}, length={})", self.descriptor, self.length())
+    }
+}
+
+/// Size in bytes of a component of the given descriptor
+pub fn component_size(component_type: &str) -> usize {
+    match component_type.chars().next() {
+        Some('Z') | Some('B') => 1,
+        Some('C') | Some('S') => 2,
+        Some('I') | Some('F') => 4,
+        // long, double and references
+        _ => 8,
+    }
+}
+
+/// Descriptor of an array whose components are of the type named by a `CONSTANT_Class` entry,
+/// e.g. `[Ljava/lang/String;` for `java/lang/String` and `[[I` for `[I`
+pub fn array_descriptor_of(class_name: &str) -> String {
+    if class_name.starts_with('[') {
+        format!("[{}", class_name)
+    } else {
+        format!("[L{};", class_name)
+    }
+}
+
+/// Name of the class or array type of a reference component descriptor the way
+/// `CONSTANT_Class` entries name types, e.g. `java/lang/String` for `Ljava/lang/String;`
+/// and `[I` for `[I`, `None` for primitive components
+pub fn component_class_name(component_type: &str) -> Option<&str> {
+    match component_type.chars().next() {
+        Some('L') => component_type
+            .strip_prefix('L')
+            .and_then(|name| name.strip_suffix(';')),
+        Some('[') => Some(component_type),
+        _ => None,
+    }
+}
+
+/// Converts an array descriptor to the name `Class.getName()` returns, e.g. `[Ljava.lang.String;`
+pub fn external_array_name(descriptor: &str) -> String {
+    descriptor.replace('/', ".")
+}
@@ -0,0 +1,884 @@
+use crate::parser::access_flags::{
+    ACC_ABSTRACT, ACC_FINAL, ACC_INTERFACE, ACC_NATIVE, ACC_PRIVATE, ACC_PROTECTED, ACC_PUBLIC,
+    ACC_STATIC,
+};
+
+/// A class provided by the VM itself instead of being loaded from a class file
+pub struct BootstrapClass {
+    pub name: &'static str,
+    pub super_class: Option<&'static str>,
+    /// Direct superinterfaces, in declaration order
+    pub interfaces: &'static [&'static str],
+    pub access_flags: u16,
+    /// Declared methods as ( name, descriptor, access_flags ), all of them are native,
+    /// or abstract for interfaces
+    pub methods: &'static [(&'static str, &'static str, u16)],
+    /// Declared fields as ( name, descriptor, access_flags )
+    pub fields: &'static [(&'static str, &'static str, u16)],
+}
+
+const PUBLIC_NATIVE: u16 = ACC_PUBLIC | ACC_NATIVE;
+const STATIC_NATIVE: u16 = ACC_PUBLIC | ACC_STATIC | ACC_NATIVE;
+const PUBLIC_ABSTRACT: u16 = ACC_PUBLIC | ACC_ABSTRACT;
+
+/// Declares a throwable class whose constructors and methods are all inherited from `java/lang/Throwable`
+const fn throwable_class(name: &'static str, super_class: &'static str) -> BootstrapClass {
+    BootstrapClass {
+        name,
+        super_class: Some(super_class),
+        interfaces: &[],
+        access_flags: ACC_PUBLIC,
+        methods: &[],
+        fields: &[],
+    }
+}
+
+/// Declares an interface without any method, only used as a type
+const fn marker_interface(name: &'static str) -> BootstrapClass {
+    BootstrapClass {
+        name,
+        super_class: Some("java/lang/Object"),
+        interfaces: &[],
+        access_flags: ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT,
+        methods: &[],
+        fields: &[],
+    }
+}
+
+/// Declares an interface with the given superinterfaces and abstract methods
+const fn interface(
+    name: &'static str,
+    interfaces: &'static [&'static str],
+    methods: &'static [(&'static str, &'static str, u16)],
+) -> BootstrapClass {
+    BootstrapClass {
+        name,
+        super_class: Some("java/lang/Object"),
+        interfaces,
+        access_flags: ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT,
+        methods,
+        fields: &[],
+    }
+}
+
+/// The core library classes zvm knows about without a class path
+const BOOTSTRAP_CLASSES: &[BootstrapClass] = &[
+    BootstrapClass {
+        name: "java/lang/Object",
+        super_class: None,
+        interfaces: &[],
+        access_flags: ACC_PUBLIC,
+        methods: &[
+            ("<init>", "()V", PUBLIC_NATIVE),
+            ("hashCode", "()I", PUBLIC_NATIVE),
+            ("equals", "(Ljava/lang/Object;)Z", PUBLIC_NATIVE),
+            ("toString", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("clone", "()Ljava/lang/Object;", ACC_PROTECTED | ACC_NATIVE),
+            ("wait", "()V", PUBLIC_NATIVE | ACC_FINAL),
+            ("wait", "(J)V", PUBLIC_NATIVE | ACC_FINAL),
+            ("notify", "()V", PUBLIC_NATIVE | ACC_FINAL),
+            ("notifyAll", "()V", PUBLIC_NATIVE | ACC_FINAL),
+        ],
+        fields: &[],
+    },
+    marker_interface("java/lang/Cloneable"),
+    marker_interface("java/io/Serializable"),
+    BootstrapClass {
+        name: "java/lang/Comparable",
+        super_class: Some("java/lang/Object"),
+        interfaces: &[],
+        access_flags: ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT,
+        methods: &[("compareTo", "(Ljava/lang/Object;)I", PUBLIC_ABSTRACT)],
+        fields: &[],
+    },
+    BootstrapClass {
+        name: "java/lang/CharSequence",
+        super_class: Some("java/lang/Object"),
+        interfaces: &[],
+        access_flags: ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT,
+        methods: &[
+            ("length", "()I", PUBLIC_ABSTRACT),
+            ("charAt", "(I)C", PUBLIC_ABSTRACT),
+            ("toString", "()Ljava/lang/String;", PUBLIC_ABSTRACT),
+        ],
+        fields: &[],
+    },
+    BootstrapClass {
+        name: "java/lang/String",
+        super_class: Some("java/lang/Object"),
+        interfaces: &[
+            "java/io/Serializable",
+            "java/lang/Comparable",
+            "java/lang/CharSequence",
+        ],
+        access_flags: ACC_PUBLIC,
+        methods: &[
+            ("equals", "(Ljava/lang/Object;)Z", PUBLIC_NATIVE),
+            ("hashCode", "()I", PUBLIC_NATIVE),
+            ("toString", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("intern", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("length", "()I", PUBLIC_NATIVE),
+            ("isEmpty", "()Z", PUBLIC_NATIVE),
+            ("charAt", "(I)C", PUBLIC_NATIVE),
+            ("substring", "(I)Ljava/lang/String;", PUBLIC_NATIVE),
+            ("substring", "(II)Ljava/lang/String;", PUBLIC_NATIVE),
+            ("indexOf", "(I)I", PUBLIC_NATIVE),
+            ("indexOf", "(II)I", PUBLIC_NATIVE),
+            ("indexOf", "(Ljava/lang/String;)I", PUBLIC_NATIVE),
+            ("indexOf", "(Ljava/lang/String;I)I", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/String;)I", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Object;)I", PUBLIC_NATIVE),
+            (
+                "split",
+                "(Ljava/lang/String;)[Ljava/lang/String;",
+                PUBLIC_NATIVE,
+            ),
+            (
+                "split",
+                "(Ljava/lang/String;I)[Ljava/lang/String;",
+                PUBLIC_NATIVE,
+            ),
+            ("toUpperCase", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("toLowerCase", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("valueOf", "(I)Ljava/lang/String;", STATIC_NATIVE),
+            ("valueOf", "(J)Ljava/lang/String;", STATIC_NATIVE),
+            ("valueOf", "(F)Ljava/lang/String;", STATIC_NATIVE),
+            ("valueOf", "(D)Ljava/lang/String;", STATIC_NATIVE),
+            ("valueOf", "(Z)Ljava/lang/String;", STATIC_NATIVE),
+            ("valueOf", "(C)Ljava/lang/String;", STATIC_NATIVE),
+            ("valueOf", "([C)Ljava/lang/String;", STATIC_NATIVE),
+            (
+                "valueOf",
+                "(Ljava/lang/Object;)Ljava/lang/String;",
+                STATIC_NATIVE,
+            ),
+            (
+                "format",
+                "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/lang/String;",
+                STATIC_NATIVE,
+            ),
+        ],
+        fields: &[
+            // UTF-16 code units of the string
+            ("value", "[C", ACC_PRIVATE),
+        ],
+    },
+    BootstrapClass {
+        name: "java/lang/StringBuilder",
+        super_class: Some("java/lang/Object"),
+        interfaces: &["java/io/Serializable", "java/lang/CharSequence"],
+        access_flags: ACC_PUBLIC,
+        methods: &[
+            ("<init>", "()V", PUBLIC_NATIVE),
+            ("<init>", "(I)V", PUBLIC_NATIVE),
+            ("<init>", "(Ljava/lang/String;)V", PUBLIC_NATIVE),
+            (
+                "append",
+                "(Ljava/lang/String;)Ljava/lang/StringBuilder;",
+                PUBLIC_NATIVE,
+            ),
+            (
+                "append",
+                "(Ljava/lang/CharSequence;)Ljava/lang/StringBuilder;",
+                PUBLIC_NATIVE,
+            ),
+            (
+                "append",
+                "(Ljava/lang/Object;)Ljava/lang/StringBuilder;",
+                PUBLIC_NATIVE,
+            ),
+            ("append", "([C)Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            ("append", "(I)Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            ("append", "(J)Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            ("append", "(F)Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            ("append", "(D)Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            ("append", "(Z)Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            ("append", "(C)Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            (
+                "insert",
+                "(ILjava/lang/String;)Ljava/lang/StringBuilder;",
+                PUBLIC_NATIVE,
+            ),
+            (
+                "insert",
+                "(ILjava/lang/CharSequence;)Ljava/lang/StringBuilder;",
+                PUBLIC_NATIVE,
+            ),
+            (
+                "insert",
+                "(ILjava/lang/Object;)Ljava/lang/StringBuilder;",
+                PUBLIC_NATIVE,
+            ),
+            ("insert", "(I[C)Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            ("insert", "(II)Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            ("insert", "(IJ)Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            ("insert", "(IF)Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            ("insert", "(ID)Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            ("insert", "(IZ)Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            ("insert", "(IC)Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            ("reverse", "()Ljava/lang/StringBuilder;", PUBLIC_NATIVE),
+            ("length", "()I", PUBLIC_NATIVE),
+            ("charAt", "(I)C", PUBLIC_NATIVE),
+            ("toString", "()Ljava/lang/String;", PUBLIC_NATIVE),
+        ],
+        fields: &[
+            // UTF-16 code units, only the first `count` are part of the contents
+            ("value", "[C", ACC_PRIVATE),
+            ("count", "I", ACC_PRIVATE),
+        ],
+    },
+    BootstrapClass {
+        name: "java/lang/System",
+        super_class: Some("java/lang/Object"),
+        interfaces: &[],
+        access_flags: ACC_PUBLIC | ACC_FINAL,
+        // The class initializer creates the standard streams
+        methods: &[
+            ("<clinit>", "()V", ACC_STATIC | ACC_NATIVE),
+            ("exit", "(I)V", STATIC_NATIVE),
+        ],
+        fields: &[
+            (
+                "out",
+                "Ljava/io/PrintStream;",
+                ACC_PUBLIC | ACC_STATIC | ACC_FINAL,
+            ),
+            (
+                "err",
+                "Ljava/io/PrintStream;",
+                ACC_PUBLIC | ACC_STATIC | ACC_FINAL,
+            ),
+        ],
+    },
+    BootstrapClass {
+        name: "java/io/PrintStream",
+        super_class: Some("java/lang/Object"),
+        interfaces: &[],
+        access_flags: ACC_PUBLIC,
+        methods: &[
+            ("print", "(Z)V", PUBLIC_NATIVE),
+            ("print", "(C)V", PUBLIC_NATIVE),
+            ("print", "(I)V", PUBLIC_NATIVE),
+            ("print", "(J)V", PUBLIC_NATIVE),
+            ("print", "(F)V", PUBLIC_NATIVE),
+            ("print", "(D)V", PUBLIC_NATIVE),
+            ("print", "([C)V", PUBLIC_NATIVE),
+            ("print", "(Ljava/lang/String;)V", PUBLIC_NATIVE),
+            ("print", "(Ljava/lang/Object;)V", PUBLIC_NATIVE),
+            ("println", "()V", PUBLIC_NATIVE),
+            ("println", "(Z)V", PUBLIC_NATIVE),
+            ("println", "(C)V", PUBLIC_NATIVE),
+            ("println", "(I)V", PUBLIC_NATIVE),
+            ("println", "(J)V", PUBLIC_NATIVE),
+            ("println", "(F)V", PUBLIC_NATIVE),
+            ("println", "(D)V", PUBLIC_NATIVE),
+            ("println", "([C)V", PUBLIC_NATIVE),
+            ("println", "(Ljava/lang/String;)V", PUBLIC_NATIVE),
+            ("println", "(Ljava/lang/Object;)V", PUBLIC_NATIVE),
+            (
+                "printf",
+                "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/io/PrintStream;",
+                PUBLIC_NATIVE,
+            ),
+            (
+                "format",
+                "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/io/PrintStream;",
+                PUBLIC_NATIVE,
+            ),
+            ("write", "(I)V", PUBLIC_NATIVE),
+            ("write", "([BII)V", PUBLIC_NATIVE),
+            ("write", "([B)V", PUBLIC_NATIVE),
+            ("flush", "()V", PUBLIC_NATIVE),
+        ],
+        fields: &[
+            // The standard stream written to, 1 for System.out and 2 for System.err
+            ("fd", "I", ACC_PRIVATE),
+        ],
+    },
+    BootstrapClass {
+        name: "java/lang/Number",
+        super_class: Some("java/lang/Object"),
+        interfaces: &["java/io/Serializable"],
+        access_flags: ACC_PUBLIC | ACC_ABSTRACT,
+        methods: &[
+            ("<init>", "()V", PUBLIC_NATIVE),
+            ("intValue", "()I", PUBLIC_ABSTRACT),
+            ("longValue", "()J", PUBLIC_ABSTRACT),
+            ("floatValue", "()F", PUBLIC_ABSTRACT),
+            ("doubleValue", "()D", PUBLIC_ABSTRACT),
+            ("byteValue", "()B", PUBLIC_NATIVE),
+            ("shortValue", "()S", PUBLIC_NATIVE),
+        ],
+        fields: &[],
+    },
+    BootstrapClass {
+        name: "java/lang/Integer",
+        super_class: Some("java/lang/Number"),
+        interfaces: &["java/io/Serializable", "java/lang/Comparable"],
+        access_flags: ACC_PUBLIC | ACC_FINAL,
+        methods: &[
+            ("valueOf", "(I)Ljava/lang/Integer;", STATIC_NATIVE),
+            ("intValue", "()I", PUBLIC_NATIVE),
+            ("longValue", "()J", PUBLIC_NATIVE),
+            ("floatValue", "()F", PUBLIC_NATIVE),
+            ("doubleValue", "()D", PUBLIC_NATIVE),
+            ("shortValue", "()S", PUBLIC_NATIVE),
+            ("byteValue", "()B", PUBLIC_NATIVE),
+            ("toString", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("toString", "(I)Ljava/lang/String;", STATIC_NATIVE),
+            ("hashCode", "()I", PUBLIC_NATIVE),
+            ("equals", "(Ljava/lang/Object;)Z", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Integer;)I", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Object;)I", PUBLIC_NATIVE),
+            ("parseInt", "(Ljava/lang/String;)I", STATIC_NATIVE),
+            ("parseInt", "(Ljava/lang/String;I)I", STATIC_NATIVE),
+            (
+                "valueOf",
+                "(Ljava/lang/String;)Ljava/lang/Integer;",
+                STATIC_NATIVE,
+            ),
+            (
+                "valueOf",
+                "(Ljava/lang/String;I)Ljava/lang/Integer;",
+                STATIC_NATIVE,
+            ),
+            ("toString", "(II)Ljava/lang/String;", STATIC_NATIVE),
+            ("toHexString", "(I)Ljava/lang/String;", STATIC_NATIVE),
+            ("toOctalString", "(I)Ljava/lang/String;", STATIC_NATIVE),
+            ("toBinaryString", "(I)Ljava/lang/String;", STATIC_NATIVE),
+            ("sum", "(II)I", STATIC_NATIVE),
+            ("max", "(II)I", STATIC_NATIVE),
+            ("min", "(II)I", STATIC_NATIVE),
+        ],
+        fields: &[("value", "I", ACC_PRIVATE | ACC_FINAL)],
+    },
+    BootstrapClass {
+        name: "java/lang/Long",
+        super_class: Some("java/lang/Number"),
+        interfaces: &["java/io/Serializable", "java/lang/Comparable"],
+        access_flags: ACC_PUBLIC | ACC_FINAL,
+        methods: &[
+            ("valueOf", "(J)Ljava/lang/Long;", STATIC_NATIVE),
+            ("intValue", "()I", PUBLIC_NATIVE),
+            ("longValue", "()J", PUBLIC_NATIVE),
+            ("floatValue", "()F", PUBLIC_NATIVE),
+            ("doubleValue", "()D", PUBLIC_NATIVE),
+            ("shortValue", "()S", PUBLIC_NATIVE),
+            ("byteValue", "()B", PUBLIC_NATIVE),
+            ("toString", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("toString", "(J)Ljava/lang/String;", STATIC_NATIVE),
+            ("hashCode", "()I", PUBLIC_NATIVE),
+            ("equals", "(Ljava/lang/Object;)Z", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Long;)I", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Object;)I", PUBLIC_NATIVE),
+            ("parseLong", "(Ljava/lang/String;)J", STATIC_NATIVE),
+            ("parseLong", "(Ljava/lang/String;I)J", STATIC_NATIVE),
+            (
+                "valueOf",
+                "(Ljava/lang/String;)Ljava/lang/Long;",
+                STATIC_NATIVE,
+            ),
+            (
+                "valueOf",
+                "(Ljava/lang/String;I)Ljava/lang/Long;",
+                STATIC_NATIVE,
+            ),
+            ("toString", "(JI)Ljava/lang/String;", STATIC_NATIVE),
+            ("toHexString", "(J)Ljava/lang/String;", STATIC_NATIVE),
+            ("toOctalString", "(J)Ljava/lang/String;", STATIC_NATIVE),
+            ("toBinaryString", "(J)Ljava/lang/String;", STATIC_NATIVE),
+        ],
+        fields: &[("value", "J", ACC_PRIVATE | ACC_FINAL)],
+    },
+    BootstrapClass {
+        name: "java/lang/Short",
+        super_class: Some("java/lang/Number"),
+        interfaces: &["java/io/Serializable", "java/lang/Comparable"],
+        access_flags: ACC_PUBLIC | ACC_FINAL,
+        methods: &[
+            ("valueOf", "(S)Ljava/lang/Short;", STATIC_NATIVE),
+            ("intValue", "()I", PUBLIC_NATIVE),
+            ("longValue", "()J", PUBLIC_NATIVE),
+            ("floatValue", "()F", PUBLIC_NATIVE),
+            ("doubleValue", "()D", PUBLIC_NATIVE),
+            ("shortValue", "()S", PUBLIC_NATIVE),
+            ("byteValue", "()B", PUBLIC_NATIVE),
+            ("toString", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("toString", "(S)Ljava/lang/String;", STATIC_NATIVE),
+            ("hashCode", "()I", PUBLIC_NATIVE),
+            ("equals", "(Ljava/lang/Object;)Z", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Short;)I", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Object;)I", PUBLIC_NATIVE),
+        ],
+        fields: &[("value", "S", ACC_PRIVATE | ACC_FINAL)],
+    },
+    BootstrapClass {
+        name: "java/lang/Byte",
+        super_class: Some("java/lang/Number"),
+        interfaces: &["java/io/Serializable", "java/lang/Comparable"],
+        access_flags: ACC_PUBLIC | ACC_FINAL,
+        methods: &[
+            ("valueOf", "(B)Ljava/lang/Byte;", STATIC_NATIVE),
+            ("intValue", "()I", PUBLIC_NATIVE),
+            ("longValue", "()J", PUBLIC_NATIVE),
+            ("floatValue", "()F", PUBLIC_NATIVE),
+            ("doubleValue", "()D", PUBLIC_NATIVE),
+            ("shortValue", "()S", PUBLIC_NATIVE),
+            ("byteValue", "()B", PUBLIC_NATIVE),
+            ("toString", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("toString", "(B)Ljava/lang/String;", STATIC_NATIVE),
+            ("hashCode", "()I", PUBLIC_NATIVE),
+            ("equals", "(Ljava/lang/Object;)Z", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Byte;)I", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Object;)I", PUBLIC_NATIVE),
+        ],
+        fields: &[("value", "B", ACC_PRIVATE | ACC_FINAL)],
+    },
+    BootstrapClass {
+        name: "java/lang/Float",
+        super_class: Some("java/lang/Number"),
+        interfaces: &["java/io/Serializable", "java/lang/Comparable"],
+        access_flags: ACC_PUBLIC | ACC_FINAL,
+        methods: &[
+            ("valueOf", "(F)Ljava/lang/Float;", STATIC_NATIVE),
+            ("intValue", "()I", PUBLIC_NATIVE),
+            ("longValue", "()J", PUBLIC_NATIVE),
+            ("floatValue", "()F", PUBLIC_NATIVE),
+            ("doubleValue", "()D", PUBLIC_NATIVE),
+            ("shortValue", "()S", PUBLIC_NATIVE),
+            ("byteValue", "()B", PUBLIC_NATIVE),
+            ("toString", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("toString", "(F)Ljava/lang/String;", STATIC_NATIVE),
+            ("hashCode", "()I", PUBLIC_NATIVE),
+            ("equals", "(Ljava/lang/Object;)Z", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Float;)I", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Object;)I", PUBLIC_NATIVE),
+            ("parseFloat", "(Ljava/lang/String;)F", STATIC_NATIVE),
+            (
+                "valueOf",
+                "(Ljava/lang/String;)Ljava/lang/Float;",
+                STATIC_NATIVE,
+            ),
+        ],
+        fields: &[("value", "F", ACC_PRIVATE | ACC_FINAL)],
+    },
+    BootstrapClass {
+        name: "java/lang/Double",
+        super_class: Some("java/lang/Number"),
+        interfaces: &["java/io/Serializable", "java/lang/Comparable"],
+        access_flags: ACC_PUBLIC | ACC_FINAL,
+        methods: &[
+            ("valueOf", "(D)Ljava/lang/Double;", STATIC_NATIVE),
+            ("intValue", "()I", PUBLIC_NATIVE),
+            ("longValue", "()J", PUBLIC_NATIVE),
+            ("floatValue", "()F", PUBLIC_NATIVE),
+            ("doubleValue", "()D", PUBLIC_NATIVE),
+            ("shortValue", "()S", PUBLIC_NATIVE),
+            ("byteValue", "()B", PUBLIC_NATIVE),
+            ("toString", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("toString", "(D)Ljava/lang/String;", STATIC_NATIVE),
+            ("hashCode", "()I", PUBLIC_NATIVE),
+            ("equals", "(Ljava/lang/Object;)Z", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Double;)I", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Object;)I", PUBLIC_NATIVE),
+            ("parseDouble", "(Ljava/lang/String;)D", STATIC_NATIVE),
+            (
+                "valueOf",
+                "(Ljava/lang/String;)Ljava/lang/Double;",
+                STATIC_NATIVE,
+            ),
+        ],
+        fields: &[("value", "D", ACC_PRIVATE | ACC_FINAL)],
+    },
+    BootstrapClass {
+        name: "java/lang/Boolean",
+        super_class: Some("java/lang/Object"),
+        interfaces: &["java/io/Serializable", "java/lang/Comparable"],
+        access_flags: ACC_PUBLIC | ACC_FINAL,
+        methods: &[
+            ("<clinit>", "()V", ACC_STATIC | ACC_NATIVE),
+            ("valueOf", "(Z)Ljava/lang/Boolean;", STATIC_NATIVE),
+            ("booleanValue", "()Z", PUBLIC_NATIVE),
+            ("toString", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("toString", "(Z)Ljava/lang/String;", STATIC_NATIVE),
+            ("hashCode", "()I", PUBLIC_NATIVE),
+            ("equals", "(Ljava/lang/Object;)Z", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Boolean;)I", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Object;)I", PUBLIC_NATIVE),
+        ],
+        fields: &[
+            ("value", "Z", ACC_PRIVATE | ACC_FINAL),
+            (
+                "TRUE",
+                "Ljava/lang/Boolean;",
+                ACC_PUBLIC | ACC_STATIC | ACC_FINAL,
+            ),
+            (
+                "FALSE",
+                "Ljava/lang/Boolean;",
+                ACC_PUBLIC | ACC_STATIC | ACC_FINAL,
+            ),
+        ],
+    },
+    BootstrapClass {
+        name: "java/lang/Character",
+        super_class: Some("java/lang/Object"),
+        interfaces: &["java/io/Serializable", "java/lang/Comparable"],
+        access_flags: ACC_PUBLIC | ACC_FINAL,
+        methods: &[
+            ("valueOf", "(C)Ljava/lang/Character;", STATIC_NATIVE),
+            ("charValue", "()C", PUBLIC_NATIVE),
+            ("toString", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("toString", "(C)Ljava/lang/String;", STATIC_NATIVE),
+            ("hashCode", "()I", PUBLIC_NATIVE),
+            ("equals", "(Ljava/lang/Object;)Z", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Character;)I", PUBLIC_NATIVE),
+            ("compareTo", "(Ljava/lang/Object;)I", PUBLIC_NATIVE),
+        ],
+        fields: &[("value", "C", ACC_PRIVATE | ACC_FINAL)],
+    },
+    BootstrapClass {
+        name: "java/util/Objects",
+        super_class: Some("java/lang/Object"),
+        interfaces: &[],
+        access_flags: ACC_PUBLIC,
+        methods: &[
+            (
+                "requireNonNull",
+                "(Ljava/lang/Object;)Ljava/lang/Object;",
+                STATIC_NATIVE,
+            ),
+            (
+                "requireNonNull",
+                "(Ljava/lang/Object;Ljava/lang/String;)Ljava/lang/Object;",
+                STATIC_NATIVE,
+            ),
+        ],
+        fields: &[],
+    },
+    // Functional interfaces, generic types are erased to java/lang/Object
+    interface(
+        "java/lang/Runnable",
+        &[],
+        &[("run", "()V", PUBLIC_ABSTRACT)],
+    ),
+    interface(
+        "java/util/Comparator",
+        &[],
+        &[(
+            "compare",
+            "(Ljava/lang/Object;Ljava/lang/Object;)I",
+            PUBLIC_ABSTRACT,
+        )],
+    ),
+    interface(
+        "java/util/function/Function",
+        &[],
+        &[(
+            "apply",
+            "(Ljava/lang/Object;)Ljava/lang/Object;",
+            PUBLIC_ABSTRACT,
+        )],
+    ),
+    interface(
+        "java/util/function/BiFunction",
+        &[],
+        &[(
+            "apply",
+            "(Ljava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;",
+            PUBLIC_ABSTRACT,
+        )],
+    ),
+    interface(
+        "java/util/function/UnaryOperator",
+        &["java/util/function/Function"],
+        &[],
+    ),
+    interface(
+        "java/util/function/BinaryOperator",
+        &["java/util/function/BiFunction"],
+        &[],
+    ),
+    interface(
+        "java/util/function/Supplier",
+        &[],
+        &[("get", "()Ljava/lang/Object;", PUBLIC_ABSTRACT)],
+    ),
+    interface(
+        "java/util/function/Consumer",
+        &[],
+        &[("accept", "(Ljava/lang/Object;)V", PUBLIC_ABSTRACT)],
+    ),
+    interface(
+        "java/util/function/BiConsumer",
+        &[],
+        &[(
+            "accept",
+            "(Ljava/lang/Object;Ljava/lang/Object;)V",
+            PUBLIC_ABSTRACT,
+        )],
+    ),
+    interface(
+        "java/util/function/Predicate",
+        &[],
+        &[("test", "(Ljava/lang/Object;)Z", PUBLIC_ABSTRACT)],
+    ),
+    interface(
+        "java/util/function/IntSupplier",
+        &[],
+        &[("getAsInt", "()I", PUBLIC_ABSTRACT)],
+    ),
+    interface(
+        "java/util/function/IntUnaryOperator",
+        &[],
+        &[("applyAsInt", "(I)I", PUBLIC_ABSTRACT)],
+    ),
+    interface(
+        "java/util/function/IntBinaryOperator",
+        &[],
+        &[("applyAsInt", "(II)I", PUBLIC_ABSTRACT)],
+    ),
+    interface(
+        "java/util/function/IntPredicate",
+        &[],
+        &[("test", "(I)Z", PUBLIC_ABSTRACT)],
+    ),
+    interface(
+        "java/util/function/IntFunction",
+        &[],
+        &[("apply", "(I)Ljava/lang/Object;", PUBLIC_ABSTRACT)],
+    ),
+    interface(
+        "java/util/function/ToIntFunction",
+        &[],
+        &[("applyAsInt", "(Ljava/lang/Object;)I", PUBLIC_ABSTRACT)],
+    ),
+    BootstrapClass {
+        name: "java/lang/Thread",
+        super_class: Some("java/lang/Object"),
+        interfaces: &["java/lang/Runnable"],
+        access_flags: ACC_PUBLIC,
+        methods: &[
+            ("<init>", "()V", PUBLIC_NATIVE),
+            ("<init>", "(Ljava/lang/Runnable;)V", PUBLIC_NATIVE),
+            (
+                "<init>",
+                "(Ljava/lang/Runnable;Ljava/lang/String;)V",
+                PUBLIC_NATIVE,
+            ),
+            ("<init>", "(Ljava/lang/String;)V", PUBLIC_NATIVE),
+            ("start", "()V", PUBLIC_NATIVE),
+            ("run", "()V", PUBLIC_NATIVE),
+            ("join", "()V", PUBLIC_NATIVE),
+            ("join", "(J)V", PUBLIC_NATIVE),
+            ("isAlive", "()Z", PUBLIC_NATIVE),
+            ("getName", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("setName", "(Ljava/lang/String;)V", PUBLIC_NATIVE),
+            ("getId", "()J", PUBLIC_NATIVE),
+            ("isDaemon", "()Z", PUBLIC_NATIVE),
+            ("setDaemon", "(Z)V", PUBLIC_NATIVE),
+            ("getPriority", "()I", PUBLIC_NATIVE),
+            ("setPriority", "(I)V", PUBLIC_NATIVE),
+            ("toString", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("currentThread", "()Ljava/lang/Thread;", STATIC_NATIVE),
+            ("sleep", "(J)V", STATIC_NATIVE),
+            ("yield", "()V", STATIC_NATIVE),
+        ],
+        fields: &[
+            ("name", "Ljava/lang/String;", ACC_PRIVATE),
+            // The Runnable run by run() unless a subclass overrides it
+            ("target", "Ljava/lang/Runnable;", ACC_PRIVATE),
+            ("tid", "J", ACC_PRIVATE),
+            ("daemon", "Z", ACC_PRIVATE),
+            ("priority", "I", ACC_PRIVATE),
+        ],
+    },
+    BootstrapClass {
+        name: "java/lang/Throwable",
+        super_class: Some("java/lang/Object"),
+        interfaces: &["java/io/Serializable"],
+        access_flags: ACC_PUBLIC,
+        methods: &[
+            ("<init>", "()V", PUBLIC_NATIVE),
+            ("<init>", "(Ljava/lang/String;)V", PUBLIC_NATIVE),
+            (
+                "<init>",
+                "(Ljava/lang/String;Ljava/lang/Throwable;)V",
+                PUBLIC_NATIVE,
+            ),
+            ("<init>", "(Ljava/lang/Throwable;)V", PUBLIC_NATIVE),
+            ("getMessage", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("getLocalizedMessage", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("getCause", "()Ljava/lang/Throwable;", PUBLIC_NATIVE),
+            (
+                "initCause",
+                "(Ljava/lang/Throwable;)Ljava/lang/Throwable;",
+                PUBLIC_NATIVE,
+            ),
+            ("fillInStackTrace", "()Ljava/lang/Throwable;", PUBLIC_NATIVE),
+            ("toString", "()Ljava/lang/String;", PUBLIC_NATIVE),
+            ("printStackTrace", "()V", PUBLIC_NATIVE),
+        ],
+        fields: &[
+            ("detailMessage", "Ljava/lang/String;", ACC_PRIVATE),
+            ("cause", "Ljava/lang/Throwable;", ACC_PRIVATE),
+            // Frames captured when the throwable was created, one description per frame
+            ("backtrace", "Ljava/lang/Object;", ACC_PRIVATE),
+        ],
+    },
+    throwable_class("java/lang/Exception", "java/lang/Throwable"),
+    throwable_class("java/lang/Error", "java/lang/Throwable"),
+    throwable_class("java/lang/RuntimeException", "java/lang/Exception"),
+    throwable_class(
+        "java/lang/IllegalArgumentException",
+        "java/lang/RuntimeException",
+    ),
+    throwable_class(
+        "java/lang/NumberFormatException",
+        "java/lang/IllegalArgumentException",
+    ),
+    throwable_class(
+        "java/lang/IllegalThreadStateException",
+        "java/lang/IllegalArgumentException",
+    ),
+    throwable_class("java/lang/InterruptedException", "java/lang/Exception"),
+    throwable_class(
+        "java/lang/IllegalMonitorStateException",
+        "java/lang/RuntimeException",
+    ),
+    throwable_class(
+        "java/lang/IllegalStateException",
+        "java/lang/RuntimeException",
+    ),
+    throwable_class(
+        "java/lang/NullPointerException",
+        "java/lang/RuntimeException",
+    ),
+    throwable_class(
+        "java/lang/UnsupportedOperationException",
+        "java/lang/RuntimeException",
+    ),
+    throwable_class(
+        "java/lang/ArithmeticException",
+        "java/lang/RuntimeException",
+    ),
+    throwable_class(
+        "java/lang/IndexOutOfBoundsException",
+        "java/lang/RuntimeException",
+    ),
+    throwable_class(
+        "java/lang/ArrayIndexOutOfBoundsException",
+        "java/lang/IndexOutOfBoundsException",
+    ),
+    throwable_class("java/lang/ClassCastException", "java/lang/RuntimeException"),
+    throwable_class(
+        "java/util/regex/PatternSyntaxException",
+        "java/lang/IllegalArgumentException",
+    ),
+    throwable_class(
+        "java/util/IllegalFormatException",
+        "java/lang/IllegalArgumentException",
+    ),
+    throwable_class(
+        "java/util/UnknownFormatConversionException",
+        "java/util/IllegalFormatException",
+    ),
+    throwable_class(
+        "java/util/MissingFormatArgumentException",
+        "java/util/IllegalFormatException",
+    ),
+    throwable_class(
+        "java/util/IllegalFormatConversionException",
+        "java/util/IllegalFormatException",
+    ),
+    throwable_class(
+        "java/util/MissingFormatWidthException",
+        "java/util/IllegalFormatException",
+    ),
+    throwable_class(
+        "java/util/FormatFlagsConversionMismatchException",
+        "java/util/IllegalFormatException",
+    ),
+    throwable_class(
+        "java/util/IllegalFormatPrecisionException",
+        "java/util/IllegalFormatException",
+    ),
+    throwable_class(
+        "java/util/IllegalFormatWidthException",
+        "java/util/IllegalFormatException",
+    ),
+    throwable_class(
+        "java/util/IllegalFormatFlagsException",
+        "java/util/IllegalFormatException",
+    ),
+    throwable_class(
+        "java/util/DuplicateFormatFlagsException",
+        "java/util/IllegalFormatException",
+    ),
+    throwable_class(
+        "java/util/IllegalFormatCodePointException",
+        "java/util/IllegalFormatException",
+    ),
+    throwable_class(
+        "java/util/IllegalFormatArgumentIndexException",
+        "java/util/IllegalFormatException",
+    ),
+    throwable_class(
+        "java/lang/StringIndexOutOfBoundsException",
+        "java/lang/IndexOutOfBoundsException",
+    ),
+    throwable_class(
+        "java/lang/NegativeArraySizeException",
+        "java/lang/RuntimeException",
+    ),
+    throwable_class(
+        "java/lang/ArrayStoreException",
+        "java/lang/RuntimeException",
+    ),
+    throwable_class(
+        "java/lang/CloneNotSupportedException",
+        "java/lang/Exception",
+    ),
+    throwable_class("java/lang/LinkageError", "java/lang/Error"),
+    throwable_class(
+        "java/lang/ExceptionInInitializerError",
+        "java/lang/LinkageError",
+    ),
+    throwable_class("java/lang/VerifyError", "java/lang/LinkageError"),
+    throwable_class("java/lang/NoClassDefFoundError", "java/lang/LinkageError"),
+    throwable_class(
+        "java/lang/IncompatibleClassChangeError",
+        "java/lang/LinkageError",
+    ),
+    throwable_class(
+        "java/lang/AbstractMethodError",
+        "java/lang/IncompatibleClassChangeError",
+    ),
+    throwable_class(
+        "java/lang/NoSuchMethodError",
+        "java/lang/IncompatibleClassChangeError",
+    ),
+    throwable_class("java/lang/BootstrapMethodError", "java/lang/LinkageError"),
+    throwable_class("java/lang/VirtualMachineError", "java/lang/Error"),
+    throwable_class(
+        "java/lang/OutOfMemoryError",
+        "java/lang/VirtualMachineError",
+    ),
+    throwable_class(
+        "java/lang/StackOverflowError",
+        "java/lang/VirtualMachineError",
+    ),
+    throwable_class("java/lang/InternalError", "java/lang/VirtualMachineError"),
+];
+
+/// Checks if a class belongs to the JDK, the VM provides the ones it supports itself
+pub fn is_jdk_class(name: &str) -> bool {
+    ["java/", "javax/", "jdk/", "sun/"]
+        .iter()
+        .any(|package| name.starts_with(package))
+}
+
+/// Finds the bootstrap class definition of the given binary name
+pub fn find_bootstrap_class(name: &str) -> Option<&'static BootstrapClass> {
+    BOOTSTRAP_CLASSES.iter().find(|class| class.name == name)
+}
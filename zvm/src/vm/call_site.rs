@@ -0,0 +1,290 @@
+use std::rc::Rc;
+
+use crate::vm::{
+    call_stack::CallStack,
+    class::{Class, Method},
+    error::VmError,
+    instruction_exec::InstructionCompleted,
+    native,
+    runtime::RuntimeDataArea,
+    value::Value,
+};
+
+/// Bootstrap method of the call sites javac 9+ emits for string concatenation
+pub const STRING_CONCAT_FACTORY: &str = "java/lang/invoke/StringConcatFactory";
+
+/// Bootstrap method of the call sites javac 8+ emits for lambda expressions and method references
+pub const LAMBDA_METAFACTORY: &str = "java/lang/invoke/LambdaMetafactory";
+
+/// `altMetafactory` flag, the functional interface instances are also `java/io/Serializable`
+pub const FLAG_SERIALIZABLE: i32 = 0x1;
+
+/// `altMetafactory` flag, a count of marker interfaces follows the flags, then the interfaces
+pub const FLAG_MARKERS: i32 = 0x2;
+
+/// `altMetafactory` flag, a count of bridge method types follows the markers, then the types
+pub const FLAG_BRIDGES: i32 = 0x4;
+
+/// Method handle kinds of the implementation methods of lambdas
+/// AS SPECIFIED BY THE SPECS: Table 5.4.3.5-A
+pub const REF_INVOKE_VIRTUAL: u8 = 5;
+pub const REF_INVOKE_STATIC: u8 = 6;
+pub const REF_INVOKE_SPECIAL: u8 = 7;
+pub const REF_NEW_INVOKE_SPECIAL: u8 = 8;
+pub const REF_INVOKE_INTERFACE: u8 = 9;
+
+/// Tag of an ordinary argument in a `makeConcatWithConstants` recipe
+const RECIPE_ARGUMENT: u16 = 0x0001;
+
+/// Tag of a constant in a `makeConcatWithConstants` recipe, the constants are the
+/// static arguments following the recipe, in order
+const RECIPE_CONSTANT: u16 = 0x0002;
+
+/// Target of an `invokedynamic` call site, the result of running its bootstrap method once
+/// when the call site is linked
+///
+/// AS SPECIFIED BY THE SPECS: §5.4.3.6, each `invokedynamic` instruction is a separate call site,
+/// later executions of the instruction reuse the target without calling the bootstrap method again
+#[derive(Debug)]
+pub enum CallSite {
+    /// String concatenation bound by `StringConcatFactory.makeConcatWithConstants`
+    /// or `StringConcatFactory.makeConcat`
+    StringConcat(Vec<RecipeElement>),
+    /// Functional interface instances bound by `LambdaMetafactory.metafactory`
+    /// or `LambdaMetafactory.altMetafactory`
+    Lambda(Rc<LambdaForm>),
+}
+
+/// A class spun for a lambda call site, its instances hold the captured arguments of the call
+/// site and forward the functional interface methods to the implementation method
+#[derive(Debug)]
+pub struct LambdaForm {
+    /// The spun class, implementing the functional interface and the marker interfaces
+    pub class: Rc<Class>,
+    /// Kind of the implementation method handle, one of the `REF_` constants
+    pub reference_kind: u8,
+    /// The implementation method, as resolved when the call site was linked
+    pub implementation: Rc<Method>,
+    /// Descriptors of the captured arguments, stored in the `arg$1`...`arg$n` fields
+    pub captured: Vec<String>,
+    /// The instantiated method type, the types the arguments of the functional interface
+    /// methods have at run time, e.g. `(Ljava/lang/Integer;)Ljava/lang/Integer;`
+    pub instantiated: String,
+}
+
+impl LambdaForm {
+    /// Name of the field of the spun class holding the captured argument at the given index
+    pub fn captured_field(&self, index: usize) -> String {
+        format!("{}.arg${}", self.class.name, index + 1)
+    }
+}
+
+/// How the value returned by the implementation method of a lambda reaches the invoker of
+/// the functional interface method forwarded to it
+#[derive(Debug, Clone)]
+pub struct LambdaReturn {
+    /// Return type of the implementation method
+    pub result: String,
+    /// Return type of the functional interface method, the returned value is converted to it
+    pub sam_result: String,
+    /// Instance created for a constructor reference, returned in place of the value
+    pub instance: Option<Value>,
+}
+
+impl LambdaReturn {
+    /// Complete the functional interface method the way its implementation method completed,
+    /// converting the returned value may throw like unboxing a null `Integer` does
+    pub fn complete(
+        &self,
+        completed: InstructionCompleted,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &CallStack,
+    ) -> Result<InstructionCompleted, VmError> {
+        Ok(match completed {
+            InstructionCompleted::ThrowException(exception) => {
+                InstructionCompleted::ThrowException(exception)
+            }
+            _ if self.sam_result == "V" => InstructionCompleted::ReturnFromMethod(None),
+            _ if self.instance.is_some() => {
+                InstructionCompleted::ReturnFromMethod(self.instance.clone())
+            }
+            InstructionCompleted::ReturnFromMethod(Some(value)) => {
+                match adapt(
+                    value,
+                    &self.result,
+                    &self.sam_result,
+                    runtime_data_area,
+                    call_stack,
+                )? {
+                    Ok(value) => InstructionCompleted::ReturnFromMethod(Some(value)),
+                    Err(thrown) => thrown,
+                }
+            }
+            completed => completed,
+        })
+    }
+}
+
+/// Part of a string concatenation recipe
+#[derive(Debug, PartialEq)]
+pub enum RecipeElement {
+    /// Text copied as is, the recipe's own characters and its `\u0002` constants
+    Text(Vec<u16>),
+    /// The next argument of the call site, formatted like `String.valueOf`
+    Argument,
+}
+
+/// Parses the recipe of `makeConcatWithConstants` into its elements, `\u0001` tags stand for the
+/// arguments of the call site and `\u0002` tags for the given constants
+pub fn parse_concat_recipe(
+    recipe: &[u16],
+    mut constants: impl Iterator<Item = Vec<u16>>,
+) -> Result<Vec<RecipeElement>, String> {
+    let mut elements = Vec::new();
+    let mut text = Vec::new();
+
+    for &c in recipe {
+        match c {
+            RECIPE_ARGUMENT => {
+                if !text.is_empty() {
+                    elements.push(RecipeElement::Text(std::mem::take(&mut text)));
+                }
+                elements.push(RecipeElement::Argument);
+            }
+            RECIPE_CONSTANT => match constants.next() {
+                Some(constant) => text.extend(constant),
+                None => return Err("Missing constant for the concat recipe".to_string()),
+            },
+            c => text.push(c),
+        }
+    }
+
+    if !text.is_empty() {
+        elements.push(RecipeElement::Text(text));
+    }
+
+    Ok(elements)
+}
+
+/// Check if a value of the source type can be passed where the target type is expected by
+/// a lambda: references are passed as they are, primitives may be widened, boxed into a box
+/// class the target type is a supertype of, or unboxed from a box class and then widened
+/// AS SPECIFIED BY THE SPECS: `LambdaMetafactory` ( linkage invariants )
+pub fn is_adaptable(source: &str, target: &str) -> bool {
+    match (is_primitive(source), is_primitive(target)) {
+        (false, false) => true,
+        (true, true) => is_widening(source, target),
+        (true, false) => {
+            native::box_class(source).is_some_and(|box_class| is_box_supertype(target, box_class))
+        }
+        (false, true) => match native::boxed_type(class_name(source)) {
+            Some(primitive) => is_widening(primitive, target),
+            // Any other reference is cast to the box class of the target type first
+            None => native::box_class(target)
+                .is_some_and(|box_class| is_box_supertype(source, box_class)),
+        },
+    }
+}
+
+/// Convert a value of the source type to the target type, widening, boxing and unboxing
+/// primitives ( the types have been checked with `is_adaptable` when the call site was linked )
+///
+/// Unboxing fails with the exception to throw, a `NullPointerException` for `null` and a
+/// `ClassCastException` for a reference the generic types let through that is not a box of
+/// a suitable type
+pub fn adapt(
+    value: Value,
+    source: &str,
+    target: &str,
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &CallStack,
+) -> Result<Result<Value, InstructionCompleted>, VmError> {
+    match (is_primitive(source), is_primitive(target)) {
+        (true, false) => match native::box_class(source) {
+            Some(box_class) => Ok(Ok(native::box_value(
+                runtime_data_area,
+                box_class,
+                value,
+                call_stack,
+            )?)),
+            None => Ok(Ok(value)),
+        },
+        (false, true) => {
+            let class_name = match &value {
+                Value::Null => {
+                    return Ok(Err(InstructionCompleted::ThrowNewException(
+                        "java/lang/NullPointerException",
+                        None,
+                    )));
+                }
+                Value::Object(handle) => runtime_data_area.heap.object(*handle).class.name.clone(),
+                _ => return Ok(Ok(widen(value, target))),
+            };
+
+            match native::boxed_type(&class_name) {
+                Some(primitive) if is_widening(primitive, target) => Ok(Ok(widen(
+                    native::boxed_value(&runtime_data_area.heap, &value),
+                    target,
+                ))),
+                _ => Ok(Err(InstructionCompleted::ThrowNewException(
+                    "java/lang/ClassCastException",
+                    Some(native::class_cast_message(
+                        &class_name,
+                        native::box_class(target).unwrap_or("java/lang/Object"),
+                    )),
+                ))),
+            }
+        }
+        _ => Ok(Ok(widen(value, target))),
+    }
+}
+
+/// Checks if a descriptor is the one of a primitive type
+fn is_primitive(descriptor: &str) -> bool {
+    !descriptor.starts_with('L') && !descriptor.starts_with('[')
+}
+
+/// Name of the class of a reference type descriptor, e.g. `java/lang/Integer`
+fn class_name(descriptor: &str) -> &str {
+    descriptor
+        .strip_prefix('L')
+        .and_then(|name| name.strip_suffix(';'))
+        .unwrap_or(descriptor)
+}
+
+/// Checks if a reference type is the box class or one of its supertypes
+fn is_box_supertype(descriptor: &str, box_class: &str) -> bool {
+    let is_number = !matches!(box_class, "java/lang/Boolean" | "java/lang/Character");
+
+    match class_name(descriptor) {
+        "java/lang/Object" | "java/io/Serializable" | "java/lang/Comparable" => true,
+        "java/lang/Number" => is_number,
+        name => name == box_class,
+    }
+}
+
+/// Checks if a primitive type converts to another one without loss of magnitude
+/// AS SPECIFIED BY THE SPECS: JLS §5.1.2 ( widening primitive conversion )
+fn is_widening(source: &str, target: &str) -> bool {
+    source == target
+        || match source {
+            "B" => matches!(target, "S" | "I" | "J" | "F" | "D"),
+            "S" | "C" | "I" => matches!(target, "I" | "J" | "F" | "D"),
+            "J" => matches!(target, "F" | "D"),
+            "F" => target == "D",
+            _ => false,
+        }
+}
+
+/// Widen a primitive value to the target type
+fn widen(value: Value, target: &str) -> Value {
+    match (value, target) {
+        (Value::Int(i), "J") => Value::Long(i as i64),
+        (Value::Int(i), "F") => Value::Float(i as f32),
+        (Value::Int(i), "D") => Value::Double(i as f64),
+        (Value::Long(l), "F") => Value::Float(l as f32),
+        (Value::Long(l), "D") => Value::Double(l as f64),
+        (Value::Float(f), "D") => Value::Double(f as f64),
+        (value, _) => value,
+    }
+}
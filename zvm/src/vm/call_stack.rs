@@ -1,66 +1,185 @@
+use std::rc::Rc;
+
 use crate::{
     debug_log,
-    parser::class_file::ClassFile,
-    vm::{runtime::RuntimeDataArea, stack_frame::Frame, value::Value},
+    parser::opcode::Opcode,
+    vm::{
+        class::{Class, Method},
+        error::{SourceContext, VmError},
+        instruction_exec::{InstructionCompleted, InstructionExecutor},
+        operand_stack::OperandStack,
+        runtime::RuntimeDataArea,
+        stack_frame::Frame,
+        thread::{self, JavaThread},
+        value::Value,
+    },
 };
 
+/// Size of a call stack when `-Xss` does not set it, the default thread stack size
+/// of HotSpot on 64 bit Linux
+pub const DEFAULT_STACK_SIZE: usize = 1024 * 1024;
+
+/// Smallest stack size `-Xss` accepts, the same as HotSpot on 64 bit Linux
+pub const MIN_STACK_SIZE: usize = 136 * 1024;
+
+/// Largest stack size `-Xss` accepts, the same as HotSpot
+pub const MAX_STACK_SIZE: usize = 1024 * 1024 * 1024;
+
+/// Bytes a frame takes besides its local variables and operand stack, the saved pc,
+/// method, constant pool and links of a HotSpot interpreter frame
+const FRAME_OVERHEAD: usize = 12 * 8;
+
+/// Bytes a nested run of the dispatch loop takes besides the frames it runs, the native
+/// frame and the frames of the JDK code calling back into Java code in HotSpot, e.g. for a
+/// `toString()` invoked by a string concatenation
+const NESTED_RUN_SIZE: usize = 1024;
+
+/// Host stack a nested run of the dispatch loop takes at most, the frames of the native
+/// calling back and of the dispatch loop running the frames
+const HOST_RUN_SIZE: usize = 16 * 1024;
+
+/// Host stack kept below the nested runs for the code they call, the deepest of them may
+/// still load classes or format a stack trace
+const HOST_STACK_RESERVE: usize = 2 * 1024 * 1024;
+
+/// Host stack the nested runs take at most whatever the stack size, beyond it they throw a
+/// StackOverflowError even if the stack has room left
+const MAX_HOST_RUNS_SIZE: usize = 256 * 1024 * 1024;
+
+/// Size of the host stack the virtual machine runs on for the given thread stack size, every
+/// nested run fitting in the stack fits in it, the threads run on the same host stack
+pub fn host_stack_size(stack_size: usize) -> usize {
+    (stack_size / NESTED_RUN_SIZE * HOST_RUN_SIZE).min(MAX_HOST_RUNS_SIZE) + HOST_STACK_RESERVE
+}
+
+/// Address of the end of the part of a host stack of the given size left for the nested runs,
+/// the stack starting from the frame calling this function
+pub fn host_stack_end(host_stack_size: usize) -> usize {
+    host_stack_address().saturating_sub(host_stack_size - HOST_STACK_RESERVE)
+}
+
+/// Address of the current frame on the host stack, which grows downwards
+fn host_stack_address() -> usize {
+    let marker = 0u8;
+    std::ptr::addr_of!(marker) as usize
+}
+
 pub struct CallStack {
     pub frames: Vec<Frame>,
-    max_depth: usize,
+    /// Size of the stack in bytes, a method whose frame does not fit in it anymore throws
+    /// a StackOverflowError instead of running
+    max_size: usize,
+    /// Estimated bytes taken by the frames, see `frame_size`
+    used: usize,
+    /// Nested runs of the dispatch loop in progress, see `InstructionExecutor::invoke_method`,
+    /// each of them takes `NESTED_RUN_SIZE` bytes of the stack
+    nested: usize,
+    /// The Java thread executing the frames
+    pub thread: JavaThread,
 }
 
 impl CallStack {
-    pub fn new(max_depth: Option<usize>) -> Self {
+    pub fn new(max_size: Option<usize>, thread: JavaThread) -> Self {
+        let max_size = max_size.unwrap_or(DEFAULT_STACK_SIZE);
+
         Self {
             frames: Vec::new(),
-            max_depth: max_depth.unwrap_or(1000),
+            max_size,
+            used: 0,
+            nested: 0,
+            thread,
         }
     }
 
-    /// Handle pushing frames
-    pub fn push_frame(
-        &mut self,
-        method_name: String,
-        bytecode: Vec<u8>,
-        max_locals: usize,
-        args: Vec<Value>,
-    ) {
-        // TODO: Handle StackOverFlowException
+    /// Sets the size of the stack in bytes ( -Xss )
+    pub fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = max_size;
+    }
 
-        // Create the frame and initialize it
-        let mut frame = Frame::new(Some(method_name.clone()), max_locals, bytecode);
+    /// Estimates the bytes the frame of a method takes on the stack, a word for every local
+    /// variable and operand stack slot on top of the fixed part of an interpreter frame
+    pub fn frame_size(method: &Method) -> usize {
+        let slots = method
+            .code
+            .as_ref()
+            .map_or(0, |code| code.max_locals as usize + code.max_stack as usize);
+
+        FRAME_OVERHEAD + slots * 8
+    }
 
-        // Get the passed arguments and store them in the current frame's local variables
-        let mut current_arg_index = 0;
-        let mut i = 0;
+    /// Whether the frame of a method fits in what is left of the stack
+    pub fn has_room_for(&self, method: &Method) -> bool {
+        self.used + Self::frame_size(method) <= self.max_size
+    }
 
-        while i < max_locals {
-            if i < frame.local_variables.vars.len() && current_arg_index < args.len() {
-                let current_arg = args[current_arg_index].clone();
+    /// Whether a nested run of the dispatch loop fits in what is left of the stack, the runs
+    /// recurse on the host stack so they take room even when they run no frame. The host
+    /// stack must have room for it as well down to the given end, see `host_stack_end`, since
+    /// the nested runs of the threads suspended in theirs are on it too
+    pub fn has_room_for_run(&self, host_stack_end: usize) -> bool {
+        self.used + NESTED_RUN_SIZE <= self.max_size
+            && host_stack_address() > host_stack_end + HOST_RUN_SIZE
+    }
 
-                // NOTE: Double and long values take two places in the local variables array
-                // of the current frame meanwhile any other type takes just one place
+    /// Size of the stack in bytes
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
 
-                if matches!(current_arg, Value::Long(_) | Value::Double(_)) {
-                    frame.local_variables.vars[i] = Some(current_arg.clone());
-                    frame.local_variables.vars[i + 1] = Some(current_arg);
-                    i += 1;
-                } else {
-                    frame.local_variables.vars[i] = Some(current_arg);
-                }
+    /// Starts a nested run of the dispatch loop, whether it fits is checked with
+    /// `has_room_for_run` beforehand
+    pub fn enter_run(&mut self) {
+        self.nested += 1;
+        self.used += NESTED_RUN_SIZE;
+    }
 
-                current_arg_index += 1;
-            }
+    /// Ends the nested run started last
+    pub fn exit_run(&mut self) {
+        self.nested -= 1;
+        self.used -= NESTED_RUN_SIZE;
+    }
+
+    /// Whether a nested run of the dispatch loop is in progress
+    pub fn is_nested(&self) -> bool {
+        self.nested > 0
+    }
+
+    /// Handle pushing frames, whether they fit is checked with `has_room_for` beforehand
+    pub fn push_frame(
+        &mut self,
+        class: Rc<Class>,
+        method: Rc<Method>,
+        args: Vec<Value>,
+    ) -> Result<(), VmError> {
+        // Only methods with a Code attribute are executed in a frame
+        let (max_locals, bytecode) = match &method.code {
+            Some(code) => (code.max_locals as usize, code.code.clone()),
+            None => (0, Vec::new()),
+        };
 
-            i += 1;
+        // Create the frame and initialize it
+        let mut frame = Frame::new(class, method, max_locals, bytecode);
+
+        // The arguments are the first local variables of the frame, a long or a double
+        // argument takes two of them
+        let mut index = 0;
+        for arg in args {
+            let wide = matches!(arg, Value::Long(_) | Value::Double(_));
+            frame.local_variables.set(index, arg)?;
+            index += if wide { 2 } else { 1 };
         }
 
+        self.used += Self::frame_size(&frame.method);
         self.frames.push(frame);
+
+        Ok(())
     }
 
     /// Handle popping frames
     pub fn pop_frame(&mut self) -> Option<Frame> {
-        self.frames.pop()
+        let frame = self.frames.pop()?;
+        self.used -= Self::frame_size(&frame.method);
+        Some(frame)
     }
     /// Handle borrowing current frame mutably
     pub fn current_frame(&mut self) -> Option<&mut Frame> {
@@ -79,46 +198,351 @@ impl CallStack {
 
     /// Handle getting current frame method name
     pub fn current_method_name(&self) -> Option<&str> {
-        self.current_frame_ref()?.method_name.as_deref()
+        self.current_frame_ref()
+            .map(|frame| frame.method.name.as_str())
+    }
+
+    /// References the stack keeps alive, the operand stacks and local variables of its
+    /// frames and its thread
+    pub fn roots(&self) -> impl Iterator<Item = &Value> {
+        self.frames
+            .iter()
+            .flat_map(|frame| {
+                frame
+                    .operand_stack
+                    .iter()
+                    .chain(frame.local_variables.values())
+            })
+            .chain(self.thread.roots())
     }
 
-    /// Handle executing frames
-    pub fn execute_frames(
+    /// Executes the frames above the given depth until they all completed, the invoked
+    /// methods run in frames pushed onto this stack rather than on the host stack
+    ///
+    /// Returns how the frame right above the depth completed, either normally with
+    /// `ReturnFromMethod` or abruptly with `ThrowException`, or `ContinueMethodExecution`
+    /// when the scheduler handed the call stack to a thread that carries on outside of
+    /// this loop, see `thread::schedule`. When the execution fails, the
+    /// error records the instruction of the current frame and the frames above the depth
+    /// are dropped.
+    pub fn execute_frames_above(
         &mut self,
-        class_file: &ClassFile,
+        depth: usize,
         runtime_data_area: &mut RuntimeDataArea,
-    ) {
-        while !self.is_empty() {
-            //TODO: This is a workaround to fool the borrow checker,
-            //TODO: Figure out a safe solution
-            // Execute using raw pointer to work around borrow checker
-            let execution_result = unsafe {
-                // Get a raw pointer to self
-                let self_ptr = self as *mut Self;
-
-                // Borrow current frame mutably
-                if let Some(current_frame) = (*self_ptr).current_frame() {
-                    // Pass self through the raw pointer ( second mutable borrow )
-                    current_frame.execute_frame(class_file, runtime_data_area, &mut *self_ptr)
-                } else {
-                    break;
+    ) -> Result<InstructionCompleted, VmError> {
+        self.dispatch(depth, runtime_data_area).map_err(|error| {
+            let error = match self.current_frame_ref() {
+                Some(frame) => error.at(SourceContext::new(&frame.method, frame.pc)),
+                None => error,
+            };
+
+            while self.size() > depth {
+                self.pop_frame();
+            }
+
+            error
+        })
+    }
+
+    /// The dispatch loop: executes the instruction at the pc of the current frame, pushes
+    /// the frames of the methods it invokes and hands their results back to their invokers
+    fn dispatch(
+        &mut self,
+        depth: usize,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
+        let instruction_executor = InstructionExecutor::new();
+
+        while self.size() > depth {
+            // The scheduler may run other threads between two instructions of this one
+            if !self.thread.tick() && !thread::schedule(runtime_data_area, self)? {
+                return Ok(InstructionCompleted::ContinueMethodExecution);
+            }
+
+            let mut completed = match self
+                .execute_instruction(&instruction_executor, runtime_data_area)?
+            {
+                InstructionCompleted::ContinueMethodExecution => continue,
+                InstructionCompleted::InvokeMethod(method, args) => {
+                    let invoked = instruction_executor
+                        .invoke(method, args, runtime_data_area, self)
+                        .or_else(|error| self.raise_java_error(error, runtime_data_area).map(Some));
+
+                    match invoked? {
+                        // The method runs in the frame just pushed
+                        None => continue,
+                        // Natives complete right away
+                        Some(InstructionCompleted::ReturnFromMethod(value)) => {
+                            self.resume_invoker(value);
+                            continue;
+                        }
+                        Some(completed) => completed,
+                    }
                 }
+                completed => completed,
             };
 
-            match execution_result {
-                Ok(returned) => {
-                    self.pop_frame();
-                    if let Some(value) = returned {
-                        if let Some(invoker_frame) = self.current_frame() {
-                            invoker_frame.operand_stack.push(value);
+            if let InstructionCompleted::ThrowNewException(class_name, message) = completed {
+                completed =
+                    InstructionCompleted::ThrowException(instruction_executor.new_exception(
+                        class_name,
+                        message,
+                        runtime_data_area,
+                        self,
+                    )?);
+            }
+
+            // Unwind the frames until one catches the exception or the invoker of the frame
+            // that completed resumes
+            loop {
+                if let InstructionCompleted::ThrowException(exception) = &completed {
+                    let frame = self.current_frame().ok_or("No current frame found")?;
+
+                    if let Some(handler) = instruction_executor.find_exception_handler(
+                        frame,
+                        frame.pc,
+                        exception,
+                        runtime_data_area,
+                    )? {
+                        debug_log!(
+                            "Exception caught, jumping to handler at pc: {}",
+                            handler.handler_pc
+                        );
+
+                        // The subroutines entered by a jsr the handler covers are left along
+                        // with the code that threw, like a ret to after that jsr would
+                        let covered = handler.start_pc as usize..handler.end_pc as usize;
+                        if let Some(depth) = frame
+                            .subroutines
+                            .iter()
+                            .position(|(_, return_address)| covered.contains(&(return_address - 1)))
+                        {
+                            frame.subroutines.truncate(depth);
                         }
+
+                        // AS SPECIFIED BY THE SPECS: the operand stack is cleared
+                        // and the exception is pushed before running the handler
+                        frame.operand_stack = OperandStack::new();
+                        frame.operand_stack.push(exception.clone());
+                        frame.pc = handler.handler_pc as usize;
+                        break;
+                    }
+                }
+
+                completed =
+                    self.complete_frame(completed, &instruction_executor, runtime_data_area)?;
+
+                if self.size() == depth {
+                    return Ok(completed);
+                }
+
+                match completed {
+                    InstructionCompleted::ReturnFromMethod(value) => {
+                        self.resume_invoker(value);
+                        break;
                     }
+                    // The exception is thrown again by the invoke instruction of the invoker
+                    _ => continue,
                 }
-                Err(msg) => {
-                    debug_log!("Error executing frame: {}", msg);
+            }
+        }
+
+        Err("No current frame found".into())
+    }
+
+    /// Executes the instruction at the pc of the current frame and moves the pc to the next
+    /// instruction to execute, the frame stays on the stack unless the instruction needs the
+    /// whole stack, see `InstructionExecutor::needs_call_stack`
+    fn execute_instruction(
+        &mut self,
+        instruction_executor: &InstructionExecutor,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
+        let frame = self.frames.last_mut().ok_or("No current frame found")?;
+        let mut pc = frame.pc;
+
+        let completed = match frame.bytecode.get(pc) {
+            Some(&byte) => match Opcode::decode(byte) {
+                Some(opcode) if instruction_executor.needs_call_stack(opcode) => {
+                    return self.execute_stack_instruction(
+                        opcode,
+                        instruction_executor,
+                        runtime_data_area,
+                    );
+                }
+                Some(opcode) => {
+                    debug_log!("Executing opcode: {:?} at pc: {}", opcode, pc);
+                    instruction_executor.execute_instruction(
+                        opcode,
+                        frame,
+                        runtime_data_area,
+                        &mut pc,
+                    )
+                }
+                None => {
+                    instruction_executor.execute_unsupported_opcode(byte, frame, runtime_data_area)
+                }
+            },
+            // Running past the last instruction returns from the method
+            None => Ok(InstructionCompleted::ReturnFromMethod(None)),
+        };
+
+        Self::advance(frame, pc, &completed);
+
+        completed.or_else(|error| self.raise_java_error(error, runtime_data_area))
+    }
+
+    /// Executes an instruction of the current frame that needs the whole stack, the frame is
+    /// taken off the stack while it runs
+    fn execute_stack_instruction(
+        &mut self,
+        opcode: Opcode,
+        instruction_executor: &InstructionExecutor,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
+        let mut frame = self.frames.pop().ok_or("No current frame found")?;
+        let mut pc = frame.pc;
+
+        debug_log!("Executing opcode: {:?} at pc: {}", opcode, pc);
+
+        let class = frame.class.clone();
+        let completed = instruction_executor.execute_stack_instruction(
+            opcode,
+            &mut frame,
+            &class.class_file,
+            runtime_data_area,
+            self,
+            &mut pc,
+        );
+
+        Self::advance(&mut frame, pc, &completed);
+        self.frames.push(frame);
+
+        completed.or_else(|error| self.raise_java_error(error, runtime_data_area))
+    }
+
+    /// Moves the pc of a frame past the instruction that completed, branches to the first
+    /// instruction leave the pc one before it, wrapped around
+    fn advance(frame: &mut Frame, pc: usize, completed: &Result<InstructionCompleted, VmError>) {
+        match completed {
+            Ok(InstructionCompleted::ContinueMethodExecution) => frame.pc = pc.wrapping_add(1),
+            Ok(InstructionCompleted::InvokeMethod(..)) => frame.return_pc = pc.wrapping_add(1),
+            _ => {}
+        }
+    }
+
+    /// Throws the linkage errors the program can catch from the instruction of the current frame
+    /// that raised them, e.g. an `AbstractMethodError` when it invokes an abstract method
+    ///
+    /// In strict mode, an instruction of the current frame using a part of the JDK or of the
+    /// class file format the VM does not implement yet throws the Java error of the gap,
+    /// telling what is missing and where, so that the program does not go on unaware of it
+    fn raise_java_error(
+        &self,
+        error: VmError,
+        runtime_data_area: &RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
+        match (error, self.current_frame_ref()) {
+            (
+                VmError::Throw {
+                    exception, message, ..
+                },
+                Some(_),
+            ) => Ok(InstructionCompleted::ThrowNewException(
+                exception,
+                Some(message),
+            )),
+            (
+                VmError::Unsupported {
+                    exception,
+                    message,
+                    context,
+                },
+                Some(frame),
+            ) if runtime_data_area.strict => {
+                let context = context
+                    .unwrap_or_else(|| Box::new(SourceContext::new(&frame.method, frame.pc)));
+                Ok(InstructionCompleted::ThrowNewException(
+                    exception,
+                    Some(format!("{}, at {}", message, context)),
+                ))
+            }
+            (error, _) => Err(error),
+        }
+    }
+
+    /// Pops the current frame once its method completed, exiting the monitor of a
+    /// synchronized method and handing the completion to the functional interface methods
+    /// forwarded to it
+    fn complete_frame(
+        &mut self,
+        mut completed: InstructionCompleted,
+        instruction_executor: &InstructionExecutor,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
+        let monitor = self.current_frame().and_then(|frame| frame.monitor.take());
+
+        // AS SPECIFIED BY THE SPECS: a method returning normally without owning the monitor
+        // it entered, after an unbalanced monitorexit, throws an IllegalMonitorStateException
+        if let Some(monitor) = &monitor
+            && !thread::monitor_exit(monitor, self)
+            && let InstructionCompleted::ReturnFromMethod(_) = completed
+        {
+            completed = InstructionCompleted::ThrowException(instruction_executor.new_exception(
+                "java/lang/IllegalMonitorStateException",
+                None,
+                runtime_data_area,
+                self,
+            )?);
+        }
+
+        let frame = self.pop_frame().ok_or("No current frame found")?;
+
+        for lambda_return in &frame.lambda_returns {
+            completed = match lambda_return.complete(completed, runtime_data_area, self)? {
+                InstructionCompleted::ThrowNewException(class_name, message) => {
+                    InstructionCompleted::ThrowException(instruction_executor.new_exception(
+                        class_name,
+                        message,
+                        runtime_data_area,
+                        self,
+                    )?)
                 }
+                completed => completed,
+            };
+        }
+
+        Ok(completed)
+    }
+
+    /// Resumes the current frame after the method its invoke instruction invoked returned,
+    /// pushing the returned value onto its operand stack
+    fn resume_invoker(&mut self, value: Option<Value>) {
+        if let Some(frame) = self.current_frame() {
+            if let Some(value) = value {
+                frame.operand_stack.push(value);
             }
+            frame.pc = frame.return_pc;
+        }
+    }
+
+    /// Runs an operation that needs every frame of the stack, e.g. a collection or a nested
+    /// invocation, from an instruction of the given frame while it is taken off the stack
+    pub fn with_frame<T>(
+        &mut self,
+        frame: &mut Frame,
+        operation: impl FnOnce(&mut CallStack) -> T,
+    ) -> T {
+        let placeholder = Frame::new(frame.class.clone(), frame.method.clone(), 0, Vec::new());
+        self.frames.push(std::mem::replace(frame, placeholder));
+
+        let result = operation(self);
+
+        if let Some(pushed) = self.frames.pop() {
+            *frame = pushed;
         }
+
+        result
     }
 
     /// Handle getting current call stack size
@@ -128,18 +552,20 @@ impl CallStack {
 
     /// Handle printing current stack frames
     pub fn print_frames(&self) {
-        for (i, frame) in self.frames.iter().enumerate() {
-            let frame_name = frame
-                .method_name
-                .as_deref()
-                .expect("Failed to get frame name!");
-            debug_log!("Frame[{}]: {}", i, frame_name);
-        }
+        debug_log!(
+            "{}",
+            self.frames
+                .iter()
+                .enumerate()
+                .map(|(i, frame)| format!("Frame[{}]: {}", i, frame.method.name))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
     }
 
     //TODO: Handle frames returning stuff
     //TODO: Handle frames returning void
-    // pub fn return_void(&mut self) -> Result<(), String> {
+    // pub fn return_void(&mut self) -> Result<(), VmError> {
     //     self.return_from_method(None)
     // }
     //TODO: Handle printing stack trace
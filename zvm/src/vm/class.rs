@@ -0,0 +1,338 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+};
+
+use crate::{
+    parser::{
+        access_flags::{
+            ACC_ABSTRACT, ACC_INTERFACE, ACC_NATIVE, ACC_PRIVATE, ACC_STATIC, ACC_SYNCHRONIZED,
+        },
+        class_file::ClassFile,
+        code_attribute::CodeAttribute,
+    },
+    vm::error::VmError,
+};
+
+/// A method declared by a loaded class
+#[derive(Debug)]
+pub struct Method {
+    /// Binary name of the declaring class
+    pub class_name: String,
+    pub name: String,
+    pub descriptor: String,
+    pub access_flags: u16,
+    /// `None` for abstract and native methods
+    pub code: Option<CodeAttribute>,
+    /// Slot of this method in the vtable of its declaring class, `None` for methods
+    /// that are never selected dynamically ( static, private and initialization methods )
+    pub vtable_index: Option<usize>,
+}
+
+impl Method {
+    pub fn is_static(&self) -> bool {
+        self.access_flags & ACC_STATIC != 0
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.access_flags & ACC_PRIVATE != 0
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.access_flags & ACC_NATIVE != 0
+    }
+
+    pub fn is_abstract(&self) -> bool {
+        self.access_flags & ACC_ABSTRACT != 0
+    }
+
+    pub fn is_synchronized(&self) -> bool {
+        self.access_flags & ACC_SYNCHRONIZED != 0
+    }
+
+    /// Checks if this method takes part in dynamic dispatch
+    /// ( neither static, private nor an initialization method )
+    pub fn is_virtual(&self) -> bool {
+        !self.is_static() && !self.is_private() && !self.name.starts_with('<')
+    }
+
+    /// Checks if two methods share the same name and descriptor
+    pub fn has_signature(&self, name: &str, descriptor: &str) -> bool {
+        self.name == name && self.descriptor == descriptor
+    }
+}
+
+/// A field declared by a loaded class
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub name: String,
+    pub descriptor: String,
+    pub access_flags: u16,
+}
+
+impl Field {
+    pub fn is_static(&self) -> bool {
+        self.access_flags & ACC_STATIC != 0
+    }
+}
+
+/// Implementation selected for an interface method in the itable of a class
+#[derive(Debug, Clone)]
+pub enum ItableEntry {
+    /// The method to invoke
+    Method(Rc<Method>),
+    /// No implementation is declared or inherited, invoking it raises an `AbstractMethodError`
+    Abstract,
+    /// More than one maximally-specific default method is inherited,
+    /// invoking it raises an `IncompatibleClassChangeError`
+    Conflict(Vec<Rc<Method>>),
+}
+
+/// Runtime representation of a loaded and linked class
+#[derive(Debug)]
+pub struct Class {
+    /// Binary name of the class, e.g. `java/lang/Object`
+    pub name: String,
+    pub access_flags: u16,
+    pub super_class: Option<Rc<Class>>,
+    /// Direct superinterfaces, in declaration order
+    pub interfaces: Vec<Rc<Class>>,
+    /// Parsed class file, empty for classes synthesized by the VM
+    pub class_file: ClassFile,
+    /// Methods declared by this class
+    pub methods: Vec<Rc<Method>>,
+    /// Fields declared by this class
+    pub fields: Vec<Field>,
+    /// Virtual method table built at link time, it holds the selected implementation
+    /// of every virtual method declared or inherited by this class.
+    /// A subclass vtable always starts with a copy of its superclass vtable, so the
+    /// slot of a method stays valid for every subclass of its declaring class.
+    pub vtable: Vec<Rc<Method>>,
+    /// Interface method table built at link time, it maps the name and descriptor of every
+    /// method declared by a superinterface to its selected implementation.
+    /// Empty for interfaces themselves, they are never the class of an object.
+    pub itable: HashMap<(String, String), ItableEntry>,
+}
+
+impl Class {
+    pub fn is_interface(&self) -> bool {
+        self.access_flags & ACC_INTERFACE != 0
+    }
+
+    pub fn is_abstract(&self) -> bool {
+        self.access_flags & ACC_ABSTRACT != 0
+    }
+
+    /// Finds a method declared by this class only
+    pub fn find_method(&self, name: &str, descriptor: &str) -> Option<Rc<Method>> {
+        self.methods
+            .iter()
+            .find(|method| method.has_signature(name, descriptor))
+            .cloned()
+    }
+
+    /// Looks up a method in this class and then in its superclasses
+    /// AS SPECIFIED BY THE SPECS: §5.4.3.3 ( method resolution, step 2 )
+    pub fn lookup_method(&self, name: &str, descriptor: &str) -> Option<Rc<Method>> {
+        if let Some(method) = self.find_method(name, descriptor) {
+            return Some(method);
+        }
+
+        self.super_class
+            .as_ref()
+            .and_then(|super_class| super_class.lookup_method(name, descriptor))
+    }
+
+    /// Looks up an instance method that can be selected for an interface method,
+    /// skipping static and private declarations
+    /// AS SPECIFIED BY THE SPECS: §5.4.6 ( method selection, steps 1 and 2 )
+    pub fn lookup_instance_method(&self, name: &str, descriptor: &str) -> Option<Rc<Method>> {
+        let declared = self.methods.iter().find(|method| {
+            method.has_signature(name, descriptor) && !method.is_static() && !method.is_private()
+        });
+
+        if let Some(method) = declared {
+            return Some(method.clone());
+        }
+
+        self.super_class
+            .as_ref()
+            .and_then(|super_class| super_class.lookup_instance_method(name, descriptor))
+    }
+
+    /// Returns every superinterface of this class, direct or not, including the ones
+    /// inherited from its superclasses, each of them only once and in declaration order
+    pub fn all_superinterfaces(&self) -> Vec<Rc<Class>> {
+        let mut superinterfaces: Vec<Rc<Class>> = Vec::new();
+        let mut pending: VecDeque<Rc<Class>> = self.interfaces.iter().cloned().collect();
+
+        let mut super_class = self.super_class.clone();
+        while let Some(class) = super_class {
+            pending.extend(class.interfaces.iter().cloned());
+            super_class = class.super_class.clone();
+        }
+
+        while let Some(interface) = pending.pop_front() {
+            if superinterfaces
+                .iter()
+                .any(|known| known.name == interface.name)
+            {
+                continue;
+            }
+
+            pending.extend(interface.interfaces.iter().cloned());
+            superinterfaces.push(interface);
+        }
+
+        superinterfaces
+    }
+
+    /// Finds the maximally-specific superinterface methods of this class with the given
+    /// name and descriptor: the non-private, non-static ones not declared by a superinterface
+    /// of another candidate's declaring interface
+    /// AS SPECIFIED BY THE SPECS: §5.4.3.3 ( method resolution, step 3 )
+    pub fn maximally_specific_methods(&self, name: &str, descriptor: &str) -> Vec<Rc<Method>> {
+        let candidates: Vec<(Rc<Class>, Rc<Method>)> = self
+            .all_superinterfaces()
+            .into_iter()
+            .filter_map(|interface| {
+                let method = interface.find_method(name, descriptor)?;
+                if method.is_static() || method.is_private() {
+                    return None;
+                }
+
+                Some((interface, method))
+            })
+            .collect();
+
+        candidates
+            .iter()
+            .filter(|(interface, _)| {
+                !candidates.iter().any(|(other, _)| {
+                    other.name != interface.name && other.is_subtype_of(&interface.name)
+                })
+            })
+            .map(|(_, method)| method.clone())
+            .collect()
+    }
+
+    /// Looks up a method in the superinterfaces of this class, preferring
+    /// the only maximally-specific default method if there is one
+    /// AS SPECIFIED BY THE SPECS: §5.4.3.3 ( method resolution, step 3 )
+    pub fn lookup_interface_method(&self, name: &str, descriptor: &str) -> Option<Rc<Method>> {
+        let methods = self.maximally_specific_methods(name, descriptor);
+        let defaults: Vec<&Rc<Method>> = methods
+            .iter()
+            .filter(|method| !method.is_abstract())
+            .collect();
+
+        if defaults.len() == 1 {
+            return Some(defaults[0].clone());
+        }
+
+        methods.into_iter().next()
+    }
+
+    /// Looks up a field in this class, then in its superinterfaces and then in its
+    /// superclasses and returns it alongside the name of the class declaring it
+    /// AS SPECIFIED BY THE SPECS: §5.4.3.2 ( field resolution )
+    pub fn lookup_field(&self, name: &str) -> Option<(String, Field)> {
+        if let Some(field) = self.fields.iter().find(|field| field.name == name) {
+            return Some((self.name.clone(), field.clone()));
+        }
+
+        if let Some(found) = self
+            .interfaces
+            .iter()
+            .find_map(|interface| interface.lookup_field(name))
+        {
+            return Some(found);
+        }
+
+        self.super_class
+            .as_ref()
+            .and_then(|super_class| super_class.lookup_field(name))
+    }
+
+    /// Checks if this class is the given class or one of its subclasses
+    pub fn is_subclass_of(&self, name: &str) -> bool {
+        if self.name == name {
+            return true;
+        }
+
+        match &self.super_class {
+            Some(super_class) => super_class.is_subclass_of(name),
+            None => false,
+        }
+    }
+
+    /// Checks if this class is the given class or interface, one of its subclasses
+    /// or one of its implementations
+    pub fn is_subtype_of(&self, name: &str) -> bool {
+        if self.name == name {
+            return true;
+        }
+
+        if self
+            .interfaces
+            .iter()
+            .any(|interface| interface.is_subtype_of(name))
+        {
+            return true;
+        }
+
+        match &self.super_class {
+            Some(super_class) => super_class.is_subtype_of(name),
+            None => false,
+        }
+    }
+
+    /// Selects the implementation of a resolved method for an instance of this class
+    /// AS SPECIFIED BY THE SPECS: §5.4.6 ( method selection ), answered by the vtable for
+    /// class methods and by the itable for interface methods
+    pub fn select_method(&self, resolved: &Rc<Method>) -> Result<Rc<Method>, VmError> {
+        if resolved.is_private() {
+            return Ok(resolved.clone());
+        }
+
+        if let Some(index) = resolved.vtable_index {
+            return Ok(self
+                .vtable
+                .get(index)
+                .cloned()
+                .unwrap_or_else(|| resolved.clone()));
+        }
+
+        let key = (resolved.name.clone(), resolved.descriptor.clone());
+
+        match self.itable.get(&key) {
+            Some(ItableEntry::Method(method)) => Ok(method.clone()),
+            Some(ItableEntry::Abstract) => Err(VmError::throw(
+                "java/lang/AbstractMethodError",
+                format!(
+                    "Receiver class {} does not define or inherit an implementation of the resolved method '{}{}' of interface {}.",
+                    self.name.replace('/', "."),
+                    resolved.name,
+                    resolved.descriptor,
+                    resolved.class_name.replace('/', ".")
+                ),
+            )),
+            Some(ItableEntry::Conflict(methods)) => Err(VmError::throw(
+                "java/lang/IncompatibleClassChangeError",
+                format!(
+                    "Conflicting default methods: {}",
+                    methods
+                        .iter()
+                        .map(|method| format!(
+                            "{}.{}",
+                            method.class_name.replace('/', "."),
+                            method.name
+                        ))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                ),
+            )),
+            None => Ok(resolved.clone()),
+        }
+    }
+}
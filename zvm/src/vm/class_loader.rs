@@ -0,0 +1,472 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+    rc::Rc,
+};
+
+use crate::{
+    debug_log,
+    parser::{
+        access_flags::{
+            ACC_FINAL, ACC_INTERFACE, ACC_PRIVATE, ACC_PROTECTED, ACC_PUBLIC, ACC_SYNTHETIC,
+        },
+        class_file::ClassFile,
+        reader::Reader,
+    },
+    vm::{
+        bootstrap::{BootstrapClass, find_bootstrap_class, is_jdk_class},
+        class::{Class, Field, ItableEntry, Method},
+        error::VmError,
+        verifier::verify_subroutines,
+    },
+};
+
+/// Loads classes from the class path ( or from the VM bootstrap classes )
+/// and links them into runtime classes
+pub struct ClassLoader {
+    /// Directories searched for `<binary name>.class` files
+    class_path: Vec<PathBuf>,
+    /// Loaded and linked classes by binary name
+    classes: HashMap<String, Rc<Class>>,
+    /// Classes whose superclass or superinterfaces are currently being loaded, used to detect cycles
+    loading: HashSet<String>,
+    /// Number of classes spun for lambda call sites so far, used to name them
+    lambda_classes: usize,
+}
+
+impl ClassLoader {
+    pub fn new() -> Self {
+        Self {
+            class_path: Vec::new(),
+            classes: HashMap::new(),
+            loading: HashSet::new(),
+            lambda_classes: 0,
+        }
+    }
+
+    /// Appends a directory to the class path
+    pub fn add_class_path(&mut self, path: PathBuf) {
+        self.class_path.push(path);
+    }
+
+    /// Loads, links and returns the class of the given binary name
+    pub fn load_class(&mut self, name: &str) -> Result<Rc<Class>, VmError> {
+        if let Some(class) = self.classes.get(name) {
+            return Ok(class.clone());
+        }
+
+        if let Some(bootstrap_class) = find_bootstrap_class(name) {
+            return self.define_bootstrap_class(bootstrap_class);
+        }
+
+        let class_file = match self.find_class_file(name) {
+            Some(class_file) => class_file,
+            None if is_jdk_class(name) => {
+                return Err(VmError::unsupported_library(format!(
+                    "class {} is not implemented",
+                    name.replace('/', ".")
+                )));
+            }
+            None => return Err(VmError::linkage(format!("NoClassDefFoundError: {}", name))),
+        };
+
+        self.define_class(class_file)
+    }
+
+    /// Links an already parsed class file and registers it in the loader
+    pub fn define_class(&mut self, class_file: ClassFile) -> Result<Rc<Class>, VmError> {
+        let name = class_file
+            .this_class_name()
+            .ok_or("ClassFormatError: invalid this_class entry")?;
+
+        if let Some(class) = self.classes.get(&name) {
+            return Ok(class.clone());
+        }
+
+        let super_class = self.load_super_class(&name, class_file.super_class_name())?;
+        let interfaces = self.load_interfaces(
+            &name,
+            class_file
+                .interface_names()
+                .ok_or("ClassFormatError: invalid interfaces entry")?,
+        )?;
+
+        let methods = class_file
+            .methods
+            .iter()
+            .map(|method_info| {
+                let method_name = class_file
+                    .get_utf8(method_info.name_index)
+                    .ok_or("ClassFormatError: invalid method name")?;
+                let descriptor = class_file
+                    .get_utf8(method_info.descriptor_index)
+                    .ok_or("ClassFormatError: invalid method descriptor")?;
+
+                Ok(Method {
+                    class_name: name.clone(),
+                    name: method_name,
+                    descriptor,
+                    access_flags: method_info.access_flags,
+                    code: class_file.get_code_attribute(method_info),
+                    vtable_index: None,
+                })
+            })
+            .collect::<Result<Vec<_>, VmError>>()?;
+
+        // Only the class files older than version 51 may contain subroutines
+        if class_file.major < 51 {
+            for method in &methods {
+                verify_subroutines(&class_file, method)?;
+            }
+        }
+
+        let fields = class_file
+            .fields
+            .iter()
+            .map(|field_info| {
+                Ok(Field {
+                    name: class_file
+                        .get_utf8(field_info.name_index)
+                        .ok_or("ClassFormatError: invalid field name")?,
+                    descriptor: class_file
+                        .get_utf8(field_info.descriptor_index)
+                        .ok_or("ClassFormatError: invalid field descriptor")?,
+                    access_flags: field_info.access_flags,
+                })
+            })
+            .collect::<Result<Vec<_>, VmError>>()?;
+
+        let access_flags = class_file.access_flags;
+        let class = Self::link(
+            name,
+            access_flags,
+            super_class,
+            interfaces,
+            class_file,
+            methods,
+            fields,
+        );
+
+        Ok(self.register(class))
+    }
+
+    /// Builds a runtime class out of a bootstrap class definition
+    fn define_bootstrap_class(
+        &mut self,
+        bootstrap_class: &BootstrapClass,
+    ) -> Result<Rc<Class>, VmError> {
+        let name = bootstrap_class.name.to_string();
+        let super_class =
+            self.load_super_class(&name, bootstrap_class.super_class.map(str::to_string))?;
+        let interfaces = self.load_interfaces(
+            &name,
+            bootstrap_class
+                .interfaces
+                .iter()
+                .map(|interface| interface.to_string())
+                .collect(),
+        )?;
+
+        let methods = bootstrap_class
+            .methods
+            .iter()
+            .map(|(method_name, descriptor, access_flags)| Method {
+                class_name: name.clone(),
+                name: method_name.to_string(),
+                descriptor: descriptor.to_string(),
+                access_flags: *access_flags,
+                code: None,
+                vtable_index: None,
+            })
+            .collect();
+
+        let fields = bootstrap_class
+            .fields
+            .iter()
+            .map(|(field_name, descriptor, access_flags)| Field {
+                name: field_name.to_string(),
+                descriptor: descriptor.to_string(),
+                access_flags: *access_flags,
+            })
+            .collect();
+
+        let class = Self::link(
+            name,
+            bootstrap_class.access_flags,
+            super_class,
+            interfaces,
+            ClassFile::default(),
+            methods,
+            fields,
+        );
+
+        Ok(self.register(class))
+    }
+
+    /// Spins the class of the instances of a lambda call site, a final class implementing
+    /// the given interfaces whose methods have no code, the VM forwards them to the
+    /// implementation method of the call site, and whose fields hold the captured arguments
+    pub fn define_lambda_class(
+        &mut self,
+        caller: &str,
+        interface_names: Vec<String>,
+        methods: Vec<(String, String)>,
+        captured: &[String],
+    ) -> Result<Rc<Class>, VmError> {
+        self.lambda_classes += 1;
+        let name = format!("{}$$Lambda${}", caller, self.lambda_classes);
+
+        let super_class = self.load_super_class(&name, Some("java/lang/Object".to_string()))?;
+        let interfaces = self.load_interfaces(&name, interface_names)?;
+
+        let methods = methods
+            .into_iter()
+            .map(|(method_name, descriptor)| Method {
+                class_name: name.clone(),
+                name: method_name,
+                descriptor,
+                access_flags: ACC_PUBLIC,
+                code: None,
+                vtable_index: None,
+            })
+            .collect();
+
+        let fields = captured
+            .iter()
+            .enumerate()
+            .map(|(index, descriptor)| Field {
+                name: format!("arg${}", index + 1),
+                descriptor: descriptor.clone(),
+                access_flags: ACC_PRIVATE | ACC_FINAL,
+            })
+            .collect();
+
+        let class = Self::link(
+            name,
+            ACC_FINAL | ACC_SYNTHETIC,
+            super_class,
+            interfaces,
+            ClassFile::default(),
+            methods,
+            fields,
+        );
+
+        Ok(self.register(class))
+    }
+
+    /// Loads the superclass of the class being defined, reporting circular inheritance
+    fn load_super_class(
+        &mut self,
+        name: &str,
+        super_class_name: Option<String>,
+    ) -> Result<Option<Rc<Class>>, VmError> {
+        let Some(super_class_name) = super_class_name else {
+            return Ok(None);
+        };
+
+        let super_class = self.load_super_type(name, &super_class_name)?;
+
+        if super_class.is_interface() {
+            return Err(VmError::linkage(format!(
+                "IncompatibleClassChangeError: class {} has interface {} as super class",
+                name.replace('/', "."),
+                super_class_name.replace('/', ".")
+            )));
+        }
+
+        Ok(Some(super_class))
+    }
+
+    /// Loads the direct superinterfaces of the class being defined
+    fn load_interfaces(
+        &mut self,
+        name: &str,
+        interface_names: Vec<String>,
+    ) -> Result<Vec<Rc<Class>>, VmError> {
+        interface_names
+            .iter()
+            .map(|interface_name| {
+                let interface = self.load_super_type(name, interface_name)?;
+
+                if !interface.is_interface() {
+                    return Err(VmError::linkage(format!(
+                        "IncompatibleClassChangeError: class {} can not implement {}, because it is not an interface",
+                        name.replace('/', "."),
+                        interface_name.replace('/', ".")
+                    )));
+                }
+
+                Ok(interface)
+            })
+            .collect()
+    }
+
+    /// Loads a superclass or superinterface of the class being defined, reporting circular inheritance
+    fn load_super_type(&mut self, name: &str, super_type_name: &str) -> Result<Rc<Class>, VmError> {
+        if !self.loading.insert(name.to_string()) {
+            return Err(VmError::linkage(format!("ClassCircularityError: {}", name)));
+        }
+
+        let super_type = self.load_class(super_type_name);
+        self.loading.remove(name);
+
+        super_type
+    }
+
+    /// Stores a freshly linked class so later lookups return the same instance
+    fn register(&mut self, class: Class) -> Rc<Class> {
+        debug_log!("Loaded class {}", class.name);
+
+        let class = Rc::new(class);
+        self.classes.insert(class.name.clone(), class.clone());
+        class
+    }
+
+    /// Reads and parses the class file of the given binary name from the class path
+    fn find_class_file(&self, name: &str) -> Option<ClassFile> {
+        self.class_path.iter().find_map(|directory| {
+            let bytes = fs::read(directory.join(format!("{}.class", name))).ok()?;
+
+            let mut reader = Reader::from_bytes(bytes);
+            reader.read();
+            Some(reader.get_class_file())
+        })
+    }
+
+    /// Links the declared methods of a class against its superclass and builds its vtable
+    ///
+    /// The vtable starts as a copy of the superclass vtable. Every declared method that
+    /// overrides an inherited one takes over its slot ( AS SPECIFIED BY THE SPECS: §5.4.5 ),
+    /// any other virtual method is appended at the end of the table.
+    /// Interface methods never get a vtable slot, they are selected through the itable.
+    fn link(
+        name: String,
+        access_flags: u16,
+        super_class: Option<Rc<Class>>,
+        interfaces: Vec<Rc<Class>>,
+        class_file: ClassFile,
+        declared_methods: Vec<Method>,
+        fields: Vec<Field>,
+    ) -> Class {
+        let package = match name.rfind('/') {
+            Some(index) => name[..index].to_string(),
+            None => String::new(),
+        };
+
+        let mut vtable = super_class
+            .as_ref()
+            .map(|super_class| super_class.vtable.clone())
+            .unwrap_or_default();
+
+        let mut methods = Vec::with_capacity(declared_methods.len());
+
+        let is_interface = access_flags & ACC_INTERFACE != 0;
+
+        for mut method in declared_methods {
+            if is_interface || !method.is_virtual() {
+                methods.push(Rc::new(method));
+                continue;
+            }
+
+            let overridden: Vec<usize> = vtable
+                .iter()
+                .enumerate()
+                .filter(|(_, inherited)| {
+                    inherited.has_signature(&method.name, &method.descriptor)
+                        && Self::can_override(&package, inherited)
+                })
+                .map(|(index, _)| index)
+                .collect();
+
+            method.vtable_index = Some(overridden.first().copied().unwrap_or(vtable.len()));
+            let method = Rc::new(method);
+
+            if overridden.is_empty() {
+                vtable.push(method.clone());
+            } else {
+                for index in overridden {
+                    vtable[index] = method.clone();
+                }
+            }
+
+            methods.push(method);
+        }
+
+        let mut class = Class {
+            name,
+            access_flags,
+            super_class,
+            interfaces,
+            class_file,
+            methods,
+            fields,
+            vtable,
+            itable: HashMap::new(),
+        };
+
+        if !is_interface {
+            class.itable = Self::build_itable(&class);
+        }
+
+        class
+    }
+
+    /// Selects the implementation of every method declared by the superinterfaces of a class
+    /// AS SPECIFIED BY THE SPECS: §5.4.6 ( method selection )
+    ///
+    /// An instance method declared or inherited from the superclasses wins, otherwise the only
+    /// maximally-specific default method is selected.
+    fn build_itable(class: &Class) -> HashMap<(String, String), ItableEntry> {
+        let mut itable = HashMap::new();
+
+        for interface in class.all_superinterfaces() {
+            for method in interface
+                .methods
+                .iter()
+                .filter(|method| method.is_virtual())
+            {
+                let key = (method.name.clone(), method.descriptor.clone());
+                if itable.contains_key(&key) {
+                    continue;
+                }
+
+                let entry = match class.lookup_instance_method(&method.name, &method.descriptor) {
+                    Some(selected) => ItableEntry::Method(selected),
+                    None => {
+                        let mut defaults: Vec<Rc<Method>> = class
+                            .maximally_specific_methods(&method.name, &method.descriptor)
+                            .into_iter()
+                            .filter(|candidate| !candidate.is_abstract())
+                            .collect();
+
+                        match defaults.len() {
+                            0 => ItableEntry::Abstract,
+                            1 => ItableEntry::Method(defaults.remove(0)),
+                            _ => ItableEntry::Conflict(defaults),
+                        }
+                    }
+                };
+
+                itable.insert(key, entry);
+            }
+        }
+
+        itable
+    }
+
+    /// Checks if a method declared in the given package can override an inherited method
+    fn can_override(package: &str, inherited: &Method) -> bool {
+        if inherited.access_flags & (ACC_PUBLIC | ACC_PROTECTED) != 0 {
+            return true;
+        }
+
+        // Package private methods can only be overridden from the same runtime package
+        let inherited_package = match inherited.class_name.rfind('/') {
+            Some(index) => &inherited.class_name[..index],
+            None => "",
+        };
+
+        inherited_package == package
+    }
+}
@@ -0,0 +1,72 @@
+use std::io::{self, Write};
+
+/// File descriptors of the standard streams, the `fd` field of a `java/io/PrintStream`
+pub const STDOUT: i32 = 1;
+pub const STDERR: i32 = 2;
+
+/// Size of the buffer of each standard stream, the one `System.out` and `System.err`
+/// get from `System.initPhase1`
+pub const BUFFER_CAPACITY: usize = 128;
+
+/// The standard output and error streams of the VM
+///
+/// Like the `BufferedOutputStream` under `System.out` and `System.err`, each stream keeps the
+/// bytes written with `PrintStream.write(int)` until a line separator, a `flush()`, a full
+/// buffer or the next `print` of the same stream. Every other write goes straight through,
+/// and bytes still buffered when the VM exits are lost the same way they are with HotSpot
+#[derive(Default)]
+pub struct Console {
+    out: Vec<u8>,
+    err: Vec<u8>,
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends bytes to the buffer of a stream, writing the buffer first if it is full
+    pub fn write(&mut self, fd: i32, bytes: &[u8]) {
+        for byte in bytes {
+            if self.buffer(fd).len() >= BUFFER_CAPACITY {
+                self.flush(fd);
+            }
+            self.buffer(fd).push(*byte);
+        }
+    }
+
+    /// Writes bytes to a stream right away, after whatever is still buffered
+    pub fn print(&mut self, fd: i32, bytes: &[u8]) {
+        self.buffer(fd).extend_from_slice(bytes);
+        self.flush(fd);
+    }
+
+    /// Writes the buffered bytes of a stream
+    pub fn flush(&mut self, fd: i32) {
+        let bytes = std::mem::take(self.buffer(fd));
+
+        // Write errors are ignored, PrintStream only records them in checkError()
+        let _ = match fd {
+            STDERR => io::stderr().write_all(&bytes),
+            _ => io::stdout()
+                .write_all(&bytes)
+                .and_then(|_| io::stdout().flush()),
+        };
+    }
+
+    fn buffer(&mut self, fd: i32) -> &mut Vec<u8> {
+        match fd {
+            STDERR => &mut self.err,
+            _ => &mut self.out,
+        }
+    }
+}
+
+/// Encodes UTF-16 code units to the UTF-8 bytes a `PrintStream` writes,
+/// unpaired surrogates are replaced by `?` like the encoder of `PrintStream` does
+pub fn encode(chars: &[u16]) -> Vec<u8> {
+    char::decode_utf16(chars.iter().copied())
+        .map(|c| c.unwrap_or('?'))
+        .collect::<String>()
+        .into_bytes()
+}
@@ -0,0 +1,268 @@
+use std::{collections::HashSet, rc::Rc};
+
+use crate::{
+    parser::{
+        class_file::ClassFile,
+        constant_pool_info::CpInfo,
+        opcode::{Opcode, instruction_length, opcode_at},
+    },
+    vm::{
+        bootstrap::is_jdk_class,
+        call_site::{self, REF_INVOKE_INTERFACE},
+        class::{Class, Method},
+        error::{SourceContext, VmError},
+        instruction_exec::InstructionExecutor,
+        runtime::RuntimeDataArea,
+    },
+};
+
+/// An instruction using a part of the JDK or of the class file format the VM does not
+/// implement yet, in strict mode executing it throws an error
+#[derive(Debug)]
+pub struct Gap {
+    pub context: SourceContext,
+    pub description: String,
+}
+
+/// Scans every method of the given class and of the classes of the class path it references,
+/// directly or not, for the instructions the VM cannot execute
+///
+/// Nothing is initialized or executed, the referenced classes, methods and fields are only
+/// resolved the way the instructions would resolve them
+pub fn scan(
+    main_class: Rc<Class>,
+    runtime_data_area: &mut RuntimeDataArea,
+) -> Result<Vec<Gap>, VmError> {
+    let instruction_executor = InstructionExecutor::new();
+    let mut gaps = Vec::new();
+    let mut scanned = HashSet::from([main_class.name.clone()]);
+    let mut pending = vec![main_class];
+
+    while let Some(class) = pending.pop() {
+        for method in &class.methods {
+            scan_method(
+                &instruction_executor,
+                &class.class_file,
+                method,
+                runtime_data_area,
+                &mut gaps,
+            );
+        }
+
+        // The classes of the program are scanned as well, the JDK is provided by the VM
+        for entry in &class.class_file.constant_pool {
+            let CpInfo::Class { name_index } = entry else {
+                continue;
+            };
+            let Some(name) = class.class_file.get_utf8(*name_index) else {
+                continue;
+            };
+
+            if name.starts_with('[') || is_jdk_class(&name) || !scanned.insert(name.clone()) {
+                continue;
+            }
+
+            // Classes that cannot be loaded fail when the program uses them, not a gap of the VM
+            if let Ok(referenced) = runtime_data_area.class_loader.load_class(&name) {
+                pending.push(referenced);
+            }
+        }
+    }
+
+    Ok(gaps)
+}
+
+/// Scans the instructions of a method, in the order of their pcs
+fn scan_method(
+    instruction_executor: &InstructionExecutor,
+    class_file: &ClassFile,
+    method: &Rc<Method>,
+    runtime_data_area: &mut RuntimeDataArea,
+    gaps: &mut Vec<Gap>,
+) {
+    let Some(code) = method.code.as_ref().map(|code| &code.code) else {
+        return;
+    };
+
+    let mut pc = 0;
+
+    while pc < code.len() {
+        let gap = match opcode_at(code, pc) {
+            Some(opcode) => scan_instruction(
+                instruction_executor,
+                class_file,
+                opcode,
+                code,
+                pc,
+                runtime_data_area,
+            ),
+            None => Some(format!("opcode 0x{:02x} is not implemented", code[pc])),
+        };
+
+        if let Some(description) = gap {
+            gaps.push(Gap {
+                context: SourceContext::new(method, pc),
+                description,
+            });
+        }
+
+        // An undefined byte is skipped alone, like the lenient mode executes it
+        pc += instruction_length(code, pc).unwrap_or(1);
+    }
+}
+
+/// Describes what the VM cannot execute of an instruction, `None` if it can execute all of it
+fn scan_instruction(
+    instruction_executor: &InstructionExecutor,
+    class_file: &ClassFile,
+    opcode: Opcode,
+    code: &[u8],
+    pc: usize,
+    runtime_data_area: &mut RuntimeDataArea,
+) -> Option<String> {
+    let index = match opcode {
+        Opcode::Ldc => *code.get(pc + 1)? as u16,
+        _ => u16::from_be_bytes([*code.get(pc + 1)?, *code.get(pc + 2)?]),
+    };
+
+    match opcode {
+        Opcode::Ldc | Opcode::Ldc_w => match class_file.constant_pool.get(index as usize)? {
+            CpInfo::Class { .. } => Some("loading a Class constant is not implemented".into()),
+            CpInfo::MethodType { .. } => {
+                Some("loading a MethodType constant is not implemented".into())
+            }
+            CpInfo::MethodHandle { .. } => {
+                Some("loading a MethodHandle constant is not implemented".into())
+            }
+            _ => None,
+        },
+        Opcode::Invokevirtual
+        | Opcode::Invokespecial
+        | Opcode::Invokestatic
+        | Opcode::Invokeinterface => {
+            let resolved =
+                instruction_executor.resolve_method_ref(class_file, index, runtime_data_area);
+            unsupported_method(resolved.map(|(_, _, _, method)| method), runtime_data_area)
+        }
+        Opcode::Getstatic | Opcode::Putstatic => {
+            let (class_name, field_name, _) = class_file.get_field_info(index)?;
+            let resolved = instruction_executor.resolve_static_field(
+                &class_name,
+                &field_name,
+                runtime_data_area,
+            );
+            unsupported(resolved.err()?)
+        }
+        Opcode::Getfield | Opcode::Putfield => {
+            let (class_name, field_name, _) = class_file.get_field_info(index)?;
+            let resolved = instruction_executor.resolve_instance_field(
+                &class_name,
+                &field_name,
+                runtime_data_area,
+            );
+            unsupported(resolved.err()?)
+        }
+        Opcode::New
+        | Opcode::Anewarray
+        | Opcode::Multianewarray
+        | Opcode::Checkcast
+        | Opcode::Instanceof => {
+            let class_name = class_file.get_class_name(index)?;
+            // Arrays of references need the class of their elements
+            let element = class_name.trim_start_matches('[');
+            let element = match element.strip_prefix('L') {
+                Some(element) if class_name.starts_with('[') => element.trim_end_matches(';'),
+                _ if class_name.starts_with('[') => return None,
+                _ => element,
+            };
+
+            unsupported(runtime_data_area.class_loader.load_class(element).err()?)
+        }
+        Opcode::Invokedynamic => {
+            scan_call_site(instruction_executor, class_file, index, runtime_data_area)
+        }
+        _ => None,
+    }
+}
+
+/// Describes what the VM cannot execute of an `invokedynamic` call site: a bootstrap method
+/// not built into the VM, or the implementation method of a lambda
+fn scan_call_site(
+    instruction_executor: &InstructionExecutor,
+    class_file: &ClassFile,
+    index: u16,
+    runtime_data_area: &mut RuntimeDataArea,
+) -> Option<String> {
+    let (bootstrap_index, _, _) = class_file.get_invoke_dynamic_info(index)?;
+    let bootstrap_method = class_file.get_bootstrap_method(bootstrap_index)?;
+    let (_, class_name, method_name, _) =
+        class_file.get_method_handle_info(bootstrap_method.method_ref)?;
+
+    match (class_name.as_str(), method_name.as_str()) {
+        (call_site::STRING_CONCAT_FACTORY, "makeConcatWithConstants" | "makeConcat") => None,
+        (call_site::LAMBDA_METAFACTORY, "metafactory" | "altMetafactory") => {
+            // AS SPECIFIED BY THE SPECS: the second static argument is the implementation method
+            let implementation = *bootstrap_method.arguments.get(1)?;
+            let (kind, class_name, method_name, descriptor) =
+                class_file.get_method_handle_info(implementation)?;
+
+            let resolved = match kind {
+                REF_INVOKE_INTERFACE => instruction_executor.resolve_interface_method(
+                    &class_name,
+                    &method_name,
+                    &descriptor,
+                    runtime_data_area,
+                ),
+                _ => instruction_executor.resolve_method(
+                    &class_name,
+                    &method_name,
+                    &descriptor,
+                    runtime_data_area,
+                ),
+            };
+
+            unsupported_method(resolved, runtime_data_area)
+        }
+        _ => Some(format!(
+            "bootstrap method {}.{} is not implemented",
+            class_name.replace('/', "."),
+            method_name
+        )),
+    }
+}
+
+/// Describes what the VM cannot execute of a method resolution: a method of the JDK it does
+/// not provide, or a native method of the JDK without an implementation
+fn unsupported_method(
+    resolved: Result<Rc<Method>, VmError>,
+    runtime_data_area: &RuntimeDataArea,
+) -> Option<String> {
+    let method = match resolved {
+        Ok(method) => method,
+        Err(error) => return unsupported(error),
+    };
+
+    let implemented = method.code.is_some()
+        || !method.is_native()
+        || !is_jdk_class(&method.class_name)
+        || runtime_data_area.natives.lookup(&method).is_some();
+
+    match implemented {
+        true => None,
+        false => Some(format!(
+            "native method {}.{}{} is not implemented",
+            method.class_name.replace('/', "."),
+            method.name,
+            method.descriptor
+        )),
+    }
+}
+
+/// The message of an error that is a gap of the VM, other resolution failures are errors
+/// of the program
+fn unsupported(error: VmError) -> Option<String> {
+    match error {
+        VmError::Unsupported { message, .. } => Some(message),
+        _ => None,
+    }
+}
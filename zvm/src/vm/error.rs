@@ -0,0 +1,196 @@
+use std::fmt;
+
+use crate::vm::class::Method;
+
+/// Where a `VmError` happened, an instruction of a method
+#[derive(Debug, Clone)]
+pub struct SourceContext {
+    pub class_name: String,
+    pub method_name: String,
+    pub descriptor: String,
+    pub pc: usize,
+    /// Source line of the instruction, from the `LineNumberTable` attribute if present
+    pub line: Option<u16>,
+}
+
+impl SourceContext {
+    /// The context of the instruction at the given pc of a method
+    pub fn new(method: &Method, pc: usize) -> Self {
+        Self {
+            class_name: method.class_name.clone(),
+            method_name: method.name.clone(),
+            descriptor: method.descriptor.clone(),
+            pc,
+            line: method.code.as_ref().and_then(|code| code.line_number(pc)),
+        }
+    }
+}
+
+impl fmt::Display for SourceContext {
+    /// e.g. `Main.main([Ljava/lang/String;)V, pc 12, line 5`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}{}, pc {}",
+            self.class_name.replace('/', "."),
+            self.method_name,
+            self.descriptor,
+            self.pc
+        )?;
+
+        match self.line {
+            Some(line) => write!(f, ", line {}", line),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A failure of the VM, as opposed to a Java exception the program can catch
+///
+/// Each error records the instruction it happened at once the dispatch loop sees it
+#[derive(Debug, Clone)]
+pub enum VmError {
+    /// A class, field or method could not be resolved or linked, e.g. a `NoSuchMethodError`
+    Linkage {
+        message: String,
+        context: Option<Box<SourceContext>>,
+    },
+    /// The bytecode breaks a static or structural constraint, e.g. an operand of the wrong type
+    Verification {
+        message: String,
+        context: Option<Box<SourceContext>>,
+    },
+    /// The VM itself failed, e.g. an invariant of the dispatch loop does not hold
+    Internal {
+        message: String,
+        context: Option<Box<SourceContext>>,
+    },
+    /// The program uses a part of the JDK or of the class file format the VM does not
+    /// implement yet, strict mode throws the Java error of the given class in its place
+    Unsupported {
+        exception: &'static str,
+        message: String,
+        context: Option<Box<SourceContext>>,
+    },
+    /// A linkage error the program can catch, e.g. an `AbstractMethodError`, the dispatch
+    /// loop throws it as an exception of the given class from the instruction that raised it
+    Throw {
+        exception: &'static str,
+        message: String,
+        context: Option<Box<SourceContext>>,
+    },
+    /// No frame of the main thread caught an exception of the given class, its stack trace
+    /// printed by the default uncaught exception handler tells where it was thrown
+    UncaughtException { class_name: String },
+}
+
+impl VmError {
+    pub fn linkage(message: impl Into<String>) -> Self {
+        Self::Linkage {
+            message: message.into(),
+            context: None,
+        }
+    }
+
+    /// A Java error of the given class, e.g. `java/lang/IncompatibleClassChangeError`
+    pub fn throw(exception: &'static str, message: impl Into<String>) -> Self {
+        Self::Throw {
+            exception,
+            message: message.into(),
+            context: None,
+        }
+    }
+
+    pub fn verification(message: impl Into<String>) -> Self {
+        Self::Verification {
+            message: message.into(),
+            context: None,
+        }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::Internal {
+            message: message.into(),
+            context: None,
+        }
+    }
+
+    /// A class, field or method of the JDK the VM does not provide
+    pub fn unsupported_library(message: impl Into<String>) -> Self {
+        Self::Unsupported {
+            exception: "java/lang/UnsupportedOperationException",
+            message: message.into(),
+            context: None,
+        }
+    }
+
+    /// An instruction or a constant the VM cannot execute
+    pub fn unsupported_instruction(message: impl Into<String>) -> Self {
+        Self::Unsupported {
+            exception: "java/lang/InternalError",
+            message: message.into(),
+            context: None,
+        }
+    }
+
+    /// Records where the error happened, unless a nested frame already did
+    pub fn at(mut self, location: SourceContext) -> Self {
+        if let Self::Linkage { context, .. }
+        | Self::Verification { context, .. }
+        | Self::Internal { context, .. }
+        | Self::Unsupported { context, .. }
+        | Self::Throw { context, .. } = &mut self
+        {
+            context.get_or_insert_with(|| Box::new(location));
+        }
+
+        self
+    }
+}
+
+impl fmt::Display for VmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, message, context) = match self {
+            Self::Linkage { message, context } => ("Linkage error", message, context),
+            Self::Verification { message, context } => ("Verification error", message, context),
+            Self::Internal { message, context } => ("Internal error", message, context),
+            Self::Unsupported {
+                message, context, ..
+            } => ("Unsupported", message, context),
+            Self::Throw {
+                exception,
+                message,
+                context,
+            } => {
+                write!(f, "{}: {}", exception.replace('/', "."), message)?;
+                return match context {
+                    Some(context) => write!(f, "\n\tat {}", context),
+                    None => Ok(()),
+                };
+            }
+            Self::UncaughtException { class_name } => {
+                return write!(f, "Uncaught exception: {}", class_name.replace('/', "."));
+            }
+        };
+
+        write!(f, "{}: {}", kind, message)?;
+
+        match context {
+            Some(context) => write!(f, "\n\tat {}", context),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Failures of the VM not classified yet are internal errors
+impl From<String> for VmError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<&str> for VmError {
+    fn from(message: &str) -> Self {
+        Self::internal(message)
+    }
+}
@@ -0,0 +1,777 @@
+use std::fmt;
+
+use crate::vm::string;
+
+/// An error of a format string or of an argument, thrown as the `java.util` exception
+/// with the given class name and message
+pub enum FormatError {
+    Exception(&'static str, String),
+    /// A valid format the formatter does not support
+    Unsupported(String),
+}
+
+/// Parts of a parsed format string
+pub enum FormatElement {
+    Text(Vec<u16>),
+    Specifier(Specifier),
+}
+
+/// Where the argument of a format specifier comes from
+#[derive(Clone, Copy, PartialEq)]
+pub enum ArgumentIndex {
+    /// The argument after the one of the previous ordinary specifier
+    Ordinary,
+    /// `%n$`, the n-th argument counting from 1
+    Explicit(usize),
+    /// `%<`, the argument of the previous specifier
+    Previous,
+}
+
+/// An argument of a format, the way the formatter sees it
+pub enum Argument {
+    Null,
+    Boolean(bool),
+    Char(u16),
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    /// The `toString()` of the argument of a `%s` specifier
+    Text(Vec<u16>),
+    /// Any other object, by the name of its class, e.g. `java.lang.String`
+    Object(String),
+}
+
+const LEFT_JUSTIFY: u16 = 1;
+const UPPERCASE: u16 = 1 << 1;
+const ALTERNATE: u16 = 1 << 2;
+const PLUS: u16 = 1 << 3;
+const LEADING_SPACE: u16 = 1 << 4;
+const ZERO_PAD: u16 = 1 << 5;
+const GROUP: u16 = 1 << 6;
+const PARENTHESES: u16 = 1 << 7;
+const PREVIOUS: u16 = 1 << 8;
+
+/// Flags with the character spelling them, in the order `Formatter` lists them in messages
+const FLAGS: &[(u16, char)] = &[
+    (LEFT_JUSTIFY, '-'),
+    (UPPERCASE, '^'),
+    (ALTERNATE, '#'),
+    (PLUS, '+'),
+    (LEADING_SPACE, ' '),
+    (ZERO_PAD, '0'),
+    (GROUP, ','),
+    (PARENTHESES, '('),
+    (PREVIOUS, '<'),
+];
+
+/// Spells the given flags, e.g. `-#`
+fn flags_string(flags: u16) -> String {
+    FLAGS
+        .iter()
+        .filter(|(flag, _)| flags & flag != 0)
+        .map(|(_, c)| *c)
+        .collect()
+}
+
+/// The flag spelled by a character of the flags of a specifier
+fn parse_flag(c: char) -> Option<u16> {
+    FLAGS
+        .iter()
+        .find(|(flag, spelling)| *spelling == c && *flag != UPPERCASE)
+        .map(|(flag, _)| *flag)
+}
+
+/// A format specifier, e.g. `%-10.3s`
+pub struct Specifier {
+    pub index: ArgumentIndex,
+    flags: u16,
+    width: Option<i32>,
+    precision: Option<i32>,
+    /// The conversion in lower case, `UPPERCASE` is set for upper case conversions
+    pub conversion: char,
+}
+
+impl fmt::Display for Specifier {
+    /// Spells the specifier the way `Formatter` does in exception messages, e.g. `%-s`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "%{}", flags_string(self.flags & !UPPERCASE))?;
+        if let ArgumentIndex::Explicit(index) = self.index {
+            write!(f, "{}$", index)?;
+        }
+        if let Some(width) = self.width {
+            write!(f, "{}", width)?;
+        }
+        if let Some(precision) = self.precision {
+            write!(f, ".{}", precision)?;
+        }
+        match self.has(UPPERCASE) {
+            true => write!(f, "{}", self.conversion.to_ascii_uppercase()),
+            false => write!(f, "{}", self.conversion),
+        }
+    }
+}
+
+fn exception(class_name: &'static str, message: String) -> FormatError {
+    FormatError::Exception(class_name, message)
+}
+
+/// Parses a format string into fixed texts and format specifiers, covering the part of
+/// `java.util.Formatter` that `printf` and `String.format` are used with in practice
+///
+/// Supported: argument indexes ( `%2$s`, `%<s` ), the flags `-#+ 0,(`, width, precision and
+/// the conversions `b h s c d o x e f g % n` along with their upper case variants. Date/time
+/// and hexadecimal floating point conversions are not supported. Numbers are formatted the
+/// way `Locale.ROOT` formats them, without localized digits or separators.
+///
+/// Formats and texts are UTF-16 code units like Java strings.
+pub fn parse(format: &[u16]) -> Result<Vec<FormatElement>, FormatError> {
+    let mut elements = Vec::new();
+    let mut text = Vec::new();
+    let mut i = 0;
+
+    while i < format.len() {
+        if format[i] != '%' as u16 {
+            text.push(format[i]);
+            i += 1;
+            continue;
+        }
+
+        if !text.is_empty() {
+            elements.push(FormatElement::Text(std::mem::take(&mut text)));
+        }
+
+        let start = i + 1;
+        let Some(&first) = format.get(start) else {
+            return Err(unknown_conversion("%".to_string()));
+        };
+
+        let (specifier, end) = match parse_specifier(format, start)? {
+            Some(parsed) => parsed,
+            None => {
+                let first = String::from_utf16_lossy(&[first]);
+                return Err(unknown_conversion(first));
+            }
+        };
+
+        elements.push(FormatElement::Specifier(specifier));
+        i = end;
+    }
+
+    if !text.is_empty() {
+        elements.push(FormatElement::Text(text));
+    }
+
+    Ok(elements)
+}
+
+fn illegal_width(width: i32) -> FormatError {
+    exception("java/util/IllegalFormatWidthException", width.to_string())
+}
+
+fn illegal_precision(precision: i32) -> FormatError {
+    exception(
+        "java/util/IllegalFormatPrecisionException",
+        precision.to_string(),
+    )
+}
+
+fn unknown_conversion(conversion: String) -> FormatError {
+    exception(
+        "java/util/UnknownFormatConversionException",
+        format!("Conversion = '{}'", conversion),
+    )
+}
+
+/// Parses the specifier starting after a `%`, `%[index$][flags][width][.precision]conversion`,
+/// `None` if there is no such specifier there. Returns the index following the specifier
+fn parse_specifier(
+    format: &[u16],
+    start: usize,
+) -> Result<Option<(Specifier, usize)>, FormatError> {
+    let at = |i: usize| format.get(i).and_then(|c| char::from_u32(*c as u32));
+    let digits_end = |from: usize| {
+        let mut end = from;
+        while at(end).is_some_and(|c| c.is_ascii_digit()) {
+            end += 1;
+        }
+        end
+    };
+    let number = |from: usize, to: usize| {
+        String::from_utf16_lossy(&format[from..to])
+            .parse::<i32>()
+            .unwrap_or(i32::MIN)
+    };
+
+    let mut i = start;
+
+    let mut index = ArgumentIndex::Ordinary;
+    let end = digits_end(i);
+    if end > i && at(end) == Some('$') {
+        let message = match number(i, end) {
+            explicit if explicit > 0 => None,
+            i32::MIN => Some("Format argument index: (not representable as int)".to_string()),
+            invalid => Some(format!("Illegal format argument index = {}", invalid)),
+        };
+        if let Some(message) = message {
+            return Err(exception(
+                "java/util/IllegalFormatArgumentIndexException",
+                message,
+            ));
+        }
+        index = ArgumentIndex::Explicit(number(i, end) as usize);
+        i = end + 1;
+    }
+
+    let mut flags = 0;
+    while let Some((flag, c)) = at(i).and_then(|c| parse_flag(c).map(|flag| (flag, c))) {
+        if flags & flag != 0 {
+            return Err(exception(
+                "java/util/DuplicateFormatFlagsException",
+                format!("Flags = '{}'", c),
+            ));
+        }
+        flags |= flag;
+        i += 1;
+    }
+    if flags & PREVIOUS != 0 {
+        index = ArgumentIndex::Previous;
+    }
+
+    let mut width = None;
+    let end = digits_end(i);
+    if end > i {
+        width = Some(number(i, end));
+        i = end;
+    }
+
+    let mut precision = None;
+    if at(i) == Some('.') {
+        let end = digits_end(i + 1);
+        if end == i + 1 {
+            return Ok(None);
+        }
+        precision = Some(number(i + 1, end));
+        i = end;
+    }
+
+    if let Some(width) = width.filter(|width| *width < 0) {
+        return Err(illegal_width(width));
+    }
+    if let Some(precision) = precision.filter(|precision| *precision < 0) {
+        return Err(illegal_precision(precision));
+    }
+
+    let conversion = match at(i) {
+        Some(c) if c.is_ascii_alphabetic() || c == '%' => c,
+        _ => return Ok(None),
+    };
+
+    if !"bBhHsScCdoxXeEfgGaAtT%n".contains(conversion) {
+        return Err(unknown_conversion(conversion.to_string()));
+    }
+    if conversion.is_ascii_uppercase() {
+        flags |= UPPERCASE;
+    }
+
+    let specifier = Specifier {
+        index,
+        flags,
+        width,
+        precision,
+        conversion: conversion.to_ascii_lowercase(),
+    };
+
+    match specifier.conversion {
+        'b' | 'h' | 's' => specifier.check_general()?,
+        'c' => specifier.check_character()?,
+        'd' | 'o' | 'x' => specifier.check_integer()?,
+        'e' | 'f' | 'g' => specifier.check_float()?,
+        '%' | 'n' => specifier.check_text()?,
+        unsupported => {
+            return Err(FormatError::Unsupported(format!(
+                "Format conversion '{}' is not supported",
+                unsupported
+            )));
+        }
+    }
+
+    Ok(Some((specifier, i + 1)))
+}
+
+impl Specifier {
+    fn has(&self, flag: u16) -> bool {
+        self.flags & flag != 0
+    }
+
+    /// Whether the specifier formats an argument, `%%` and `%n` do not
+    pub fn takes_argument(&self) -> bool {
+        !matches!(self.conversion, '%' | 'n')
+    }
+
+    /// Error of a specifier whose argument index is past the last argument
+    pub fn missing_argument(&self) -> FormatError {
+        exception(
+            "java/util/MissingFormatArgumentException",
+            format!("Format specifier '{}'", self),
+        )
+    }
+
+    fn missing_width(&self) -> FormatError {
+        exception("java/util/MissingFormatWidthException", self.to_string())
+    }
+
+    fn illegal_flags(&self) -> FormatError {
+        exception(
+            "java/util/IllegalFormatFlagsException",
+            format!("Flags = '{}'", flags_string(self.flags)),
+        )
+    }
+
+    /// Fails on the first of the given flags the specifier has
+    fn check_bad_flags(&self, flags: &[u16]) -> Result<(), FormatError> {
+        match flags.iter().find(|flag| self.has(**flag)) {
+            Some(flag) => Err(exception(
+                "java/util/FormatFlagsConversionMismatchException",
+                format!(
+                    "Conversion = {}, Flags = {}",
+                    self.conversion,
+                    flags_string(*flag)
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn check_general(&self) -> Result<(), FormatError> {
+        if matches!(self.conversion, 'b' | 'h') {
+            self.check_bad_flags(&[ALTERNATE])?;
+        }
+        if self.width.is_none() && self.has(LEFT_JUSTIFY) {
+            return Err(self.missing_width());
+        }
+        self.check_bad_flags(&[PLUS, LEADING_SPACE, ZERO_PAD, GROUP, PARENTHESES])
+    }
+
+    fn check_character(&self) -> Result<(), FormatError> {
+        if let Some(precision) = self.precision {
+            return Err(illegal_precision(precision));
+        }
+        self.check_bad_flags(&[ALTERNATE, PLUS, LEADING_SPACE, ZERO_PAD, GROUP, PARENTHESES])?;
+        if self.width.is_none() && self.has(LEFT_JUSTIFY) {
+            return Err(self.missing_width());
+        }
+        Ok(())
+    }
+
+    fn check_numeric(&self) -> Result<(), FormatError> {
+        if self.width.is_none() && (self.has(LEFT_JUSTIFY) || self.has(ZERO_PAD)) {
+            return Err(self.missing_width());
+        }
+        if (self.has(PLUS) && self.has(LEADING_SPACE))
+            || (self.has(LEFT_JUSTIFY) && self.has(ZERO_PAD))
+        {
+            return Err(self.illegal_flags());
+        }
+        Ok(())
+    }
+
+    fn check_integer(&self) -> Result<(), FormatError> {
+        self.check_numeric()?;
+        if let Some(precision) = self.precision {
+            return Err(illegal_precision(precision));
+        }
+        match self.conversion {
+            'd' => self.check_bad_flags(&[ALTERNATE]),
+            _ => self.check_bad_flags(&[GROUP]),
+        }
+    }
+
+    fn check_float(&self) -> Result<(), FormatError> {
+        self.check_numeric()?;
+        match self.conversion {
+            'e' => self.check_bad_flags(&[GROUP]),
+            'g' => self.check_bad_flags(&[ALTERNATE]),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_text(&self) -> Result<(), FormatError> {
+        if let Some(precision) = self.precision {
+            return Err(illegal_precision(precision));
+        }
+        match self.conversion {
+            '%' => {
+                if self.flags & !LEFT_JUSTIFY != 0 {
+                    return Err(self.illegal_flags());
+                }
+                if self.width.is_none() && self.has(LEFT_JUSTIFY) {
+                    return Err(self.missing_width());
+                }
+            }
+            _ => {
+                if let Some(width) = self.width {
+                    return Err(illegal_width(width));
+                }
+                if self.flags != 0 {
+                    return Err(self.illegal_flags());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Formats an argument, which is `Argument::Null` for the specifiers without one
+    pub fn format(&self, argument: &Argument) -> Result<Vec<u16>, FormatError> {
+        match (self.conversion, argument) {
+            ('%', _) => Ok(self.justify(vec!['%' as u16])),
+            ('n', _) => Ok(vec!['\n' as u16]),
+            ('b', Argument::Null) => Ok(self.text("false".encode_utf16().collect())),
+            ('b', Argument::Boolean(value)) => {
+                Ok(self.text(value.to_string().encode_utf16().collect()))
+            }
+            ('b', _) => Ok(self.text("true".encode_utf16().collect())),
+            (_, Argument::Null) => Ok(self.text("null".encode_utf16().collect())),
+            ('s', Argument::Text(text)) => {
+                // Only java.util.Formattable arguments take the '#' flag
+                self.check_bad_flags(&[ALTERNATE])?;
+                Ok(self.text(text.clone()))
+            }
+            ('h', Argument::Int(hash_code)) => {
+                Ok(self.text(format!("{:x}", hash_code).encode_utf16().collect()))
+            }
+            ('c', Argument::Char(c)) => Ok(self.character(vec![*c])),
+            ('c', Argument::Byte(value)) => self.code_point(*value as i32),
+            ('c', Argument::Short(value)) => self.code_point(*value as i32),
+            ('c', Argument::Int(value)) => self.code_point(*value),
+            ('d' | 'o' | 'x', Argument::Byte(value)) => self.integer(*value as i64, 8),
+            ('d' | 'o' | 'x', Argument::Short(value)) => self.integer(*value as i64, 16),
+            ('d' | 'o' | 'x', Argument::Int(value)) => self.integer(*value as i64, 32),
+            ('d' | 'o' | 'x', Argument::Long(value)) => self.integer(*value, 64),
+            ('e' | 'f' | 'g', Argument::Float(value)) => Ok(self.floating(*value as f64)),
+            ('e' | 'f' | 'g', Argument::Double(value)) => Ok(self.floating(*value)),
+            (conversion, argument) => Err(exception(
+                "java/util/IllegalFormatConversionException",
+                format!("{} != {}", conversion, argument.class_name()),
+            )),
+        }
+    }
+
+    /// Pads a text with spaces up to the width, on the right with the `-` flag
+    fn justify(&self, mut text: Vec<u16>) -> Vec<u16> {
+        let padding = match self.width {
+            Some(width) => (width as usize).saturating_sub(text.len()),
+            None => 0,
+        };
+        let spaces = std::iter::repeat_n(' ' as u16, padding);
+
+        match self.has(LEFT_JUSTIFY) {
+            true => {
+                text.extend(spaces);
+                text
+            }
+            false => spaces.chain(text).collect(),
+        }
+    }
+
+    /// Formats a text, truncated to the precision
+    fn text(&self, mut text: Vec<u16>) -> Vec<u16> {
+        if let Some(precision) = self.precision {
+            text.truncate(precision as usize);
+        }
+        self.character(text)
+    }
+
+    fn character(&self, text: Vec<u16>) -> Vec<u16> {
+        match self.has(UPPERCASE) {
+            true => self.justify(string::to_upper_case(&text)),
+            false => self.justify(text),
+        }
+    }
+
+    fn code_point(&self, code_point: i32) -> Result<Vec<u16>, FormatError> {
+        match char::from_u32(code_point as u32) {
+            Some(c) => Ok(self.character(c.encode_utf16(&mut [0; 2]).to_vec())),
+            // Surrogates are valid code points of their own in Java
+            None if (0xD800..=0xDFFF).contains(&code_point) => {
+                Ok(self.character(vec![code_point as u16]))
+            }
+            None => Err(exception(
+                "java/util/IllegalFormatCodePointException",
+                format!("Code point = {:#x}", code_point),
+            )),
+        }
+    }
+
+    /// Formats an integer of the given size in bits
+    fn integer(&self, value: i64, bits: u32) -> Result<Vec<u16>, FormatError> {
+        let mut formatted = String::new();
+
+        if self.conversion == 'd' {
+            let negative = value < 0;
+            self.leading_sign(&mut formatted, negative);
+            self.magnitude(
+                &mut formatted,
+                &value.unsigned_abs().to_string(),
+                self.adjusted_width(negative),
+            );
+            self.trailing_sign(&mut formatted, negative);
+        } else {
+            self.check_bad_flags(&[PARENTHESES, LEADING_SPACE, PLUS])?;
+
+            // Negative values are formatted as their unsigned two's complement
+            let unsigned = value as u64 & (u64::MAX >> (64 - bits));
+            let (prefix, digits) = match self.conversion {
+                'o' => ("0", format!("{:o}", unsigned)),
+                _ => ("0x", format!("{:x}", unsigned)),
+            };
+
+            if self.has(ALTERNATE) {
+                formatted.push_str(prefix);
+            }
+            if self.has(ZERO_PAD) {
+                let length = formatted.len() + digits.len();
+                let zeros = self.width.unwrap_or(0) as usize;
+                formatted.extend(std::iter::repeat_n('0', zeros.saturating_sub(length)));
+            }
+            formatted.push_str(&digits);
+
+            if self.has(UPPERCASE) {
+                formatted = formatted.to_ascii_uppercase();
+            }
+        }
+
+        Ok(self.justify(formatted.encode_utf16().collect()))
+    }
+
+    /// Formats a floating point value, float arguments are widened to double first
+    fn floating(&self, value: f64) -> Vec<u16> {
+        let mut formatted = String::new();
+        // -0.0 is negative too
+        let negative = value.is_sign_negative() && !value.is_nan();
+
+        if value.is_nan() {
+            formatted.push_str("NaN");
+        } else if value.is_infinite() {
+            self.leading_sign(&mut formatted, negative);
+            formatted.push_str("Infinity");
+            self.trailing_sign(&mut formatted, negative);
+        } else {
+            self.leading_sign(&mut formatted, negative);
+            self.finite(&mut formatted, value.abs(), negative);
+            self.trailing_sign(&mut formatted, negative);
+        }
+
+        if self.has(UPPERCASE) {
+            formatted = formatted.to_ascii_uppercase();
+        }
+        self.justify(formatted.encode_utf16().collect())
+    }
+
+    /// Formats the magnitude of a finite floating point value, in computerized scientific
+    /// notation for `%e`, as a decimal for `%f` and either way for `%g`
+    fn finite(&self, formatted: &mut String, value: f64, negative: bool) {
+        let precision = self.precision.map_or(6, |precision| precision as usize);
+        let mut decimal = Decimal::new(value);
+
+        let fraction = match self.conversion {
+            'e' => precision,
+            'f' => return self.decimal(formatted, decimal, precision, negative),
+            _ => {
+                // AS SPECIFIED BY THE SPECS: after rounding to the precision, values from 10^-4
+                // to 10^precision are formatted as decimals, the precision being the total
+                // number of significant digits
+                let precision = precision.max(1) as i32;
+                decimal.round(precision);
+                let exponent = decimal.exponent - 1;
+
+                if value == 0.0 {
+                    return self.decimal(formatted, decimal, precision as usize - 1, negative);
+                }
+                if (-4..precision).contains(&exponent) {
+                    let fraction = (precision - exponent - 1) as usize;
+                    return self.decimal(formatted, decimal, fraction, negative);
+                }
+                precision as usize - 1
+            }
+        };
+
+        decimal.round(fraction as i32 + 1);
+        let exponent = decimal.exponent - 1;
+        let exponent = format!(
+            "e{}{:02}",
+            if exponent < 0 { '-' } else { '+' },
+            exponent.unsigned_abs()
+        );
+
+        let mut mantissa = String::new();
+        mantissa.push(decimal.digit(0) as char);
+        if fraction > 0 || self.has(ALTERNATE) {
+            mantissa.push('.');
+        }
+        mantissa.extend((1..=fraction as i32).map(|i| decimal.digit(i) as char));
+
+        let width = self
+            .width
+            .map(|width| self.adjust_width(width - exponent.len() as i32, negative));
+        self.magnitude(formatted, &mantissa, width);
+        formatted.push_str(&exponent);
+    }
+
+    /// Formats a magnitude as a decimal with the given number of fraction digits
+    fn decimal(
+        &self,
+        formatted: &mut String,
+        mut decimal: Decimal,
+        fraction: usize,
+        negative: bool,
+    ) {
+        decimal.round(decimal.exponent + fraction as i32);
+
+        let mut digits: String = (0..decimal.exponent.max(1))
+            .map(|i| match decimal.exponent > 0 {
+                true => decimal.digit(i) as char,
+                false => '0',
+            })
+            .collect();
+        if fraction > 0 || self.has(ALTERNATE) {
+            digits.push('.');
+        }
+        digits.extend((0..fraction as i32).map(|i| decimal.digit(decimal.exponent + i) as char));
+
+        self.magnitude(formatted, &digits, self.adjusted_width(negative));
+    }
+
+    /// The width left for the magnitude and the leading sign, a trailing `)` takes one
+    fn adjusted_width(&self, negative: bool) -> Option<i32> {
+        self.width.map(|width| self.adjust_width(width, negative))
+    }
+
+    fn adjust_width(&self, width: i32, negative: bool) -> i32 {
+        match negative && self.has(PARENTHESES) {
+            true => width - 1,
+            false => width,
+        }
+    }
+
+    fn leading_sign(&self, formatted: &mut String, negative: bool) {
+        match negative {
+            false if self.has(PLUS) => formatted.push('+'),
+            false if self.has(LEADING_SPACE) => formatted.push(' '),
+            true if self.has(PARENTHESES) => formatted.push('('),
+            true => formatted.push('-'),
+            false => {}
+        }
+    }
+
+    fn trailing_sign(&self, formatted: &mut String, negative: bool) {
+        if negative && self.has(PARENTHESES) {
+            formatted.push(')');
+        }
+    }
+
+    /// Appends the digits of a magnitude, grouped by thousands with the `,` flag and padded
+    /// with zeros after the sign up to the given width with the `0` flag
+    fn magnitude(&self, formatted: &mut String, digits: &str, width: Option<i32>) {
+        let begin = formatted.len();
+        let dot = digits.find('.').unwrap_or(digits.len());
+
+        for (i, c) in digits.chars().enumerate() {
+            formatted.push(c);
+            if self.has(GROUP) && i + 1 < dot && (dot - i) % 3 == 1 {
+                formatted.push(',');
+            }
+        }
+
+        if let Some(width) = width
+            && self.has(ZERO_PAD)
+        {
+            let zeros = (width as usize).saturating_sub(formatted.len());
+            formatted.insert_str(begin, &"0".repeat(zeros));
+        }
+    }
+}
+
+impl Argument {
+    fn class_name(&self) -> &str {
+        match self {
+            Argument::Null => "null",
+            Argument::Boolean(_) => "java.lang.Boolean",
+            Argument::Char(_) => "java.lang.Character",
+            Argument::Byte(_) => "java.lang.Byte",
+            Argument::Short(_) => "java.lang.Short",
+            Argument::Int(_) => "java.lang.Integer",
+            Argument::Long(_) => "java.lang.Long",
+            Argument::Float(_) => "java.lang.Float",
+            Argument::Double(_) => "java.lang.Double",
+            Argument::Text(_) => "java.lang.String",
+            Argument::Object(class_name) => class_name,
+        }
+    }
+}
+
+/// The decimal digits of a floating point value, `0.d1d2d3... * 10^exponent`, starting from
+/// the shortest digits that uniquely distinguish the value like `Double.toString` does
+struct Decimal {
+    /// ASCII digits, without leading zeros unless the value is zero
+    digits: Vec<u8>,
+    exponent: i32,
+}
+
+impl Decimal {
+    fn new(value: f64) -> Self {
+        let (digits, exponent) = string::decimal_digits(&format!("{:e}", value.abs()));
+        Self {
+            digits,
+            exponent: exponent + 1,
+        }
+    }
+
+    /// The digit at the given index, digits past the known ones are zeros
+    fn digit(&self, index: i32) -> u8 {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.digits.get(index))
+            .copied()
+            .unwrap_or(b'0')
+    }
+
+    /// Rounds half up to the given number of digits, the exponent grows when
+    /// the digits carry over, e.g. 9.96 to 2 digits gives 10
+    fn round(&mut self, digits: i32) {
+        let Ok(keep) = usize::try_from(digits) else {
+            // Too small to show up in the digits kept
+            self.digits = vec![b'0'];
+            return;
+        };
+        if keep >= self.digits.len() {
+            return;
+        }
+
+        let round_up = self.digits[keep] >= b'5';
+        self.digits.truncate(keep);
+
+        if !round_up {
+            if self.digits.is_empty() {
+                self.digits.push(b'0');
+            }
+            return;
+        }
+
+        for digit in self.digits.iter_mut().rev() {
+            if *digit == b'9' {
+                *digit = b'0';
+            } else {
+                *digit += 1;
+                return;
+            }
+        }
+
+        // Every digit carried over, like 0.999 rounded to 10^0
+        self.digits.insert(0, b'1');
+        self.digits.truncate(keep.max(1));
+        self.exponent += 1;
+    }
+}
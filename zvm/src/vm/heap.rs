@@ -0,0 +1,313 @@
+use crate::{
+    debug_log,
+    vm::{
+        array::{self, Array},
+        object::Object,
+        value::Value,
+    },
+};
+
+/// Default maximum heap size when no `-Xmx` option is given
+pub const DEFAULT_MAX_HEAP_SIZE: usize = 256 * 1024 * 1024;
+
+/// Heap usage that triggers the first collection, the threshold then grows with the live data
+const INITIAL_COLLECTION_THRESHOLD: usize = 1024 * 1024;
+
+/// Estimated size of the header of every object and array, in bytes
+const HEADER_SIZE: usize = 16;
+
+/// Estimated size of a field, in bytes
+const SLOT_SIZE: usize = 8;
+
+/// Reference to an object or an array allocated in the heap
+///
+/// Handles index the heap's slot table, the collector never moves what a handle refers to,
+/// so a handle stays valid as long as the object or array is reachable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle(usize);
+
+/// Contents of an allocated heap slot
+pub enum HeapData {
+    Object(Object),
+    Array(Array),
+}
+
+struct HeapSlot {
+    data: HeapData,
+    /// Estimated size of the allocation, in bytes
+    size: usize,
+    /// Identity hash code, chosen at allocation time
+    hash_code: i32,
+    /// Set by the mark phase of a collection when the slot is reachable from the roots
+    marked: bool,
+}
+
+/// Garbage collected heap holding every object and array created by the program
+pub struct Heap {
+    /// Allocated objects and arrays indexed by their handle, `None` for free slots
+    slots: Vec<Option<HeapSlot>>,
+    /// Free slots, reused by the next allocations
+    free_slots: Vec<usize>,
+    /// Estimated bytes taken by the allocated slots, including unreachable ones
+    used: usize,
+    /// Maximum heap size ( -Xmx ), an allocation that does not fit throws OutOfMemoryError
+    max_size: usize,
+    /// Usage above which the next allocation first collects garbage
+    collection_threshold: usize,
+    /// Values the VM itself holds on to outside of any frame, e.g. an uncaught exception
+    /// while its stack trace is printed, they are roots of every collection
+    pinned: Vec<Value>,
+    /// State of the xor-shift generator of identity hash codes
+    hash_seed: u32,
+    /// Whether reservations may go past the maximum heap size, see `set_unlimited`
+    unlimited: bool,
+}
+
+/// Room made in the heap by `Heap::reserve`, the only way to allocate objects and arrays
+pub struct Reservation {
+    /// Bytes left for the allocations
+    size: usize,
+}
+
+/// Failure of a reservation that does not fit in the maximum heap size ( -Xmx )
+#[derive(Debug)]
+pub struct OutOfMemory;
+
+impl Heap {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            slots: Vec::new(),
+            free_slots: Vec::new(),
+            used: 0,
+            max_size,
+            collection_threshold: INITIAL_COLLECTION_THRESHOLD.min(max_size),
+            pinned: Vec::new(),
+            hash_seed: 0x2545_F491,
+            unlimited: false,
+        }
+    }
+
+    /// Sets the maximum heap size ( -Xmx )
+    pub fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = max_size;
+        self.collection_threshold = self.collection_threshold.min(max_size);
+    }
+
+    /// Estimated size of an object, in bytes
+    pub fn object_size(object: &Object) -> usize {
+        HEADER_SIZE + SLOT_SIZE * object.fields.len()
+    }
+
+    /// Estimated size of an array of the given component type and length, in bytes,
+    /// primitive components take their own width
+    pub fn array_size(component_type: &str, length: usize) -> usize {
+        HEADER_SIZE + array::component_size(component_type) * length
+    }
+
+    /// Estimated size of the array, in bytes
+    pub fn size_of_array(array: &Array) -> usize {
+        Self::array_size(array.component_type(), array.length())
+    }
+
+    /// Makes room for allocations of the given total size, first collecting garbage with
+    /// `collect` when the usage would cross the collection threshold, fails if they do not fit
+    /// in the maximum heap size even after the collection
+    ///
+    /// No collection runs while the reservation is used, so the objects and arrays allocated
+    /// with it need not be reachable from any root until the last one is
+    pub fn reserve(
+        &mut self,
+        size: usize,
+        collect: impl FnOnce(&mut Self),
+    ) -> Result<Reservation, OutOfMemory> {
+        if self.used.saturating_add(size) > self.collection_threshold {
+            collect(self);
+        }
+
+        if self.used.saturating_add(size) > self.max_size && !self.unlimited {
+            return Err(OutOfMemory);
+        }
+
+        Ok(Reservation { size })
+    }
+
+    /// Lets the reservations go past the maximum heap size while the VM creates the
+    /// OutOfMemoryError of a reservation that did not fit, like the errors HotSpot
+    /// preallocates for that purpose
+    pub fn set_unlimited(&mut self, unlimited: bool) {
+        self.unlimited = unlimited;
+    }
+
+    /// Allocates an object with room reserved beforehand and returns a reference to it
+    pub fn allocate_object(&mut self, reservation: &mut Reservation, object: Object) -> Value {
+        let size = Self::object_size(&object);
+        Value::Object(self.allocate(reservation, HeapData::Object(object), size))
+    }
+
+    /// Allocates an array with room reserved beforehand and returns a reference to it
+    pub fn allocate_array(&mut self, reservation: &mut Reservation, array: Array) -> Value {
+        let size = Self::size_of_array(&array);
+        Value::Array(self.allocate(reservation, HeapData::Array(array), size))
+    }
+
+    fn allocate(&mut self, reservation: &mut Reservation, data: HeapData, size: usize) -> Handle {
+        reservation.size = reservation.size.saturating_sub(size);
+
+        let slot = HeapSlot {
+            data,
+            size,
+            hash_code: self.next_hash_code(),
+            marked: false,
+        };
+
+        self.used += size;
+
+        match self.free_slots.pop() {
+            Some(index) => {
+                self.slots[index] = Some(slot);
+                Handle(index)
+            }
+            None => {
+                self.slots.push(Some(slot));
+                Handle(self.slots.len() - 1)
+            }
+        }
+    }
+
+    /// Generates the next identity hash code with Marsaglia's xor-shift scheme, like HotSpot
+    fn next_hash_code(&mut self) -> i32 {
+        let mut x = self.hash_seed;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.hash_seed = x;
+
+        // Identity hash codes are 31 bits wide and never 0
+        ((x & 0x7FFF_FFFF) as i32).max(1)
+    }
+
+    fn slot(&self, handle: Handle) -> &HeapSlot {
+        self.slots[handle.0]
+            .as_ref()
+            .expect("Dangling heap handle: the referenced slot was collected")
+    }
+
+    fn slot_mut(&mut self, handle: Handle) -> &mut HeapSlot {
+        self.slots[handle.0]
+            .as_mut()
+            .expect("Dangling heap handle: the referenced slot was collected")
+    }
+
+    /// Borrows the object the handle refers to
+    pub fn object(&self, handle: Handle) -> &Object {
+        match &self.slot(handle).data {
+            HeapData::Object(object) => object,
+            HeapData::Array(_) => panic!("Heap handle {:?} refers to an array", handle),
+        }
+    }
+
+    /// Borrows the object the handle refers to mutably
+    pub fn object_mut(&mut self, handle: Handle) -> &mut Object {
+        match &mut self.slot_mut(handle).data {
+            HeapData::Object(object) => object,
+            HeapData::Array(_) => panic!("Heap handle {:?} refers to an array", handle),
+        }
+    }
+
+    /// Borrows the array the handle refers to
+    pub fn array(&self, handle: Handle) -> &Array {
+        match &self.slot(handle).data {
+            HeapData::Array(array) => array,
+            HeapData::Object(_) => panic!("Heap handle {:?} refers to an object", handle),
+        }
+    }
+
+    /// Borrows the array the handle refers to mutably
+    pub fn array_mut(&mut self, handle: Handle) -> &mut Array {
+        match &mut self.slot_mut(handle).data {
+            HeapData::Array(array) => array,
+            HeapData::Object(_) => panic!("Heap handle {:?} refers to an object", handle),
+        }
+    }
+
+    /// Retrieves the identity hash code of the object or array the handle refers to
+    pub fn identity_hash_code(&self, handle: Handle) -> i32 {
+        self.slot(handle).hash_code
+    }
+
+    /// Keeps a value, and everything reachable from it, alive until it is unpinned
+    /// ( primitives and null keep nothing alive and are not pinned )
+    pub fn pin(&mut self, value: Value) {
+        if matches!(value, Value::Object(_) | Value::Array(_)) {
+            self.pinned.push(value);
+        }
+    }
+
+    /// Releases a value kept alive by `pin`
+    pub fn unpin(&mut self, value: &Value) {
+        if let Some(index) = self
+            .pinned
+            .iter()
+            .rposition(|pinned| pinned.same_reference(value))
+        {
+            self.pinned.remove(index);
+        }
+    }
+
+    /// Frees every object and array not reachable from the given roots or the pinned values
+    /// ( mark-sweep )
+    pub fn collect<'a>(&mut self, roots: impl Iterator<Item = &'a Value>) {
+        // Mark phase: trace the object graph from the roots, with an explicit
+        // stack instead of recursion so that long linked lists are no issue
+        let mut pending: Vec<Handle> = roots.filter_map(Value::handle).collect();
+        pending.extend(self.pinned.iter().filter_map(Value::handle));
+
+        while let Some(handle) = pending.pop() {
+            let slot = self.slot_mut(handle);
+
+            if slot.marked {
+                continue;
+            }
+
+            slot.marked = true;
+
+            match &slot.data {
+                HeapData::Object(object) => {
+                    pending.extend(object.fields.values().filter_map(Value::handle))
+                }
+                HeapData::Array(array) => {
+                    pending.extend(array.references().iter().filter_map(Value::handle))
+                }
+            }
+        }
+
+        // Sweep phase: free the unmarked slots and clear the marks for the next collection
+        let mut freed = 0;
+
+        for (index, entry) in self.slots.iter_mut().enumerate() {
+            match entry {
+                Some(slot) if slot.marked => slot.marked = false,
+                Some(slot) => {
+                    freed += slot.size;
+                    *entry = None;
+                    self.free_slots.push(index);
+                }
+                None => {}
+            }
+        }
+
+        self.used -= freed;
+
+        // Collect again once the live data doubled, to keep collections rare in large heaps
+        self.collection_threshold = (self.used * 2)
+            .max(INITIAL_COLLECTION_THRESHOLD)
+            .min(self.max_size);
+
+        debug_log!(
+            "GC: freed {} bytes, {} bytes live, next collection at {} bytes",
+            freed,
+            self.used,
+            self.collection_threshold
+        );
+    }
+}
@@ -1,9 +1,31 @@
-use std::{cell::RefCell, rc::Rc};
+use std::rc::Rc;
 
 use crate::{
     debug_log,
-    parser::{class_file::ClassFile, constant_pool_info::CpInfo, opcode::Opcode},
-    vm::{call_stack::CallStack, runtime::RuntimeDataArea, stack_frame::Frame, value::Value},
+    parser::{
+        access_flags::ACC_PUBLIC, class_file::ClassFile, code_attribute::ExceptionTableEntry,
+        constant_pool_info::CpInfo, descriptor, opcode::Opcode,
+    },
+    vm::{
+        array::{self, Array, ArrayElements},
+        bootstrap::is_jdk_class,
+        call_site::{self, CallSite, LambdaForm, LambdaReturn, RecipeElement},
+        call_stack::CallStack,
+        class::{Class, Method},
+        error::{SourceContext, VmError},
+        heap::{Heap, Reservation},
+        local::{LocalType, Slot},
+        native::{
+            self, MAX_STACK_TRACE_DEPTH, THROWABLE_BACKTRACE, THROWABLE_CAUSE,
+            THROWABLE_DETAIL_MESSAGE,
+        },
+        null_pointer::null_pointer_message,
+        object::Object,
+        runtime::RuntimeDataArea,
+        stack_frame::Frame,
+        string, thread,
+        value::Value,
+    },
 };
 
 pub struct InstructionExecutor;
@@ -23,6 +45,22 @@ pub enum InstructionCompleted {
     /// Indicates that the instruction was not a return, and thus the execution should
     /// resume from the instruction at the program counter.
     ContinueMethodExecution,
+
+    /// Indicates that the instruction threw the given exception, or that a method it invoked
+    /// completed abruptly with it. The caller should look for a handler in the current method
+    /// and return the exception to its own caller if there is none.
+    ThrowException(Value),
+
+    /// Indicates that the instruction failed with a runtime exception of the given class and
+    /// detail message that the VM itself must create, e.g. an ArithmeticException for a division
+    /// by zero. The frame allocates it and then handles it like `ThrowException`.
+    ThrowNewException(&'static str, Option<String>),
+
+    /// Indicates that the instruction invokes the given method with the given arguments. The
+    /// caller runs it in a new frame, or in place for natives, and resumes the current method
+    /// at the next instruction once it returns. A native completing with it hands its own
+    /// invocation over to the method.
+    InvokeMethod(Rc<Method>, Vec<Value>),
 }
 
 impl InstructionExecutor {
@@ -30,15 +68,15 @@ impl InstructionExecutor {
         Self {}
     }
 
+    /// Executes an instruction that only needs its own frame, the frame stays on the call
+    /// stack while it runs, see `needs_call_stack` for the others
     pub fn execute_instruction(
         &self,
         opcode: Opcode,
         frame: &mut Frame,
-        class_file: &ClassFile,
         runtime_data_area: &mut RuntimeDataArea,
-        call_stack: &mut CallStack,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         match opcode {
             Opcode::Iconstm1 => self.execute_iconst_m1(frame),
             Opcode::Aconst_null => self.execute_aconst_null(frame),
@@ -57,82 +95,78 @@ impl InstructionExecutor {
             Opcode::Dconst1 => self.execute_dconst_1(frame),
             Opcode::Bipush => self.execute_bipush(frame, pc),
             Opcode::Sipush => self.execute_sipush(frame, pc),
-            Opcode::Ldc => self.execute_ldc(frame, class_file, pc),
-            Opcode::Ldc_w => self.execute_ldc_w(frame, class_file, pc),
-            Opcode::Ldc2_w => self.execute_ldc2_w(frame, class_file, pc),
-            //TODO: For now, Iload<n>, Lload<n>, Fload<n>, and Dload<n>
-            // instructions can be handled by the same function
-            // as I don't do type validation yet, but in the future,
-            // this should be separated
+            // The instructions resolving constants borrow the constant pool through a handle
+            // on the class of their own, the frame is borrowed mutably
+            Opcode::Ldc2_w => {
+                let class = frame.class.clone();
+                self.execute_ldc2_w(frame, &class.class_file, pc)
+            }
             Opcode::Iload => self.execute_iload(frame, pc),
-            Opcode::Lload => self.execute_iload(frame, pc),
-            Opcode::Fload => self.execute_iload(frame, pc),
-            Opcode::Dload => self.execute_iload(frame, pc),
-            Opcode::Iload0 => self.execute_iload_0(frame),
-            Opcode::Iload1 => self.execute_iload_1(frame),
-            Opcode::Iload2 => self.execute_iload_2(frame),
-            Opcode::Iload3 => self.execute_iload_3(frame),
-            Opcode::Lload0 => self.execute_iload_0(frame),
-            Opcode::Lload1 => self.execute_iload_1(frame),
-            Opcode::Lload2 => self.execute_iload_2(frame),
-            Opcode::Lload3 => self.execute_iload_3(frame),
-            Opcode::Fload0 => self.execute_iload_0(frame),
-            Opcode::Fload1 => self.execute_iload_1(frame),
-            Opcode::Fload2 => self.execute_iload_2(frame),
-            Opcode::Fload3 => self.execute_iload_3(frame),
-            Opcode::Dload0 => self.execute_iload_0(frame),
-            Opcode::Dload1 => self.execute_iload_1(frame),
-            Opcode::Dload2 => self.execute_iload_2(frame),
-            Opcode::Dload3 => self.execute_iload_3(frame),
+            Opcode::Lload => self.execute_lload(frame, pc),
+            Opcode::Fload => self.execute_fload(frame, pc),
+            Opcode::Dload => self.execute_dload(frame, pc),
+            Opcode::Iload0 => self.load_local(frame, 0, LocalType::Int),
+            Opcode::Iload1 => self.load_local(frame, 1, LocalType::Int),
+            Opcode::Iload2 => self.load_local(frame, 2, LocalType::Int),
+            Opcode::Iload3 => self.load_local(frame, 3, LocalType::Int),
+            Opcode::Lload0 => self.load_local(frame, 0, LocalType::Long),
+            Opcode::Lload1 => self.load_local(frame, 1, LocalType::Long),
+            Opcode::Lload2 => self.load_local(frame, 2, LocalType::Long),
+            Opcode::Lload3 => self.load_local(frame, 3, LocalType::Long),
+            Opcode::Fload0 => self.load_local(frame, 0, LocalType::Float),
+            Opcode::Fload1 => self.load_local(frame, 1, LocalType::Float),
+            Opcode::Fload2 => self.load_local(frame, 2, LocalType::Float),
+            Opcode::Fload3 => self.load_local(frame, 3, LocalType::Float),
+            Opcode::Dload0 => self.load_local(frame, 0, LocalType::Double),
+            Opcode::Dload1 => self.load_local(frame, 1, LocalType::Double),
+            Opcode::Dload2 => self.load_local(frame, 2, LocalType::Double),
+            Opcode::Dload3 => self.load_local(frame, 3, LocalType::Double),
             Opcode::Aload => self.execute_aload(frame, pc),
             Opcode::Aload_0 => self.execute_aload_0(frame),
             Opcode::Aload_1 => self.execute_aload_1(frame),
             Opcode::Aload_2 => self.execute_aload_2(frame),
             Opcode::Aload_3 => self.execute_aload_3(frame),
-            Opcode::Iaload => self.execute_iaload(frame),
-            Opcode::Laload => self.execute_laload(frame),
-            Opcode::Faload => self.execute_faload(frame),
-            Opcode::Daload => self.execute_daload(frame),
-            Opcode::Aaload => self.execute_aaload(frame),
-            Opcode::Baload => self.execute_baload(frame),
-            Opcode::Caload => self.execute_caload(frame),
-            Opcode::Saload => self.execute_saload(frame),
-            //TODO: For now, Istore_<n>, Lstore_<n>, Fstore_<n>, and Dstore_<n>
-            // instructions can be handled by the same function
-            // as I don't do type validation yet, but in the future,
-            // this should be separated
+            Opcode::Iaload => self.execute_iaload(frame, runtime_data_area),
+            Opcode::Laload => self.execute_laload(frame, runtime_data_area),
+            Opcode::Faload => self.execute_faload(frame, runtime_data_area),
+            Opcode::Daload => self.execute_daload(frame, runtime_data_area),
+            Opcode::Aaload => self.execute_aaload(frame, runtime_data_area),
+            Opcode::Baload => self.execute_baload(frame, runtime_data_area),
+            Opcode::Caload => self.execute_caload(frame, runtime_data_area),
+            Opcode::Saload => self.execute_saload(frame, runtime_data_area),
             Opcode::Istore => self.execute_istore(frame, pc),
-            Opcode::Lstore => self.execute_istore(frame, pc),
-            Opcode::Fstore => self.execute_istore(frame, pc),
-            Opcode::Dstore => self.execute_istore(frame, pc),
-            Opcode::Astore => self.execute_istore(frame, pc),
-            Opcode::Istore_0 => self.execute_istore_0(frame),
-            Opcode::Istore_1 => self.execute_istore_1(frame),
-            Opcode::Istore_2 => self.execute_istore_2(frame),
-            Opcode::Istore_3 => self.execute_istore_3(frame),
-            Opcode::Lstore_0 => self.execute_istore_0(frame),
-            Opcode::Lstore_1 => self.execute_istore_1(frame),
-            Opcode::Lstore_2 => self.execute_istore_2(frame),
-            Opcode::Lstore_3 => self.execute_istore_3(frame),
-            Opcode::Fstore_0 => self.execute_istore_0(frame),
-            Opcode::Fstore_1 => self.execute_istore_1(frame),
-            Opcode::Fstore_2 => self.execute_istore_2(frame),
-            Opcode::Fstore_3 => self.execute_istore_3(frame),
-            Opcode::Dstore_0 => self.execute_istore_0(frame),
-            Opcode::Dstore_1 => self.execute_istore_1(frame),
-            Opcode::Dstore_2 => self.execute_istore_2(frame),
-            Opcode::Dstore_3 => self.execute_istore_3(frame),
-            Opcode::Astore_0 => self.execute_istore_0(frame),
-            Opcode::Astore_1 => self.execute_istore_1(frame),
-            Opcode::Astore_2 => self.execute_istore_2(frame),
-            Opcode::Astore_3 => self.execute_istore_3(frame),
-            Opcode::Iastore => self.execute_iastore(frame),
-            Opcode::Lastore => self.execute_lastore(frame),
-            Opcode::Fastore => self.execute_fastore(frame),
-            Opcode::Dastore => self.execute_dastore(frame),
-            Opcode::Bastore => self.execute_bastore(frame),
-            Opcode::Castore => self.execute_castore(frame),
-            Opcode::Sastore => self.execute_sastore(frame),
+            Opcode::Lstore => self.execute_lstore(frame, pc),
+            Opcode::Fstore => self.execute_fstore(frame, pc),
+            Opcode::Dstore => self.execute_dstore(frame, pc),
+            Opcode::Astore => self.execute_astore(frame, pc),
+            Opcode::Istore_0 => self.store_local(frame, 0, LocalType::Int),
+            Opcode::Istore_1 => self.store_local(frame, 1, LocalType::Int),
+            Opcode::Istore_2 => self.store_local(frame, 2, LocalType::Int),
+            Opcode::Istore_3 => self.store_local(frame, 3, LocalType::Int),
+            Opcode::Lstore_0 => self.store_local(frame, 0, LocalType::Long),
+            Opcode::Lstore_1 => self.store_local(frame, 1, LocalType::Long),
+            Opcode::Lstore_2 => self.store_local(frame, 2, LocalType::Long),
+            Opcode::Lstore_3 => self.store_local(frame, 3, LocalType::Long),
+            Opcode::Fstore_0 => self.store_local(frame, 0, LocalType::Float),
+            Opcode::Fstore_1 => self.store_local(frame, 1, LocalType::Float),
+            Opcode::Fstore_2 => self.store_local(frame, 2, LocalType::Float),
+            Opcode::Fstore_3 => self.store_local(frame, 3, LocalType::Float),
+            Opcode::Dstore_0 => self.store_local(frame, 0, LocalType::Double),
+            Opcode::Dstore_1 => self.store_local(frame, 1, LocalType::Double),
+            Opcode::Dstore_2 => self.store_local(frame, 2, LocalType::Double),
+            Opcode::Dstore_3 => self.store_local(frame, 3, LocalType::Double),
+            Opcode::Astore_0 => self.store_local(frame, 0, LocalType::Reference),
+            Opcode::Astore_1 => self.store_local(frame, 1, LocalType::Reference),
+            Opcode::Astore_2 => self.store_local(frame, 2, LocalType::Reference),
+            Opcode::Astore_3 => self.store_local(frame, 3, LocalType::Reference),
+            Opcode::Iastore => self.execute_iastore(frame, runtime_data_area),
+            Opcode::Lastore => self.execute_lastore(frame, runtime_data_area),
+            Opcode::Fastore => self.execute_fastore(frame, runtime_data_area),
+            Opcode::Dastore => self.execute_dastore(frame, runtime_data_area),
+            Opcode::Aastore => self.execute_aastore(frame, runtime_data_area),
+            Opcode::Bastore => self.execute_bastore(frame, runtime_data_area),
+            Opcode::Castore => self.execute_castore(frame, runtime_data_area),
+            Opcode::Sastore => self.execute_sastore(frame, runtime_data_area),
             Opcode::Pop => self.execute_pop(frame),
             Opcode::Pop2 => self.execute_pop2(frame),
             Opcode::Dup => self.execute_dup(frame),
@@ -212,7 +246,11 @@ impl InstructionExecutor {
             Opcode::If_icmpge => self.execute_if_icmpge(frame, pc),
             Opcode::If_icmpgt => self.execute_if_icmpgt(frame, pc),
             Opcode::If_icmple => self.execute_if_icmple(frame, pc),
+            Opcode::If_acmpeq => self.execute_if_acmpeq(frame, pc),
+            Opcode::If_acmpne => self.execute_if_acmpne(frame, pc),
             Opcode::Goto => self.execute_goto(frame, pc),
+            Opcode::Jsr => self.execute_jsr(frame, pc),
+            Opcode::Ret => self.execute_ret(frame, pc),
             Opcode::Tableswitch => self.execute_tableswitch(frame, pc),
             Opcode::Lookupswitch => self.execute_lookupswitch(frame, pc),
             Opcode::Ireturn => self.execute_ireturn(frame),
@@ -221,128 +259,222 @@ impl InstructionExecutor {
             Opcode::Dreturn => self.execute_dreturn(frame),
             Opcode::Areturn => self.execute_areturn(frame),
             Opcode::Return => self.execute_return(),
-            Opcode::Getstatic => self.execute_getstatic(frame, class_file, runtime_data_area, pc),
-            Opcode::Putstatic => self.execute_putstatic(frame, class_file, runtime_data_area, pc),
-            Opcode::Invokevirtual => self.execute_invokevirtual(frame, class_file, pc),
+            Opcode::Getfield => {
+                let class = frame.class.clone();
+                self.execute_getfield(frame, &class.class_file, runtime_data_area, pc)
+            }
+            Opcode::Putfield => {
+                let class = frame.class.clone();
+                self.execute_putfield(frame, &class.class_file, runtime_data_area, pc)
+            }
+            Opcode::Invokevirtual => {
+                let class = frame.class.clone();
+                self.execute_invokevirtual(frame, &class.class_file, runtime_data_area, pc)
+            }
             Opcode::Invokespecial => {
-                // TODO: implement invokespecial
-                debug_log!("  Unhandled opcode: {:?}", opcode);
-                Ok(InstructionCompleted::ContinueMethodExecution)
+                let class = frame.class.clone();
+                self.execute_invokespecial(frame, &class.class_file, runtime_data_area, pc)
             }
-            Opcode::Invokestatic => {
-                self.execute_invokestatic(frame, class_file, runtime_data_area, call_stack, pc)
+            Opcode::Invokeinterface => {
+                let class = frame.class.clone();
+                self.execute_invokeinterface(frame, &class.class_file, runtime_data_area, pc)
+            }
+            Opcode::Athrow => self.execute_athrow(frame),
+            Opcode::Arraylength => self.execute_arraylength(frame, runtime_data_area),
+            Opcode::Checkcast => {
+                let class = frame.class.clone();
+                self.execute_checkcast(frame, &class.class_file, runtime_data_area, pc)
+            }
+            Opcode::Instanceof => {
+                let class = frame.class.clone();
+                self.execute_instanceof(frame, &class.class_file, runtime_data_area, pc)
             }
-            Opcode::Newarray => self.execute_newarray(frame, pc),
-            Opcode::Arraylength => self.execute_arraylength(frame),
             Opcode::Goto_w => self.execute_goto_w(frame, pc),
+            Opcode::Jsr_w => self.execute_jsr_w(frame, pc),
             Opcode::Ifnull => self.execute_ifnull(frame, pc),
             Opcode::Ifnonnull => self.execute_ifnonnull(frame, pc),
-            _ => {
-                debug_log!("  Unhandled opcode: {:?}", opcode);
-                Ok(InstructionCompleted::ContinueMethodExecution)
+            Opcode::Wide => self.execute_wide(frame, pc),
+            Opcode::Nop => Ok(InstructionCompleted::ContinueMethodExecution),
+            Opcode::Ldc
+            | Opcode::Ldc_w
+            | Opcode::Getstatic
+            | Opcode::Putstatic
+            | Opcode::Invokestatic
+            | Opcode::Invokedynamic
+            | Opcode::New
+            | Opcode::Monitorenter
+            | Opcode::Monitorexit
+            | Opcode::Newarray
+            | Opcode::Anewarray
+            | Opcode::Multianewarray => Err(VmError::internal(format!(
+                "{:?} needs the call stack",
+                opcode
+            ))),
+        }
+    }
+
+    /// Whether an instruction needs every frame of the call stack rather than only its own,
+    /// because it may initialize a class, allocate or block the thread
+    pub fn needs_call_stack(&self, opcode: Opcode) -> bool {
+        matches!(
+            opcode,
+            Opcode::Ldc
+                | Opcode::Ldc_w
+                | Opcode::Getstatic
+                | Opcode::Putstatic
+                | Opcode::Invokestatic
+                | Opcode::Invokedynamic
+                | Opcode::New
+                | Opcode::Monitorenter
+                | Opcode::Monitorexit
+                | Opcode::Newarray
+                | Opcode::Anewarray
+                | Opcode::Multianewarray
+        )
+    }
+
+    /// Executes an instruction that needs every frame of the call stack, its own frame is
+    /// taken off the stack while it runs and put back with `CallStack::with_frame` for what
+    /// needs the whole stack
+    pub fn execute_stack_instruction(
+        &self,
+        opcode: Opcode,
+        frame: &mut Frame,
+        class_file: &ClassFile,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        match opcode {
+            Opcode::Ldc => self.execute_ldc(frame, class_file, runtime_data_area, call_stack, pc),
+            Opcode::Ldc_w => {
+                self.execute_ldc_w(frame, class_file, runtime_data_area, call_stack, pc)
+            }
+            Opcode::Getstatic => {
+                self.execute_getstatic(frame, class_file, runtime_data_area, call_stack, pc)
+            }
+            Opcode::Putstatic => {
+                self.execute_putstatic(frame, class_file, runtime_data_area, call_stack, pc)
             }
+            Opcode::Invokestatic => {
+                self.execute_invokestatic(frame, class_file, runtime_data_area, call_stack, pc)
+            }
+            Opcode::Invokedynamic => {
+                self.execute_invokedynamic(frame, class_file, runtime_data_area, call_stack, pc)
+            }
+            Opcode::New => self.execute_new(frame, class_file, runtime_data_area, call_stack, pc),
+            Opcode::Monitorenter => self.execute_monitorenter(frame, call_stack),
+            Opcode::Monitorexit => self.execute_monitorexit(frame, call_stack),
+            Opcode::Newarray => self.execute_newarray(frame, runtime_data_area, call_stack, pc),
+            Opcode::Anewarray => {
+                self.execute_anewarray(frame, class_file, runtime_data_area, call_stack, pc)
+            }
+            Opcode::Multianewarray => {
+                self.execute_multianewarray(frame, class_file, runtime_data_area, call_stack, pc)
+            }
+            opcode => self.execute_instruction(opcode, frame, runtime_data_area, pc),
         }
     }
 
     /// Push integer constant -1 onto the operand stack
-    fn execute_iconst_m1(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_iconst_m1(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Int(-1));
         debug_log!("  iconst_m1");
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
-    fn execute_aconst_null(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_aconst_null(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Null);
         debug_log!("  aconst_null");
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
     /// Push integer constant 0 onto the operand stack
-    fn execute_iconst_0(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_iconst_0(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Int(0));
         debug_log!("  iconst_0");
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
     /// Push integer constant 1 onto the operand stack
-    fn execute_iconst_1(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_iconst_1(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Int(1));
         debug_log!("  iconst_1");
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
     /// Push integer constant 2 onto the operand stack
-    fn execute_iconst_2(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_iconst_2(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Int(2));
         debug_log!("  iconst_2");
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
     /// Push integer constant 3 onto the operand stack
-    fn execute_iconst_3(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_iconst_3(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Int(3));
         debug_log!("  iconst_3");
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
     /// Push integer constant 4 onto the operand stack
-    fn execute_iconst_4(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_iconst_4(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Int(4));
         debug_log!("  iconst_4");
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
     /// Push integer constant 5 onto the operand stack
-    fn execute_iconst_5(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_iconst_5(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Int(5));
         debug_log!("  iconst_5");
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
     /// Push long constant 0 onto the operand stack
-    fn execute_lconst_0(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_lconst_0(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Long(0 as i64));
         debug_log!("  lconst_0");
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
     /// Push long constant 1 onto the operand stack
-    fn execute_lconst_1(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_lconst_1(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Long(1 as i64));
         debug_log!("  lconst_1");
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
     /// Push float constant 0 onto the operand stack
-    fn execute_fconst_0(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_fconst_0(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Float(0.0));
         debug_log!("  fconst_0");
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
     /// Push float constant 1 onto the operand stack
-    fn execute_fconst_1(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_fconst_1(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Float(1.0));
         debug_log!("  fconst_1");
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
     /// Push float constant 2 onto the operand stack
-    fn execute_fconst_2(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_fconst_2(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Float(2.0));
         debug_log!("  fconst_2");
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
     /// Push double constant 0 onto the operand stack
-    fn execute_dconst_0(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dconst_0(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Double(0.0 as f64));
         debug_log!("  dconst_0");
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
     /// Push double constant 1 onto the operand stack
-    fn execute_dconst_1(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dconst_1(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         frame.operand_stack.push(Value::Double(1.0 as f64));
         debug_log!("  dconst_1");
         Ok(InstructionCompleted::ContinueMethodExecution)
@@ -353,7 +485,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let value = frame.bytecode[*pc] as i8 as i32;
         frame.operand_stack.push(Value::Int(value));
@@ -370,7 +502,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let high = frame.bytecode[*pc] as u16;
         *pc += 1;
@@ -389,19 +521,23 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         class_file: &ClassFile,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let index = frame.bytecode[*pc] as u16;
 
         if let Some(cp_entry) = class_file.constant_pool.get(index as usize) {
             match cp_entry {
                 CpInfo::String { .. } => {
-                    if let Some(string_val) = class_file.get_string(index) {
-                        frame
-                            .operand_stack
-                            .push(Value::Reference(string_val.clone()));
-                        debug_log!("  ldc \"{}\"", string_val);
+                    // AS SPECIFIED BY THE SPECS: string literals are interned
+                    if let Some(chars) = class_file.get_string_utf16(index) {
+                        debug_log!("  ldc \"{}\"", String::from_utf16_lossy(&chars));
+                        let string = call_stack.with_frame(frame, |call_stack| {
+                            runtime_data_area.intern_string(chars, call_stack)
+                        })?;
+                        frame.operand_stack.push(string);
                     }
                 }
                 CpInfo::Integer { .. } => {
@@ -416,11 +552,14 @@ impl InstructionExecutor {
                         debug_log!("  ldc {}f", float_val);
                     }
                 }
+                CpInfo::Class { .. } | CpInfo::MethodType { .. } | CpInfo::MethodHandle { .. } => {
+                    return self.load_unsupported_constant(frame, cp_entry, runtime_data_area);
+                }
                 _ => {
-                    return Err(format!(
+                    return Err(VmError::verification(format!(
                         "Invalid constant pool entry type for ldc at index {}",
                         index
-                    ));
+                    )));
                 }
             }
         }
@@ -434,8 +573,10 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         class_file: &ClassFile,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let index_high = frame.bytecode[*pc] as u16;
         *pc += 1;
@@ -459,27 +600,91 @@ impl InstructionExecutor {
                     }
                 }
                 CpInfo::String { .. } => {
-                    if let Some(string_val) = class_file.get_string(index as u16) {
-                        frame
-                            .operand_stack
-                            .push(Value::Reference(string_val.clone()));
-                        debug_log!("  ldc_w \"{}\"", string_val);
+                    // AS SPECIFIED BY THE SPECS: string literals are interned
+                    if let Some(chars) = class_file.get_string_utf16(index as u16) {
+                        debug_log!("  ldc_w \"{}\"", String::from_utf16_lossy(&chars));
+                        let string = call_stack.with_frame(frame, |call_stack| {
+                            runtime_data_area.intern_string(chars, call_stack)
+                        })?;
+                        frame.operand_stack.push(string);
                     }
                 }
-                // Handle Class, MethodHandle, etc.
-                _ => return Err(format!("ldc_w cannot load Category 2 or invalid types")),
+                CpInfo::Class { .. } | CpInfo::MethodType { .. } | CpInfo::MethodHandle { .. } => {
+                    return self.load_unsupported_constant(frame, cp_entry, runtime_data_area);
+                }
+                _ => {
+                    return Err(VmError::verification(
+                        "ldc_w cannot load Category 2 or invalid types",
+                    ));
+                }
             }
         }
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
+    /// Load a `java/lang/Class`, `java/lang/invoke/MethodType` or `java/lang/invoke/MethodHandle`
+    /// constant, the VM has no instances of these classes yet: strict mode fails, otherwise
+    /// null is pushed in their place
+    fn load_unsupported_constant(
+        &self,
+        frame: &mut Frame,
+        cp_entry: &CpInfo,
+        runtime_data_area: &RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
+        let kind = match cp_entry {
+            CpInfo::Class { .. } => "Class",
+            CpInfo::MethodType { .. } => "MethodType",
+            _ => "MethodHandle",
+        };
+
+        if runtime_data_area.strict {
+            return Err(VmError::unsupported_instruction(format!(
+                "loading a {} constant is not implemented",
+                kind
+            )));
+        }
+
+        eprintln!(
+            "Warning: pushing null for an unsupported {} constant at {}",
+            kind,
+            SourceContext::new(&frame.method, frame.pc)
+        );
+        frame.operand_stack.push(Value::Null);
+
+        Ok(InstructionCompleted::ContinueMethodExecution)
+    }
+
+    /// Execute a reserved opcode or a byte that is not an opcode: strict mode fails,
+    /// otherwise the byte is skipped
+    pub fn execute_unsupported_opcode(
+        &self,
+        byte: u8,
+        frame: &Frame,
+        runtime_data_area: &RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
+        if runtime_data_area.strict {
+            return Err(VmError::unsupported_instruction(format!(
+                "opcode 0x{:02x} is not implemented",
+                byte
+            )));
+        }
+
+        eprintln!(
+            "Warning: skipping unsupported opcode 0x{:02x} at {}",
+            byte,
+            SourceContext::new(&frame.method, frame.pc)
+        );
+
+        Ok(InstructionCompleted::ContinueMethodExecution)
+    }
+
     /// Load a long or a double value from the constant pool and push it to the operand stack
     fn execute_ldc2_w(
         &self,
         frame: &mut Frame,
         class_file: &ClassFile,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let index_high = frame.bytecode[*pc] as u16;
         *pc += 1;
@@ -506,7 +711,8 @@ impl InstructionExecutor {
                     return Err(format!(
                         "Invalid constant pool entry type for ldc2_w at index {}",
                         index
-                    ));
+                    )
+                    .into());
                 }
             }
         }
@@ -514,72 +720,102 @@ impl InstructionExecutor {
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
-    /// Load an integer value at the index of the next byte's value from the bytecode
+    /// Load an int value at the index of the next byte's value from the bytecode
     /// from the frame's local variables and push it to the operand stack
     fn execute_iload(
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
-        //TODO: I assume the variable will always be an integer type as specified by the specs
-        // I think we should do a check here, but I'll choose to keep the logic simple
-        // Same applies to the other iload_<n> instruction implementations
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let index = frame.bytecode[*pc] as usize;
-        if let Some(variable) = frame.local_variables.get(index) {
-            frame.operand_stack.push(variable.clone());
-            debug_log!("  iload \"{:?}\"", variable);
-        }
-
-        Ok(InstructionCompleted::ContinueMethodExecution)
+        self.load_local(frame, index, LocalType::Int)
     }
 
-    /// Load an integer value at the index of 0
+    /// Load a long value at the index of the next byte's value from the bytecode
     /// from the frame's local variables and push it to the operand stack
-    fn execute_iload_0(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
-        let index = 0 as usize;
-        if let Some(variable) = frame.local_variables.get(index) {
-            frame.operand_stack.push(variable.clone());
-            debug_log!("  iload_0 \"{:?}\"", variable);
-        }
-
-        Ok(InstructionCompleted::ContinueMethodExecution)
+    fn execute_lload(
+        &self,
+        frame: &mut Frame,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let index = frame.bytecode[*pc] as usize;
+        self.load_local(frame, index, LocalType::Long)
     }
 
-    /// Load an integer value at the index of 1
+    /// Load a float value at the index of the next byte's value from the bytecode
     /// from the frame's local variables and push it to the operand stack
-    fn execute_iload_1(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
-        let index = 1 as usize;
-        if let Some(variable) = frame.local_variables.get(index) {
-            frame.operand_stack.push(variable.clone());
-            debug_log!("  iload_1 \"{:?}\"", variable);
-        }
-
-        Ok(InstructionCompleted::ContinueMethodExecution)
+    fn execute_fload(
+        &self,
+        frame: &mut Frame,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let index = frame.bytecode[*pc] as usize;
+        self.load_local(frame, index, LocalType::Float)
     }
 
-    /// Load an integer value at the index of 2
+    /// Load a double value at the index of the next byte's value from the bytecode
     /// from the frame's local variables and push it to the operand stack
-    fn execute_iload_2(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
-        let index = 2 as usize;
-        if let Some(variable) = frame.local_variables.get(index) {
-            frame.operand_stack.push(variable.clone());
-            debug_log!("  iload_2 \"{:?}\"", variable);
+    fn execute_dload(
+        &self,
+        frame: &mut Frame,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let index = frame.bytecode[*pc] as usize;
+        self.load_local(frame, index, LocalType::Double)
+    }
+
+    /// Push the local variable at an index to the operand stack, it must be of the type
+    /// the load instruction expects
+    ///
+    /// AS SPECIFIED BY THE SPECS: a long or a double is loaded from the lower of its two
+    /// slots, and a return address stored by `astore` cannot be loaded back
+    fn load_local(
+        &self,
+        frame: &mut Frame,
+        index: usize,
+        expected: LocalType,
+    ) -> Result<InstructionCompleted, VmError> {
+        if let Some(invalid) =
+            self.check_local_index(frame, index, expected, "Get long/double overflows locals")
+        {
+            return Ok(invalid);
         }
 
-        Ok(InstructionCompleted::ContinueMethodExecution)
+        match frame.local_variables.get(index) {
+            Some(Value::ReturnAddress(_)) => Ok(self.return_address_load(frame, index)),
+            Some(value) if expected.matches(value) => {
+                frame.operand_stack.push(value.clone());
+                debug_log!("  load[{}] = {:?}", index, value);
+                Ok(InstructionCompleted::ContinueMethodExecution)
+            }
+            _ => Ok(self.bad_local_type(frame, index, expected)),
+        }
     }
 
-    /// Load an integer value at the index of 3
-    /// from the frame's local variables and push it to the operand stack
-    fn execute_iload_3(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
-        let index = 3 as usize;
-        if let Some(variable) = frame.local_variables.get(index) {
-            frame.operand_stack.push(variable.clone());
-            debug_log!("  iload_3 \"{:?}\"", variable);
+    /// Throw a VerifyError if an access of the given type at an index is out of the local
+    /// variables of the frame, a long or a double uses the next slot too, whose overflow
+    /// is reported with the given message
+    fn check_local_index(
+        &self,
+        frame: &Frame,
+        index: usize,
+        expected: LocalType,
+        overflow: &str,
+    ) -> Option<InstructionCompleted> {
+        if !frame.local_variables.contains(index) {
+            return Some(self.invalid_local_index(frame, index));
         }
 
-        Ok(InstructionCompleted::ContinueMethodExecution)
+        let wide = matches!(expected, LocalType::Long | LocalType::Double);
+        if wide && !frame.local_variables.contains(index + 1) {
+            return Some(self.local_variable_error(frame, overflow, index));
+        }
+
+        None
     }
 
     /// Load the reference located at the index of the next byte's value in the bytecode
@@ -588,1001 +824,1163 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
-        //TODO: I assume the variable will always be a reference type as specified by the specs
-        // I think we should do a check here, but I'll choose to keep the logic simple
-        // Same applies to the other aload_<n> instruction implementations
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let index = frame.bytecode[*pc] as usize;
-
-        if let Some(value) = frame.local_variables.get(index) {
-            frame.operand_stack.push(value.clone());
-            debug_log!("  aload = {:?}", value);
-        } else {
-            return Err("Local variable is not initialized".to_string());
-        }
-
-        Ok(InstructionCompleted::ContinueMethodExecution)
+        self.load_local(frame, index, LocalType::Reference)
     }
 
     /// Load the reference located at the index of 0
     /// from the frame's local variables and push it to the operand stack
-    fn execute_aload_0(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
-        if let Some(value) = frame.local_variables.get(0) {
-            frame.operand_stack.push(value.clone());
-            debug_log!("  aload_0 = {:?}", value);
-        } else {
-            return Err("Local variable 0 is not initialized".to_string());
-        }
-
-        Ok(InstructionCompleted::ContinueMethodExecution)
+    fn execute_aload_0(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
+        self.load_local(frame, 0, LocalType::Reference)
     }
 
     /// Load the reference located at the index of 1
     /// from the frame's local variables and push it to the operand stack
-    fn execute_aload_1(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
-        if let Some(value) = frame.local_variables.get(1) {
-            frame.operand_stack.push(value.clone());
-            debug_log!("  aload_1 = {:?}", value);
-        } else {
-            return Err("Local variable 1 is not initialized".to_string());
-        }
-
-        Ok(InstructionCompleted::ContinueMethodExecution)
+    fn execute_aload_1(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
+        self.load_local(frame, 1, LocalType::Reference)
     }
 
     /// Load the reference located at the index of 2
     /// from the frame's local variables and push it to the operand stack
-    fn execute_aload_2(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
-        if let Some(value) = frame.local_variables.get(2) {
-            frame.operand_stack.push(value.clone());
-            debug_log!("  aload_2 = {:?}", value);
-        } else {
-            return Err("Local variable 2 is not initialized".to_string());
-        }
-
-        Ok(InstructionCompleted::ContinueMethodExecution)
+    fn execute_aload_2(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
+        self.load_local(frame, 2, LocalType::Reference)
     }
 
     /// Load the reference located at the index of 3
     /// from the frame's local variables and push it to the operand stack
-    fn execute_aload_3(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
-        if let Some(value) = frame.local_variables.get(3) {
-            frame.operand_stack.push(value.clone());
-            debug_log!("  aload_3 = {:?}", value);
-        } else {
-            return Err("Local variable 3 is not initialized".to_string());
-        }
-
-        Ok(InstructionCompleted::ContinueMethodExecution)
+    fn execute_aload_3(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
+        self.load_local(frame, 3, LocalType::Reference)
     }
 
     /// Load int from array
     /// Pops index and arrayref from stack, pushes value at array[index]
-    fn execute_iaload(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_iaload(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle StackOverflow Exception
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("iaload: expected int index, got {:?}", other)),
-            None => return Err("iaload: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "iaload: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index before converting to usize
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array immutably
-                let array = arrayref.borrow();
+                let array = runtime_data_area.heap.array(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
                 // Get value from array
-                match &array[index_usize] {
-                    Value::Int(value) => {
-                        frame.operand_stack.push(Value::Int(*value));
-                        debug_log!("  iaload [{}] = {}", index, value);
-                        Ok(InstructionCompleted::ContinueMethodExecution)
-                    }
-                    other => Err(format!("iaload: array element is not int, got {:?}", other)),
-                }
-            }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot load from null array".to_string())
+                let ArrayElements::Int(values) = &array.elements else {
+                    return Err(VmError::verification(format!(
+                        "iaload: expected int array, got {}",
+                        array.descriptor
+                    )));
+                };
+                let value = values[index_usize];
+
+                debug_log!("  iaload [{}] = {:?}", index, value);
+                frame.operand_stack.push(Value::Int(value));
+                Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(other) => Err(format!("iaload: expected array reference, got {:?}", other)),
-            None => Err("iaload: failed to pop arrayref".to_string()),
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
+                "iaload: expected array reference, got {:?}",
+                other
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
     /// Load long from array
     /// Pops index and arrayref from stack, pushes value at array[index]
-    fn execute_laload(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_laload(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle StackOverflow Exception
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("laload: expected int index, got {:?}", other)),
-            None => return Err("laload: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "laload: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index before converting to usize
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array immutably
-                let array = arrayref.borrow();
+                let array = runtime_data_area.heap.array(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
                 // Get value from array
-                match &array[index_usize] {
-                    Value::Long(value) => {
-                        frame.operand_stack.push(Value::Long(*value));
-                        debug_log!("  laload [{}] = {}", index, value);
-                        Ok(InstructionCompleted::ContinueMethodExecution)
-                    }
-                    other => Err(format!(
-                        "laload: array element is not long, got {:?}",
-                        other
-                    )),
-                }
-            }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot load from null array".to_string())
+                let ArrayElements::Long(values) = &array.elements else {
+                    return Err(VmError::verification(format!(
+                        "laload: expected long array, got {}",
+                        array.descriptor
+                    )));
+                };
+                let value = values[index_usize];
+
+                debug_log!("  laload [{}] = {:?}", index, value);
+                frame.operand_stack.push(Value::Long(value));
+                Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(other) => Err(format!("laload: expected array reference, got {:?}", other)),
-            None => Err("laload: failed to pop arrayref".to_string()),
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
+                "laload: expected array reference, got {:?}",
+                other
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
     /// Load float from array
     /// Pops index and arrayref from stack, pushes value at array[index]
-    fn execute_faload(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_faload(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle StackOverflow Exception
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("faload: expected int index, got {:?}", other)),
-            None => return Err("faload: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "faload: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index before converting to usize
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array immutably
-                let array = arrayref.borrow();
+                let array = runtime_data_area.heap.array(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
                 // Get value from array
-                match &array[index_usize] {
-                    Value::Float(value) => {
-                        frame.operand_stack.push(Value::Float(*value));
-                        debug_log!("  faload [{}] = {}", index, value);
-                        Ok(InstructionCompleted::ContinueMethodExecution)
-                    }
-                    other => Err(format!(
-                        "faload: array element is not float, got {:?}",
-                        other
-                    )),
-                }
-            }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot load from null array".to_string())
+                let ArrayElements::Float(values) = &array.elements else {
+                    return Err(VmError::verification(format!(
+                        "faload: expected float array, got {}",
+                        array.descriptor
+                    )));
+                };
+                let value = values[index_usize];
+
+                debug_log!("  faload [{}] = {:?}", index, value);
+                frame.operand_stack.push(Value::Float(value));
+                Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(other) => Err(format!("faload: expected array reference, got {:?}", other)),
-            None => Err("faload: failed to pop arrayref".to_string()),
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
+                "faload: expected array reference, got {:?}",
+                other
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
     /// Load double from array
     /// Pops index and arrayref from stack, pushes value at array[index]
-    fn execute_daload(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_daload(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle StackOverflow Exception
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("daload: expected int index, got {:?}", other)),
-            None => return Err("daload: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "daload: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index before converting to usize
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array immutably
-                let array = arrayref.borrow();
+                let array = runtime_data_area.heap.array(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
                 // Get value from array
-                match &array[index_usize] {
-                    Value::Double(value) => {
-                        frame.operand_stack.push(Value::Double(*value));
-                        debug_log!("  daload [{}] = {}", index, value);
-                        Ok(InstructionCompleted::ContinueMethodExecution)
-                    }
-                    other => Err(format!(
-                        "daload: array element is not double, got {:?}",
-                        other
-                    )),
-                }
-            }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot load from null array".to_string())
+                let ArrayElements::Double(values) = &array.elements else {
+                    return Err(VmError::verification(format!(
+                        "daload: expected double array, got {}",
+                        array.descriptor
+                    )));
+                };
+                let value = values[index_usize];
+
+                debug_log!("  daload [{}] = {:?}", index, value);
+                frame.operand_stack.push(Value::Double(value));
+                Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(other) => Err(format!("daload: expected array reference, got {:?}", other)),
-            None => Err("daload: failed to pop arrayref".to_string()),
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
+                "daload: expected array reference, got {:?}",
+                other
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
     /// Load a reference value from an array and push it to the operand stack
-    fn execute_aaload(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_aaload(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         if frame.operand_stack.len() < 2 {
-            return Err("Stack underflow: aaload requires 2 operands".to_string());
+            return Ok(self.operand_stack_underflow(frame));
         }
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("aaload: expected int index, got {:?}", other)),
-            None => return Err("aaload: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "aaload: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index before converting to usize
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array immutably
-                let array = arrayref.borrow();
+                let array = runtime_data_area.heap.array(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
                 // Get value from array
-                // NOTE: I think we should wrap the Reference(String) value
-                // type in a Rc<RefCell<>> too to properly follow the JVM specs
-                match &array[index_usize] {
-                    Value::Reference(value) => {
-                        frame.operand_stack.push(Value::Reference(value.clone()));
-                        debug_log!("  aaload [{}] = {}", index, value);
-                        Ok(InstructionCompleted::ContinueMethodExecution)
-                    }
-                    other => Err(format!(
-                        "aaload: array element is not reference, got {:?}",
-                        other
-                    )),
-                }
-            }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot load from null array".to_string())
+                let ArrayElements::Reference(values) = &array.elements else {
+                    return Err(format!(
+                        "aaload: expected reference array, got {}",
+                        array.descriptor
+                    )
+                    .into());
+                };
+                let value = values[index_usize].clone();
+
+                debug_log!("  aaload [{}] = {:?}", index, value);
+                frame.operand_stack.push(value);
+                Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(other) => Err(format!("aaload: expected array reference, got {:?}", other)),
-            None => Err("aaload: failed to pop arrayref".to_string()),
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
+                "aaload: expected array reference, got {:?}",
+                other
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
     /// Load a reference value from an array and push it to the operand stack
-    fn execute_baload(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_baload(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         if frame.operand_stack.len() < 2 {
-            return Err("Stack underflow: baload requires 2 operands".to_string());
+            return Ok(self.operand_stack_underflow(frame));
         }
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("baload: expected int index, got {:?}", other)),
-            None => return Err("baload: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "baload: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index before converting to usize
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array immutably
-                let array = arrayref.borrow();
+                let array = runtime_data_area.heap.array(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
-                // Get value from array ( should be a byte stored as int )
-                match &array[index_usize] {
-                    Value::Int(byte_val) => {
-                        frame.operand_stack.push(Value::Int(*byte_val));
-                        debug_log!("  baload [{}] = {}", index, byte_val);
-                        Ok(InstructionCompleted::ContinueMethodExecution)
-                    }
-                    other => Err(format!("baload: array element is not int, got {:?}", other)),
-                }
-            }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot load from null array".to_string())
+                // Get value from array
+                let ArrayElements::Byte(values) = &array.elements else {
+                    return Err(format!(
+                        "baload: expected byte or boolean array, got {}",
+                        array.descriptor
+                    )
+                    .into());
+                };
+                let value = values[index_usize];
+
+                // Sign-extend the byte to int
+                debug_log!("  baload [{}] = {:?}", index, value);
+                frame.operand_stack.push(Value::Int(value as i32));
+                Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(other) => Err(format!("baload: expected array reference, got {:?}", other)),
-            None => Err("baload: failed to pop arrayref".to_string()),
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
+                "baload: expected array reference, got {:?}",
+                other
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
     /// Load a reference value from an array and push it to the operand stack
-    fn execute_caload(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_caload(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         if frame.operand_stack.len() < 2 {
-            return Err("Stack underflow: caload requires 2 operands".to_string());
+            return Ok(self.operand_stack_underflow(frame));
         }
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("caload: expected int index, got {:?}", other)),
-            None => return Err("caload: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "caload: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index before converting to usize
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array immutably
-                let array = arrayref.borrow();
+                let array = runtime_data_area.heap.array(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
-                // Get value from array ( should be a char stored as int )
-                match &array[index_usize] {
-                    Value::Int(byte_val) => {
-                        frame.operand_stack.push(Value::Int(*byte_val));
-                        debug_log!("  caload [{}] = {}", index, byte_val);
-                        Ok(InstructionCompleted::ContinueMethodExecution)
-                    }
-                    other => Err(format!("caload: array element is not int, got {:?}", other)),
-                }
+                // Get value from array
+                let ArrayElements::Char(values) = &array.elements else {
+                    return Err(VmError::verification(format!(
+                        "caload: expected char array, got {}",
+                        array.descriptor
+                    )));
+                };
+                let value = values[index_usize];
+
+                // Zero-extend the char to int
+                debug_log!("  caload [{}] = {:?}", index, value);
+                frame.operand_stack.push(Value::Int(value as i32));
+                Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot load from null array".to_string())
-            }
-            Some(other) => Err(format!("caload: expected array reference, got {:?}", other)),
-            None => Err("caload: failed to pop arrayref".to_string()),
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
+                "caload: expected array reference, got {:?}",
+                other
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
     /// Load a reference value from an array and push it to the operand stack
-    fn execute_saload(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_saload(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         if frame.operand_stack.len() < 2 {
-            return Err("Stack underflow: saload requires 2 operands".to_string());
+            return Ok(self.operand_stack_underflow(frame));
         }
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("saload: expected int index, got {:?}", other)),
-            None => return Err("saload: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "saload: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index before converting to usize
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array immutably
-                let array = arrayref.borrow();
+                let array = runtime_data_area.heap.array(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
-                // Get value from array ( should be a short stored as int )
-                match &array[index_usize] {
-                    Value::Int(byte_val) => {
-                        frame.operand_stack.push(Value::Int(*byte_val));
-                        debug_log!("  saload [{}] = {}", index, byte_val);
-                        Ok(InstructionCompleted::ContinueMethodExecution)
-                    }
-                    other => Err(format!("saload: array element is not int, got {:?}", other)),
-                }
-            }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot load from null array".to_string())
+                // Get value from array
+                let ArrayElements::Short(values) = &array.elements else {
+                    return Err(VmError::verification(format!(
+                        "saload: expected short array, got {}",
+                        array.descriptor
+                    )));
+                };
+                let value = values[index_usize];
+
+                // Sign-extend the short to int
+                debug_log!("  saload [{}] = {:?}", index, value);
+                frame.operand_stack.push(Value::Int(value as i32));
+                Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(other) => Err(format!("saload: expected array reference, got {:?}", other)),
-            None => Err("saload: failed to pop arrayref".to_string()),
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
+                "saload: expected array reference, got {:?}",
+                other
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
-    /// Store an integer value popped from the operand stack
+    /// Store an int value popped from the operand stack
     /// at the index of the next byte's value from the bytecode in the frame's local variables
     fn execute_istore(
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let index = frame.bytecode[*pc] as usize;
-
-        if let Some(value) = frame.operand_stack.pop() {
-            frame.local_variables.set(index, value.clone());
-            debug_log!("  istore[{}] = {:?}", index, value);
-        } else {
-            debug_log!("operand stack was empty!");
-        }
-
-        Ok(InstructionCompleted::ContinueMethodExecution)
+        self.store_local(frame, index, LocalType::Int)
     }
 
-    /// Store an integer value popped from the operand stack
-    /// at the index of the 0 in the frame's local variables
-    fn execute_istore_0(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
-        let index = 0 as usize;
-
-        if let Some(value) = frame.operand_stack.pop() {
-            frame.local_variables.set(index, value.clone());
-            debug_log!("  istore_0[{}] = {:?}", index, value);
-        } else {
-            debug_log!("operand stack was empty!");
-        }
-
-        Ok(InstructionCompleted::ContinueMethodExecution)
+    /// Store a long value popped from the operand stack
+    /// at the index of the next byte's value from the bytecode in the frame's local variables
+    fn execute_lstore(
+        &self,
+        frame: &mut Frame,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let index = frame.bytecode[*pc] as usize;
+        self.store_local(frame, index, LocalType::Long)
     }
 
-    /// Store an integer value popped from the operand stack
-    /// at the index of the 1 in the frame's local variables
-    fn execute_istore_1(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
-        let index = 1 as usize;
-
-        if let Some(value) = frame.operand_stack.pop() {
-            frame.local_variables.set(index, value.clone());
-            debug_log!("  istore_1[{}] = {:?}", index, value);
-        } else {
-            debug_log!("operand stack was empty!");
-        }
+    /// Store a float value popped from the operand stack
+    /// at the index of the next byte's value from the bytecode in the frame's local variables
+    fn execute_fstore(
+        &self,
+        frame: &mut Frame,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let index = frame.bytecode[*pc] as usize;
+        self.store_local(frame, index, LocalType::Float)
+    }
 
-        Ok(InstructionCompleted::ContinueMethodExecution)
+    /// Store a double value popped from the operand stack
+    /// at the index of the next byte's value from the bytecode in the frame's local variables
+    fn execute_dstore(
+        &self,
+        frame: &mut Frame,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let index = frame.bytecode[*pc] as usize;
+        self.store_local(frame, index, LocalType::Double)
     }
 
-    /// Store an integer value popped from the operand stack
-    /// at the index of the 2 in the frame's local variables
-    fn execute_istore_2(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
-        let index = 2 as usize;
+    /// Store a reference or a return address popped from the operand stack
+    /// at the index of the next byte's value from the bytecode in the frame's local variables
+    fn execute_astore(
+        &self,
+        frame: &mut Frame,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let index = frame.bytecode[*pc] as usize;
+        self.store_local(frame, index, LocalType::Reference)
+    }
 
-        if let Some(value) = frame.operand_stack.pop() {
-            frame.local_variables.set(index, value.clone());
-            debug_log!("  istore_2[{}] = {:?}", index, value);
-        } else {
-            debug_log!("operand stack was empty!");
+    /// Store the value popped from the operand stack by a store instruction
+    /// at an index in the frame's local variables, it must be of the type the instruction expects
+    ///
+    /// AS SPECIFIED BY THE SPECS: `astore` also stores the return addresses pushed by `jsr`
+    fn store_local(
+        &self,
+        frame: &mut Frame,
+        index: usize,
+        expected: LocalType,
+    ) -> Result<InstructionCompleted, VmError> {
+        if let Some(invalid) =
+            self.check_local_index(frame, index, expected, "Local variable table overflow")
+        {
+            return Ok(invalid);
         }
 
-        Ok(InstructionCompleted::ContinueMethodExecution)
-    }
-
-    /// Store an integer value popped from the operand stack
-    /// at the index of the 3 in the frame's local variables
-    fn execute_istore_3(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
-        let index = 3 as usize;
+        let Some(value) = frame.operand_stack.pop() else {
+            return Ok(self.operand_stack_underflow(frame));
+        };
 
-        if let Some(value) = frame.operand_stack.pop() {
-            frame.local_variables.set(index, value.clone());
-            debug_log!("  istore_3[{}] = {:?}", index, value);
-        } else {
-            debug_log!("operand stack was empty!");
+        let accepted = expected.matches(&value)
+            || (expected == LocalType::Reference && matches!(value, Value::ReturnAddress(_)));
+        if !accepted {
+            return Ok(self.bad_stack_type(frame, &value, expected));
         }
 
+        debug_log!("  store[{}] = {:?}", index, value);
+        frame.local_variables.set(index, value)?;
+
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
     /// Pop value, index, and arrayref from the operand stack
     /// and set arrayref[index] = value
-    fn execute_iastore(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_iastore(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle StackOverflow Exception
 
         // Pop value to store
         let value = match frame.operand_stack.pop() {
             Some(Value::Int(v)) => v,
-            Some(other) => return Err(format!("iastore: expected int value, got {:?}", other)),
-            None => return Err("iastore: failed to pop value".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "iastore: expected int value, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("iastore: expected int index, got {:?}", other)),
-            None => return Err("iastore: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "iastore: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array mutably
-                let mut array = arrayref.borrow_mut();
+                let array = runtime_data_area.heap.array_mut(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
+                let ArrayElements::Int(values) = &mut array.elements else {
+                    return Err(VmError::verification(format!(
+                        "iastore: expected int array, got {}",
+                        array.descriptor
+                    )));
+                };
+
                 // Store value in array
-                array[index_usize] = Value::Int(value);
-                debug_log!("  iastore [{}] = {}", index, value);
+                values[index_usize] = value;
+                debug_log!("  iastore [{}] = {:?}", index, values[index_usize]);
 
                 Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot store to null array".to_string())
-            }
-            Some(other) => Err(format!(
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
                 "iastore: expected array reference, got {:?}",
                 other
-            )),
-            None => Err("iastore: failed to pop arrayref".to_string()),
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
     /// Pop value, index, and arrayref from the operand stack
     /// and set arrayref[index] = value
-    fn execute_lastore(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_lastore(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle StackOverflow Exception
 
         // Pop value to store
         let value = match frame.operand_stack.pop() {
             Some(Value::Long(v)) => v,
-            Some(other) => return Err(format!("lastore: expected long value, got {:?}", other)),
-            None => return Err("lastore: failed to pop value".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "lastore: expected long value, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("lastore: expected int index, got {:?}", other)),
-            None => return Err("lastore: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "lastore: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array mutably
-                let mut array = arrayref.borrow_mut();
+                let array = runtime_data_area.heap.array_mut(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
+                let ArrayElements::Long(values) = &mut array.elements else {
+                    return Err(VmError::verification(format!(
+                        "lastore: expected long array, got {}",
+                        array.descriptor
+                    )));
+                };
+
                 // Store value in array
-                array[index_usize] = Value::Long(value);
-                debug_log!("  lastore [{}] = {}", index, value);
+                values[index_usize] = value;
+                debug_log!("  lastore [{}] = {:?}", index, values[index_usize]);
 
                 Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot store to null array".to_string())
-            }
-            Some(other) => Err(format!(
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
                 "lastore: expected array reference, got {:?}",
                 other
-            )),
-            None => Err("lastore: failed to pop arrayref".to_string()),
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
     /// Pop value, index, and arrayref from the operand stack
     /// and set arrayref[index] = value
-    fn execute_fastore(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_fastore(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle StackOverflow Exception
 
         // Pop value to store
         let value = match frame.operand_stack.pop() {
             Some(Value::Float(v)) => v,
-            Some(other) => return Err(format!("fastore: expected float value, got {:?}", other)),
-            None => return Err("fastore: failed to pop value".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "fastore: expected float value, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("fastore: expected int index, got {:?}", other)),
-            None => return Err("fastore: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "fastore: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array mutably
-                let mut array = arrayref.borrow_mut();
+                let array = runtime_data_area.heap.array_mut(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
+                let ArrayElements::Float(values) = &mut array.elements else {
+                    return Err(VmError::verification(format!(
+                        "fastore: expected float array, got {}",
+                        array.descriptor
+                    )));
+                };
+
                 // Store value in array
-                array[index_usize] = Value::Float(value);
-                debug_log!("  fastore [{}] = {}", index, value);
+                values[index_usize] = value;
+                debug_log!("  fastore [{}] = {:?}", index, values[index_usize]);
 
                 Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot store to null array".to_string())
-            }
-            Some(other) => Err(format!(
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
                 "fastore: expected array reference, got {:?}",
                 other
-            )),
-            None => Err("fastore: failed to pop arrayref".to_string()),
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
     /// Pop value, index, and arrayref from the operand stack
     /// and set arrayref[index] = value
-    fn execute_dastore(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dastore(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle StackOverflow Exception
 
         // Pop value to store
         let value = match frame.operand_stack.pop() {
             Some(Value::Double(v)) => v,
-            Some(other) => return Err(format!("dastore: expected double value, got {:?}", other)),
-            None => return Err("dastore: failed to pop value".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "dastore: expected double value, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("dastore: expected int index, got {:?}", other)),
-            None => return Err("dastore: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "dastore: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array mutably
-                let mut array = arrayref.borrow_mut();
+                let array = runtime_data_area.heap.array_mut(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
+                let ArrayElements::Double(values) = &mut array.elements else {
+                    return Err(format!(
+                        "dastore: expected double array, got {}",
+                        array.descriptor
+                    )
+                    .into());
+                };
+
                 // Store value in array
-                array[index_usize] = Value::Double(value);
-                debug_log!("  dastore [{}] = {}", index, value);
+                values[index_usize] = value;
+                debug_log!("  dastore [{}] = {:?}", index, values[index_usize]);
 
                 Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot store to null array".to_string())
-            }
-            Some(other) => Err(format!(
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
                 "dastore: expected array reference, got {:?}",
                 other
-            )),
-            None => Err("dastore: failed to pop arrayref".to_string()),
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
     /// Pop value, index, and arrayref from the operand stack
     /// and set arrayref[index] = value
-    fn execute_bastore(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_bastore(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle StackOverflow Exception
 
         // Pop value to store
         let value = match frame.operand_stack.pop() {
             Some(Value::Int(v)) => v,
-            Some(other) => return Err(format!("bastore: expected int value, got {:?}", other)),
-            None => return Err("bastore: failed to pop value".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "bastore: expected int value, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("bastore: expected int index, got {:?}", other)),
-            None => return Err("bastore: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "bastore: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array mutably
-                let mut array = arrayref.borrow_mut();
+                let array = runtime_data_area.heap.array_mut(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
+                // AS SPECIFIED BY THE SPECS: the int is truncated to a byte, or for an array
+                // of booleans narrowed by taking the bitwise AND of value and 1
+                let narrowed = match array.descriptor.as_str() {
+                    "[Z" => (value & 1) as i8,
+                    _ => value as i8,
+                };
+
+                let ArrayElements::Byte(values) = &mut array.elements else {
+                    return Err(format!(
+                        "bastore: expected byte or boolean array, got {}",
+                        array.descriptor
+                    )
+                    .into());
+                };
+
                 // Store value in array
-                let byte_value = (value as i8) as i32;
-                array[index_usize] = Value::Int(byte_value);
+                values[index_usize] = narrowed;
+                debug_log!("  bastore [{}] = {}", index, narrowed);
 
-                debug_log!("  bastore [{}] = {}", index, value);
                 Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot store to null array".to_string())
-            }
-            Some(other) => Err(format!(
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
                 "bastore: expected array reference, got {:?}",
                 other
-            )),
-            None => Err("bastore: failed to pop arrayref".to_string()),
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
     /// Pop value, index, and arrayref from the operand stack
     /// and set arrayref[index] = value
-    fn execute_castore(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_castore(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle StackOverflow Exception
 
         // Pop value to store
         let value = match frame.operand_stack.pop() {
             Some(Value::Int(v)) => v,
-            Some(other) => return Err(format!("castore: expected int value, got {:?}", other)),
-            None => return Err("castore: failed to pop value".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "castore: expected int value, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("castore: expected int index, got {:?}", other)),
-            None => return Err("castore: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "castore: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array mutably
-                let mut array = arrayref.borrow_mut();
+                let array = runtime_data_area.heap.array_mut(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
+                let ArrayElements::Char(values) = &mut array.elements else {
+                    return Err(VmError::verification(format!(
+                        "castore: expected char array, got {}",
+                        array.descriptor
+                    )));
+                };
+
+                // Truncate the int to a char
                 // Store value in array
-                let byte_value = (value as u16) as i32;
-                array[index_usize] = Value::Int(byte_value);
+                values[index_usize] = value as u16;
+                debug_log!("  castore [{}] = {:?}", index, values[index_usize]);
 
-                debug_log!("  castore [{}] = {}", index, value);
                 Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot store to null array".to_string())
-            }
-            Some(other) => Err(format!(
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
                 "castore: expected array reference, got {:?}",
                 other
-            )),
-            None => Err("castore: failed to pop arrayref".to_string()),
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
     /// Pop value, index, and arrayref from the operand stack
     /// and set arrayref[index] = value
-    fn execute_sastore(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_sastore(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle StackOverflow Exception
 
         // Pop value to store
         let value = match frame.operand_stack.pop() {
             Some(Value::Int(v)) => v,
-            Some(other) => return Err(format!("sastore: expected int value, got {:?}", other)),
-            None => return Err("sastore: failed to pop value".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "sastore: expected int value, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop index
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("sastore: expected int index, got {:?}", other)),
-            None => return Err("sastore: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "sastore: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // Pop array reference
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                // Check for negative index
-                if index < 0 {
-                    return Err(format!("ArrayIndexOutOfBoundsException: {}", index));
-                }
-
                 let index_usize = index as usize;
 
                 // Borrow the array mutably
-                let mut array = arrayref.borrow_mut();
+                let array = runtime_data_area.heap.array_mut(arrayref);
 
-                // Check upper bound
-                if index_usize >= array.len() {
-                    return Err(format!(
-                        "ArrayIndexOutOfBoundsException: Index {} out of bounds for length {}",
-                        index,
-                        array.len()
-                    ));
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= array.length() {
+                    return Ok(self.array_index_out_of_bounds(index, array.length()));
                 }
 
+                let ArrayElements::Short(values) = &mut array.elements else {
+                    return Err(VmError::verification(format!(
+                        "sastore: expected short array, got {}",
+                        array.descriptor
+                    )));
+                };
+
+                // Truncate the int to a short
                 // Store value in array
-                let byte_value = (value as i16) as i32;
-                array[index_usize] = Value::Int(byte_value);
+                values[index_usize] = value as i16;
+                debug_log!("  sastore [{}] = {:?}", index, values[index_usize]);
 
-                debug_log!("  sastore [{}] = {}", index, value);
                 Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            Some(Value::Null) => {
-                Err("NullPointerException: Cannot store to null array".to_string())
-            }
-            Some(other) => Err(format!(
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
                 "sastore: expected array reference, got {:?}",
                 other
-            )),
-            None => Err("sastore: failed to pop arrayref".to_string()),
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
+        }
+    }
+
+    /// Pop value, index, and arrayref from the operand stack
+    /// and set arrayref[index] = value
+    /// AS SPECIFIED BY THE SPECS: a non null value must be assignable to the component type
+    /// of the array, otherwise an ArrayStoreException is thrown
+    fn execute_aastore(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
+        if frame.operand_stack.len() < 3 {
+            return Ok(self.operand_stack_underflow(frame));
+        }
+
+        // Pop value to store
+        let value = match frame.operand_stack.pop() {
+            Some(value @ (Value::Object(_) | Value::Array(_) | Value::Null)) => value,
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "aastore: expected reference value, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
+        };
+
+        // Pop index
+        let index = match frame.operand_stack.pop() {
+            Some(Value::Int(i)) => i,
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "aastore: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
+        };
+
+        // Pop array reference
+        match frame.operand_stack.pop() {
+            Some(Value::Array(arrayref)) => {
+                let index_usize = index as usize;
+                let array = runtime_data_area.heap.array(arrayref);
+                let length = array.length();
+
+                // Check bounds, a negative index is out of bounds too
+                if index < 0 || index_usize >= length {
+                    return Ok(self.array_index_out_of_bounds(index, length));
+                }
+
+                // Check the store against the actual component type, String[] may be
+                // stored in an Object[] variable
+                if let Some(value_type) = self.runtime_type_name(&value, runtime_data_area) {
+                    let component_type = array::component_class_name(array.component_type())
+                        .ok_or(VmError::verification(
+                            "aastore: expected array of references",
+                        ))?
+                        .to_string();
+
+                    if !self.is_assignable(&value_type, &component_type, runtime_data_area)? {
+                        return Ok(InstructionCompleted::ThrowNewException(
+                            "java/lang/ArrayStoreException",
+                            Some(value_type.replace('/', ".")),
+                        ));
+                    }
+                }
+
+                let ArrayElements::Reference(values) =
+                    &mut runtime_data_area.heap.array_mut(arrayref).elements
+                else {
+                    return Err(VmError::verification(
+                        "aastore: expected array of references",
+                    ));
+                };
+
+                debug_log!("  aastore [{}] = {:?}", index, value);
+                values[index_usize] = value;
+
+                Ok(InstructionCompleted::ContinueMethodExecution)
+            }
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
+                "aastore: expected array reference, got {:?}",
+                other
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
     /// Pop the top operand stack value
     /// The pop instruction must not be used unless value is a value of a category 1 computational type
-    fn execute_pop(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_pop(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: This implementation lacks type validation
         let stack_size = frame.operand_stack.len();
         debug_log!("stack_size: {}", stack_size);
@@ -1599,7 +1997,7 @@ impl InstructionExecutor {
     /// Pop the top one or two operand stack values
     /// Form1: each of value1 and value2 is a value of a category 1 computational type
     /// Form2: value is a value of a category 2 computational type
-    fn execute_pop2(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_pop2(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: This implementation lacks type validation
         let stack_size = frame.operand_stack.len();
         if stack_size > 1 {
@@ -1618,7 +2016,7 @@ impl InstructionExecutor {
 
     /// ..., value →
     // ..., value, value
-    fn execute_dup(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dup(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         let stack_size = frame.operand_stack.len();
         if stack_size > 0 {
             if let Some(value) = frame.operand_stack.peek() {
@@ -1633,7 +2031,7 @@ impl InstructionExecutor {
 
     // ..., value2, value1 →
     // ..., value1, value2, value1
-    fn execute_dup_x1(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dup_x1(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         let stack_size = frame.operand_stack.len();
         if stack_size > 1 {
             if let Some(value) = frame.operand_stack.peek() {
@@ -1654,7 +2052,7 @@ impl InstructionExecutor {
     // ..., value2, value1 →
     // ..., value1, value2, value1
     // where value1 is a value of a category 1 computational type and value2 is a value of a category 2 computational type
-    fn execute_dup_x2(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dup_x2(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         let stack_size = frame.operand_stack.len();
         if stack_size > 2 {
             if let Some(value) = frame.operand_stack.peek() {
@@ -1679,7 +2077,7 @@ impl InstructionExecutor {
     // ..., value →
     // ..., value, value
     // where value is a value of a category 2 computational type
-    fn execute_dup2(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dup2(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         let stack_size = frame.operand_stack.len();
         if stack_size > 1 {
             let value1 = frame.operand_stack.peek_at(stack_size - 1).cloned();
@@ -1708,7 +2106,7 @@ impl InstructionExecutor {
     // ..., value2, value1 →
     // ..., value1, value2, value1
     // where value1 is a value of a category 2 computational type and value2 is a value of a category 1 computational type
-    fn execute_dup2_x1(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dup2_x1(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         let stack_size = frame.operand_stack.len();
         if stack_size > 2 {
             let value1 = frame.operand_stack.peek_at(stack_size - 1).cloned();
@@ -1745,7 +2143,7 @@ impl InstructionExecutor {
     // ..., value2, value1 →
     // ..., value1, value2, value1
     // where value1 and value2 are both values of a category 2 computational type
-    fn execute_dup2_x2(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dup2_x2(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         let stack_size = frame.operand_stack.len();
         if stack_size > 3 {
             let value1 = frame.operand_stack.peek_at(stack_size - 1).cloned();
@@ -1782,7 +2180,7 @@ impl InstructionExecutor {
     }
 
     /// Swap the top two operand stack values
-    fn execute_swap(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_swap(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: This implementation lacks type validation
         let stack_size = frame.operand_stack.len();
         if stack_size > 1 {
@@ -1796,7 +2194,7 @@ impl InstructionExecutor {
 
     /// Pop two integer values from the operand stack, adds them, and then
     /// push the result back onto the operand stack
-    fn execute_iadd(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_iadd(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
@@ -1812,7 +2210,7 @@ impl InstructionExecutor {
 
     /// Pop two long values from the operand stack, adds them, and then
     /// push the result back onto the operand stack
-    fn execute_ladd(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_ladd(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Long(value2)) = frame.operand_stack.pop() {
@@ -1828,7 +2226,7 @@ impl InstructionExecutor {
 
     /// Pop two float values from the operand stack, adds them, and then
     /// push the result back onto the operand stack
-    fn execute_fadd(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_fadd(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Float(value2)) = frame.operand_stack.pop() {
@@ -1844,7 +2242,7 @@ impl InstructionExecutor {
 
     /// Pop two double values from the operand stack, adds them, and then
     /// push the result back onto the operand stack
-    fn execute_dadd(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dadd(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Double(value2)) = frame.operand_stack.pop() {
@@ -1860,7 +2258,7 @@ impl InstructionExecutor {
 
     /// Pop two integer values from the operand stack, subtracts them, and then
     /// push the result back onto the operand stack
-    fn execute_isub(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_isub(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
@@ -1876,7 +2274,7 @@ impl InstructionExecutor {
 
     /// Pop two long values from the operand stack, subtracts them, and then
     /// push the result back onto the operand stack
-    fn execute_lsub(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_lsub(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Long(value2)) = frame.operand_stack.pop() {
@@ -1892,7 +2290,7 @@ impl InstructionExecutor {
 
     /// Pop two float values from the operand stack, subtracts them, and then
     /// push the result back onto the operand stack
-    fn execute_fsub(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_fsub(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Float(value2)) = frame.operand_stack.pop() {
@@ -1908,7 +2306,7 @@ impl InstructionExecutor {
 
     /// Pop two double values from the operand stack, subtracts them, and then
     /// push the result back onto the operand stack
-    fn execute_dsub(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dsub(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Double(value2)) = frame.operand_stack.pop() {
@@ -1924,7 +2322,7 @@ impl InstructionExecutor {
 
     /// Pop two integer values from the operand stack, multiplies them, and then
     /// push the result back onto the operand stack
-    fn execute_imul(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_imul(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
@@ -1940,7 +2338,7 @@ impl InstructionExecutor {
 
     /// Pop two long values from the operand stack, multiplies them, and then
     /// push the result back onto the operand stack
-    fn execute_lmul(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_lmul(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Long(value2)) = frame.operand_stack.pop() {
@@ -1956,7 +2354,7 @@ impl InstructionExecutor {
 
     /// Pop two float values from the operand stack, multiplies them, and then
     /// push the result back onto the operand stack
-    fn execute_fmul(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_fmul(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Float(value2)) = frame.operand_stack.pop() {
@@ -1972,7 +2370,7 @@ impl InstructionExecutor {
 
     /// Pop two double values from the operand stack, multiplies them, and then
     /// push the result back onto the operand stack
-    fn execute_dmul(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dmul(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Double(value2)) = frame.operand_stack.pop() {
@@ -1988,14 +2386,21 @@ impl InstructionExecutor {
 
     /// Pop two integer values from the operand stack, divides them, and then
     /// push the result back onto the operand stack
-    fn execute_idiv(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_idiv(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
-        //TODO: Handle division by zero
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Int(value1)) = frame.operand_stack.pop() {
-                let value = value1.wrapping_div(value2);
-
+                // AS SPECIFIED BY THE SPECS: an integer division by zero throws an ArithmeticException
+                if value2 == 0 {
+                    return Ok(InstructionCompleted::ThrowNewException(
+                        "java/lang/ArithmeticException",
+                        Some("/ by zero".to_string()),
+                    ));
+                }
+
+                let value = value1.wrapping_div(value2);
+
                 frame.operand_stack.push(Value::Int(value));
             }
         }
@@ -2005,12 +2410,19 @@ impl InstructionExecutor {
 
     /// Pop two long values from the operand stack, divides them, and then
     /// push the result back onto the operand stack
-    fn execute_ldiv(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_ldiv(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
-        //TODO: Handle division by zero
         if let Some(Value::Long(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Long(value1)) = frame.operand_stack.pop() {
+                // AS SPECIFIED BY THE SPECS: an integer division by zero throws an ArithmeticException
+                if value2 == 0 {
+                    return Ok(InstructionCompleted::ThrowNewException(
+                        "java/lang/ArithmeticException",
+                        Some("/ by zero".to_string()),
+                    ));
+                }
+
                 let value = value1.wrapping_div(value2);
 
                 frame.operand_stack.push(Value::Long(value));
@@ -2022,7 +2434,7 @@ impl InstructionExecutor {
 
     /// Pop two float values from the operand stack, divides them, and then
     /// push the result back onto the operand stack
-    fn execute_fdiv(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_fdiv(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         //TODO: Handle division by zero
@@ -2039,7 +2451,7 @@ impl InstructionExecutor {
 
     /// Pop two double values from the operand stack, divides them, and then
     /// push the result back onto the operand stack
-    fn execute_ddiv(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_ddiv(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         //TODO: Handle division by zero
@@ -2056,12 +2468,19 @@ impl InstructionExecutor {
 
     /// Pop two integer values from the operand stack, calculates their remainder,
     /// and then push the result back onto the operand stack
-    fn execute_irem(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_irem(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
-        //TODO: Handle division by zero
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Int(value1)) = frame.operand_stack.pop() {
+                // AS SPECIFIED BY THE SPECS: an integer division by zero throws an ArithmeticException
+                if value2 == 0 {
+                    return Ok(InstructionCompleted::ThrowNewException(
+                        "java/lang/ArithmeticException",
+                        Some("/ by zero".to_string()),
+                    ));
+                }
+
                 debug_log!("value1: {}, value2: {}", value1, value2);
 
                 let value = value1.wrapping_rem(value2);
@@ -2077,12 +2496,19 @@ impl InstructionExecutor {
 
     /// Pop two long values from the operand stack, calculates their remainder,
     /// and then push the result back onto the operand stack
-    fn execute_lrem(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_lrem(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
-        //TODO: Handle division by zero
         if let Some(Value::Long(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Long(value1)) = frame.operand_stack.pop() {
+                // AS SPECIFIED BY THE SPECS: an integer division by zero throws an ArithmeticException
+                if value2 == 0 {
+                    return Ok(InstructionCompleted::ThrowNewException(
+                        "java/lang/ArithmeticException",
+                        Some("/ by zero".to_string()),
+                    ));
+                }
+
                 debug_log!("value1: {}, value2: {}", value1, value2);
 
                 let value = value1.wrapping_rem(value2);
@@ -2098,7 +2524,7 @@ impl InstructionExecutor {
 
     /// Pop two float values from the operand stack, calculates their remainder,
     /// and then push the result back onto the operand stack
-    fn execute_frem(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_frem(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         //TODO: Handle division by zero
@@ -2119,7 +2545,7 @@ impl InstructionExecutor {
 
     /// Pop two double values from the operand stack, calculates their remainder,
     /// and then push the result back onto the operand stack
-    fn execute_drem(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_drem(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         //TODO: Handle division by zero
@@ -2140,7 +2566,7 @@ impl InstructionExecutor {
 
     /// Pop an integer value from the operand stack, negates it, and then
     /// push the result back onto the operand stack
-    fn execute_ineg(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_ineg(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Int(value)) = frame.operand_stack.pop() {
@@ -2158,7 +2584,7 @@ impl InstructionExecutor {
 
     /// Pop a long value from the operand stack, negates it, and then
     /// push the result back onto the operand stack
-    fn execute_lneg(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_lneg(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Long(value)) = frame.operand_stack.pop() {
@@ -2176,7 +2602,7 @@ impl InstructionExecutor {
 
     /// Pop a float value from the operand stack, negates it, and then
     /// push the result back onto the operand stack
-    fn execute_fneg(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_fneg(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Float(value)) = frame.operand_stack.pop() {
@@ -2194,7 +2620,7 @@ impl InstructionExecutor {
 
     /// Pop a double value from the operand stack, negates it, and then
     /// push the result back onto the operand stack
-    fn execute_dneg(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dneg(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle insufficient number of values in the operand stack
         //TODO: Handle overflows
         if let Some(Value::Double(value)) = frame.operand_stack.pop() {
@@ -2213,7 +2639,7 @@ impl InstructionExecutor {
     /// Pop two integer values from the operand stack and shift value1
     /// left by s bit positions, where s is the low 5 bits of value2
     /// and then push the result back to the operand stack
-    fn execute_ishl(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_ishl(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Int(value1)) = frame.operand_stack.pop() {
                 let mask = (value2 & 0x1F) as u32;
@@ -2229,7 +2655,7 @@ impl InstructionExecutor {
     /// Pop one integer and one long values from the operand stack and shift value1
     /// left by s bit positions, where s is the low 6 bits of value2
     /// and then push the result back to the operand stack
-    fn execute_lshl(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_lshl(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Long(value1)) = frame.operand_stack.pop() {
                 let mask = (value2 & 0x3F) as u32;
@@ -2245,7 +2671,7 @@ impl InstructionExecutor {
     /// Pop two integer values from the operand stack and shift value1
     /// right by s bit positions, where s is the low 5 bits of value2
     /// and then push the result back to the operand stack
-    fn execute_ishr(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_ishr(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Int(value1)) = frame.operand_stack.pop() {
                 let mask = (value2 & 0x1F) as u32;
@@ -2261,7 +2687,7 @@ impl InstructionExecutor {
     /// Pop one integer and one long values from the operand stack and shift value1
     /// right by s bit positions, where s is the low 6 bits of value2
     /// and then push the result back to the operand stack
-    fn execute_lshr(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_lshr(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Long(value1)) = frame.operand_stack.pop() {
                 let mask = (value2 & 0x3F) as u32;
@@ -2277,7 +2703,7 @@ impl InstructionExecutor {
     /// Pop two integer values from the operand stack and shift value1 right
     /// by s bit positions zero-extended, where s is the low 5 bits of value2
     /// and then push the result back to the operand stack
-    fn execute_iushr(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_iushr(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Int(value1)) = frame.operand_stack.pop() {
                 let mask = (value2 & 0x1F) as u32;
@@ -2294,7 +2720,7 @@ impl InstructionExecutor {
     /// Pop one integer and one long values from the operand stack and shift value1
     /// right by s bit positions zero-extended, where s is the low 6 bits of value2
     /// and then push the result back to the operand stack
-    fn execute_lushr(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_lushr(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Long(value1)) = frame.operand_stack.pop() {
                 let mask = (value2 & 0x3F) as u32;
@@ -2310,7 +2736,7 @@ impl InstructionExecutor {
 
     /// Pop two integer values from the operand stack and perform BITWISE AND on
     /// both of them and then push the result back to the operand stack
-    fn execute_iand(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_iand(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle empty stack and type validation
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Int(value1)) = frame.operand_stack.pop() {
@@ -2325,7 +2751,7 @@ impl InstructionExecutor {
 
     /// Pop two long values from the operand stack and perform BITWISE AND on
     /// both of them and then push the result back to the operand stack
-    fn execute_land(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_land(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle empty stack and type validation
         if let Some(Value::Long(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Long(value1)) = frame.operand_stack.pop() {
@@ -2340,7 +2766,7 @@ impl InstructionExecutor {
 
     /// Pop two integer values from the operand stack and perform BITWISE OR on
     /// both of them and then push the result back to the operand stack
-    fn execute_ior(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_ior(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle empty stack and type validation
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Int(value1)) = frame.operand_stack.pop() {
@@ -2355,7 +2781,7 @@ impl InstructionExecutor {
 
     /// Pop two long values from the operand stack and perform BITWISE OR on
     /// both of them and then push the result back to the operand stack
-    fn execute_lor(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_lor(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle empty stack and type validation
         if let Some(Value::Long(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Long(value1)) = frame.operand_stack.pop() {
@@ -2370,7 +2796,7 @@ impl InstructionExecutor {
 
     /// Pop two integer values from the operand stack and perform BITWISE XOR on
     /// both of them and then push the result back to the operand stack
-    fn execute_ixor(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_ixor(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle empty stack and type validation
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Int(value1)) = frame.operand_stack.pop() {
@@ -2385,7 +2811,7 @@ impl InstructionExecutor {
 
     /// Pop two long values from the operand stack and perform BITWISE XOR on
     /// both of them and then push the result back to the operand stack
-    fn execute_lxor(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_lxor(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         //TODO: Handle empty stack and type validation
         if let Some(Value::Long(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Long(value1)) = frame.operand_stack.pop() {
@@ -2405,24 +2831,85 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let index = frame.bytecode[*pc] as usize;
         *pc += 1;
         // Cast to i8 first to get the signed value, then extend to i32
         let constant = frame.bytecode[*pc] as i8 as i32;
+        self.increment_local(frame, index, constant)
+    }
 
-        if let Some(Value::Int(value)) = frame.local_variables.get(index) {
-            let new_value = value.wrapping_add(constant);
-            frame.local_variables.set(index, Value::Int(new_value));
+    /// Increment the int local variable at an index by a signed constant
+    fn increment_local(
+        &self,
+        frame: &mut Frame,
+        index: usize,
+        constant: i32,
+    ) -> Result<InstructionCompleted, VmError> {
+        if !frame.local_variables.contains(index) {
+            return Ok(self.invalid_local_index(frame, index));
         }
 
+        let Some(Value::Int(value)) = frame.local_variables.get(index) else {
+            return Ok(self.bad_local_type(frame, index, LocalType::Int));
+        };
+
+        let new_value = value.wrapping_add(constant);
+        frame.local_variables.set(index, Value::Int(new_value))?;
+
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
+    /// Execute the load, store, `iinc` or `ret` instruction following a `wide` prefix, whose
+    /// local variable index is the next two bytes instead of one, and for `iinc` the constant too
+    ///
+    /// `wide ret` relies on the subroutine support of `jsr` and `ret`, it returns through
+    /// `return_from_subroutine` like a `ret` with a one byte index
+    fn execute_wide(
+        &self,
+        frame: &mut Frame,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let opcode = Opcode::from(frame.bytecode[*pc]);
+        *pc += 1;
+        let index_high = frame.bytecode[*pc] as usize;
+        *pc += 1;
+        let index_low = frame.bytecode[*pc] as usize;
+
+        // AS SPECIFIED BY THE SPECS: (indexbyte1 << 8) | indexbyte2
+        let index = (index_high << 8) | index_low;
+
+        match opcode {
+            Opcode::Iload => self.load_local(frame, index, LocalType::Int),
+            Opcode::Lload => self.load_local(frame, index, LocalType::Long),
+            Opcode::Fload => self.load_local(frame, index, LocalType::Float),
+            Opcode::Dload => self.load_local(frame, index, LocalType::Double),
+            Opcode::Aload => self.load_local(frame, index, LocalType::Reference),
+            Opcode::Istore => self.store_local(frame, index, LocalType::Int),
+            Opcode::Lstore => self.store_local(frame, index, LocalType::Long),
+            Opcode::Fstore => self.store_local(frame, index, LocalType::Float),
+            Opcode::Dstore => self.store_local(frame, index, LocalType::Double),
+            Opcode::Astore => self.store_local(frame, index, LocalType::Reference),
+            Opcode::Iinc => {
+                *pc += 1;
+                let constant_high = frame.bytecode[*pc] as u16;
+                *pc += 1;
+                let constant_low = frame.bytecode[*pc] as u16;
+
+                // AS SPECIFIED BY THE SPECS: (constbyte1 << 8) | constbyte2
+                let constant = ((constant_high << 8) | constant_low) as i16 as i32;
+                self.increment_local(frame, index, constant)
+            }
+            Opcode::Ret => self.return_from_subroutine(frame, index, pc),
+            _ => Err(format!("Unsupported opcode after wide: {:?}", opcode).into()),
+        }
+    }
+
     /// Pop an integer value from the current frame's operand stack, cast it into a long, and
     /// finally push it back to the operand stack
-    fn execute_i2l(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_i2l(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value)) = frame.operand_stack.pop() {
             let result = value as i64;
             frame.operand_stack.push(Value::Long(result));
@@ -2434,7 +2921,7 @@ impl InstructionExecutor {
 
     /// Pop an integer value from the current frame's operand stack, cast it into a float, and
     /// finally push it back to the operand stack
-    fn execute_i2f(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_i2f(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value)) = frame.operand_stack.pop() {
             let result = value as f32;
             frame.operand_stack.push(Value::Float(result));
@@ -2446,7 +2933,7 @@ impl InstructionExecutor {
 
     /// Pop an integer value from the current frame's operand stack, cast it into a double, and
     /// finally push it back to the operand stack
-    fn execute_i2d(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_i2d(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value)) = frame.operand_stack.pop() {
             let result = value as f64;
             frame.operand_stack.push(Value::Double(result));
@@ -2458,7 +2945,7 @@ impl InstructionExecutor {
 
     /// Pop a long value from the current frame's operand stack, cast it into an integer, and
     /// finally push it back to the operand stack
-    fn execute_l2i(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_l2i(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Long(value)) = frame.operand_stack.pop() {
             let result = value as i32;
             frame.operand_stack.push(Value::Int(result));
@@ -2470,7 +2957,7 @@ impl InstructionExecutor {
 
     /// Pop a long value from the current frame's operand stack, cast it into a float, and
     /// finally push it back to the operand stack
-    fn execute_l2f(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_l2f(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Long(value)) = frame.operand_stack.pop() {
             let result = value as f32;
             frame.operand_stack.push(Value::Float(result));
@@ -2482,7 +2969,7 @@ impl InstructionExecutor {
 
     /// Pop a long value from the current frame's operand stack, cast it into a double, and
     /// finally push it back to the operand stack
-    fn execute_l2d(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_l2d(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Long(value)) = frame.operand_stack.pop() {
             let result = value as f64;
             frame.operand_stack.push(Value::Double(result));
@@ -2494,7 +2981,7 @@ impl InstructionExecutor {
 
     /// Pop a float value from the current frame's operand stack, cast it into an integer, and
     /// finally push it back to the operand stack
-    fn execute_f2i(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_f2i(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Float(value)) = frame.operand_stack.pop() {
             // AS SPECIFIED BY THE SPECS:
             // NaN converts to 0
@@ -2520,7 +3007,7 @@ impl InstructionExecutor {
 
     /// Pop a float value from the current frame's operand stack, cast it into a long, and
     /// finally push it back to the operand stack
-    fn execute_f2l(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_f2l(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Float(value)) = frame.operand_stack.pop() {
             // AS SPECIFIED BY THE SPECS:
             // NaN converts to 0
@@ -2546,7 +3033,7 @@ impl InstructionExecutor {
 
     /// Pop a float value from the current frame's operand stack, cast it into a double, and
     /// finally push it back to the operand stack
-    fn execute_f2d(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_f2d(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Float(value)) = frame.operand_stack.pop() {
             let result = value as f64;
             frame.operand_stack.push(Value::Double(result));
@@ -2558,7 +3045,7 @@ impl InstructionExecutor {
 
     /// Pop a double value from the current frame's operand stack, cast it into an integer, and
     /// finally push it back to the operand stack
-    fn execute_d2i(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_d2i(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Double(value)) = frame.operand_stack.pop() {
             // AS SPECIFIED BY THE SPECS:
             // NaN converts to 0
@@ -2584,7 +3071,7 @@ impl InstructionExecutor {
 
     /// Pop a double value from the current frame's operand stack, cast it into a double, and
     /// finally push it back to the operand stack
-    fn execute_d2l(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_d2l(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Double(value)) = frame.operand_stack.pop() {
             // AS SPECIFIED BY THE SPECS:
             // NaN converts to 0
@@ -2610,7 +3097,7 @@ impl InstructionExecutor {
 
     /// Pop a double value from the current frame's operand stack, cast it into a float, and
     /// finally push it back to the operand stack
-    fn execute_d2f(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_d2f(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Double(value)) = frame.operand_stack.pop() {
             let result = value as f32;
             debug_log!("  d2f {} -> {}", value, result);
@@ -2622,7 +3109,7 @@ impl InstructionExecutor {
 
     /// Pop an integer value from the current frame's operand stack, cast it into a byte, and
     /// finally push it back to the operand stack
-    fn execute_i2b(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_i2b(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value)) = frame.operand_stack.pop() {
             let result = (value as i8) as i32;
             frame.operand_stack.push(Value::Int(result));
@@ -2635,7 +3122,7 @@ impl InstructionExecutor {
     /// Compare two floats on the operand stack
     /// If either value is NaN, push -1 onto the stack
     /// Otherwise: push 1 if value1 > value2, 0 if equal, -1 if value1 < value2
-    fn execute_fcmpl(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_fcmpl(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Float(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Float(value1)) = frame.operand_stack.pop() {
                 let result = if value1.is_nan() || value2.is_nan() {
@@ -2659,7 +3146,7 @@ impl InstructionExecutor {
     /// Compare two floats on the operand stack
     /// If either value is NaN, push 1 onto the stack
     /// Otherwise: push 1 if value1 > value2, 0 if equal, -1 if value1 < value2
-    fn execute_fcmpg(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_fcmpg(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Float(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Float(value1)) = frame.operand_stack.pop() {
                 let result = if value1.is_nan() || value2.is_nan() {
@@ -2683,7 +3170,7 @@ impl InstructionExecutor {
     /// Compare two doubles on the operand stack
     /// If either value is NaN, push -1 onto the stack
     /// Otherwise: push 1 if value1 > value2, 0 if equal, -1 if value1 < value2
-    fn execute_dcmpl(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dcmpl(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Double(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Double(value1)) = frame.operand_stack.pop() {
                 let result = if value1.is_nan() || value2.is_nan() {
@@ -2707,7 +3194,7 @@ impl InstructionExecutor {
     /// Compare two doubles on the operand stack
     /// If either value is NaN, push 1 onto the stack
     /// Otherwise: push 1 if value1 > value2, 0 if equal, -1 if value1 < value2
-    fn execute_dcmpg(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dcmpg(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Double(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Double(value1)) = frame.operand_stack.pop() {
                 let result = if value1.is_nan() || value2.is_nan() {
@@ -2730,7 +3217,7 @@ impl InstructionExecutor {
 
     /// Pop an integer value from the current frame's operand stack, cast it into a char, and
     /// finally push it back to the operand stack
-    fn execute_i2c(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_i2c(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value)) = frame.operand_stack.pop() {
             let result = (value as u16) as i32;
             frame.operand_stack.push(Value::Int(result));
@@ -2742,7 +3229,7 @@ impl InstructionExecutor {
 
     /// Pop an integer value from the current frame's operand stack, cast it into a short, and
     /// finally push it back to the operand stack
-    fn execute_i2s(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_i2s(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value)) = frame.operand_stack.pop() {
             let result = (value as i16) as i32;
             frame.operand_stack.push(Value::Int(result));
@@ -2754,7 +3241,7 @@ impl InstructionExecutor {
 
     /// Compare two longs on the operand stack
     /// push 1 if value1 > value2, 0 if equal, -1 if value1 < value2
-    fn execute_lcmp(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_lcmp(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Long(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Long(value1)) = frame.operand_stack.pop() {
                 let result = if value1 > value2 {
@@ -2778,7 +3265,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value)) = frame.operand_stack.pop() {
             if value == 0 {
                 *pc += 1;
@@ -2791,7 +3278,7 @@ impl InstructionExecutor {
 
                 // NOTE: The offset is relative to the address of the if<cond> opcode itself,
                 // not the current PC. Using isize arithmetic avoids underflow on backward branches.
-                let branch_base = (*pc as isize) - 3;
+                let branch_base = (*pc as isize) - 2;
                 let target = (branch_base + offset as isize) as usize;
                 *pc = target.wrapping_sub(1);
             } else {
@@ -2807,7 +3294,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value)) = frame.operand_stack.pop() {
             if value != 0 {
                 *pc += 1;
@@ -2820,7 +3307,7 @@ impl InstructionExecutor {
 
                 // NOTE: The offset is relative to the address of the if<cond> opcode itself,
                 // not the current PC. Using isize arithmetic avoids underflow on backward branches.
-                let branch_base = (*pc as isize) - 3;
+                let branch_base = (*pc as isize) - 2;
                 let target = (branch_base + offset as isize) as usize;
                 *pc = target.wrapping_sub(1);
             } else {
@@ -2835,7 +3322,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value)) = frame.operand_stack.pop() {
             if value < 0 {
                 *pc += 1;
@@ -2848,7 +3335,7 @@ impl InstructionExecutor {
 
                 // NOTE: The offset is relative to the address of the if<cond> opcode itself,
                 // not the current PC. Using isize arithmetic avoids underflow on backward branches.
-                let branch_base = (*pc as isize) - 3;
+                let branch_base = (*pc as isize) - 2;
                 let target = (branch_base + offset as isize) as usize;
                 *pc = target.wrapping_sub(1);
             } else {
@@ -2864,7 +3351,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value)) = frame.operand_stack.pop() {
             if value >= 0 {
                 *pc += 1;
@@ -2877,7 +3364,7 @@ impl InstructionExecutor {
 
                 // NOTE: The offset is relative to the address of the if<cond> opcode itself,
                 // not the current PC. Using isize arithmetic avoids underflow on backward branches.
-                let branch_base = (*pc as isize) - 3;
+                let branch_base = (*pc as isize) - 2;
                 let target = (branch_base + offset as isize) as usize;
                 *pc = target.wrapping_sub(1);
             } else {
@@ -2893,7 +3380,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value)) = frame.operand_stack.pop() {
             if value > 0 {
                 *pc += 1;
@@ -2906,7 +3393,7 @@ impl InstructionExecutor {
 
                 // NOTE: The offset is relative to the address of the if<cond> opcode itself,
                 // not the current PC. Using isize arithmetic avoids underflow on backward branches.
-                let branch_base = (*pc as isize) - 3;
+                let branch_base = (*pc as isize) - 2;
                 let target = (branch_base + offset as isize) as usize;
                 *pc = target.wrapping_sub(1);
             } else {
@@ -2922,7 +3409,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value)) = frame.operand_stack.pop() {
             if value <= 0 {
                 *pc += 1;
@@ -2935,7 +3422,7 @@ impl InstructionExecutor {
 
                 // NOTE: The offset is relative to the address of the if<cond> opcode itself,
                 // not the current PC. Using isize arithmetic avoids underflow on backward branches.
-                let branch_base = (*pc as isize) - 3;
+                let branch_base = (*pc as isize) - 2;
                 let target = (branch_base + offset as isize) as usize;
                 *pc = target.wrapping_sub(1);
             } else {
@@ -2951,7 +3438,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Int(value1)) = frame.operand_stack.pop() {
                 if value1 == value2 {
@@ -2965,7 +3452,7 @@ impl InstructionExecutor {
 
                     // NOTE: The offset is relative to the address of the if<cond> opcode itself,
                     // not the current PC
-                    let branch_base = (*pc as isize) - 3;
+                    let branch_base = (*pc as isize) - 2;
                     let target = (branch_base + offset as isize) as usize;
                     *pc = target.wrapping_sub(1);
                 } else {
@@ -2982,7 +3469,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Int(value1)) = frame.operand_stack.pop() {
                 if value1 != value2 {
@@ -2996,7 +3483,7 @@ impl InstructionExecutor {
 
                     // NOTE: The offset is relative to the address of the if<cond> opcode itself,
                     // not the current PC
-                    let branch_base = (*pc as isize) - 3;
+                    let branch_base = (*pc as isize) - 2;
                     let target = (branch_base + offset as isize) as usize;
                     *pc = target.wrapping_sub(1);
                 } else {
@@ -3014,7 +3501,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Int(value1)) = frame.operand_stack.pop() {
                 if value1 < value2 {
@@ -3028,7 +3515,7 @@ impl InstructionExecutor {
 
                     // NOTE: The offset is relative to the address of the if<cond> opcode itself,
                     // not the current PC
-                    let branch_base = (*pc as isize) - 3;
+                    let branch_base = (*pc as isize) - 2;
                     let target = (branch_base + offset as isize) as usize;
                     *pc = target.wrapping_sub(1);
                 } else {
@@ -3046,7 +3533,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Int(value1)) = frame.operand_stack.pop() {
                 if value1 >= value2 {
@@ -3060,7 +3547,7 @@ impl InstructionExecutor {
 
                     // NOTE: The offset is relative to the address of the if<cond> opcode itself,
                     // not the current PC
-                    let branch_base = (*pc as isize) - 3;
+                    let branch_base = (*pc as isize) - 2;
                     let target = (branch_base + offset as isize) as usize;
                     *pc = target.wrapping_sub(1);
                 } else {
@@ -3077,7 +3564,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Int(value1)) = frame.operand_stack.pop() {
                 if value1 > value2 {
@@ -3091,7 +3578,7 @@ impl InstructionExecutor {
 
                     // NOTE: The offset is relative to the address of the if<cond> opcode itself,
                     // not the current PC
-                    let branch_base = (*pc as isize) - 3;
+                    let branch_base = (*pc as isize) - 2;
                     let target = (branch_base + offset as isize) as usize;
                     *pc = target.wrapping_sub(1);
                 } else {
@@ -3109,7 +3596,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value2)) = frame.operand_stack.pop() {
             if let Some(Value::Int(value1)) = frame.operand_stack.pop() {
                 if value1 <= value2 {
@@ -3123,7 +3610,7 @@ impl InstructionExecutor {
 
                     // NOTE: The offset is relative to the address of the if<cond> opcode itself,
                     // not the current PC
-                    let branch_base = (*pc as isize) - 3;
+                    let branch_base = (*pc as isize) - 2;
                     let target = (branch_base + offset as isize) as usize;
                     *pc = target.wrapping_sub(1);
                 } else {
@@ -3135,13 +3622,84 @@ impl InstructionExecutor {
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
+    /// Pop two references from the operand stack and check if they refer to the same object
+    /// ( string literals are interned, so equal literals are the same object )
+    fn execute_if_acmpeq(
+        &self,
+        frame: &mut Frame,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        let value2 = frame
+            .operand_stack
+            .pop()
+            .ok_or(VmError::verification("if_acmpeq: stack underflow"))?;
+        let value1 = frame
+            .operand_stack
+            .pop()
+            .ok_or(VmError::verification("if_acmpeq: stack underflow"))?;
+
+        if value1.same_reference(&value2) {
+            *pc += 1;
+            let index_high = frame.bytecode[*pc] as u16;
+            *pc += 1;
+            let index_low = frame.bytecode[*pc] as u16;
+
+            // AS SPECIFIED BY THE SPECS: (branchbyte1 << 8) | branchbyte2
+            let offset = ((index_high << 8) | index_low) as i16;
+
+            // NOTE: The offset is relative to the address of the if_acmp<cond> opcode itself
+            let branch_base = (*pc as isize) - 2;
+            let target = (branch_base + offset as isize) as usize;
+            *pc = target.wrapping_sub(1);
+        } else {
+            *pc += 2;
+        }
+
+        Ok(InstructionCompleted::ContinueMethodExecution)
+    }
+
+    /// Pop two references from the operand stack and check if they refer to different objects
+    fn execute_if_acmpne(
+        &self,
+        frame: &mut Frame,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        let value2 = frame
+            .operand_stack
+            .pop()
+            .ok_or(VmError::verification("if_acmpne: stack underflow"))?;
+        let value1 = frame
+            .operand_stack
+            .pop()
+            .ok_or(VmError::verification("if_acmpne: stack underflow"))?;
+
+        if !value1.same_reference(&value2) {
+            *pc += 1;
+            let index_high = frame.bytecode[*pc] as u16;
+            *pc += 1;
+            let index_low = frame.bytecode[*pc] as u16;
+
+            // AS SPECIFIED BY THE SPECS: (branchbyte1 << 8) | branchbyte2
+            let offset = ((index_high << 8) | index_low) as i16;
+
+            // NOTE: The offset is relative to the address of the if_acmp<cond> opcode itself
+            let branch_base = (*pc as isize) - 2;
+            let target = (branch_base + offset as isize) as usize;
+            *pc = target.wrapping_sub(1);
+        } else {
+            *pc += 2;
+        }
+
+        Ok(InstructionCompleted::ContinueMethodExecution)
+    }
+
     /// Unconditionally branch to a target address specified by a 16-bit signed offset
     /// from the address of the goto opcode itself
     fn execute_goto(
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let branchbyte1 = frame.bytecode[*pc] as u16;
         *pc += 1;
@@ -3161,6 +3719,122 @@ impl InstructionExecutor {
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
+    /// Push the address of the next instruction as a return address and jump to the subroutine
+    /// at the offset of the next two bytes, relative to the jsr opcode like goto
+    fn execute_jsr(
+        &self,
+        frame: &mut Frame,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let branchbyte1 = frame.bytecode[*pc] as u16;
+        *pc += 1;
+        let branchbyte2 = frame.bytecode[*pc] as u16;
+
+        // AS SPECIFIED BY THE SPECS: (branchbyte1 << 8) | branchbyte2
+        let offset = ((branchbyte1 << 8) | (branchbyte2)) as i16;
+        self.jump_to_subroutine(frame, offset as isize, pc)
+    }
+
+    /// Like jsr, with the offset of the next four bytes
+    fn execute_jsr_w(
+        &self,
+        frame: &mut Frame,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let branchbyte1 = frame.bytecode[*pc] as u32;
+        *pc += 1;
+        let branchbyte2 = frame.bytecode[*pc] as u32;
+        *pc += 1;
+        let branchbyte3 = frame.bytecode[*pc] as u32;
+        *pc += 1;
+        let branchbyte4 = frame.bytecode[*pc] as u32;
+
+        // AS SPECIFIED BY THE SPECS:
+        //(branchbyte1 << 24) | (branchbyte2 << 16) | (branchbyte3 << 8) | branchbyte4
+        let offset =
+            ((branchbyte1 << 24) | (branchbyte2 << 16) | (branchbyte3 << 8) | branchbyte4) as i32;
+        self.jump_to_subroutine(frame, offset as isize, pc)
+    }
+
+    /// Enter the subroutine at an offset from the jsr instruction at the frame's pc, the pc
+    /// being on the last byte of the instruction
+    ///
+    /// AS SPECIFIED BY THE SPECS: §4.9.1, class files of version 51.0 or above cannot contain
+    /// jsr or ret, and §4.10.2.5, a subroutine cannot call itself, even indirectly
+    fn jump_to_subroutine(
+        &self,
+        frame: &mut Frame,
+        offset: isize,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        if frame.class.class_file.major >= 51 {
+            return Ok(self.bad_instruction(frame));
+        }
+
+        let target = (frame.pc as isize + offset) as usize;
+        if frame.subroutines.iter().any(|(entry, _)| *entry == target) {
+            return Ok(self.old_verify_error(frame, "Recursive call to jsr entry".to_string()));
+        }
+
+        let return_address = *pc + 1;
+        frame.subroutines.push((target, return_address));
+        frame
+            .operand_stack
+            .push(Value::ReturnAddress(return_address));
+        *pc = target.wrapping_sub(1);
+
+        debug_log!("  jsr {} (target: {})", offset, target);
+        Ok(InstructionCompleted::ContinueMethodExecution)
+    }
+
+    /// Return from a subroutine to the return address in the local variable at the index of
+    /// the next byte's value
+    fn execute_ret(
+        &self,
+        frame: &mut Frame,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let index = frame.bytecode[*pc] as usize;
+        self.return_from_subroutine(frame, index, pc)
+    }
+
+    /// Continue at the return address in the local variable at an index, leaving the subroutine
+    /// it was pushed for along with any subroutine it called that did not return
+    fn return_from_subroutine(
+        &self,
+        frame: &mut Frame,
+        index: usize,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        if frame.class.class_file.major >= 51 {
+            return Ok(self.bad_instruction(frame));
+        }
+        if !frame.local_variables.contains(index) {
+            return Ok(self.invalid_local_index(frame, index));
+        }
+        let Some(Value::ReturnAddress(return_address)) = frame.local_variables.get(index) else {
+            return Ok(self.register_wrong_type(frame, index));
+        };
+        let return_address = *return_address;
+
+        // A return address can only be used once, by the subroutine it was pushed for
+        let Some(depth) = frame
+            .subroutines
+            .iter()
+            .rposition(|(_, address)| *address == return_address)
+        else {
+            return Ok(self.old_verify_error(frame, "Illegal return from subroutine".to_string()));
+        };
+        frame.subroutines.truncate(depth);
+        *pc = return_address.wrapping_sub(1);
+
+        debug_log!("  ret {} (target: {})", index, return_address);
+        Ok(InstructionCompleted::ContinueMethodExecution)
+    }
+
     /// This instruction is used for multi-way conditional branching.
     /// It checks an integer value from the operand stack against
     /// a range of case values and branches to a specific instruction address based on the match.
@@ -3168,7 +3842,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         // Save the initial address as we will need it later
         let tableswitch_start = *pc;
         *pc += 1;
@@ -3195,8 +3869,13 @@ impl InstructionExecutor {
         // The index is the parameter of the switch case
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
-            Some(other) => return Err(format!("tableswitch: expected int index, got {:?}", other)),
-            None => return Err("tableswitch: failed to pop index".to_string()),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "tableswitch: expected int index, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // If the index is out of bound, fallback to default case
@@ -3232,7 +3911,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         // Save the initial address as we will need it later
         let lookupswitch_start = *pc;
         *pc += 1;
@@ -3259,9 +3938,12 @@ impl InstructionExecutor {
         let index = match frame.operand_stack.pop() {
             Some(Value::Int(i)) => i,
             Some(other) => {
-                return Err(format!("lookupswitch: expected int index, got {:?}", other));
+                return Err(VmError::verification(format!(
+                    "lookupswitch: expected int index, got {:?}",
+                    other
+                )));
             }
-            None => return Err("lookupswitch: failed to pop index".to_string()),
+            None => return Ok(self.operand_stack_underflow(frame)),
         };
 
         // If the index is out of bound, fallback to default case
@@ -3308,71 +3990,80 @@ impl InstructionExecutor {
 
     /// Pop an integer value from the current stack's operand stack and return it to the
     /// invoker frame
-    fn execute_ireturn(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_ireturn(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Int(value)) = frame.operand_stack.pop() {
             debug_log!("  Ireturn: {}", value);
             Ok(InstructionCompleted::ReturnFromMethod(Some(Value::Int(
                 value,
             ))))
         } else {
-            Err("Ireturn: operand stack was empty or top value was not an Integer".to_string())
+            Err(VmError::verification(
+                "Ireturn: operand stack was empty or top value was not an Integer",
+            ))
         }
     }
 
     /// Pop a long value from the current stack's operand stack and return it to the
     /// invoker frame
-    fn execute_lreturn(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_lreturn(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Long(value)) = frame.operand_stack.pop() {
             debug_log!("  Lreturn: {}", value);
             Ok(InstructionCompleted::ReturnFromMethod(Some(Value::Long(
                 value,
             ))))
         } else {
-            Err("Lreturn: operand stack was empty or top value was not a Long".to_string())
+            Err(VmError::verification(
+                "Lreturn: operand stack was empty or top value was not a Long",
+            ))
         }
     }
 
     /// Pop a float value from the current stack's operand stack and return it to the
     /// invoker frame
-    fn execute_freturn(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_freturn(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Float(value)) = frame.operand_stack.pop() {
             debug_log!("  Freturn: {}", value);
             Ok(InstructionCompleted::ReturnFromMethod(Some(Value::Float(
                 value,
             ))))
         } else {
-            Err("Freturn: operand stack was empty or top value was not a Float".to_string())
+            Err(VmError::verification(
+                "Freturn: operand stack was empty or top value was not a Float",
+            ))
         }
     }
 
     /// Pop a double value from the current stack's operand stack and return it to the
     /// invoker frame
-    fn execute_dreturn(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_dreturn(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
         if let Some(Value::Double(value)) = frame.operand_stack.pop() {
             debug_log!("  Dreturn: {}", value);
             Ok(InstructionCompleted::ReturnFromMethod(Some(Value::Double(
                 value,
             ))))
         } else {
-            Err("Dreturn: operand stack was empty or top value was not a Double".to_string())
+            Err(VmError::verification(
+                "Dreturn: operand stack was empty or top value was not a Double",
+            ))
         }
     }
 
     /// Pop a reference value from the current stack's operand stack and return it to the
     /// invoker frame
-    fn execute_areturn(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
-        if let Some(Value::Reference(objectref)) = frame.operand_stack.pop() {
-            debug_log!("  Areturn: {}", objectref);
-            Ok(InstructionCompleted::ReturnFromMethod(Some(
-                Value::Reference(objectref),
-            )))
-        } else {
-            Err("Areturn: operand stack was empty or top value was not a Reference".to_string())
+    fn execute_areturn(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
+        match frame.operand_stack.pop() {
+            Some(objectref @ (Value::Array(_) | Value::Object(_) | Value::Null)) => {
+                debug_log!("  Areturn: {:?}", objectref);
+                Ok(InstructionCompleted::ReturnFromMethod(Some(objectref)))
+            }
+            _ => Err(VmError::verification(
+                "Areturn: operand stack was empty or top value was not a Reference",
+            )),
         }
     }
 
     /// Breaks the current frame's execution loop ( return void )
-    fn execute_return(&self) -> Result<InstructionCompleted, String> {
+    fn execute_return(&self) -> Result<InstructionCompleted, VmError> {
         debug_log!("  return");
         // Signal to break the execution loop
         Ok(InstructionCompleted::ReturnFromMethod(None))
@@ -3386,8 +4077,9 @@ impl InstructionExecutor {
         frame: &mut Frame,
         class_file: &ClassFile,
         runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let index_high = frame.bytecode[*pc] as u16;
         *pc += 1;
@@ -3396,22 +4088,21 @@ impl InstructionExecutor {
         // AS SPECIFIED BY THE SPECS: (byte1 << 8) | byte2
         let field_ref = (index_high << 8) | index_low;
 
-        //TODO: Handle all java standard classes
         if let Some((class_name, field_name, descriptor)) = class_file.get_field_info(field_ref) {
             debug_log!("GETSTATIC: {}.{}:{}", class_name, field_name, descriptor);
 
-            //TODO: Handle all java standard classes
-            if class_name == "java/lang/System" {
-                frame
-                    .operand_stack
-                    .push(Value::Reference("System.out".to_string()));
-                debug_log!("  getstatic System.out");
-            } else {
-                let static_field = format!("{}.{}", class_name, field_name);
-                if let Some(value) = runtime_data_area.static_fields.get(&static_field) {
-                    frame.operand_stack.push(value.clone());
-                    debug_log!("  getstatic {} = {:?}", field_name, value);
-                }
+            let (static_field, declaring_class) =
+                self.resolve_static_field(&class_name, &field_name, runtime_data_area)?;
+
+            if let Some(exception) =
+                self.initialize_class_of(&declaring_class, frame, runtime_data_area, call_stack)?
+            {
+                return Ok(InstructionCompleted::ThrowException(exception));
+            }
+
+            if let Some(value) = runtime_data_area.static_fields.get(&static_field) {
+                frame.operand_stack.push(value.clone());
+                debug_log!("  getstatic {} = {:?}", field_name, value);
             }
         }
 
@@ -3426,8 +4117,9 @@ impl InstructionExecutor {
         frame: &mut Frame,
         class_file: &ClassFile,
         runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let index_high = frame.bytecode[*pc] as u16;
         *pc += 1;
@@ -3437,78 +4129,140 @@ impl InstructionExecutor {
         let field_ref = (index_high << 8) | index_low;
 
         if let Some(value) = frame.operand_stack.pop() {
-            if let Some((class_name, field_name, _)) = class_file.get_field_info(field_ref) {
-                runtime_data_area
-                    .static_fields
-                    .insert(format!("{}.{}", class_name, field_name), value.clone());
-                debug_log!("  putstatic {}.{} = {:?}", class_name, field_name, value);
+            if let Some((class_name, field_name, descriptor)) = class_file.get_field_info(field_ref)
+            {
+                let (static_field, declaring_class) =
+                    self.resolve_static_field(&class_name, &field_name, runtime_data_area)?;
+
+                // The popped value must survive collections while the initializer runs
+                runtime_data_area.heap.pin(value.clone());
+                let initialized = self.initialize_class_of(
+                    &declaring_class,
+                    frame,
+                    runtime_data_area,
+                    call_stack,
+                );
+                runtime_data_area.heap.unpin(&value);
+
+                if let Some(exception) = initialized? {
+                    return Ok(InstructionCompleted::ThrowException(exception));
+                }
+
+                let value = self.narrow_to_field_type(value, &descriptor);
+                debug_log!("  putstatic {} = {:?}", static_field, value);
+                runtime_data_area.static_fields.insert(static_field, value);
             }
         }
 
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
-    /// Load a non-static method reference located at the index of the next two bytes' value in the bytecode
-    /// after applying the indexing equation specified by the specs from the constant pool
-    /// and invoke it
-    /// (Needs an object reference, resolved at runtime with dynamic dispatch)
-    fn execute_invokevirtual(
+    /// Load an instance field reference located at the index of the next two bytes' value in the bytecode
+    /// after applying the indexing equation specified by the specs from the constant pool,
+    /// pop the object reference from the operand stack and push the field's value
+    fn execute_getfield(
         &self,
         frame: &mut Frame,
         class_file: &ClassFile,
+        runtime_data_area: &mut RuntimeDataArea,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let index_high = frame.bytecode[*pc] as u16;
         *pc += 1;
         let index_low = frame.bytecode[*pc] as u16;
 
         // AS SPECIFIED BY THE SPECS: (indexbyte1 << 8) | indexbyte2
-        let method_ref = (index_high << 8) | index_low;
+        let field_ref = (index_high << 8) | index_low;
 
-        //TODO: Handle all java standard classes
-        if let Some((class_name, method_name, descriptor)) = class_file.get_method_info(method_ref)
-        {
-            debug_log!(
-                "INVOKEVIRTUAL: {}.{}:{}",
-                class_name,
-                method_name,
-                descriptor
-            );
-
-            if class_name == "java/io/PrintStream" {
-                if let Some(arg) = frame.operand_stack.pop() {
-                    if let Some(_print_stream) = frame.operand_stack.pop() {
-                        match arg {
-                            Value::Reference(s) => println!("{}", s),
-                            Value::Int(i) => println!("{}", i),
-                            Value::Long(l) => println!("{}", l),
-                            Value::Float(f) => println!("{}", f),
-                            Value::Double(d) => println!("{}", d),
-                            _ => println!("{:?}", arg),
-                        }
-                    }
-                }
-            } else {
-                debug_log!("Unsupported Class yet");
+        let (class_name, field_name, descriptor) =
+            class_file.get_field_info(field_ref).ok_or_else(|| {
+                VmError::verification(format!("getfield: invalid field reference #{}", field_ref))
+            })?;
+        let field_key = self.resolve_instance_field(&class_name, &field_name, runtime_data_area)?;
+
+        match frame.operand_stack.pop() {
+            Some(Value::Object(object)) => {
+                let value = runtime_data_area
+                    .heap
+                    .object(object)
+                    .fields
+                    .get(&field_key)
+                    .cloned()
+                    .unwrap_or_else(|| Value::default_for(&descriptor));
+
+                debug_log!("  getfield {} = {:?}", field_key, value);
+                frame.operand_stack.push(value);
+                Ok(InstructionCompleted::ContinueMethodExecution)
             }
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
+                "getfield: expected object reference, got {:?}",
+                other
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
+    }
 
-        Ok(InstructionCompleted::ContinueMethodExecution)
+    /// Load an instance field reference located at the index of the next two bytes' value in the bytecode
+    /// after applying the indexing equation specified by the specs from the constant pool,
+    /// pop the value and the object reference from the operand stack and set the field's value
+    fn execute_putfield(
+        &self,
+        frame: &mut Frame,
+        class_file: &ClassFile,
+        runtime_data_area: &mut RuntimeDataArea,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let index_high = frame.bytecode[*pc] as u16;
+        *pc += 1;
+        let index_low = frame.bytecode[*pc] as u16;
+
+        // AS SPECIFIED BY THE SPECS: (indexbyte1 << 8) | indexbyte2
+        let field_ref = (index_high << 8) | index_low;
+
+        let (class_name, field_name, descriptor) =
+            class_file.get_field_info(field_ref).ok_or_else(|| {
+                VmError::verification(format!("putfield: invalid field reference #{}", field_ref))
+            })?;
+        let field_key = self.resolve_instance_field(&class_name, &field_name, runtime_data_area)?;
+
+        let value = match frame.operand_stack.pop() {
+            Some(value) => self.narrow_to_field_type(value, &descriptor),
+            None => return Ok(self.operand_stack_underflow(frame)),
+        };
+
+        match frame.operand_stack.pop() {
+            Some(Value::Object(object)) => {
+                debug_log!("  putfield {} = {:?}", field_key, value);
+                runtime_data_area
+                    .heap
+                    .object_mut(object)
+                    .fields
+                    .insert(field_key, value);
+                Ok(InstructionCompleted::ContinueMethodExecution)
+            }
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
+                "putfield: expected object reference, got {:?}",
+                other
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
+        }
     }
 
-    /// Load a static method reference located at the index of the next two bytes' value in the bytecode
+    /// Load a non-static method reference located at the index of the next two bytes' value in the bytecode
     /// after applying the indexing equation specified by the specs from the constant pool
     /// and invoke it
-    /// (No object needed, resolved at compile time)
-    fn execute_invokestatic(
+    /// (Needs an object reference, resolved at runtime with dynamic dispatch)
+    fn execute_invokevirtual(
         &self,
         frame: &mut Frame,
         class_file: &ClassFile,
         runtime_data_area: &mut RuntimeDataArea,
-        call_stack: &mut CallStack,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let index_high = frame.bytecode[*pc] as u16;
         *pc += 1;
@@ -3517,177 +4271,2659 @@ impl InstructionExecutor {
         // AS SPECIFIED BY THE SPECS: (indexbyte1 << 8) | indexbyte2
         let method_ref = (index_high << 8) | index_low;
 
-        if let Some((class_name, method_name, descriptor)) = class_file.get_method_info(method_ref)
-        {
-            debug_log!(
-                "  invokestatic {}.{}:{}",
-                class_name,
-                method_name,
-                descriptor
-            );
+        let (class_name, method_name, descriptor) =
+            class_file.get_method_info(method_ref).ok_or_else(|| {
+                VmError::verification(format!(
+                    "invokevirtual: invalid method reference #{}",
+                    method_ref
+                ))
+            })?;
 
-            //TODO: Complete implementation
-            let params_count = self.count_method_params(&descriptor);
-            let mut params = Vec::new();
+        debug_log!(
+            "INVOKEVIRTUAL: {}.{}:{}",
+            class_name,
+            method_name,
+            descriptor
+        );
 
-            for i in 0..params_count {
-                if let Some(arg) = frame.operand_stack.pop() {
-                    debug_log!("param[{}] = {:?}", i, arg);
-                    params.push(arg);
-                }
-            }
+        let resolved =
+            self.resolve_method(&class_name, &method_name, &descriptor, runtime_data_area)?;
 
-            params.reverse();
+        if resolved.is_static() {
+            return Err(VmError::throw(
+                "java/lang/IncompatibleClassChangeError",
+                format!(
+                    "Expected non-static method {}.{}{}",
+                    class_name.replace('/', "."),
+                    method_name,
+                    descriptor
+                ),
+            ));
+        }
 
-            // Handle local class method
-            let method_info = match class_file.find_method(&method_name) {
-                Some(method) => method,
-                None => {
-                    debug_log!("No {} method found", method_name);
-                    return Ok(InstructionCompleted::ContinueMethodExecution);
-                }
-            };
+        let Some(mut args) = self.pop_arguments(frame, &descriptor) else {
+            return Ok(self.operand_stack_underflow(frame));
+        };
+        let Some(objectref) = frame.operand_stack.pop() else {
+            return Ok(self.operand_stack_underflow(frame));
+        };
 
-            // I assume that there will be always one attribute and it's the code attribute
-            let attribute_info = &method_info.attributes[0];
-            let info_bytes = &attribute_info.info;
+        // AS SPECIFIED BY THE SPECS: the method to invoke is selected from the class of objectref
+        let selected = match &objectref {
+            Value::Object(object) => runtime_data_area
+                .heap
+                .object(*object)
+                .class
+                .select_method(&resolved)?,
+            Value::Null => return Ok(self.null_pointer_exception(frame)),
+            _ => resolved,
+        };
 
-            // Extract max_locals (two big-endian bytes) from info_bytes[2..3]
-            let max_locals = u16::from_be_bytes([info_bytes[2], info_bytes[3]]);
+        debug_log!(
+            "  invokevirtual selected {}.{}{}",
+            selected.class_name,
+            selected.name,
+            selected.descriptor
+        );
 
-            // Extract code_length (four big-endian bytes) from info_bytes[4..8]
-            let code_length =
-                u32::from_be_bytes([info_bytes[4], info_bytes[5], info_bytes[6], info_bytes[7]])
-                    as usize;
+        args.insert(0, objectref);
 
-            // Extract bytecode from info_bytes[8..8+code_length]
-            let bytecode = info_bytes[8..8 + code_length].to_vec();
+        Ok(InstructionCompleted::InvokeMethod(selected, args))
+    }
 
-            let method_name = class_file
-                .get_utf8(method_info.name_index)
-                .ok_or("Failed to get method name")?;
+    /// Load an instance method reference located at the index of the next two bytes' value in the bytecode
+    /// after applying the indexing equation specified by the specs from the constant pool
+    /// and invoke it without dynamic dispatch
+    /// (Used for instance initialization methods, private methods and superclass methods)
+    fn execute_invokespecial(
+        &self,
+        frame: &mut Frame,
+        class_file: &ClassFile,
+        runtime_data_area: &mut RuntimeDataArea,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let index_high = frame.bytecode[*pc] as u16;
+        *pc += 1;
+        let index_low = frame.bytecode[*pc] as u16;
 
-            call_stack.push_frame(method_name, bytecode, max_locals as usize, params);
+        // AS SPECIFIED BY THE SPECS: (indexbyte1 << 8) | indexbyte2
+        let method_ref = (index_high << 8) | index_low;
 
-            let execution_result = unsafe {
-                // Get a raw pointer to self
-                let self_ptr = call_stack as *mut CallStack;
+        let (class_name, method_name, descriptor, mut method) =
+            self.resolve_method_ref(class_file, method_ref, runtime_data_area)?;
 
-                // Borrow current frame mutably
-                if let Some(current_frame) = (*self_ptr).current_frame() {
-                    // Pass self through the raw pointer ( second mutable borrow )
-                    current_frame.execute_frame(class_file, runtime_data_area, &mut *self_ptr)
-                } else {
-                    Err("No current frame found".to_string())
-                }
-            };
+        debug_log!(
+            "  invokespecial {}.{}:{}",
+            class_name,
+            method_name,
+            descriptor
+        );
 
-            match execution_result {
-                Ok(returned) => {
-                    call_stack.pop_frame();
-                    if let Some(value) = returned {
-                        if let Some(invoker_frame) = call_stack.current_frame() {
-                            invoker_frame.operand_stack.push(value);
-                        }
-                    }
-                }
-                Err(msg) => {
-                    debug_log!("Error executing frame: {}", msg);
-                }
-            }
+        let resolved_class = runtime_data_area.class_loader.load_class(&class_name)?;
+
+        // AS SPECIFIED BY THE SPECS: if the resolved method is not an instance initialization method,
+        // the symbolic reference names a superclass of the current class and ACC_SUPER is set
+        // ( always the case since Java 8 ), the method is looked up again starting from the direct
+        // superclass of the current class, and then from its superinterfaces
+        let current_class = frame.class.clone();
+        if method_name != "<init>"
+            && !resolved_class.is_interface()
+            && current_class.name != resolved_class.name
+            && current_class.is_subclass_of(&resolved_class.name)
+        {
+            method = current_class
+                .super_class
+                .as_ref()
+                .and_then(|super_class| {
+                    super_class
+                        .lookup_method(&method_name, &descriptor)
+                        .or_else(|| super_class.lookup_interface_method(&method_name, &descriptor))
+                })
+                .ok_or_else(|| {
+                    VmError::throw(
+                        "java/lang/AbstractMethodError",
+                        format!(
+                            "{}.{}{}",
+                            class_name.replace('/', "."),
+                            method_name,
+                            descriptor
+                        ),
+                    )
+                })?;
+        }
+
+        let Some(mut args) = self.pop_arguments(frame, &descriptor) else {
+            return Ok(self.operand_stack_underflow(frame));
+        };
 
-            //TODO: Handle external class methods
+        match frame.operand_stack.pop() {
+            Some(Value::Null) => return Ok(self.null_pointer_exception(frame)),
+            Some(objectref) => args.insert(0, objectref),
+            None => return Ok(self.operand_stack_underflow(frame)),
         }
 
-        Ok(InstructionCompleted::ContinueMethodExecution)
+        Ok(InstructionCompleted::InvokeMethod(method, args))
     }
 
-    /// Create a new primitive array
-    /// of the type corresponding to the next byte's value of the frame's bytecode
-    /// and of the count corresponding to the top value of the frame's operand stack
-    fn execute_newarray(
+    /// Load a static method reference located at the index of the next two bytes' value in the bytecode
+    /// after applying the indexing equation specified by the specs from the constant pool
+    /// and invoke it
+    /// (No object needed, resolved at compile time)
+    fn execute_invokestatic(
         &self,
         frame: &mut Frame,
+        class_file: &ClassFile,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
-        let atype = frame.bytecode[*pc];
+        let index_high = frame.bytecode[*pc] as u16;
+        *pc += 1;
+        let index_low = frame.bytecode[*pc] as u16;
+
+        // AS SPECIFIED BY THE SPECS: (indexbyte1 << 8) | indexbyte2
+        let method_ref = (index_high << 8) | index_low;
+
+        let (class_name, method_name, descriptor, method) =
+            self.resolve_method_ref(class_file, method_ref, runtime_data_area)?;
+
+        debug_log!(
+            "  invokestatic {}.{}:{}",
+            class_name,
+            method_name,
+            descriptor
+        );
+
+        if !method.is_static() {
+            return Err(VmError::throw(
+                "java/lang/IncompatibleClassChangeError",
+                format!(
+                    "Expected static method {}.{}{}",
+                    class_name.replace('/', "."),
+                    method_name,
+                    descriptor
+                ),
+            ));
+        }
+
+        // AS SPECIFIED BY THE SPECS: the class that declared the resolved method is initialized,
+        // before popping the arguments so that they stay reachable while the initializer runs
+        let declaring_class = runtime_data_area
+            .class_loader
+            .load_class(&method.class_name)?;
+        if let Some(exception) =
+            self.initialize_class_of(&declaring_class, frame, runtime_data_area, call_stack)?
+        {
+            return Ok(InstructionCompleted::ThrowException(exception));
+        }
+
+        let Some(params) = self.pop_arguments(frame, &descriptor) else {
+            return Ok(self.operand_stack_underflow(frame));
+        };
+
+        Ok(InstructionCompleted::InvokeMethod(method, params))
+    }
+
+    /// Load an interface method reference located at the index of the next two bytes' value in the bytecode
+    /// after applying the indexing equation specified by the specs from the constant pool
+    /// and invoke it
+    /// (Needs an object reference, resolved at runtime through the itable of its class)
+    fn execute_invokeinterface(
+        &self,
+        frame: &mut Frame,
+        class_file: &ClassFile,
+        runtime_data_area: &mut RuntimeDataArea,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let index_high = frame.bytecode[*pc] as u16;
+        *pc += 1;
+        let index_low = frame.bytecode[*pc] as u16;
+
+        // AS SPECIFIED BY THE SPECS: the count operand and the trailing zero byte are
+        // historical, the arguments size is known from the descriptor
+        *pc += 2;
+
+        // AS SPECIFIED BY THE SPECS: (indexbyte1 << 8) | indexbyte2
+        let method_ref = (index_high << 8) | index_low;
+
+        let (interface_name, method_name, descriptor) = class_file
+            .get_interface_method_info(method_ref)
+            .ok_or_else(|| {
+                format!(
+                    "invokeinterface: invalid interface method reference #{}",
+                    method_ref
+                )
+            })?;
+
+        debug_log!(
+            "  invokeinterface {}.{}:{}",
+            interface_name,
+            method_name,
+            descriptor
+        );
+
+        let resolved = self.resolve_interface_method(
+            &interface_name,
+            &method_name,
+            &descriptor,
+            runtime_data_area,
+        )?;
+
+        if resolved.is_static() {
+            return Err(VmError::throw(
+                "java/lang/IncompatibleClassChangeError",
+                format!(
+                    "Expected non-static method {}.{}{}",
+                    interface_name.replace('/', "."),
+                    method_name,
+                    descriptor
+                ),
+            ));
+        }
+
+        let Some(mut args) = self.pop_arguments(frame, &descriptor) else {
+            return Ok(self.operand_stack_underflow(frame));
+        };
+        let Some(objectref) = frame.operand_stack.pop() else {
+            return Ok(self.operand_stack_underflow(frame));
+        };
+
+        let selected = match &objectref {
+            Value::Object(object) => {
+                let class = runtime_data_area.heap.object(*object).class.clone();
+
+                // AS SPECIFIED BY THE SPECS: the class of objectref must implement the interface
+                if !class.is_subtype_of(&interface_name) {
+                    return Err(VmError::throw(
+                        "java/lang/IncompatibleClassChangeError",
+                        format!(
+                            "Class {} does not implement the requested interface {}",
+                            class.name.replace('/', "."),
+                            interface_name.replace('/', ".")
+                        ),
+                    ));
+                }
+
+                class.select_method(&resolved)?
+            }
+            Value::Null => return Ok(self.null_pointer_exception(frame)),
+            _ => resolved,
+        };
+
+        debug_log!(
+            "  invokeinterface selected {}.{}{}",
+            selected.class_name,
+            selected.name,
+            selected.descriptor
+        );
+
+        args.insert(0, objectref);
+
+        Ok(InstructionCompleted::InvokeMethod(selected, args))
+    }
+
+    /// Invoke the target of the dynamic call site referenced at the index of the next two bytes'
+    /// value in the bytecode, linking the call site first if this is its first execution
+    /// ( the two following bytes are always zero )
+    fn execute_invokedynamic(
+        &self,
+        frame: &mut Frame,
+        class_file: &ClassFile,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        let instruction_pc = *pc;
+
+        *pc += 1;
+        let index_high = frame.bytecode[*pc] as u16;
+        *pc += 1;
+        let index_low = frame.bytecode[*pc] as u16;
+        *pc += 2;
+
+        // AS SPECIFIED BY THE SPECS: (indexbyte1 << 8) | indexbyte2
+        let index = (index_high << 8) | index_low;
+
+        let (_, _, descriptor) = class_file.get_invoke_dynamic_info(index).ok_or_else(|| {
+            VmError::verification(format!(
+                "invokedynamic: invalid call site specifier #{}",
+                index
+            ))
+        })?;
+
+        debug_log!("  invokedynamic #{} {}", index, descriptor);
+
+        let key = (Rc::as_ptr(&frame.method), instruction_pc);
+        let call_site = match runtime_data_area.call_sites.get(&key) {
+            Some(call_site) => call_site.clone(),
+            None => match self.link_call_site(class_file, index, runtime_data_area)? {
+                Ok(call_site) => {
+                    let call_site = Rc::new(call_site);
+                    runtime_data_area.call_sites.insert(key, call_site.clone());
+                    call_site
+                }
+                // AS SPECIFIED BY THE SPECS: a call site whose linkage failed is not linked,
+                // executing it again retries the linkage
+                Err(message) => {
+                    return Ok(InstructionCompleted::ThrowNewException(
+                        "java/lang/BootstrapMethodError",
+                        Some(message),
+                    ));
+                }
+            },
+        };
+
+        let Some(args) = self.pop_arguments(frame, &descriptor) else {
+            return Ok(self.operand_stack_underflow(frame));
+        };
+
+        // Both may call back into Java code or collect garbage
+        call_stack.with_frame(frame, |call_stack| match call_site.as_ref() {
+            CallSite::StringConcat(recipe) => {
+                self.concat_strings(recipe, &descriptor, args, runtime_data_area, call_stack)
+            }
+            CallSite::Lambda(lambda_form) => {
+                self.new_lambda(lambda_form, args, runtime_data_area, call_stack)
+            }
+        })
+    }
+
+    /// Link a dynamic call site by running its bootstrap method, the bootstrap methods of
+    /// the core library are built into the VM, the inner error describes a linkage failure
+    /// and the outer one any other bootstrap method, which the VM does not implement
+    fn link_call_site(
+        &self,
+        class_file: &ClassFile,
+        index: u16,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<Result<CallSite, String>, VmError> {
+        let (bootstrap_index, name, descriptor) =
+            class_file.get_invoke_dynamic_info(index).ok_or_else(|| {
+                VmError::verification(format!(
+                    "invokedynamic: invalid call site specifier #{}",
+                    index
+                ))
+            })?;
+
+        let bootstrap_method = class_file
+            .get_bootstrap_method(bootstrap_index)
+            .ok_or_else(|| {
+                format!(
+                    "invokedynamic: missing bootstrap method #{}",
+                    bootstrap_index
+                )
+            })?;
+
+        let (_, class_name, method_name, _) = class_file
+            .get_method_handle_info(bootstrap_method.method_ref)
+            .ok_or_else(|| {
+                format!(
+                    "invokedynamic: invalid bootstrap method handle #{}",
+                    bootstrap_method.method_ref
+                )
+            })?;
+
+        debug_log!(
+            "  linking call site {} with {}.{}",
+            descriptor,
+            class_name,
+            method_name
+        );
+
+        let call_site = match (class_name.as_str(), method_name.as_str()) {
+            (call_site::STRING_CONCAT_FACTORY, "makeConcatWithConstants") => {
+                let Some((recipe, constants)) = bootstrap_method.arguments.split_first() else {
+                    return Ok(Err("makeConcatWithConstants: missing recipe".to_string()));
+                };
+
+                let recipe = class_file
+                    .get_string_utf16(*recipe)
+                    .ok_or("makeConcatWithConstants: the recipe is not a string")?;
+
+                let constants = constants
+                    .iter()
+                    .map(|constant| self.constant_to_string(class_file, *constant))
+                    .collect::<Result<Vec<Vec<u16>>, VmError>>()?;
+
+                let recipe = match call_site::parse_concat_recipe(&recipe, constants.into_iter()) {
+                    Ok(recipe) => recipe,
+                    Err(message) => return Ok(Err(message)),
+                };
+
+                // AS SPECIFIED BY THE SPECS: the recipe must have a tag for every argument
+                let tags = recipe
+                    .iter()
+                    .filter(|element| **element == RecipeElement::Argument)
+                    .count();
+                let arguments = descriptor::parameter_types(&descriptor).len();
+
+                if tags != arguments {
+                    return Ok(Err(format!(
+                        "Mismatched number of concat arguments: recipe wants {} arguments, but signature provides {}",
+                        tags, arguments
+                    )));
+                }
+
+                CallSite::StringConcat(recipe)
+            }
+            (call_site::STRING_CONCAT_FACTORY, "makeConcat") => {
+                // Every argument is concatenated as is
+                let arguments = descriptor::parameter_types(&descriptor).len();
+                CallSite::StringConcat((0..arguments).map(|_| RecipeElement::Argument).collect())
+            }
+            (call_site::LAMBDA_METAFACTORY, "metafactory" | "altMetafactory") => {
+                match self.link_lambda(
+                    class_file,
+                    &name,
+                    &descriptor,
+                    &bootstrap_method.arguments,
+                    method_name == "altMetafactory",
+                    runtime_data_area,
+                )? {
+                    Ok(lambda_form) => CallSite::Lambda(lambda_form),
+                    Err(message) => return Ok(Err(message)),
+                }
+            }
+            // A gap of the VM rather than a linkage failure, like for `zvm check`
+            _ => {
+                return Err(VmError::unsupported_library(format!(
+                    "bootstrap method {}.{} is not implemented",
+                    class_name.replace('/', "."),
+                    method_name
+                )));
+            }
+        };
+
+        Ok(Ok(call_site))
+    }
+
+    /// Convert a loadable constant, a static argument of a bootstrap method,
+    /// to its string representation
+    fn constant_to_string(&self, class_file: &ClassFile, index: u16) -> Result<Vec<u16>, VmError> {
+        if let Some(CpInfo::String { .. }) = class_file.constant_pool.get(index as usize) {
+            return class_file.get_string_utf16(index).ok_or_else(|| {
+                VmError::verification(format!("Invalid string constant #{}", index))
+            });
+        }
+
+        let string = match class_file.constant_pool.get(index as usize) {
+            Some(CpInfo::Integer { .. }) => class_file.get_integer(index).map(|i| i.to_string()),
+            Some(CpInfo::Long { .. }) => class_file.get_long(index).map(|l| l.to_string()),
+            Some(CpInfo::Float { .. }) => class_file.get_float(index).map(string::float_to_string),
+            Some(CpInfo::Double { .. }) => {
+                class_file.get_double(index).map(string::double_to_string)
+            }
+            _ => None,
+        };
+
+        string
+            .map(|string| string.encode_utf16().collect())
+            .ok_or_else(|| {
+                VmError::internal(format!("Unsupported bootstrap method argument #{}", index))
+            })
+    }
+
+    /// Concatenate the arguments of a string concatenation call site following its recipe
+    /// and push the resulting string onto the operand stack of the current frame
+    fn concat_strings(
+        &self,
+        recipe: &[RecipeElement],
+        descriptor: &str,
+        args: Vec<Value>,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+    ) -> Result<InstructionCompleted, VmError> {
+        // The arguments left the operand stack, keep them alive while their toString() runs
+        for arg in &args {
+            runtime_data_area.heap.pin(arg.clone());
+        }
+
+        let types = descriptor::parameter_types(descriptor);
+        let mut concatenated = Vec::new();
+        let mut arguments = types.iter().zip(&args);
+        let mut thrown = None;
+
+        for element in recipe {
+            match element {
+                RecipeElement::Text(text) => concatenated.extend_from_slice(text),
+                RecipeElement::Argument => {
+                    let Some((descriptor, arg)) = arguments.next() else {
+                        break;
+                    };
+
+                    match native::string_value_of(descriptor, arg, runtime_data_area, call_stack)? {
+                        Ok(chars) => concatenated.extend(chars),
+                        Err(completed) => {
+                            thrown = Some(completed);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        for arg in &args {
+            runtime_data_area.heap.unpin(arg);
+        }
+
+        if let Some(completed) = thrown {
+            return Ok(completed);
+        }
+
+        let string = runtime_data_area.new_string(concatenated, call_stack)?;
+        call_stack
+            .current_frame()
+            .ok_or("No current frame found")?
+            .operand_stack
+            .push(string);
+
+        Ok(InstructionCompleted::ContinueMethodExecution)
+    }
+
+    /// Link a lambda call site, spinning the class of its functional interface instances
+    /// out of the static arguments of `metafactory`, the sam method type, the implementation
+    /// method handle and the instantiated method type, `altMetafactory` follows them with flags
+    /// and the marker interfaces and bridge method types the flags announce
+    fn link_lambda(
+        &self,
+        class_file: &ClassFile,
+        name: &str,
+        descriptor: &str,
+        arguments: &[u16],
+        alternate: bool,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<Result<Rc<LambdaForm>, String>, VmError> {
+        let &[sam_type, implementation, instantiated, ref extra @ ..] = arguments else {
+            return Ok(Err(
+                "LambdaMetafactory: missing static arguments".to_string()
+            ));
+        };
+
+        let Some(interface) = descriptor::return_type(descriptor)
+            .strip_prefix('L')
+            .and_then(|interface| interface.strip_suffix(';'))
+        else {
+            return Ok(Err(format!(
+                "LambdaMetafactory: {} does not return an interface",
+                descriptor
+            )));
+        };
+
+        let sam_descriptor = class_file
+            .get_method_type(sam_type)
+            .ok_or("LambdaMetafactory: the sam method type is not a method type")?;
+
+        let (reference_kind, class_name, method_name, method_descriptor) = class_file
+            .get_method_handle_info(implementation)
+            .ok_or("LambdaMetafactory: the implementation is not a method handle")?;
+
+        let instantiated = class_file
+            .get_method_type(instantiated)
+            .ok_or("LambdaMetafactory: the instantiated method type is not a method type")?;
+
+        let mut interfaces = vec![interface.to_string()];
+        let mut methods = vec![(name.to_string(), sam_descriptor.clone())];
+
+        if alternate {
+            let mut extra = extra.iter();
+            let flags = extra
+                .next()
+                .and_then(|flags| class_file.get_integer(*flags))
+                .ok_or("altMetafactory: missing flags")?;
+
+            if flags & call_site::FLAG_SERIALIZABLE != 0 {
+                interfaces.push("java/io/Serializable".to_string());
+            }
+
+            if flags & call_site::FLAG_MARKERS != 0 {
+                let count = extra
+                    .next()
+                    .and_then(|count| class_file.get_integer(*count))
+                    .ok_or("altMetafactory: missing marker interface count")?;
+
+                for _ in 0..count {
+                    let marker = extra
+                        .next()
+                        .and_then(|marker| class_file.get_class_name(*marker))
+                        .ok_or("altMetafactory: missing marker interface")?;
+                    interfaces.push(marker);
+                }
+            }
+
+            if flags & call_site::FLAG_BRIDGES != 0 {
+                let count = extra
+                    .next()
+                    .and_then(|count| class_file.get_integer(*count))
+                    .ok_or("altMetafactory: missing bridge count")?;
+
+                for _ in 0..count {
+                    let bridge = extra
+                        .next()
+                        .and_then(|bridge| class_file.get_method_type(*bridge))
+                        .ok_or("altMetafactory: missing bridge method type")?;
+                    methods.push((name.to_string(), bridge));
+                }
+            }
+        }
+
+        interfaces.dedup();
+        methods.dedup();
+
+        // AS SPECIFIED BY THE SPECS: §5.4.3.5, the implementation method handle
+        // is resolved when the call site is linked
+        let class = runtime_data_area.class_loader.load_class(&class_name)?;
+        let implementation = match (reference_kind, class.is_interface()) {
+            (call_site::REF_INVOKE_INTERFACE, _) | (_, true) => self.resolve_interface_method(
+                &class_name,
+                &method_name,
+                &method_descriptor,
+                runtime_data_area,
+            )?,
+            _ => self.resolve_method(
+                &class_name,
+                &method_name,
+                &method_descriptor,
+                runtime_data_area,
+            )?,
+        };
+
+        let is_static = reference_kind == call_site::REF_INVOKE_STATIC;
+        if implementation.is_static() != is_static
+            || (reference_kind == call_site::REF_NEW_INVOKE_SPECIAL) != (method_name == "<init>")
+        {
+            return Ok(Err(format!(
+                "LambdaMetafactory: invalid implementation kind {} for {}.{}{}",
+                reference_kind, class_name, method_name, method_descriptor
+            )));
+        }
+
+        // The captured arguments and the sam method arguments are passed in order
+        // to the receiver and the parameters of the implementation
+        let captured: Vec<String> = descriptor::parameter_types(descriptor)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        let receiver = format!("L{};", class_name);
+        let mut parameters = descriptor::parameter_types(&method_descriptor);
+        if matches!(
+            reference_kind,
+            call_site::REF_INVOKE_VIRTUAL
+                | call_site::REF_INVOKE_SPECIAL
+                | call_site::REF_INVOKE_INTERFACE
+        ) {
+            parameters.insert(0, &receiver);
+        }
+
+        let result = match reference_kind {
+            call_site::REF_NEW_INVOKE_SPECIAL => receiver.as_str(),
+            _ => descriptor::return_type(&method_descriptor),
+        };
+
+        // AS SPECIFIED BY THE SPECS: the arguments have the types of the instantiated method
+        // type at run time, which the functional interface methods erase
+        let arguments = captured
+            .iter()
+            .map(String::as_str)
+            .chain(descriptor::parameter_types(&instantiated))
+            .collect::<Vec<&str>>();
+        let instantiated_result = descriptor::return_type(&instantiated);
+
+        for (_, interface_descriptor) in &methods {
+            let sam_result = descriptor::return_type(interface_descriptor);
+
+            let is_adaptable = arguments.len() == parameters.len()
+                && descriptor::parameter_types(interface_descriptor).len() + captured.len()
+                    == arguments.len()
+                && arguments
+                    .iter()
+                    .zip(&parameters)
+                    .all(|(argument, parameter)| call_site::is_adaptable(argument, parameter))
+                && (sam_result == "V"
+                    || (call_site::is_adaptable(result, instantiated_result)
+                        && call_site::is_adaptable(instantiated_result, sam_result)));
+
+            if !is_adaptable {
+                return Ok(Err(format!(
+                    "Type mismatch for lambda argument or return: {}.{}{} can not implement {}{}",
+                    class_name.replace('/', "."),
+                    method_name,
+                    method_descriptor,
+                    name,
+                    interface_descriptor
+                )));
+            }
+        }
+
+        let caller = class_file.this_class_name().ok_or(VmError::verification(
+            "ClassFormatError: invalid this_class entry",
+        ))?;
+
+        let class = runtime_data_area
+            .class_loader
+            .define_lambda_class(&caller, interfaces, methods, &captured)?;
+
+        debug_log!(
+            "  spun {} for {}.{}{}",
+            class.name,
+            class_name,
+            method_name,
+            method_descriptor
+        );
+
+        let lambda_form = Rc::new(LambdaForm {
+            class,
+            reference_kind,
+            implementation,
+            captured,
+            instantiated,
+        });
+
+        runtime_data_area
+            .lambda_forms
+            .insert(lambda_form.class.name.clone(), lambda_form.clone());
+
+        Ok(Ok(lambda_form))
+    }
+
+    /// Create a functional interface instance of a lambda call site holding the captured
+    /// arguments and push its reference onto the operand stack of the current frame
+    fn new_lambda(
+        &self,
+        lambda_form: &LambdaForm,
+        args: Vec<Value>,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+    ) -> Result<InstructionCompleted, VmError> {
+        let mut lambda = Object::new(lambda_form.class.clone());
+
+        for (index, arg) in args.into_iter().enumerate() {
+            lambda.fields.insert(lambda_form.captured_field(index), arg);
+        }
+
+        // The captured arguments left the operand stack, keep them alive during a collection
+        let captured = lambda.fields.values().cloned().collect::<Vec<Value>>();
+        for value in &captured {
+            runtime_data_area.heap.pin(value.clone());
+        }
+
+        let allocated = runtime_data_area.allocate_object(lambda, call_stack);
+
+        for value in &captured {
+            runtime_data_area.heap.unpin(value);
+        }
+
+        call_stack
+            .current_frame()
+            .ok_or("No current frame found")?
+            .operand_stack
+            .push(allocated?);
+
+        Ok(InstructionCompleted::ContinueMethodExecution)
+    }
+
+    /// Forward a method of a functional interface instance to the implementation method
+    /// of its lambda call site, passing the captured arguments before the method arguments
+    ///
+    /// Returns how the method completed when the implementation method completed in place,
+    /// `None` when it runs in the frame pushed onto the call stack
+    fn invoke_lambda(
+        &self,
+        lambda_form: &LambdaForm,
+        method: &Method,
+        args: &[Value],
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+    ) -> Result<Option<InstructionCompleted>, VmError> {
+        let Some((Value::Object(lambda), method_args)) = args.split_first() else {
+            return Err(format!(
+                "Invalid receiver for {}.{}{}",
+                method.class_name, method.name, method.descriptor
+            )
+            .into());
+        };
+
+        let lambda = runtime_data_area.heap.object(*lambda);
+        let mut arguments = (0..lambda_form.captured.len())
+            .map(|index| {
+                lambda
+                    .fields
+                    .get(&lambda_form.captured_field(index))
+                    .cloned()
+                    .unwrap_or(Value::Null)
+            })
+            .collect::<Vec<Value>>();
+        arguments.extend(method_args.iter().cloned());
+
+        let implementation = lambda_form.implementation.clone();
+        let mut parameters = descriptor::parameter_types(&lambda_form.implementation.descriptor);
+
+        let (target, arguments, instance) = match lambda_form.reference_kind {
+            call_site::REF_INVOKE_STATIC => {
+                let class = runtime_data_area
+                    .class_loader
+                    .load_class(&implementation.class_name)?;
+
+                if let Some(exception) =
+                    self.initialize_class(&class, runtime_data_area, call_stack)?
+                {
+                    return Ok(Some(InstructionCompleted::ThrowException(exception)));
+                }
+
+                let arguments = match self.adapt_arguments(
+                    lambda_form,
+                    arguments,
+                    &parameters,
+                    runtime_data_area,
+                    call_stack,
+                )? {
+                    Ok(arguments) => arguments,
+                    Err(thrown) => return Ok(Some(thrown)),
+                };
+                (implementation, arguments, None)
+            }
+            call_site::REF_NEW_INVOKE_SPECIAL => {
+                let class = runtime_data_area
+                    .class_loader
+                    .load_class(&implementation.class_name)?;
+
+                if class.is_interface() || class.is_abstract() {
+                    return Err(VmError::linkage(format!(
+                        "InstantiationError: {}",
+                        class.name
+                    )));
+                }
+
+                if let Some(exception) =
+                    self.initialize_class(&class, runtime_data_area, call_stack)?
+                {
+                    return Ok(Some(InstructionCompleted::ThrowException(exception)));
+                }
+
+                let instance = self.instantiate(class, runtime_data_area, call_stack)?;
+
+                // The instance is only reachable from here while boxing the arguments allocates
+                runtime_data_area.heap.pin(instance.clone());
+                let adapted = self.adapt_arguments(
+                    lambda_form,
+                    arguments,
+                    &parameters,
+                    runtime_data_area,
+                    call_stack,
+                );
+                runtime_data_area.heap.unpin(&instance);
+
+                let mut arguments = match adapted? {
+                    Ok(arguments) => arguments,
+                    Err(thrown) => return Ok(Some(thrown)),
+                };
+                arguments.insert(0, instance.clone());
+
+                (implementation, arguments, Some(instance))
+            }
+            reference_kind => {
+                let receiver = match arguments.first() {
+                    Some(Value::Null) | None => {
+                        return Ok(Some(InstructionCompleted::ThrowNewException(
+                            "java/lang/NullPointerException",
+                            None,
+                        )));
+                    }
+                    Some(receiver) => receiver.clone(),
+                };
+
+                // AS SPECIFIED BY THE SPECS: virtual and interface method handles select
+                // the method of the receiver, special method handles invoke the exact method
+                let selected = match (reference_kind, &receiver) {
+                    (call_site::REF_INVOKE_SPECIAL, _) => implementation,
+                    (_, Value::Object(handle)) => runtime_data_area
+                        .heap
+                        .object(*handle)
+                        .class
+                        .select_method(&implementation)?,
+                    _ => implementation,
+                };
+
+                parameters.insert(0, "Ljava/lang/Object;");
+                let arguments = match self.adapt_arguments(
+                    lambda_form,
+                    arguments,
+                    &parameters,
+                    runtime_data_area,
+                    call_stack,
+                )? {
+                    Ok(arguments) => arguments,
+                    Err(thrown) => return Ok(Some(thrown)),
+                };
+                (selected, arguments, None)
+            }
+        };
+
+        let lambda_return = LambdaReturn {
+            result: descriptor::return_type(&target.descriptor).to_string(),
+            sam_result: descriptor::return_type(&method.descriptor).to_string(),
+            instance,
+        };
+
+        match self.invoke(target, arguments, runtime_data_area, call_stack)? {
+            Some(completed) => Ok(Some(lambda_return.complete(
+                completed,
+                runtime_data_area,
+                call_stack,
+            )?)),
+            None => {
+                call_stack
+                    .current_frame()
+                    .ok_or("No current frame found")?
+                    .lambda_returns
+                    .push(lambda_return);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Convert the arguments forwarded by a lambda, the captured ones and the ones of the
+    /// instantiated method type, to the parameter types of its implementation method
+    ///
+    /// Fails with the exception to throw when an argument cannot be unboxed
+    fn adapt_arguments(
+        &self,
+        lambda_form: &LambdaForm,
+        arguments: Vec<Value>,
+        parameters: &[&str],
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &CallStack,
+    ) -> Result<Result<Vec<Value>, InstructionCompleted>, VmError> {
+        let sources = lambda_form
+            .captured
+            .iter()
+            .map(String::as_str)
+            .chain(descriptor::parameter_types(&lambda_form.instantiated));
+
+        // The boxes already created are only reachable from here while the next ones are
+        let mut adapted = Vec::with_capacity(arguments.len());
+        let mut failure = None;
+        for ((argument, source), parameter) in arguments.into_iter().zip(sources).zip(parameters) {
+            match call_site::adapt(argument, source, parameter, runtime_data_area, call_stack) {
+                Ok(Ok(argument)) => {
+                    runtime_data_area.heap.pin(argument.clone());
+                    adapted.push(argument);
+                }
+                Ok(Err(thrown)) => {
+                    failure = Some(Ok(thrown));
+                    break;
+                }
+                Err(error) => {
+                    failure = Some(Err(error));
+                    break;
+                }
+            }
+        }
+
+        for argument in adapted.iter().rev() {
+            runtime_data_area.heap.unpin(argument);
+        }
+
+        match failure {
+            Some(thrown) => Ok(Err(thrown?)),
+            None => Ok(Ok(adapted)),
+        }
+    }
+
+    /// Create a new instance of the class referenced at the index of the next two bytes' value
+    /// in the bytecode after applying the indexing equation specified by the specs from the
+    /// constant pool and push its reference onto the operand stack
+    fn execute_new(
+        &self,
+        frame: &mut Frame,
+        class_file: &ClassFile,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let index_high = frame.bytecode[*pc] as u16;
+        *pc += 1;
+        let index_low = frame.bytecode[*pc] as u16;
+
+        // AS SPECIFIED BY THE SPECS: (indexbyte1 << 8) | indexbyte2
+        let class_ref = (index_high << 8) | index_low;
+
+        let class_name = class_file.get_class_name(class_ref).ok_or_else(|| {
+            VmError::verification(format!("new: invalid class reference #{}", class_ref))
+        })?;
+
+        let class = runtime_data_area.class_loader.load_class(&class_name)?;
+
+        if class.is_interface() || class.is_abstract() {
+            return Err(VmError::linkage(format!(
+                "InstantiationError: {}",
+                class_name
+            )));
+        }
+
+        if let Some(exception) =
+            self.initialize_class_of(&class, frame, runtime_data_area, call_stack)?
+        {
+            return Ok(InstructionCompleted::ThrowException(exception));
+        }
+
+        debug_log!("  new {}", class_name);
+
+        // Throwables capture the frame creating them
+        let instance = call_stack.with_frame(frame, |call_stack| {
+            self.instantiate(class, runtime_data_area, call_stack)
+        })?;
+        frame.operand_stack.push(instance);
+
+        Ok(InstructionCompleted::ContinueMethodExecution)
+    }
+
+    /// Pop an exception reference from the operand stack and throw it
+    fn execute_athrow(&self, frame: &mut Frame) -> Result<InstructionCompleted, VmError> {
+        match frame.operand_stack.pop() {
+            Some(exception @ Value::Object(_)) => {
+                debug_log!("  athrow {:?}", exception);
+                Ok(InstructionCompleted::ThrowException(exception))
+            }
+            // AS SPECIFIED BY THE SPECS: throwing null throws a NullPointerException instead
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
+                "athrow: expected exception reference, got {:?}",
+                other
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
+        }
+    }
+
+    /// Enter the monitor of the object on top of the operand stack, blocking while
+    /// another thread owns it
+    fn execute_monitorenter(
+        &self,
+        frame: &mut Frame,
+        call_stack: &mut CallStack,
+    ) -> Result<InstructionCompleted, VmError> {
+        let object = match frame.operand_stack.pop() {
+            Some(Value::Null) => return Ok(self.null_pointer_exception(frame)),
+            Some(object @ (Value::Object(_) | Value::Array(_))) => object,
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "monitorenter: expected object reference, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
+        };
+
+        debug_log!("  monitorenter {:?}", object);
+        thread::monitor_enter(&object, call_stack);
+
+        Ok(InstructionCompleted::ContinueMethodExecution)
+    }
+
+    /// Exit the monitor of the object on top of the operand stack
+    fn execute_monitorexit(
+        &self,
+        frame: &mut Frame,
+        call_stack: &CallStack,
+    ) -> Result<InstructionCompleted, VmError> {
+        let object = match frame.operand_stack.pop() {
+            Some(Value::Null) => return Ok(self.null_pointer_exception(frame)),
+            Some(object @ (Value::Object(_) | Value::Array(_))) => object,
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "monitorexit: expected object reference, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
+        };
+
+        debug_log!("  monitorexit {:?}", object);
+
+        // AS SPECIFIED BY THE SPECS: exiting a monitor the thread does not own throws
+        // an IllegalMonitorStateException
+        match thread::monitor_exit(&object, call_stack) {
+            true => Ok(InstructionCompleted::ContinueMethodExecution),
+            false => Ok(InstructionCompleted::ThrowNewException(
+                "java/lang/IllegalMonitorStateException",
+                None,
+            )),
+        }
+    }
+
+    /// Create a new instance of the given class, throwables additionally capture
+    /// the frames of the call stack they were created in
+    fn instantiate(
+        &self,
+        class: Rc<Class>,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &CallStack,
+    ) -> Result<Value, VmError> {
+        let is_throwable = class.is_subclass_of("java/lang/Throwable");
+        let mut object = Object::new(class);
+
+        if !is_throwable {
+            return runtime_data_area.allocate_object(object, call_stack);
+        }
+
+        // Each line of the backtrace is only reachable from here until the array holds it
+        let mut lines = Vec::new();
+        let mut failure = None;
+
+        for frame in call_stack.frames.iter().rev().take(MAX_STACK_TRACE_DEPTH) {
+            match runtime_data_area.new_string_from(&self.describe_frame(frame), call_stack) {
+                Ok(line) => {
+                    runtime_data_area.heap.pin(line.clone());
+                    lines.push(line);
+                }
+                Err(error) => {
+                    failure = Some(error);
+                    break;
+                }
+            }
+        }
+
+        let backtrace = Array::from_elements("[Ljava/lang/String;".to_string(), lines.clone());
+        object
+            .fields
+            .insert(THROWABLE_BACKTRACE.to_string(), Value::Null);
+
+        // The array is only reachable from the throwable, both fit in one reservation
+        let reserved = match failure {
+            Some(error) => Err(error),
+            None => runtime_data_area.reserve(
+                Heap::size_of_array(&backtrace) + Heap::object_size(&object),
+                call_stack,
+            ),
+        };
+
+        for line in lines.iter().rev() {
+            runtime_data_area.heap.unpin(line);
+        }
+
+        let mut reservation = reserved?;
+        let backtrace = runtime_data_area
+            .heap
+            .allocate_array(&mut reservation, backtrace);
+        object
+            .fields
+            .insert(THROWABLE_BACKTRACE.to_string(), backtrace);
+
+        Ok(runtime_data_area
+            .heap
+            .allocate_object(&mut reservation, object))
+    }
+
+    /// Describe a frame the way stack traces print it, e.g. `Main.main(Main.java:5)`
+    fn describe_frame(&self, frame: &Frame) -> String {
+        let location = match (
+            frame.class.class_file.source_file(),
+            frame
+                .method
+                .code
+                .as_ref()
+                .and_then(|code| code.line_number(frame.pc)),
+        ) {
+            (Some(source_file), Some(line_number)) => format!("{}:{}", source_file, line_number),
+            (Some(source_file), None) => source_file,
+            (None, _) => "Unknown Source".to_string(),
+        };
+
+        format!(
+            "{}.{}({})",
+            frame.class.name.replace('/', "."),
+            frame.method.name,
+            location
+        )
+    }
+
+    /// Create a new throwable of the given class with an optional message, an OutOfMemoryError
+    /// in its place if it does not fit in the heap
+    pub fn new_exception(
+        &self,
+        class_name: &str,
+        message: Option<String>,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &CallStack,
+    ) -> Result<Value, VmError> {
+        // An OutOfMemoryError needs no room in the heap, like the ones HotSpot preallocates
+        let out_of_memory = class_name == "java/lang/OutOfMemoryError";
+
+        runtime_data_area.heap.set_unlimited(out_of_memory);
+        let exception = self.allocate_exception(class_name, message, runtime_data_area, call_stack);
+        runtime_data_area.heap.set_unlimited(false);
+
+        match exception {
+            Err(VmError::Throw {
+                exception: "java/lang/OutOfMemoryError",
+                message,
+                ..
+            }) if !out_of_memory => self.new_exception(
+                "java/lang/OutOfMemoryError",
+                Some(message),
+                runtime_data_area,
+                call_stack,
+            ),
+            exception => exception,
+        }
+    }
+
+    /// Allocate a new throwable of the given class and its message
+    fn allocate_exception(
+        &self,
+        class_name: &str,
+        message: Option<String>,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &CallStack,
+    ) -> Result<Value, VmError> {
+        let class = runtime_data_area.class_loader.load_class(class_name)?;
+        let exception = self.instantiate(class, runtime_data_area, call_stack)?;
+
+        if let (Value::Object(object), Some(message)) = (&exception, message) {
+            // The throwable is only reachable from here until it is thrown
+            runtime_data_area.heap.pin(exception.clone());
+            let message = runtime_data_area.new_string_from(&message, call_stack);
+            runtime_data_area.heap.unpin(&exception);
+
+            runtime_data_area
+                .heap
+                .object_mut(*object)
+                .fields
+                .insert(THROWABLE_DETAIL_MESSAGE.to_string(), message?);
+        }
+
+        Ok(exception)
+    }
+
+    /// Throw a NullPointerException for the instruction the frame is executing, with a message
+    /// describing the failed action and, when it can be found, the null expression
+    fn null_pointer_exception(&self, frame: &Frame) -> InstructionCompleted {
+        InstructionCompleted::ThrowNewException(
+            "java/lang/NullPointerException",
+            null_pointer_message(frame),
+        )
+    }
+
+    /// Throw an ArrayIndexOutOfBoundsException for an access at the given index
+    fn array_index_out_of_bounds(&self, index: i32, length: usize) -> InstructionCompleted {
+        InstructionCompleted::ThrowNewException(
+            "java/lang/ArrayIndexOutOfBoundsException",
+            Some(format!(
+                "Index {} out of bounds for length {}",
+                index, length
+            )),
+        )
+    }
+
+    /// Throw a VerifyError for a jsr or ret in a class file of version 51.0 or above, which
+    /// the type checking verifier does not accept
+    fn bad_instruction(&self, frame: &Frame) -> InstructionCompleted {
+        let opcode = frame.bytecode[frame.pc];
+
+        InstructionCompleted::ThrowNewException(
+            "java/lang/VerifyError",
+            Some(format!(
+                "Bad instruction: {:02x}\nException Details:\n  Location:\n    {}.{}{} @{}: {}\n  Reason:\n    Error exists in the bytecode",
+                opcode,
+                frame.class.name,
+                frame.method.name,
+                frame.method.descriptor,
+                frame.pc,
+                format!("{:?}", Opcode::from(opcode)).to_lowercase()
+            )),
+        )
+    }
+
+    /// Throw a VerifyError for a ret whose local variable does not hold a return address
+    fn register_wrong_type(&self, frame: &Frame, index: usize) -> InstructionCompleted {
+        self.old_verify_error(frame, format!("Register {} contains wrong type", index))
+    }
+
+    /// Throw a VerifyError for a load of the return address stored by a subroutine
+    fn return_address_load(&self, frame: &Frame, index: usize) -> InstructionCompleted {
+        self.old_verify_error(
+            frame,
+            format!("Cannot load return address from register {}", index),
+        )
+    }
+
+    /// Throw a VerifyError in the format of the type inferencing verifier, the one checking
+    /// class files older than version 50.0
+    fn old_verify_error(&self, frame: &Frame, message: String) -> InstructionCompleted {
+        InstructionCompleted::ThrowNewException(
+            "java/lang/VerifyError",
+            Some(format!(
+                "(class: {}, method: {} signature: {}) {}",
+                frame.class.name, frame.method.name, frame.method.descriptor, message
+            )),
+        )
+    }
+
+    /// Throw a VerifyError for an instruction whose local variable index is not below the
+    /// `max_locals` of its method ( the class file verifier would have rejected the method )
+    fn invalid_local_index(&self, frame: &Frame, index: usize) -> InstructionCompleted {
+        self.local_variable_error(frame, "Illegal local variable number", index)
+    }
+
+    /// Throw a VerifyError in the format of the type checking verifier for an invalid
+    /// local variable index
+    fn local_variable_error(
+        &self,
+        frame: &Frame,
+        title: &str,
+        index: usize,
+    ) -> InstructionCompleted {
+        let opcode = format!("{:?}", Opcode::from(frame.bytecode[frame.pc]));
+
+        InstructionCompleted::ThrowNewException(
+            "java/lang/VerifyError",
+            Some(format!(
+                "{}\nException Details:\n  Location:\n    {}.{}{} @{}: {}\n  Reason:\n    Local index {} is invalid",
+                title,
+                frame.class.name,
+                frame.method.name,
+                frame.method.descriptor,
+                frame.pc,
+                opcode.to_lowercase(),
+                index
+            )),
+        )
+    }
+
+    /// Throw a VerifyError for a load or an `iinc` whose local variable does not hold a value
+    /// of the type the instruction expects, e.g. the second slot of a long
+    fn bad_local_type(
+        &self,
+        frame: &Frame,
+        index: usize,
+        expected: LocalType,
+    ) -> InstructionCompleted {
+        if frame.class.class_file.major < 50 {
+            return self.register_wrong_type(frame, index);
+        }
+
+        let found = match frame.local_variables.slot(index) {
+            Some(Slot::Value(value)) => self.verifier_type(value),
+            Some(Slot::Upper) => match frame.local_variables.get(index - 1) {
+                Some(Value::Long(_)) => "long_2nd",
+                _ => "double_2nd",
+            },
+            _ => "top",
+        };
+
+        self.type_check_error(
+            frame,
+            "Bad local variable type",
+            format!("Type {} (current frame, locals[{}])", found, index),
+            expected,
+        )
+    }
+
+    /// Throw a VerifyError for a store whose value popped from the operand stack is not
+    /// of the type the instruction expects
+    fn bad_stack_type(
+        &self,
+        frame: &Frame,
+        value: &Value,
+        expected: LocalType,
+    ) -> InstructionCompleted {
+        if frame.class.class_file.major < 50 {
+            let expected = match expected {
+                LocalType::Reference => "object",
+                expected => expected.verifier_name(),
+            };
+            return self
+                .old_verify_error(frame, format!("Expecting to find {} on stack", expected));
+        }
+
+        // AS SPECIFIED BY THE SPECS: the verifier counts longs and doubles as two entries
+        let depth: usize = frame
+            .operand_stack
+            .iter()
+            .map(|value| match value {
+                Value::Long(_) | Value::Double(_) => 2,
+                _ => 1,
+            })
+            .sum();
+
+        self.type_check_error(
+            frame,
+            "Bad type on operand stack",
+            format!(
+                "Type {} (current frame, stack[{}])",
+                self.verifier_type(value),
+                depth
+            ),
+            expected,
+        )
+    }
+
+    /// Name of the type of a value in the messages of the type checking verifier
+    fn verifier_type(&self, value: &Value) -> &'static str {
+        match value {
+            Value::Int(_) => "integer",
+            Value::Long(_) => "long",
+            Value::Float(_) => "float",
+            Value::Double(_) => "double",
+            Value::Null => "null",
+            Value::Object(_) | Value::Array(_) => "reference",
+            Value::ReturnAddress(_) => "returnAddress",
+        }
+    }
+
+    /// Throw a VerifyError in the format of the type checking verifier for a value of the
+    /// wrong type, the one checking class files of version 50.0 and above
+    fn type_check_error(
+        &self,
+        frame: &Frame,
+        title: &str,
+        found: String,
+        expected: LocalType,
+    ) -> InstructionCompleted {
+        let opcode = format!("{:?}", Opcode::from(frame.bytecode[frame.pc]));
+
+        InstructionCompleted::ThrowNewException(
+            "java/lang/VerifyError",
+            Some(format!(
+                "{}\nException Details:\n  Location:\n    {}.{}{} @{}: {}\n  Reason:\n    {} is not assignable to {}",
+                title,
+                frame.class.name,
+                frame.method.name,
+                frame.method.descriptor,
+                frame.pc,
+                opcode.to_lowercase(),
+                found,
+                expected.verifier_name()
+            )),
+        )
+    }
+
+    /// Throw a VerifyError for an instruction that pops more values than the operand stack holds
+    /// ( the class file verifier would have rejected such a method before running it )
+    fn operand_stack_underflow(&self, frame: &Frame) -> InstructionCompleted {
+        let opcode = format!("{:?}", Opcode::from(frame.bytecode[frame.pc]));
+
+        InstructionCompleted::ThrowNewException(
+            "java/lang/VerifyError",
+            Some(format!(
+                "Operand stack underflow\nException Details:\n  Location:\n    {}.{}{} @{}: {}\n  Reason:\n    Attempt to pop empty stack.",
+                frame.class.name,
+                frame.method.name,
+                frame.method.descriptor,
+                frame.pc,
+                opcode.to_lowercase()
+            )),
+        )
+    }
+
+    /// Make room in the heap for the allocations of an instruction of the given frame, with the
+    /// frame back on the call stack so that a collection finds its references
+    fn reserve_heap_for(
+        &self,
+        frame: &mut Frame,
+        size: usize,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+    ) -> Result<Reservation, VmError> {
+        call_stack.with_frame(frame, |call_stack| {
+            runtime_data_area.reserve(size, call_stack)
+        })
+    }
+
+    /// Name of the runtime type of a reference the way `CONSTANT_Class` entries name types,
+    /// the class name of an object ( `java/lang/String` ) or the descriptor of an array ( `[I` ),
+    /// `None` for null
+    fn runtime_type_name(
+        &self,
+        value: &Value,
+        runtime_data_area: &RuntimeDataArea,
+    ) -> Option<String> {
+        match value {
+            Value::Object(handle) => {
+                Some(runtime_data_area.heap.object(*handle).class.name.clone())
+            }
+            Value::Array(handle) => Some(runtime_data_area.heap.array(*handle).descriptor.clone()),
+            _ => None,
+        }
+    }
+
+    /// Check if a value of the source type can be assigned to the target type, both named
+    /// the way `CONSTANT_Class` entries name types
+    /// AS SPECIFIED BY THE SPECS: the assignment rules of aastore, checkcast and instanceof
+    fn is_assignable(
+        &self,
+        source: &str,
+        target: &str,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<bool, VmError> {
+        if source == target {
+            return Ok(true);
+        }
+
+        if let Some(source_component) = source.strip_prefix('[') {
+            return match target.strip_prefix('[') {
+                // Arrays of references are covariant, arrays of primitives are not
+                Some(target_component) => match (
+                    array::component_class_name(source_component),
+                    array::component_class_name(target_component),
+                ) {
+                    (Some(source_class), Some(target_class)) => {
+                        self.is_assignable(source_class, target_class, runtime_data_area)
+                    }
+                    _ => Ok(false),
+                },
+                // Arrays only implement Cloneable and Serializable
+                None => Ok(matches!(
+                    target,
+                    "java/lang/Object" | "java/lang/Cloneable" | "java/io/Serializable"
+                )),
+            };
+        }
+
+        if target.starts_with('[') {
+            return Ok(false);
+        }
+
+        let class = runtime_data_area.class_loader.load_class(source)?;
+        Ok(class.is_subtype_of(target))
+    }
+
+    /// Find the handler of the current method covering the given pc that catches
+    /// the given exception and return its exception table entry
+    /// AS SPECIFIED BY THE SPECS: the exception table is searched in order, a catch type of 0
+    /// matches any exception, any other matches its class and subclasses
+    pub fn find_exception_handler(
+        &self,
+        frame: &Frame,
+        pc: usize,
+        exception: &Value,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<Option<ExceptionTableEntry>, VmError> {
+        let Some(code) = &frame.method.code else {
+            return Ok(None);
+        };
+
+        let exception_class = match exception {
+            Value::Object(object) => runtime_data_area.heap.object(*object).class.clone(),
+            _ => return Ok(None),
+        };
+
+        for entry in code.exception_table.iter() {
+            if pc < entry.start_pc as usize || pc >= entry.end_pc as usize {
+                continue;
+            }
+
+            if entry.catch_type == 0 {
+                return Ok(Some(entry.clone()));
+            }
+
+            let catch_class_name = frame
+                .class
+                .class_file
+                .get_class_name(entry.catch_type)
+                .ok_or_else(|| {
+                    VmError::verification(format!("Invalid catch type #{}", entry.catch_type))
+                })?;
+            let catch_class = runtime_data_area
+                .class_loader
+                .load_class(&catch_class_name)?;
+
+            if exception_class.is_subclass_of(&catch_class.name) {
+                return Ok(Some(entry.clone()));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Initialize the class an instruction of the given frame uses, unless it already is,
+    /// with the frame back on the call stack while the initializer runs
+    fn initialize_class_of(
+        &self,
+        class: &Rc<Class>,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+    ) -> Result<Option<Value>, VmError> {
+        if runtime_data_area.initialized_classes.contains(&class.name) {
+            return Ok(None);
+        }
+
+        call_stack.with_frame(frame, |call_stack| {
+            self.initialize_class(class, runtime_data_area, call_stack)
+        })
+    }
+
+    /// Initialize a class by preparing its static fields and executing its `<clinit>` method,
+    /// its superclass is initialized first
+    /// AS SPECIFIED BY THE SPECS: §5.5 ( initialization )
+    ///
+    /// Returns the exception thrown by the initialization, if any.
+    pub fn initialize_class(
+        &self,
+        class: &Rc<Class>,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+    ) -> Result<Option<Value>, VmError> {
+        // AS SPECIFIED BY THE SPECS: a class whose initialization failed is in an erroneous
+        // state, using it again throws a NoClassDefFoundError
+        if let Some(error) = runtime_data_area
+            .erroneous_classes
+            .get(&class.name)
+            .cloned()
+        {
+            return self
+                .could_not_initialize(class, error, runtime_data_area, call_stack)
+                .map(Some);
+        }
+
+        // Mark the class first so a recursive request from its own <clinit> returns immediately
+        if !runtime_data_area
+            .initialized_classes
+            .insert(class.name.clone())
+        {
+            return Ok(None);
+        }
+
+        let exception = self.run_initialization(class, runtime_data_area, call_stack)?;
+
+        if let Some(exception) = &exception {
+            let error = self.initialization_error(exception, runtime_data_area, call_stack)?;
+            runtime_data_area.initialized_classes.remove(&class.name);
+            runtime_data_area
+                .erroneous_classes
+                .insert(class.name.clone(), error);
+        }
+
+        Ok(exception)
+    }
+
+    /// Initialize the superclass and the superinterfaces of a class, its static fields and
+    /// run its `<clinit>` method, returns the exception thrown by any of them
+    fn run_initialization(
+        &self,
+        class: &Rc<Class>,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+    ) -> Result<Option<Value>, VmError> {
+        if let Some(super_class) = &class.super_class
+            && let Some(exception) =
+                self.initialize_class(super_class, runtime_data_area, call_stack)?
+        {
+            return Ok(Some(exception));
+        }
+
+        // AS SPECIFIED BY THE SPECS: the superinterfaces declaring default methods are
+        // initialized alongside a class, initializing an interface does not initialize its superinterfaces
+        if !class.is_interface() {
+            for interface in class.interfaces.iter().filter(|interface| {
+                interface
+                    .methods
+                    .iter()
+                    .any(|method| !method.is_abstract() && !method.is_static())
+            }) {
+                if let Some(exception) =
+                    self.initialize_class(interface, runtime_data_area, call_stack)?
+                {
+                    return Ok(Some(exception));
+                }
+            }
+        }
+
+        for field in class.fields.iter().filter(|field| field.is_static()) {
+            runtime_data_area
+                .static_fields
+                .entry(format!("{}.{}", class.name, field.name))
+                .or_insert_with(|| Value::default_for(&field.descriptor));
+        }
+
+        if let Some(clinit) = class.find_method("<clinit>", "()V") {
+            debug_log!("Initializing class {}", class.name);
+
+            if let InstructionCompleted::ThrowException(exception) =
+                self.invoke_method(clinit, Vec::new(), runtime_data_area, call_stack)?
+            {
+                return self
+                    .wrap_initializer_exception(exception, runtime_data_area, call_stack)
+                    .map(Some);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Record why the initialization of a class failed in the `ExceptionInInitializerError`
+    /// the later uses of the class are caused by, with the message and the stack trace of the
+    /// original exception, e.g. `Exception java.lang.RuntimeException: boom [in thread "main"]`
+    fn initialization_error(
+        &self,
+        exception: &Value,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &CallStack,
+    ) -> Result<Value, VmError> {
+        let heap = &runtime_data_area.heap;
+        let is_wrapped = matches!(exception, Value::Object(object)
+            if heap.object(*object).class.name == "java/lang/ExceptionInInitializerError");
+        let original = match exception {
+            Value::Object(object) if is_wrapped => heap
+                .object(*object)
+                .fields
+                .get(THROWABLE_CAUSE)
+                .cloned()
+                .filter(|cause| !matches!(cause, Value::Null))
+                .unwrap_or_else(|| exception.clone()),
+            _ => exception.clone(),
+        };
+
+        let message = format!(
+            "Exception {} [in thread \"{}\"]",
+            native::throwable_to_string(heap, &original),
+            thread::thread_name(runtime_data_area, &call_stack.thread.object)
+        );
+        let backtrace = match &original {
+            Value::Object(object) => heap
+                .object(*object)
+                .fields
+                .get(THROWABLE_BACKTRACE)
+                .cloned(),
+            _ => None,
+        };
+
+        let error = self.new_exception(
+            "java/lang/ExceptionInInitializerError",
+            Some(message),
+            runtime_data_area,
+            call_stack,
+        )?;
+
+        if let (Value::Object(object), Some(backtrace)) = (&error, backtrace) {
+            runtime_data_area
+                .heap
+                .object_mut(*object)
+                .fields
+                .insert(THROWABLE_BACKTRACE.to_string(), backtrace);
+        }
+
+        Ok(error)
+    }
+
+    /// Create the `NoClassDefFoundError` thrown when a class in the erroneous state is used,
+    /// caused by the error recorded when its initialization failed
+    fn could_not_initialize(
+        &self,
+        class: &Class,
+        error: Value,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &CallStack,
+    ) -> Result<Value, VmError> {
+        let exception = self.new_exception(
+            "java/lang/NoClassDefFoundError",
+            Some(format!(
+                "Could not initialize class {}",
+                class.name.replace('/', ".")
+            )),
+            runtime_data_area,
+            call_stack,
+        )?;
+
+        if let Value::Object(object) = &exception {
+            runtime_data_area
+                .heap
+                .object_mut(*object)
+                .fields
+                .insert(THROWABLE_CAUSE.to_string(), error);
+        }
+
+        Ok(exception)
+    }
+
+    /// Wrap an exception thrown by a class initializer in an `ExceptionInInitializerError`
+    /// AS SPECIFIED BY THE SPECS: errors are rethrown as they are
+    fn wrap_initializer_exception(
+        &self,
+        exception: Value,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &CallStack,
+    ) -> Result<Value, VmError> {
+        let is_error = match &exception {
+            Value::Object(object) => runtime_data_area
+                .heap
+                .object(*object)
+                .class
+                .is_subclass_of("java/lang/Error"),
+            _ => false,
+        };
+
+        if is_error {
+            return Ok(exception);
+        }
+
+        let error = self.new_exception(
+            "java/lang/ExceptionInInitializerError",
+            None,
+            runtime_data_area,
+            call_stack,
+        )?;
+
+        if let Value::Object(object) = &error {
+            runtime_data_area
+                .heap
+                .object_mut(*object)
+                .fields
+                .insert(THROWABLE_CAUSE.to_string(), exception);
+        }
+
+        Ok(error)
+    }
+
+    /// Invoke a resolved (and selected) method with the given arguments, it completes either
+    /// normally with the value it returned if any or abruptly with an uncaught exception
+    ///
+    /// The method runs to completion in a nested dispatch loop, for the VM and the natives
+    /// calling back into Java code, instructions hand their invocations to the current loop
+    pub fn invoke_method(
+        &self,
+        method: Rc<Method>,
+        args: Vec<Value>,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+    ) -> Result<InstructionCompleted, VmError> {
+        // AS SPECIFIED BY THE SPECS: §2.5.2, the nested runs recurse on the host stack, a
+        // recursion going through them, e.g. a `toString()` concatenating its fields,
+        // throws a StackOverflowError once the stack is full like any other
+        if !call_stack.has_room_for_run(runtime_data_area.host_stack_end) {
+            return Ok(InstructionCompleted::ThrowException(self.new_exception(
+                "java/lang/StackOverflowError",
+                None,
+                runtime_data_area,
+                call_stack,
+            )?));
+        }
+
+        let depth = call_stack.size();
+        call_stack.enter_run();
+
+        let completed = match self.invoke(method, args, runtime_data_area, call_stack) {
+            Ok(Some(completed)) => Ok(completed),
+            Ok(None) => call_stack.execute_frames_above(depth, runtime_data_area),
+            Err(error) => Err(error),
+        };
+
+        call_stack.exit_run();
+        completed
+    }
+
+    /// Start the invocation of a resolved (and selected) method with the given arguments,
+    /// pushing its frame onto the call stack for the dispatch loop to run it
+    ///
+    /// Returns how the method completed when it completed in place, which natives do,
+    /// `None` when its frame was pushed
+    pub fn invoke(
+        &self,
+        method: Rc<Method>,
+        args: Vec<Value>,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+    ) -> Result<Option<InstructionCompleted>, VmError> {
+        // Registered natives run in place of the bytecode of intrinsics, and methods of the
+        // classes spun for lambda call sites are forwarded by the VM
+        let native = runtime_data_area.natives.lookup(&method);
+        let lambda_form = match method.code {
+            Some(_) => None,
+            None => runtime_data_area
+                .lambda_forms
+                .get(&method.class_name)
+                .cloned(),
+        };
+
+        if native.is_some() || lambda_form.is_some() {
+            // The arguments are in no frame anymore, keep them alive while the native
+            // runs since it may allocate or call back into Java code
+            for arg in &args {
+                runtime_data_area.heap.pin(arg.clone());
+            }
+
+            let completed = match (native, lambda_form) {
+                (Some(native), _) => {
+                    native(&method, &args, runtime_data_area, call_stack).map(Some)
+                }
+                (None, Some(lambda_form)) => {
+                    self.invoke_lambda(&lambda_form, &method, &args, runtime_data_area, call_stack)
+                }
+                (None, None) => unreachable!(),
+            };
+
+            for arg in &args {
+                runtime_data_area.heap.unpin(arg);
+            }
+
+            // Exceptions thrown by native methods are created as soon as they are thrown,
+            // so that invokers only ever see the exception object
+            return match completed? {
+                Some(InstructionCompleted::ThrowNewException(class_name, message)) => {
+                    Ok(Some(InstructionCompleted::ThrowException(
+                        self.new_exception(class_name, message, runtime_data_area, call_stack)?,
+                    )))
+                }
+                // A native handing its invocation over to a Java method, which returns
+                // to the invoker of the native
+                Some(InstructionCompleted::InvokeMethod(method, args)) => {
+                    self.invoke(method, args, runtime_data_area, call_stack)
+                }
+                completed => Ok(completed),
+            };
+        }
+
+        if method.code.is_none() {
+            return match method.is_native() {
+                true if is_jdk_class(&method.class_name) => {
+                    Err(VmError::unsupported_library(format!(
+                        "native method {}.{}{} is not implemented",
+                        method.class_name.replace('/', "."),
+                        method.name,
+                        method.descriptor
+                    )))
+                }
+                true => Err(VmError::linkage(format!(
+                    "UnsatisfiedLinkError: {}.{}{}",
+                    method.class_name, method.name, method.descriptor
+                ))),
+                false => Err(VmError::throw(
+                    "java/lang/AbstractMethodError",
+                    format!(
+                        "{}.{}{}",
+                        method.class_name.replace('/', "."),
+                        method.name,
+                        method.descriptor
+                    ),
+                )),
+            };
+        }
+
+        // AS SPECIFIED BY THE SPECS: §2.5.2, a method whose frame needs more stack than
+        // is left throws a StackOverflowError
+        if !call_stack.has_room_for(&method) {
+            return Ok(Some(InstructionCompleted::ThrowException(
+                self.new_exception(
+                    "java/lang/StackOverflowError",
+                    None,
+                    runtime_data_area,
+                    call_stack,
+                )?,
+            )));
+        }
+
+        let class = runtime_data_area
+            .class_loader
+            .load_class(&method.class_name)?;
+
+        // AS SPECIFIED BY THE SPECS: §2.11.10, a synchronized method enters the monitor of
+        // its receiver, or of its class when static, before running and exits it however
+        // it completes ( the monitor is entered once the arguments are in the frame )
+        let monitor = match (method.is_synchronized(), args.first()) {
+            (false, _) => None,
+            (true, Some(receiver)) if !method.is_static() => Some(receiver.clone()),
+            (true, _) => {
+                // The arguments left the operand stack of the invoker
+                for arg in &args {
+                    runtime_data_area.heap.pin(arg.clone());
+                }
+
+                let monitor = runtime_data_area.class_monitor(&method.class_name, call_stack);
+
+                for arg in args.iter().rev() {
+                    runtime_data_area.heap.unpin(arg);
+                }
+
+                Some(monitor?)
+            }
+        };
+
+        call_stack.push_frame(class, method, args)?;
+
+        if let Some(monitor) = monitor {
+            thread::monitor_enter(&monitor, call_stack);
+
+            if let Some(frame) = call_stack.current_frame() {
+                frame.monitor = Some(monitor);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Resolve a method symbolic reference against the referenced class and its superclasses
+    /// AS SPECIFIED BY THE SPECS: §5.4.3.3 ( method resolution )
+    pub fn resolve_method(
+        &self,
+        class_name: &str,
+        method_name: &str,
+        descriptor: &str,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<Rc<Method>, VmError> {
+        // AS SPECIFIED BY THE SPECS: array types have the methods of java/lang/Object,
+        // e.g. int[].clone() is referenced as [I.clone:()Ljava/lang/Object;
+        let class = match class_name.starts_with('[') {
+            true => runtime_data_area
+                .class_loader
+                .load_class("java/lang/Object")?,
+            false => runtime_data_area.class_loader.load_class(class_name)?,
+        };
+
+        if class.is_interface() {
+            return Err(VmError::throw(
+                "java/lang/IncompatibleClassChangeError",
+                format!(
+                    "Found interface {}, but class was expected",
+                    class_name.replace('/', ".")
+                ),
+            ));
+        }
+
+        class
+            .lookup_method(method_name, descriptor)
+            .or_else(|| class.lookup_interface_method(method_name, descriptor))
+            .ok_or_else(|| self.no_such_method(&class.name, method_name, descriptor))
+    }
+
+    /// Resolve an interface method symbolic reference against the referenced interface,
+    /// `java/lang/Object` and then the superinterfaces
+    /// AS SPECIFIED BY THE SPECS: §5.4.3.4 ( interface method resolution )
+    pub fn resolve_interface_method(
+        &self,
+        interface_name: &str,
+        method_name: &str,
+        descriptor: &str,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<Rc<Method>, VmError> {
+        let interface = runtime_data_area.class_loader.load_class(interface_name)?;
+
+        if !interface.is_interface() {
+            return Err(VmError::throw(
+                "java/lang/IncompatibleClassChangeError",
+                format!(
+                    "Found class {}, but interface was expected",
+                    interface_name.replace('/', ".")
+                ),
+            ));
+        }
+
+        if let Some(method) = interface.find_method(method_name, descriptor) {
+            return Ok(method);
+        }
+
+        // Interfaces extend java/lang/Object, only its public instance methods are visible
+        let object_method = interface
+            .super_class
+            .as_ref()
+            .and_then(|object| object.find_method(method_name, descriptor))
+            .filter(|method| method.access_flags & ACC_PUBLIC != 0 && !method.is_static());
+
+        object_method
+            .or_else(|| interface.lookup_interface_method(method_name, descriptor))
+            .ok_or_else(|| self.no_such_method(interface_name, method_name, descriptor))
+    }
+
+    /// The error of a method resolution that failed, a gap of the VM when the class
+    /// belongs to the JDK
+    fn no_such_method(&self, class_name: &str, method_name: &str, descriptor: &str) -> VmError {
+        match is_jdk_class(class_name) {
+            true => VmError::unsupported_library(format!(
+                "method {}.{}{} is not implemented",
+                class_name.replace('/', "."),
+                method_name,
+                descriptor
+            )),
+            false => VmError::throw(
+                "java/lang/NoSuchMethodError",
+                format!(
+                    "'{}.{}{}'",
+                    class_name.replace('/', "."),
+                    method_name,
+                    descriptor
+                ),
+            ),
+        }
+    }
+
+    /// The error of a field resolution that failed, a gap of the VM when the class
+    /// belongs to the JDK
+    fn no_such_field(&self, class_name: &str, field_name: &str) -> VmError {
+        match is_jdk_class(class_name) {
+            true => VmError::unsupported_library(format!(
+                "field {}.{} is not implemented",
+                class_name.replace('/', "."),
+                field_name
+            )),
+            false => VmError::linkage(format!("NoSuchFieldError: {}", field_name)),
+        }
+    }
+
+    /// Resolve either a `Methodref` or an `InterfaceMethodref` symbolic reference,
+    /// invokestatic and invokespecial accept both kinds since Java 8
+    pub fn resolve_method_ref(
+        &self,
+        class_file: &ClassFile,
+        method_ref: u16,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<(String, String, String, Rc<Method>), VmError> {
+        if let Some((class_name, method_name, descriptor)) = class_file.get_method_info(method_ref)
+        {
+            let method =
+                self.resolve_method(&class_name, &method_name, &descriptor, runtime_data_area)?;
+            return Ok((class_name, method_name, descriptor, method));
+        }
+
+        if let Some((interface_name, method_name, descriptor)) =
+            class_file.get_interface_method_info(method_ref)
+        {
+            let method = self.resolve_interface_method(
+                &interface_name,
+                &method_name,
+                &descriptor,
+                runtime_data_area,
+            )?;
+            return Ok((interface_name, method_name, descriptor, method));
+        }
+
+        Err(VmError::verification(format!(
+            "Invalid method reference #{}",
+            method_ref
+        )))
+    }
+
+    /// Resolve a static field symbolic reference and return the key of the field
+    /// in the runtime static fields alongside its declaring class, which must be
+    /// initialized before the field is accessed
+    pub fn resolve_static_field(
+        &self,
+        class_name: &str,
+        field_name: &str,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<(String, Rc<Class>), VmError> {
+        let class = runtime_data_area.class_loader.load_class(class_name)?;
+
+        let (declaring_class_name, _) = class
+            .lookup_field(field_name)
+            .ok_or_else(|| self.no_such_field(class_name, field_name))?;
+
+        let declaring_class = runtime_data_area
+            .class_loader
+            .load_class(&declaring_class_name)?;
+
+        Ok((
+            format!("{}.{}", declaring_class_name, field_name),
+            declaring_class,
+        ))
+    }
+
+    /// Resolve an instance field symbolic reference and return the key of the field
+    /// in the fields of an object
+    pub fn resolve_instance_field(
+        &self,
+        class_name: &str,
+        field_name: &str,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<String, VmError> {
+        let class = runtime_data_area.class_loader.load_class(class_name)?;
+
+        match class.lookup_field(field_name) {
+            Some((declaring_class_name, field)) if !field.is_static() => {
+                Ok(format!("{}.{}", declaring_class_name, field_name))
+            }
+            Some(_) => Err(VmError::throw(
+                "java/lang/IncompatibleClassChangeError",
+                format!(
+                    "Expected non-static field {}.{}",
+                    class_name.replace('/', "."),
+                    field_name
+                ),
+            )),
+            None => Err(self.no_such_field(class_name, field_name)),
+        }
+    }
+
+    /// Pop the arguments of a method call from the operand stack in declaration order,
+    /// `None` if the operand stack holds fewer values than the method takes
+    fn pop_arguments(&self, frame: &mut Frame, descriptor: &str) -> Option<Vec<Value>> {
+        let params_count = descriptor::parameter_types(descriptor).len();
+        let mut params = Vec::with_capacity(params_count);
+
+        while params.len() < params_count {
+            let arg = frame.operand_stack.pop()?;
+            debug_log!("param[{}] = {:?}", params.len(), arg);
+            params.push(arg);
+        }
+
+        params.reverse();
+
+        Some(params)
+    }
+
+    /// Narrow an int value stored in a boolean, byte, char or short field
+    /// AS SPECIFIED BY THE SPECS: putfield and putstatic truncate the value to the field type
+    fn narrow_to_field_type(&self, value: Value, descriptor: &str) -> Value {
+        match (value, descriptor) {
+            (Value::Int(i), "Z") => Value::Int(i & 1),
+            (Value::Int(i), "B") => Value::Int(i as i8 as i32),
+            (Value::Int(i), "C") => Value::Int(i as u16 as i32),
+            (Value::Int(i), "S") => Value::Int(i as i16 as i32),
+            (value, _) => value,
+        }
+    }
+
+    /// Create a new primitive array
+    /// of the type corresponding to the next byte's value of the frame's bytecode
+    /// and of the count corresponding to the top value of the frame's operand stack
+    fn execute_newarray(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let atype = frame.bytecode[*pc];
 
         //TODO: Handle empty stack exceptions
         if let Some(Value::Int(count)) = frame.operand_stack.pop() {
             if count < 0 {
-                return Err(format!("NegativeArraySizeException: {}", count));
+                return Ok(InstructionCompleted::ThrowNewException(
+                    "java/lang/NegativeArraySizeException",
+                    Some(count.to_string()),
+                ));
             }
 
-            let array = match atype {
-                4 => {
-                    // false = 0
-                    vec![Value::Int(0); count as usize]
-                }
-                5 => {
-                    // '\0' = 0
-                    vec![Value::Int(0); count as usize]
-                }
-                6 => {
-                    vec![Value::Float(0.0); count as usize]
-                }
-                7 => {
-                    vec![Value::Double(0.0); count as usize]
-                }
-                8 => {
-                    // byte stored as int
-                    vec![Value::Int(0); count as usize]
-                }
-                9 => {
-                    // short stored as int
-                    vec![Value::Int(0); count as usize]
-                }
-                10 => {
-                    vec![Value::Int(0); count as usize]
-                }
-                11 => {
-                    vec![Value::Long(0); count as usize]
+            // AS SPECIFIED BY THE SPECS: atype codes of the primitive array types
+            let descriptor = match atype {
+                4 => "[Z",
+                5 => "[C",
+                6 => "[F",
+                7 => "[D",
+                8 => "[B",
+                9 => "[S",
+                10 => "[I",
+                11 => "[J",
+                _ => {
+                    return Err(VmError::verification(format!(
+                        "Invalid array type: {}",
+                        atype
+                    )));
                 }
-                _ => return Err(format!("Invalid array type: {}", atype)),
             };
 
-            // Push array reference onto the stack
+            let mut reservation = self.reserve_heap_for(
+                frame,
+                Heap::array_size(&descriptor[1..], count as usize),
+                runtime_data_area,
+                call_stack,
+            )?;
+
+            // Push array reference onto the stack, the components are stored at their own width
             frame
                 .operand_stack
-                .push(Value::Array(Rc::new(RefCell::new(array))));
-
-            let type_name = match atype {
-                4 => "boolean",
-                5 => "char",
-                6 => "float",
-                7 => "double",
-                8 => "byte",
-                9 => "short",
-                10 => "int",
-                11 => "long",
-                _ => "unknown",
-            };
+                .push(runtime_data_area.heap.allocate_array(
+                    &mut reservation,
+                    Array::new(descriptor.to_string(), count as usize),
+                ));
+
+            debug_log!("  newarray {} [length={}]", descriptor, count);
+        }
+
+        Ok(InstructionCompleted::ContinueMethodExecution)
+    }
+
+    /// Create a new array of references
+    /// whose component type is the class, interface or array type referenced at the index of
+    /// the next two bytes' value in the bytecode after applying the indexing equation specified
+    /// by the specs from the constant pool, and of the count corresponding to the top value
+    /// of the frame's operand stack
+    fn execute_anewarray(
+        &self,
+        frame: &mut Frame,
+        class_file: &ClassFile,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let index_high = frame.bytecode[*pc] as u16;
+        *pc += 1;
+        let index_low = frame.bytecode[*pc] as u16;
+
+        // AS SPECIFIED BY THE SPECS: (indexbyte1 << 8) | indexbyte2
+        let class_index = (index_high << 8) | index_low;
+
+        let component_name = class_file.get_class_name(class_index).ok_or_else(|| {
+            VmError::verification(format!(
+                "anewarray: invalid class reference #{}",
+                class_index
+            ))
+        })?;
+
+        let count = match frame.operand_stack.pop() {
+            Some(Value::Int(count)) => count,
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "anewarray: expected int count, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
+        };
+
+        if count < 0 {
+            return Ok(InstructionCompleted::ThrowNewException(
+                "java/lang/NegativeArraySizeException",
+                Some(count.to_string()),
+            ));
+        }
+
+        let descriptor = array::array_descriptor_of(&component_name);
+
+        let mut reservation = self.reserve_heap_for(
+            frame,
+            Heap::array_size(&descriptor[1..], count as usize),
+            runtime_data_area,
+            call_stack,
+        )?;
+        debug_log!("  anewarray {} [length={}]", descriptor, count);
+
+        // Every component is null
+        frame.operand_stack.push(
+            runtime_data_area
+                .heap
+                .allocate_array(&mut reservation, Array::new(descriptor, count as usize)),
+        );
+
+        Ok(InstructionCompleted::ContinueMethodExecution)
+    }
+
+    /// Create a new multidimensional array
+    /// of the array type referenced at the index of the next two bytes' value in the bytecode
+    /// after applying the indexing equation specified by the specs from the constant pool,
+    /// the next byte is the number of dimensions to create whose counts are popped from the
+    /// frame's operand stack
+    fn execute_multianewarray(
+        &self,
+        frame: &mut Frame,
+        class_file: &ClassFile,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        *pc += 1;
+        let index_high = frame.bytecode[*pc] as u16;
+        *pc += 1;
+        let index_low = frame.bytecode[*pc] as u16;
+        *pc += 1;
+        let dimensions = frame.bytecode[*pc] as usize;
+
+        // AS SPECIFIED BY THE SPECS: (indexbyte1 << 8) | indexbyte2
+        let class_index = (index_high << 8) | index_low;
+
+        let descriptor = class_file.get_class_name(class_index).ok_or_else(|| {
+            VmError::verification(format!(
+                "multianewarray: invalid class reference #{}",
+                class_index
+            ))
+        })?;
+
+        if dimensions == 0 || descriptor.chars().take_while(|c| *c == '[').count() < dimensions {
+            return Err(format!(
+                "multianewarray: {} has less than {} dimensions",
+                descriptor, dimensions
+            )
+            .into());
+        }
+
+        if frame.operand_stack.len() < dimensions {
+            return Ok(self.operand_stack_underflow(frame));
+        }
+
+        // The count of the outermost dimension is the deepest on the operand stack
+        let mut counts = Vec::with_capacity(dimensions);
+        for _ in 0..dimensions {
+            match frame.operand_stack.pop() {
+                Some(Value::Int(count)) => counts.push(count),
+                Some(other) => {
+                    return Err(VmError::verification(format!(
+                        "multianewarray: expected int count, got {:?}",
+                        other
+                    )));
+                }
+                None => return Ok(self.operand_stack_underflow(frame)),
+            }
+        }
+        counts.reverse();
+
+        // AS SPECIFIED BY THE SPECS: any negative count throws a NegativeArraySizeException,
+        // even in dimensions that would not be created because an outer count is 0
+        if let Some(count) = counts.iter().find(|count| **count < 0) {
+            return Ok(InstructionCompleted::ThrowNewException(
+                "java/lang/NegativeArraySizeException",
+                Some(count.to_string()),
+            ));
+        }
+
+        // Reserve the whole tree at once, the arrays allocated for the inner dimensions
+        // are not reachable from any root until the outermost one is pushed
+        let mut size = 0usize;
+        let mut arrays = 1usize;
+        for (dimension, count) in counts.iter().enumerate() {
+            let array_size = Heap::array_size(&descriptor[dimension + 1..], *count as usize);
+            size = size.saturating_add(arrays.saturating_mul(array_size));
+            arrays = arrays.saturating_mul(*count as usize);
+        }
+
+        let mut reservation = self.reserve_heap_for(frame, size, runtime_data_area, call_stack)?;
+
+        debug_log!("  multianewarray {} {:?}", descriptor, counts);
+
+        let array = self.new_multi_array(&descriptor, &counts, runtime_data_area, &mut reservation);
+        frame.operand_stack.push(array);
+
+        Ok(InstructionCompleted::ContinueMethodExecution)
+    }
+
+    /// Allocate an array of the given type whose first dimensions have the given counts,
+    /// the components of the innermost created dimension have their default value
+    fn new_multi_array(
+        &self,
+        descriptor: &str,
+        counts: &[i32],
+        runtime_data_area: &mut RuntimeDataArea,
+        reservation: &mut Reservation,
+    ) -> Value {
+        let count = counts[0] as usize;
+
+        let array = match counts.len() {
+            1 => Array::new(descriptor.to_string(), count),
+            _ => {
+                let component_type = &descriptor[1..];
+                let elements = (0..count)
+                    .map(|_| {
+                        self.new_multi_array(
+                            component_type,
+                            &counts[1..],
+                            runtime_data_area,
+                            reservation,
+                        )
+                    })
+                    .collect();
+
+                Array::from_elements(descriptor.to_string(), elements)
+            }
+        };
 
-            debug_log!("  newarray {} [length={}]", type_name, count);
+        runtime_data_area.heap.allocate_array(reservation, array)
+    }
+
+    /// Check that the reference on top of the operand stack is null or assignable to the type
+    /// referenced at the index of the next two bytes' value in the bytecode after applying the
+    /// indexing equation specified by the specs, leaving the operand stack unchanged,
+    /// otherwise a ClassCastException is thrown
+    fn execute_checkcast(
+        &self,
+        frame: &mut Frame,
+        class_file: &ClassFile,
+        runtime_data_area: &mut RuntimeDataArea,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        let instruction_pc = *pc;
+
+        *pc += 1;
+        let index_high = frame.bytecode[*pc] as u16;
+        *pc += 1;
+        let index_low = frame.bytecode[*pc] as u16;
+
+        // AS SPECIFIED BY THE SPECS: (indexbyte1 << 8) | indexbyte2
+        let class_index = (index_high << 8) | index_low;
+
+        let objectref = match frame.operand_stack.peek() {
+            Some(value @ (Value::Object(_) | Value::Array(_) | Value::Null)) => value.clone(),
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "checkcast: expected reference value, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
+        };
+
+        // AS SPECIFIED BY THE SPECS: a null reference passes any check
+        let Some(source) = self.runtime_type_name(&objectref, runtime_data_area) else {
+            return Ok(InstructionCompleted::ContinueMethodExecution);
+        };
+
+        let target = class_file.get_class_name(class_index).ok_or_else(|| {
+            VmError::verification(format!(
+                "checkcast: invalid class reference #{}",
+                class_index
+            ))
+        })?;
+
+        debug_log!("  checkcast {} to {}", source, target);
+
+        let key = (Rc::as_ptr(&frame.method), instruction_pc);
+        if !self.is_instance_of(key, &source, &target, runtime_data_area)? {
+            return Ok(InstructionCompleted::ThrowNewException(
+                "java/lang/ClassCastException",
+                Some(native::class_cast_message(&source, &target)),
+            ));
         }
 
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
 
+    /// Pop a reference from the operand stack and push 1 if it is assignable to the type
+    /// referenced at the index of the next two bytes' value in the bytecode after applying the
+    /// indexing equation specified by the specs, 0 if it is not or if it is null
+    fn execute_instanceof(
+        &self,
+        frame: &mut Frame,
+        class_file: &ClassFile,
+        runtime_data_area: &mut RuntimeDataArea,
+        pc: &mut usize,
+    ) -> Result<InstructionCompleted, VmError> {
+        let instruction_pc = *pc;
+
+        *pc += 1;
+        let index_high = frame.bytecode[*pc] as u16;
+        *pc += 1;
+        let index_low = frame.bytecode[*pc] as u16;
+
+        // AS SPECIFIED BY THE SPECS: (indexbyte1 << 8) | indexbyte2
+        let class_index = (index_high << 8) | index_low;
+
+        let objectref = match frame.operand_stack.pop() {
+            Some(value @ (Value::Object(_) | Value::Array(_) | Value::Null)) => value,
+            Some(other) => {
+                return Err(VmError::verification(format!(
+                    "instanceof: expected reference value, got {:?}",
+                    other
+                )));
+            }
+            None => return Ok(self.operand_stack_underflow(frame)),
+        };
+
+        let is_instance = match self.runtime_type_name(&objectref, runtime_data_area) {
+            Some(source) => {
+                let target = class_file.get_class_name(class_index).ok_or_else(|| {
+                    format!("instanceof: invalid class reference #{}", class_index)
+                })?;
+
+                debug_log!("  instanceof {} {}", source, target);
+
+                let key = (Rc::as_ptr(&frame.method), instruction_pc);
+                self.is_instance_of(key, &source, &target, runtime_data_area)?
+            }
+            // AS SPECIFIED BY THE SPECS: null is not an instance of any type
+            None => false,
+        };
+
+        frame.operand_stack.push(Value::Int(is_instance as i32));
+
+        Ok(InstructionCompleted::ContinueMethodExecution)
+    }
+
+    /// Check if a reference of the source runtime type is assignable to the target type of a
+    /// checkcast or instanceof instruction, the last type that passed the check is cached
+    /// per instruction so that a check repeated in a loop is a single comparison
+    fn is_instance_of(
+        &self,
+        key: (*const Method, usize),
+        source: &str,
+        target: &str,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<bool, VmError> {
+        if runtime_data_area.type_checks.get(&key).map(String::as_str) == Some(source) {
+            return Ok(true);
+        }
+
+        // AS SPECIFIED BY THE SPECS: the target class is resolved, array types through
+        // their element class
+        let class_name = match target.starts_with('[') {
+            true => array::component_class_name(target.trim_start_matches('[')),
+            false => Some(target),
+        };
+        if let Some(class_name) = class_name {
+            runtime_data_area.class_loader.load_class(class_name)?;
+        }
+
+        let is_instance = self.is_assignable(source, target, runtime_data_area)?;
+
+        if is_instance {
+            runtime_data_area
+                .type_checks
+                .insert(key, source.to_string());
+        }
+
+        Ok(is_instance)
+    }
+
     /// Calculate the length of some array by popping its arrayref from the frame's operand stack
     /// and pushing its length back onto the operand stack
-    fn execute_arraylength(&self, frame: &mut Frame) -> Result<InstructionCompleted, String> {
+    fn execute_arraylength(
+        &self,
+        frame: &mut Frame,
+        runtime_data_area: &mut RuntimeDataArea,
+    ) -> Result<InstructionCompleted, VmError> {
         if frame.operand_stack.len() == 0 {
-            return Err("Stack underflow: arraylength requires 1 operand".to_string());
+            return Ok(self.operand_stack_underflow(frame));
         }
 
         match frame.operand_stack.pop() {
             Some(Value::Array(arrayref)) => {
-                let length = arrayref.borrow().len();
+                let length = runtime_data_area.heap.array(arrayref).length();
 
                 // arrays can't be larger than i32::MAX in JVM
                 let length_i32 = if length > i32::MAX as usize {
-                    return Err(format!("Array too large: {}", length));
+                    return Err(format!("Array too large: {}", length).into());
                 } else {
                     length as i32
                 };
@@ -3696,12 +6932,12 @@ impl InstructionExecutor {
                 debug_log!("  arraylength [length={}]", length);
                 Ok(InstructionCompleted::ContinueMethodExecution)
             }
-            //TODO: Handle null references
-            Some(other) => Err(format!(
+            Some(Value::Null) => Ok(self.null_pointer_exception(frame)),
+            Some(other) => Err(VmError::verification(format!(
                 "arraylength: expected array reference, got {:?}",
                 other
-            )),
-            None => Err("arraylength: failed to pop value from stack".to_string()),
+            ))),
+            None => Ok(self.operand_stack_underflow(frame)),
         }
     }
 
@@ -3711,7 +6947,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         *pc += 1;
         let branchbyte1 = frame.bytecode[*pc] as u32;
         *pc += 1;
@@ -3742,7 +6978,7 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         match frame.operand_stack.pop() {
             Some(Value::Null) => {
                 *pc += 1;
@@ -3755,22 +6991,22 @@ impl InstructionExecutor {
 
                 // NOTE: The offset is relative to the address of the if<cond> opcode itself,
                 // not the current PC
-                let branch_base = (*pc as isize) - 3;
+                let branch_base = (*pc as isize) - 2;
                 let target = (branch_base + offset as isize) as usize;
                 *pc = target.wrapping_sub(1);
             }
-            Some(Value::Reference(_)) | Some(Value::Array(_)) => {
+            Some(Value::Array(_)) | Some(Value::Object(_)) => {
                 // Value is a non-null reference, don't branch
                 *pc += 2;
             }
             Some(other) => {
-                return Err(format!(
+                return Err(VmError::verification(format!(
                     "ifnull: value must be a reference, got {:?}",
                     other
-                ));
+                )));
             }
             None => {
-                return Err("ifnull: operand stack underflow".to_string());
+                return Ok(self.operand_stack_underflow(frame));
             }
         }
 
@@ -3782,13 +7018,13 @@ impl InstructionExecutor {
         &self,
         frame: &mut Frame,
         pc: &mut usize,
-    ) -> Result<InstructionCompleted, String> {
+    ) -> Result<InstructionCompleted, VmError> {
         match frame.operand_stack.pop() {
             Some(Value::Null) => {
                 // Value is null, don't branch
                 *pc += 2;
             }
-            Some(Value::Reference(_)) | Some(Value::Array(_)) => {
+            Some(Value::Array(_)) | Some(Value::Object(_)) => {
                 *pc += 1;
                 let index_high = frame.bytecode[*pc] as u16;
                 *pc += 1;
@@ -3799,112 +7035,21 @@ impl InstructionExecutor {
 
                 // NOTE: The offset is relative to the address of the if<cond> opcode itself,
                 // not the current PC
-                let branch_base = (*pc as isize) - 3;
+                let branch_base = (*pc as isize) - 2;
                 let target = (branch_base + offset as isize) as usize;
                 *pc = target.wrapping_sub(1);
             }
             Some(other) => {
-                return Err(format!(
+                return Err(VmError::verification(format!(
                     "ifnonnull: value must be a reference, got {:?}",
                     other
-                ));
+                )));
             }
             None => {
-                return Err("ifnonnull: operand stack underflow".to_string());
+                return Ok(self.operand_stack_underflow(frame));
             }
         }
 
         Ok(InstructionCompleted::ContinueMethodExecution)
     }
-
-    /// Count the number of params passed to some function call
-    fn count_method_params(&self, descriptor: &str) -> usize {
-        // Extract the characters in the descriptor string
-        // and initialize a final count variable and a loop pointer
-        let chars: Vec<char> = descriptor.chars().collect();
-        let mut count = 0;
-        let mut i = 0;
-
-        // Find the opening parenthesis
-        while i < chars.len() && chars[i] != '(' {
-            i += 1;
-        }
-
-        if i >= chars.len() {
-            debug_log!("Warning: Invalid method descriptor format: {}", descriptor);
-            return 0;
-        }
-
-        // Skip the opening '('
-        i += 1;
-
-        // Keep parsing until hitting the closing parenthesis
-        while i < chars.len() && chars[i] != ')' {
-            match chars[i] {
-                // Primitive types take only one count
-                'B' | 'C' | 'D' | 'F' | 'I' | 'J' | 'S' | 'Z' => {
-                    count += 1;
-                    i += 1;
-                }
-                // Object types take only one count and they start with 'L' and end with ';'
-                'L' => {
-                    count += 1;
-                    i += 1;
-                    while i < chars.len() && chars[i] != ';' {
-                        i += 1;
-                    }
-
-                    // Skip the ';'
-                    if i < chars.len() {
-                        i += 1;
-                    }
-                }
-                // Array types take only one count and they start with '['
-                '[' => {
-                    count += 1;
-                    i += 1;
-
-                    // Skip all the array dimensions
-                    while i < chars.len() && chars[i] == '[' {
-                        i += 1;
-                    }
-
-                    // Skip the component type
-                    if i < chars.len() {
-                        match chars[i] {
-                            'B' | 'C' | 'D' | 'F' | 'I' | 'J' | 'S' | 'Z' => {
-                                i += 1;
-                            }
-                            'L' => {
-                                i += 1;
-                                while i < chars.len() && chars[i] != ';' {
-                                    i += 1;
-                                }
-
-                                // Skip the ';'
-                                if i < chars.len() {
-                                    i += 1;
-                                }
-                            }
-                            _ => {
-                                debug_log!("Warning: Unknown array component type: {}", chars[i]);
-                                i += 1;
-                            }
-                        }
-                    }
-                }
-                _ => {
-                    debug_log!("Warning: Unknown parameter type: {}", chars[i]);
-                    i += 1;
-                }
-            }
-        }
-        debug_log!(
-            "Method descriptor '{}' has {} parameters",
-            descriptor,
-            count
-        );
-
-        count
-    }
 }
@@ -1,26 +1,134 @@
-use crate::vm::value::Value;
+use crate::vm::{error::VmError, value::Value};
+
+/// Computational type of a local variable, the one the typed load and store instructions
+/// expect, e.g. `lload` and `lstore` work on longs only
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LocalType {
+    Int,
+    Long,
+    Float,
+    Double,
+    /// Objects, arrays and null, `astore` also stores return addresses
+    Reference,
+}
+
+impl LocalType {
+    /// Checks if a value is of this type
+    pub fn matches(self, value: &Value) -> bool {
+        matches!(
+            (self, value),
+            (Self::Int, Value::Int(_))
+                | (Self::Long, Value::Long(_))
+                | (Self::Float, Value::Float(_))
+                | (Self::Double, Value::Double(_))
+                | (
+                    Self::Reference,
+                    Value::Object(_) | Value::Array(_) | Value::Null
+                )
+        )
+    }
+
+    /// Name of the type in the messages of the type checking verifier
+    pub fn verifier_name(self) -> &'static str {
+        match self {
+            Self::Int => "integer",
+            Self::Long => "long",
+            Self::Float => "float",
+            Self::Double => "double",
+            Self::Reference => "reference type",
+        }
+    }
+}
+
+/// A slot of the local variables array
+#[derive(Clone, Debug)]
+pub enum Slot {
+    /// Nothing was stored in the slot yet, or the value stored was partly overwritten
+    Unset,
+    Value(Value),
+    /// Second slot of the long or double stored in the slot before, it cannot be loaded
+    Upper,
+}
 
 /// Local Variables for method execution
+///
+/// AS SPECIFIED BY THE SPECS: §2.6.1, a long or a double takes two consecutive slots and is
+/// accessed through the lower one, storing into either slot invalidates the value
 #[derive(Clone, Debug)]
 pub struct LocalVariables {
-    pub vars: Vec<Option<Value>>,
+    slots: Vec<Slot>,
 }
 
 impl LocalVariables {
     pub fn new(size: usize) -> Self {
         Self {
-            vars: vec![None; size],
+            slots: vec![Slot::Unset; size],
         }
     }
 
-    pub fn set(&mut self, index: usize, value: Value) {
-        if index < self.vars.len() {
-            self.vars[index] = Some(value);
+    /// Stores a value at an index, in two slots for longs and doubles
+    pub fn set(&mut self, index: usize, value: Value) -> Result<(), VmError> {
+        let wide = matches!(value, Value::Long(_) | Value::Double(_));
+        let size = if wide { 2 } else { 1 };
+
+        if index + size > self.slots.len() {
+            return Err(VmError::verification(format!(
+                "Local variable {} is out of the {} local variables of the method",
+                index + size - 1,
+                self.slots.len()
+            )));
+        }
+
+        // The long or double whose second slot is overwritten becomes unusable
+        if index > 0 && matches!(self.slots[index], Slot::Upper) {
+            self.slots[index - 1] = Slot::Unset;
         }
+
+        // And so does the second slot of the long or double overwritten
+        let last = index + size - 1;
+        if self.is_wide(last) {
+            self.slots[last + 1] = Slot::Unset;
+        }
+
+        self.slots[index] = Slot::Value(value);
+        if wide {
+            self.slots[index + 1] = Slot::Upper;
+        }
+
+        Ok(())
+    }
+
+    /// Whether an index is one of the `max_locals` slots of the frame
+    pub fn contains(&self, index: usize) -> bool {
+        index < self.slots.len()
     }
 
+    /// Returns the value stored at an index, `None` if the slot holds no usable value
     pub fn get(&self, index: usize) -> Option<&Value> {
-        // We want to return a reference instead of moving ownership
-        self.vars.get(index).and_then(|v| v.as_ref())
+        match self.slots.get(index)? {
+            Slot::Value(value) => Some(value),
+            Slot::Unset | Slot::Upper => None,
+        }
+    }
+
+    /// Returns the slot at an index, `None` if the index is out of range
+    pub fn slot(&self, index: usize) -> Option<&Slot> {
+        self.slots.get(index)
+    }
+
+    /// Iterates over the values stored in the slots, each long and double once
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.slots.iter().filter_map(|slot| match slot {
+            Slot::Value(value) => Some(value),
+            Slot::Unset | Slot::Upper => None,
+        })
+    }
+
+    /// Whether the slot at an index holds a long or a double, the next slot is its second one
+    fn is_wide(&self, index: usize) -> bool {
+        matches!(
+            self.slots.get(index),
+            Some(Slot::Value(Value::Long(_) | Value::Double(_)))
+        )
     }
 }
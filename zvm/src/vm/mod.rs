@@ -1,9 +1,28 @@
+pub mod array;
+pub mod bootstrap;
+pub mod call_site;
 pub mod call_stack;
+pub mod class;
+pub mod class_loader;
+pub mod console;
+pub mod coverage;
+pub mod error;
+pub mod formatter;
+pub mod heap;
 pub mod instruction_exec;
 pub mod local;
 pub mod logging;
+pub mod native;
+pub mod null_pointer;
+pub mod number;
+pub mod object;
 pub mod operand_stack;
+pub mod regex;
 pub mod runtime;
+pub mod simulation;
 pub mod stack_frame;
+pub mod string;
+pub mod thread;
 pub mod value;
+pub mod verifier;
 pub mod vm;
@@ -0,0 +1,3212 @@
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    process,
+};
+
+use crate::{
+    parser::descriptor,
+    vm::{
+        array::{self, Array, ArrayElements},
+        bootstrap::find_bootstrap_class,
+        call_stack::CallStack,
+        class::Method,
+        console,
+        error::VmError,
+        formatter::{self, Argument, ArgumentIndex, FormatElement, FormatError},
+        heap::{Handle, Heap},
+        instruction_exec::{InstructionCompleted, InstructionExecutor},
+        number,
+        object::Object,
+        regex::Regex,
+        runtime::RuntimeDataArea,
+        string,
+        thread::{self, THREAD_DAEMON, THREAD_NAME, THREAD_PRIORITY, THREAD_TARGET},
+        value::Value,
+    },
+};
+
+/// Keys of the `java/lang/Throwable` fields in the fields of an object
+pub const THROWABLE_DETAIL_MESSAGE: &str = "java/lang/Throwable.detailMessage";
+pub const THROWABLE_CAUSE: &str = "java/lang/Throwable.cause";
+pub const THROWABLE_BACKTRACE: &str = "java/lang/Throwable.backtrace";
+
+/// Most frames a throwable captures, the innermost ones, like HotSpot's
+/// `MaxJavaStackTraceDepth`
+pub const MAX_STACK_TRACE_DEPTH: usize = 1024;
+
+/// Keys of the `java/lang/StringBuilder` fields in the fields of an object
+const STRING_BUILDER_VALUE: &str = "java/lang/StringBuilder.value";
+const STRING_BUILDER_COUNT: &str = "java/lang/StringBuilder.count";
+
+/// Initial capacity of a `StringBuilder` created without contents
+const STRING_BUILDER_CAPACITY: usize = 16;
+
+/// Keys of the standard streams in the static fields
+const SYSTEM_OUT: &str = "java/lang/System.out";
+const SYSTEM_ERR: &str = "java/lang/System.err";
+
+/// Key of the standard stream a `java/io/PrintStream` writes to in the fields of an object
+const PRINT_STREAM_FD: &str = "java/io/PrintStream.fd";
+
+/// Completion of a native method, either normal with `ReturnFromMethod` or abrupt with
+/// `ThrowNewException`, or `ThrowException` when a Java method it calls, like a `toString()`,
+/// throws. `Err` reports a failure of the VM itself
+pub type NativeResult = Result<InstructionCompleted, VmError>;
+
+/// A method implemented in Rust instead of bytecode, it receives the invoked method, its
+/// arguments with the receiver first for instance methods, the runtime data area and the
+/// call stack of the invoking thread
+pub type NativeMethod = fn(&Method, &[Value], &mut RuntimeDataArea, &mut CallStack) -> NativeResult;
+
+/// Rust implementations of methods keyed by ( class, name, descriptor ), both for `ACC_NATIVE`
+/// methods and for intrinsics taking the place of methods that have bytecode
+#[derive(Default)]
+pub struct NativeRegistry {
+    /// Natives by class name, then by method name, each overload along with its descriptor
+    methods: HashMap<String, HashMap<String, Vec<(String, NativeMethod)>>>,
+}
+
+impl NativeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the Rust implementation of a method, replacing the previous one if any
+    pub fn register(
+        &mut self,
+        class_name: &str,
+        name: &str,
+        descriptor: &str,
+        native: NativeMethod,
+    ) {
+        let overloads = self
+            .methods
+            .entry(class_name.to_string())
+            .or_default()
+            .entry(name.to_string())
+            .or_default();
+
+        match overloads.iter_mut().find(|(known, _)| known == descriptor) {
+            Some((_, registered)) => *registered = native,
+            None => overloads.push((descriptor.to_string(), native)),
+        }
+    }
+
+    /// Finds the Rust implementation registered for the given method
+    pub fn lookup(&self, method: &Method) -> Option<NativeMethod> {
+        self.methods
+            .get(&method.class_name)?
+            .get(&method.name)?
+            .iter()
+            .find(|(descriptor, _)| *descriptor == method.descriptor)
+            .map(|(_, native)| *native)
+    }
+}
+
+/// The natives of the bootstrap classes, every VM registers them when it is created
+pub const BUILTIN_NATIVES: &[(&str, &str, &str, NativeMethod)] = &[
+    ("java/lang/Object", "<init>", "()V", object_init),
+    ("java/lang/Object", "hashCode", "()I", object_hash_code),
+    (
+        "java/lang/Object",
+        "equals",
+        "(Ljava/lang/Object;)Z",
+        object_equals,
+    ),
+    (
+        "java/lang/Object",
+        "toString",
+        "()Ljava/lang/String;",
+        object_to_string,
+    ),
+    (
+        "java/lang/Object",
+        "clone",
+        "()Ljava/lang/Object;",
+        object_clone,
+    ),
+    ("java/lang/Object", "wait", "()V", object_wait),
+    ("java/lang/Object", "wait", "(J)V", object_wait),
+    ("java/lang/Object", "notify", "()V", object_notify),
+    ("java/lang/Object", "notifyAll", "()V", object_notify),
+    ("java/lang/System", "<clinit>", "()V", system_clinit),
+    ("java/lang/System", "exit", "(I)V", system_exit),
+    ("java/io/PrintStream", "print", "(Z)V", print_stream_print),
+    ("java/io/PrintStream", "print", "(C)V", print_stream_print),
+    ("java/io/PrintStream", "print", "(I)V", print_stream_print),
+    ("java/io/PrintStream", "print", "(J)V", print_stream_print),
+    ("java/io/PrintStream", "print", "(F)V", print_stream_print),
+    ("java/io/PrintStream", "print", "(D)V", print_stream_print),
+    ("java/io/PrintStream", "print", "([C)V", print_stream_print),
+    (
+        "java/io/PrintStream",
+        "print",
+        "(Ljava/lang/String;)V",
+        print_stream_print,
+    ),
+    (
+        "java/io/PrintStream",
+        "print",
+        "(Ljava/lang/Object;)V",
+        print_stream_print,
+    ),
+    ("java/io/PrintStream", "println", "()V", print_stream_print),
+    ("java/io/PrintStream", "println", "(Z)V", print_stream_print),
+    ("java/io/PrintStream", "println", "(C)V", print_stream_print),
+    ("java/io/PrintStream", "println", "(I)V", print_stream_print),
+    ("java/io/PrintStream", "println", "(J)V", print_stream_print),
+    ("java/io/PrintStream", "println", "(F)V", print_stream_print),
+    ("java/io/PrintStream", "println", "(D)V", print_stream_print),
+    (
+        "java/io/PrintStream",
+        "println",
+        "([C)V",
+        print_stream_print,
+    ),
+    (
+        "java/io/PrintStream",
+        "println",
+        "(Ljava/lang/String;)V",
+        print_stream_print,
+    ),
+    (
+        "java/io/PrintStream",
+        "println",
+        "(Ljava/lang/Object;)V",
+        print_stream_print,
+    ),
+    (
+        "java/io/PrintStream",
+        "printf",
+        "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/io/PrintStream;",
+        print_stream_printf,
+    ),
+    (
+        "java/io/PrintStream",
+        "format",
+        "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/io/PrintStream;",
+        print_stream_printf,
+    ),
+    (
+        "java/io/PrintStream",
+        "write",
+        "(I)V",
+        print_stream_write_byte,
+    ),
+    (
+        "java/io/PrintStream",
+        "write",
+        "([BII)V",
+        print_stream_write,
+    ),
+    ("java/io/PrintStream", "write", "([B)V", print_stream_write),
+    ("java/io/PrintStream", "flush", "()V", print_stream_flush),
+    ("java/lang/Number", "<init>", "()V", object_init),
+    ("java/lang/Number", "byteValue", "()B", box_unbox),
+    ("java/lang/Number", "shortValue", "()S", box_unbox),
+    (
+        "java/lang/Integer",
+        "valueOf",
+        "(I)Ljava/lang/Integer;",
+        box_value_of,
+    ),
+    ("java/lang/Integer", "intValue", "()I", box_unbox),
+    ("java/lang/Integer", "longValue", "()J", box_unbox),
+    ("java/lang/Integer", "floatValue", "()F", box_unbox),
+    ("java/lang/Integer", "doubleValue", "()D", box_unbox),
+    ("java/lang/Integer", "shortValue", "()S", box_unbox),
+    ("java/lang/Integer", "byteValue", "()B", box_unbox),
+    (
+        "java/lang/Integer",
+        "toString",
+        "()Ljava/lang/String;",
+        box_to_string,
+    ),
+    (
+        "java/lang/Integer",
+        "toString",
+        "(I)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    ("java/lang/Integer", "hashCode", "()I", box_hash_code),
+    (
+        "java/lang/Integer",
+        "equals",
+        "(Ljava/lang/Object;)Z",
+        box_equals,
+    ),
+    (
+        "java/lang/Integer",
+        "compareTo",
+        "(Ljava/lang/Integer;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Integer",
+        "compareTo",
+        "(Ljava/lang/Object;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Integer",
+        "parseInt",
+        "(Ljava/lang/String;)I",
+        box_parse,
+    ),
+    (
+        "java/lang/Integer",
+        "parseInt",
+        "(Ljava/lang/String;I)I",
+        box_parse,
+    ),
+    (
+        "java/lang/Integer",
+        "valueOf",
+        "(Ljava/lang/String;)Ljava/lang/Integer;",
+        box_parse,
+    ),
+    (
+        "java/lang/Integer",
+        "valueOf",
+        "(Ljava/lang/String;I)Ljava/lang/Integer;",
+        box_parse,
+    ),
+    (
+        "java/lang/Integer",
+        "toString",
+        "(II)Ljava/lang/String;",
+        box_to_string_radix,
+    ),
+    (
+        "java/lang/Integer",
+        "toHexString",
+        "(I)Ljava/lang/String;",
+        box_to_unsigned_string,
+    ),
+    (
+        "java/lang/Integer",
+        "toOctalString",
+        "(I)Ljava/lang/String;",
+        box_to_unsigned_string,
+    ),
+    (
+        "java/lang/Integer",
+        "toBinaryString",
+        "(I)Ljava/lang/String;",
+        box_to_unsigned_string,
+    ),
+    ("java/lang/Integer", "sum", "(II)I", box_arithmetic),
+    ("java/lang/Integer", "max", "(II)I", box_arithmetic),
+    ("java/lang/Integer", "min", "(II)I", box_arithmetic),
+    (
+        "java/lang/Long",
+        "valueOf",
+        "(J)Ljava/lang/Long;",
+        box_value_of,
+    ),
+    ("java/lang/Long", "intValue", "()I", box_unbox),
+    ("java/lang/Long", "longValue", "()J", box_unbox),
+    ("java/lang/Long", "floatValue", "()F", box_unbox),
+    ("java/lang/Long", "doubleValue", "()D", box_unbox),
+    ("java/lang/Long", "shortValue", "()S", box_unbox),
+    ("java/lang/Long", "byteValue", "()B", box_unbox),
+    (
+        "java/lang/Long",
+        "toString",
+        "()Ljava/lang/String;",
+        box_to_string,
+    ),
+    (
+        "java/lang/Long",
+        "toString",
+        "(J)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    ("java/lang/Long", "hashCode", "()I", box_hash_code),
+    (
+        "java/lang/Long",
+        "equals",
+        "(Ljava/lang/Object;)Z",
+        box_equals,
+    ),
+    (
+        "java/lang/Long",
+        "compareTo",
+        "(Ljava/lang/Long;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Long",
+        "compareTo",
+        "(Ljava/lang/Object;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Long",
+        "parseLong",
+        "(Ljava/lang/String;)J",
+        box_parse,
+    ),
+    (
+        "java/lang/Long",
+        "parseLong",
+        "(Ljava/lang/String;I)J",
+        box_parse,
+    ),
+    (
+        "java/lang/Long",
+        "valueOf",
+        "(Ljava/lang/String;)Ljava/lang/Long;",
+        box_parse,
+    ),
+    (
+        "java/lang/Long",
+        "valueOf",
+        "(Ljava/lang/String;I)Ljava/lang/Long;",
+        box_parse,
+    ),
+    (
+        "java/lang/Long",
+        "toString",
+        "(JI)Ljava/lang/String;",
+        box_to_string_radix,
+    ),
+    (
+        "java/lang/Long",
+        "toHexString",
+        "(J)Ljava/lang/String;",
+        box_to_unsigned_string,
+    ),
+    (
+        "java/lang/Long",
+        "toOctalString",
+        "(J)Ljava/lang/String;",
+        box_to_unsigned_string,
+    ),
+    (
+        "java/lang/Long",
+        "toBinaryString",
+        "(J)Ljava/lang/String;",
+        box_to_unsigned_string,
+    ),
+    (
+        "java/lang/Short",
+        "valueOf",
+        "(S)Ljava/lang/Short;",
+        box_value_of,
+    ),
+    ("java/lang/Short", "intValue", "()I", box_unbox),
+    ("java/lang/Short", "longValue", "()J", box_unbox),
+    ("java/lang/Short", "floatValue", "()F", box_unbox),
+    ("java/lang/Short", "doubleValue", "()D", box_unbox),
+    ("java/lang/Short", "shortValue", "()S", box_unbox),
+    ("java/lang/Short", "byteValue", "()B", box_unbox),
+    (
+        "java/lang/Short",
+        "toString",
+        "()Ljava/lang/String;",
+        box_to_string,
+    ),
+    (
+        "java/lang/Short",
+        "toString",
+        "(S)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    ("java/lang/Short", "hashCode", "()I", box_hash_code),
+    (
+        "java/lang/Short",
+        "equals",
+        "(Ljava/lang/Object;)Z",
+        box_equals,
+    ),
+    (
+        "java/lang/Short",
+        "compareTo",
+        "(Ljava/lang/Short;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Short",
+        "compareTo",
+        "(Ljava/lang/Object;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Byte",
+        "valueOf",
+        "(B)Ljava/lang/Byte;",
+        box_value_of,
+    ),
+    ("java/lang/Byte", "intValue", "()I", box_unbox),
+    ("java/lang/Byte", "longValue", "()J", box_unbox),
+    ("java/lang/Byte", "floatValue", "()F", box_unbox),
+    ("java/lang/Byte", "doubleValue", "()D", box_unbox),
+    ("java/lang/Byte", "shortValue", "()S", box_unbox),
+    ("java/lang/Byte", "byteValue", "()B", box_unbox),
+    (
+        "java/lang/Byte",
+        "toString",
+        "()Ljava/lang/String;",
+        box_to_string,
+    ),
+    (
+        "java/lang/Byte",
+        "toString",
+        "(B)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    ("java/lang/Byte", "hashCode", "()I", box_hash_code),
+    (
+        "java/lang/Byte",
+        "equals",
+        "(Ljava/lang/Object;)Z",
+        box_equals,
+    ),
+    (
+        "java/lang/Byte",
+        "compareTo",
+        "(Ljava/lang/Byte;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Byte",
+        "compareTo",
+        "(Ljava/lang/Object;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Float",
+        "valueOf",
+        "(F)Ljava/lang/Float;",
+        box_value_of,
+    ),
+    ("java/lang/Float", "intValue", "()I", box_unbox),
+    ("java/lang/Float", "longValue", "()J", box_unbox),
+    ("java/lang/Float", "floatValue", "()F", box_unbox),
+    ("java/lang/Float", "doubleValue", "()D", box_unbox),
+    ("java/lang/Float", "shortValue", "()S", box_unbox),
+    ("java/lang/Float", "byteValue", "()B", box_unbox),
+    (
+        "java/lang/Float",
+        "toString",
+        "()Ljava/lang/String;",
+        box_to_string,
+    ),
+    (
+        "java/lang/Float",
+        "toString",
+        "(F)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    ("java/lang/Float", "hashCode", "()I", box_hash_code),
+    (
+        "java/lang/Float",
+        "equals",
+        "(Ljava/lang/Object;)Z",
+        box_equals,
+    ),
+    (
+        "java/lang/Float",
+        "compareTo",
+        "(Ljava/lang/Float;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Float",
+        "compareTo",
+        "(Ljava/lang/Object;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Float",
+        "parseFloat",
+        "(Ljava/lang/String;)F",
+        box_parse_floating,
+    ),
+    (
+        "java/lang/Float",
+        "valueOf",
+        "(Ljava/lang/String;)Ljava/lang/Float;",
+        box_parse_floating,
+    ),
+    (
+        "java/lang/Double",
+        "valueOf",
+        "(D)Ljava/lang/Double;",
+        box_value_of,
+    ),
+    ("java/lang/Double", "intValue", "()I", box_unbox),
+    ("java/lang/Double", "longValue", "()J", box_unbox),
+    ("java/lang/Double", "floatValue", "()F", box_unbox),
+    ("java/lang/Double", "doubleValue", "()D", box_unbox),
+    ("java/lang/Double", "shortValue", "()S", box_unbox),
+    ("java/lang/Double", "byteValue", "()B", box_unbox),
+    (
+        "java/lang/Double",
+        "toString",
+        "()Ljava/lang/String;",
+        box_to_string,
+    ),
+    (
+        "java/lang/Double",
+        "toString",
+        "(D)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    ("java/lang/Double", "hashCode", "()I", box_hash_code),
+    (
+        "java/lang/Double",
+        "equals",
+        "(Ljava/lang/Object;)Z",
+        box_equals,
+    ),
+    (
+        "java/lang/Double",
+        "compareTo",
+        "(Ljava/lang/Double;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Double",
+        "compareTo",
+        "(Ljava/lang/Object;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Double",
+        "parseDouble",
+        "(Ljava/lang/String;)D",
+        box_parse_floating,
+    ),
+    (
+        "java/lang/Double",
+        "valueOf",
+        "(Ljava/lang/String;)Ljava/lang/Double;",
+        box_parse_floating,
+    ),
+    ("java/lang/Boolean", "<clinit>", "()V", boolean_clinit),
+    (
+        "java/lang/Boolean",
+        "valueOf",
+        "(Z)Ljava/lang/Boolean;",
+        box_value_of,
+    ),
+    ("java/lang/Boolean", "booleanValue", "()Z", box_unbox),
+    (
+        "java/lang/Boolean",
+        "toString",
+        "()Ljava/lang/String;",
+        box_to_string,
+    ),
+    (
+        "java/lang/Boolean",
+        "toString",
+        "(Z)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    ("java/lang/Boolean", "hashCode", "()I", box_hash_code),
+    (
+        "java/lang/Boolean",
+        "equals",
+        "(Ljava/lang/Object;)Z",
+        box_equals,
+    ),
+    (
+        "java/lang/Boolean",
+        "compareTo",
+        "(Ljava/lang/Boolean;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Boolean",
+        "compareTo",
+        "(Ljava/lang/Object;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Character",
+        "valueOf",
+        "(C)Ljava/lang/Character;",
+        box_value_of,
+    ),
+    ("java/lang/Character", "charValue", "()C", box_unbox),
+    (
+        "java/lang/Character",
+        "toString",
+        "()Ljava/lang/String;",
+        box_to_string,
+    ),
+    (
+        "java/lang/Character",
+        "toString",
+        "(C)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    ("java/lang/Character", "hashCode", "()I", box_hash_code),
+    (
+        "java/lang/Character",
+        "equals",
+        "(Ljava/lang/Object;)Z",
+        box_equals,
+    ),
+    (
+        "java/lang/Character",
+        "compareTo",
+        "(Ljava/lang/Character;)I",
+        box_compare_to,
+    ),
+    (
+        "java/lang/Character",
+        "compareTo",
+        "(Ljava/lang/Object;)I",
+        box_compare_to,
+    ),
+    (
+        "java/util/Objects",
+        "requireNonNull",
+        "(Ljava/lang/Object;)Ljava/lang/Object;",
+        objects_require_non_null,
+    ),
+    (
+        "java/util/Objects",
+        "requireNonNull",
+        "(Ljava/lang/Object;Ljava/lang/String;)Ljava/lang/Object;",
+        objects_require_non_null,
+    ),
+    ("java/lang/Throwable", "<init>", "()V", throwable_init),
+    (
+        "java/lang/Throwable",
+        "<init>",
+        "(Ljava/lang/String;)V",
+        throwable_init,
+    ),
+    (
+        "java/lang/Throwable",
+        "<init>",
+        "(Ljava/lang/String;Ljava/lang/Throwable;)V",
+        throwable_init,
+    ),
+    (
+        "java/lang/Throwable",
+        "<init>",
+        "(Ljava/lang/Throwable;)V",
+        throwable_init_with_cause,
+    ),
+    (
+        "java/lang/Throwable",
+        "getMessage",
+        "()Ljava/lang/String;",
+        throwable_get_message,
+    ),
+    (
+        "java/lang/Throwable",
+        "getLocalizedMessage",
+        "()Ljava/lang/String;",
+        throwable_get_message,
+    ),
+    (
+        "java/lang/Throwable",
+        "getCause",
+        "()Ljava/lang/Throwable;",
+        throwable_get_cause,
+    ),
+    (
+        "java/lang/Throwable",
+        "initCause",
+        "(Ljava/lang/Throwable;)Ljava/lang/Throwable;",
+        throwable_init_cause,
+    ),
+    (
+        "java/lang/Throwable",
+        "fillInStackTrace",
+        "()Ljava/lang/Throwable;",
+        returns_this,
+    ),
+    (
+        "java/lang/Throwable",
+        "toString",
+        "()Ljava/lang/String;",
+        throwable_to_string_native,
+    ),
+    (
+        "java/lang/Throwable",
+        "printStackTrace",
+        "()V",
+        throwable_print_stack_trace,
+    ),
+    (
+        "java/lang/String",
+        "equals",
+        "(Ljava/lang/Object;)Z",
+        string_equals,
+    ),
+    ("java/lang/String", "hashCode", "()I", string_hash_code),
+    (
+        "java/lang/String",
+        "toString",
+        "()Ljava/lang/String;",
+        returns_this,
+    ),
+    (
+        "java/lang/String",
+        "intern",
+        "()Ljava/lang/String;",
+        string_intern,
+    ),
+    ("java/lang/String", "length", "()I", string_length),
+    ("java/lang/String", "isEmpty", "()Z", string_is_empty),
+    ("java/lang/String", "charAt", "(I)C", string_char_at),
+    (
+        "java/lang/String",
+        "substring",
+        "(I)Ljava/lang/String;",
+        string_substring,
+    ),
+    (
+        "java/lang/String",
+        "substring",
+        "(II)Ljava/lang/String;",
+        string_substring,
+    ),
+    ("java/lang/String", "indexOf", "(I)I", string_index_of_char),
+    ("java/lang/String", "indexOf", "(II)I", string_index_of_char),
+    (
+        "java/lang/String",
+        "indexOf",
+        "(Ljava/lang/String;)I",
+        string_index_of,
+    ),
+    (
+        "java/lang/String",
+        "indexOf",
+        "(Ljava/lang/String;I)I",
+        string_index_of,
+    ),
+    (
+        "java/lang/String",
+        "compareTo",
+        "(Ljava/lang/String;)I",
+        string_compare_to,
+    ),
+    (
+        "java/lang/String",
+        "compareTo",
+        "(Ljava/lang/Object;)I",
+        string_compare_to,
+    ),
+    (
+        "java/lang/String",
+        "split",
+        "(Ljava/lang/String;)[Ljava/lang/String;",
+        string_split,
+    ),
+    (
+        "java/lang/String",
+        "split",
+        "(Ljava/lang/String;I)[Ljava/lang/String;",
+        string_split,
+    ),
+    (
+        "java/lang/String",
+        "toUpperCase",
+        "()Ljava/lang/String;",
+        string_to_upper_case,
+    ),
+    (
+        "java/lang/String",
+        "toLowerCase",
+        "()Ljava/lang/String;",
+        string_to_lower_case,
+    ),
+    (
+        "java/lang/String",
+        "valueOf",
+        "(I)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    (
+        "java/lang/String",
+        "valueOf",
+        "(J)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    (
+        "java/lang/String",
+        "valueOf",
+        "(F)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    (
+        "java/lang/String",
+        "valueOf",
+        "(D)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    (
+        "java/lang/String",
+        "valueOf",
+        "(Z)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    (
+        "java/lang/String",
+        "valueOf",
+        "(C)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    (
+        "java/lang/String",
+        "valueOf",
+        "([C)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    (
+        "java/lang/String",
+        "valueOf",
+        "(Ljava/lang/Object;)Ljava/lang/String;",
+        string_value_of_native,
+    ),
+    (
+        "java/lang/String",
+        "format",
+        "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/lang/String;",
+        string_format,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "<init>",
+        "()V",
+        string_builder_init,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "<init>",
+        "(I)V",
+        string_builder_init,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "<init>",
+        "(Ljava/lang/String;)V",
+        string_builder_init_with_string,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "append",
+        "(Ljava/lang/String;)Ljava/lang/StringBuilder;",
+        string_builder_append,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "append",
+        "(Ljava/lang/CharSequence;)Ljava/lang/StringBuilder;",
+        string_builder_append,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "append",
+        "(Ljava/lang/Object;)Ljava/lang/StringBuilder;",
+        string_builder_append,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "append",
+        "([C)Ljava/lang/StringBuilder;",
+        string_builder_append,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "append",
+        "(I)Ljava/lang/StringBuilder;",
+        string_builder_append,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "append",
+        "(J)Ljava/lang/StringBuilder;",
+        string_builder_append,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "append",
+        "(F)Ljava/lang/StringBuilder;",
+        string_builder_append,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "append",
+        "(D)Ljava/lang/StringBuilder;",
+        string_builder_append,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "append",
+        "(Z)Ljava/lang/StringBuilder;",
+        string_builder_append,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "append",
+        "(C)Ljava/lang/StringBuilder;",
+        string_builder_append,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "insert",
+        "(ILjava/lang/String;)Ljava/lang/StringBuilder;",
+        string_builder_insert,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "insert",
+        "(ILjava/lang/CharSequence;)Ljava/lang/StringBuilder;",
+        string_builder_insert,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "insert",
+        "(ILjava/lang/Object;)Ljava/lang/StringBuilder;",
+        string_builder_insert,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "insert",
+        "(I[C)Ljava/lang/StringBuilder;",
+        string_builder_insert,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "insert",
+        "(II)Ljava/lang/StringBuilder;",
+        string_builder_insert,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "insert",
+        "(IJ)Ljava/lang/StringBuilder;",
+        string_builder_insert,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "insert",
+        "(IF)Ljava/lang/StringBuilder;",
+        string_builder_insert,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "insert",
+        "(ID)Ljava/lang/StringBuilder;",
+        string_builder_insert,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "insert",
+        "(IZ)Ljava/lang/StringBuilder;",
+        string_builder_insert,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "insert",
+        "(IC)Ljava/lang/StringBuilder;",
+        string_builder_insert,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "reverse",
+        "()Ljava/lang/StringBuilder;",
+        string_builder_reverse,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "length",
+        "()I",
+        string_builder_length,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "charAt",
+        "(I)C",
+        string_builder_char_at,
+    ),
+    (
+        "java/lang/StringBuilder",
+        "toString",
+        "()Ljava/lang/String;",
+        string_builder_to_string,
+    ),
+    ("java/lang/Thread", "<init>", "()V", thread_init),
+    (
+        "java/lang/Thread",
+        "<init>",
+        "(Ljava/lang/Runnable;)V",
+        thread_init,
+    ),
+    (
+        "java/lang/Thread",
+        "<init>",
+        "(Ljava/lang/Runnable;Ljava/lang/String;)V",
+        thread_init,
+    ),
+    (
+        "java/lang/Thread",
+        "<init>",
+        "(Ljava/lang/String;)V",
+        thread_init,
+    ),
+    ("java/lang/Thread", "start", "()V", thread_start),
+    ("java/lang/Thread", "run", "()V", thread_run),
+    ("java/lang/Thread", "join", "()V", thread_join),
+    ("java/lang/Thread", "join", "(J)V", thread_join),
+    ("java/lang/Thread", "isAlive", "()Z", thread_is_alive),
+    (
+        "java/lang/Thread",
+        "getName",
+        "()Ljava/lang/String;",
+        thread_get_name,
+    ),
+    (
+        "java/lang/Thread",
+        "setName",
+        "(Ljava/lang/String;)V",
+        thread_set_name,
+    ),
+    ("java/lang/Thread", "getId", "()J", thread_get_id),
+    ("java/lang/Thread", "isDaemon", "()Z", thread_is_daemon),
+    ("java/lang/Thread", "setDaemon", "(Z)V", thread_set_daemon),
+    (
+        "java/lang/Thread",
+        "getPriority",
+        "()I",
+        thread_get_priority,
+    ),
+    (
+        "java/lang/Thread",
+        "setPriority",
+        "(I)V",
+        thread_set_priority,
+    ),
+    (
+        "java/lang/Thread",
+        "toString",
+        "()Ljava/lang/String;",
+        thread_to_string,
+    ),
+    (
+        "java/lang/Thread",
+        "currentThread",
+        "()Ljava/lang/Thread;",
+        thread_current_thread,
+    ),
+    ("java/lang/Thread", "sleep", "(J)V", thread_sleep),
+    ("java/lang/Thread", "yield", "()V", thread_yield),
+];
+
+/// Completes a native method normally with the given value, `None` for void methods
+fn returns(value: Option<Value>) -> NativeResult {
+    Ok(InstructionCompleted::ReturnFromMethod(value))
+}
+
+/// Native of the methods returning their receiver, like `String.toString()`
+fn returns_this(
+    _: &Method,
+    args: &[Value],
+    _: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    returns(Some(args[0].clone()))
+}
+
+fn object_init(
+    _: &Method,
+    _: &[Value],
+    _: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    returns(None)
+}
+
+fn object_hash_code(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let hash_code = identity_hash_code(&runtime_data_area.heap, &args[0]);
+    returns(Some(Value::Int(hash_code)))
+}
+
+fn object_equals(
+    _: &Method,
+    args: &[Value],
+    _: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let equal = args[0].same_reference(&args[1]);
+    returns(Some(Value::Int(equal as i32)))
+}
+
+fn object_to_string(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+    let class_name = match &args[0] {
+        Value::Object(handle) => heap.object(*handle).class.name.replace('/', "."),
+        Value::Array(handle) => array::external_array_name(&heap.array(*handle).descriptor),
+        _ => "java.lang.Object".to_string(),
+    };
+
+    let description = format!("{}@{:x}", class_name, identity_hash_code(heap, &args[0]));
+    returns(Some(
+        runtime_data_area.new_string_from(&description, call_stack)?,
+    ))
+}
+
+fn object_clone(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+
+    let copy = match &args[0] {
+        // AS SPECIFIED BY THE SPECS: arrays are cloned with a shallow copy of their components
+        Value::Array(handle) => {
+            let array = heap.array(*handle);
+            let copy = Array {
+                descriptor: array.descriptor.clone(),
+                elements: array.elements.clone(),
+            };
+            runtime_data_area.allocate_array(copy, call_stack)?
+        }
+        Value::Object(handle) => {
+            let object = heap.object(*handle);
+
+            if !object.class.is_subtype_of("java/lang/Cloneable") {
+                return Ok(InstructionCompleted::ThrowNewException(
+                    "java/lang/CloneNotSupportedException",
+                    Some(object.class.name.replace('/', ".")),
+                ));
+            }
+
+            let copy = Object {
+                class: object.class.clone(),
+                fields: object.fields.clone(),
+            };
+            runtime_data_area.allocate_object(copy, call_stack)?
+        }
+        other => return Err(format!("clone: expected object reference, got {:?}", other).into()),
+    };
+
+    returns(Some(copy))
+}
+
+fn object_wait(
+    _: &Method,
+    args: &[Value],
+    _: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let timeout = match args.get(1) {
+        None => None,
+        Some(&Value::Long(millis)) if millis < 0 => {
+            return Ok(InstructionCompleted::ThrowNewException(
+                "java/lang/IllegalArgumentException",
+                Some("timeout value is negative".to_string()),
+            ));
+        }
+        // A timeout of zero waits until notified
+        Some(&Value::Long(millis)) => (millis > 0).then_some(millis as u64),
+        Some(other) => return Err(format!("Expected a long argument, got {:?}", other).into()),
+    };
+
+    // AS SPECIFIED BY THE SPECS: only the owner of the monitor may wait on the object
+    match thread::wait(&args[0], timeout, call_stack) {
+        true => returns(None),
+        false => Ok(not_monitor_owner()),
+    }
+}
+
+fn object_notify(
+    method: &Method,
+    args: &[Value],
+    _: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let all = method.name == "notifyAll";
+
+    match thread::notify(&args[0], all, call_stack) {
+        true => returns(None),
+        false => Ok(not_monitor_owner()),
+    }
+}
+
+/// Initializes `java/lang/System`, creating its standard streams
+fn system_clinit(
+    _: &Method,
+    _: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let class = runtime_data_area
+        .class_loader
+        .load_class("java/io/PrintStream")?;
+
+    for (key, fd) in [(SYSTEM_OUT, console::STDOUT), (SYSTEM_ERR, console::STDERR)] {
+        let mut stream = Object::new(class.clone());
+        stream
+            .fields
+            .insert(PRINT_STREAM_FD.to_string(), Value::Int(fd));
+
+        let stream = runtime_data_area.allocate_object(stream, call_stack)?;
+        runtime_data_area
+            .static_fields
+            .insert(key.to_string(), stream);
+    }
+
+    returns(None)
+}
+
+/// Terminates the VM right away with the given status, whatever the other threads are doing
+/// and without unwinding the frames, so that no `finally` block runs
+fn system_exit(
+    _: &Method,
+    args: &[Value],
+    _: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let status = match args[0] {
+        Value::Int(status) => status,
+        ref other => return Err(format!("Expected an int argument, got {:?}", other).into()),
+    };
+
+    // Like HotSpot, what `write(int)` left in the buffer of a standard stream is lost
+    let _ = io::stdout().flush();
+    process::exit(status);
+}
+
+/// The standard stream a `PrintStream` writes to
+fn print_stream_fd(heap: &Heap, this: &Value) -> i32 {
+    match get_field(heap, this, PRINT_STREAM_FD) {
+        Value::Int(fd) => fd,
+        _ => console::STDOUT,
+    }
+}
+
+/// Writes the string `String.valueOf` gives for the argument to the stream, followed by a
+/// line separator for `println`, the overload is selected by the descriptor so that chars,
+/// booleans and char arrays are not printed as numbers or objects
+fn print_stream_print(
+    method: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let mut chars = match descriptor::parameter_types(&method.descriptor).first() {
+        Some(descriptor) => {
+            match string_value_of(descriptor, &args[1], runtime_data_area, call_stack)? {
+                Ok(chars) => chars,
+                Err(thrown) => return Ok(thrown),
+            }
+        }
+        None => Vec::new(),
+    };
+
+    if method.name == "println" {
+        chars.push('\n' as u16);
+    }
+
+    let fd = print_stream_fd(&runtime_data_area.heap, &args[0]);
+    runtime_data_area
+        .console
+        .print(fd, &console::encode(&chars));
+    returns(None)
+}
+
+/// `printf` and `format`, the text formatted before a failing format specifier is written
+/// too since `Formatter` appends to the stream as it goes
+fn print_stream_printf(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let (formatted, thrown) = format(&args[1], &args[2], runtime_data_area, call_stack)?;
+
+    if !formatted.is_empty() {
+        let fd = print_stream_fd(&runtime_data_area.heap, &args[0]);
+        runtime_data_area
+            .console
+            .print(fd, &console::encode(&formatted));
+    }
+
+    match thrown {
+        Some(thrown) => Ok(thrown),
+        None => returns(Some(args[0].clone())),
+    }
+}
+
+/// `write(int)`, writes the low eight bits of the argument to the buffer of the stream,
+/// which is flushed when the byte is a line feed
+fn print_stream_write_byte(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let byte = int_arg(args, 1)?;
+    let fd = print_stream_fd(&runtime_data_area.heap, &args[0]);
+
+    runtime_data_area.console.write(fd, &[byte as u8]);
+    if byte == '\n' as i32 {
+        runtime_data_area.console.flush(fd);
+    }
+    returns(None)
+}
+
+/// `write(byte[])` and `write(byte[], int, int)`, the bytes are written right away
+fn print_stream_write(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+
+    let bytes = match &args[1] {
+        Value::Array(handle) => match &heap.array(*handle).elements {
+            ArrayElements::Byte(bytes) => bytes,
+            _ => return Err(format!("Expected a byte array, got {:?}", args[1]).into()),
+        },
+        _ => return Ok(null_pointer_exception()),
+    };
+
+    let (offset, length) = match args.len() {
+        4 => (int_arg(args, 2)?, int_arg(args, 3)?),
+        _ => (0, bytes.len() as i32),
+    };
+
+    // Short writes are copied to the buffer of the stream with System.arraycopy, longer
+    // ones are written straight to the file which checks the bounds itself
+    let length_of = bytes.len();
+    let out_of_bounds = match (offset, length) {
+        _ if offset >= 0 && length >= 0 && offset as i64 + length as i64 <= length_of as i64 => {
+            None
+        }
+        _ if length >= console::BUFFER_CAPACITY as i32 => Some(None),
+        (offset, _) if offset < 0 => Some(Some(format!(
+            "arraycopy: source index {} out of bounds for byte[{}]",
+            offset, length_of
+        ))),
+        (_, length) if length < 0 => {
+            Some(Some(format!("arraycopy: length {} is negative", length)))
+        }
+        (offset, length) => Some(Some(format!(
+            "arraycopy: last source index {} out of bounds for byte[{}]",
+            offset + length,
+            length_of
+        ))),
+    };
+
+    if let Some(message) = out_of_bounds {
+        let class_name = match message {
+            Some(_) => "java/lang/ArrayIndexOutOfBoundsException",
+            None => "java/lang/IndexOutOfBoundsException",
+        };
+        return Ok(InstructionCompleted::ThrowNewException(class_name, message));
+    }
+
+    let bytes: Vec<u8> = bytes[offset as usize..(offset + length) as usize]
+        .iter()
+        .map(|byte| *byte as u8)
+        .collect();
+    let fd = print_stream_fd(heap, &args[0]);
+    runtime_data_area.console.print(fd, &bytes);
+    returns(None)
+}
+
+fn print_stream_flush(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let fd = print_stream_fd(&runtime_data_area.heap, &args[0]);
+    runtime_data_area.console.flush(fd);
+    returns(None)
+}
+
+/// Formats the arguments of `String.format` and `PrintStream.printf` with the given format,
+/// returns the text formatted so far along with the exception thrown by the format
+/// specifier or the `toString()` method that failed, if any
+fn format(
+    format: &Value,
+    arguments: &Value,
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> Result<(Vec<u16>, Option<InstructionCompleted>), VmError> {
+    let heap = &runtime_data_area.heap;
+
+    let Some(format) = string::string_chars(heap, format).map(<[u16]>::to_vec) else {
+        return Ok((Vec::new(), Some(null_pointer_exception())));
+    };
+    // A null array passes null to every format specifier
+    let arguments = match arguments {
+        Value::Array(handle) => Some(heap.array(*handle).references().to_vec()),
+        _ => None,
+    };
+
+    let elements = match formatter::parse(&format) {
+        Ok(elements) => elements,
+        Err(error) => return Ok((Vec::new(), Some(format_exception(error)?))),
+    };
+
+    let mut formatted = Vec::new();
+    let mut ordinary = 0;
+    let mut last = None;
+
+    for element in elements {
+        let specifier = match element {
+            FormatElement::Text(text) => {
+                formatted.extend(text);
+                continue;
+            }
+            FormatElement::Specifier(specifier) => specifier,
+        };
+
+        let mut argument = Argument::Null;
+
+        if specifier.takes_argument() {
+            let index = match specifier.index {
+                ArgumentIndex::Ordinary => {
+                    ordinary += 1;
+                    Some(ordinary - 1)
+                }
+                ArgumentIndex::Explicit(index) => Some(index - 1),
+                ArgumentIndex::Previous => last,
+            };
+            last = index;
+
+            let value = match (index, &arguments) {
+                (Some(_), None) => Value::Null,
+                (Some(index), Some(arguments)) if index < arguments.len() => {
+                    arguments[index].clone()
+                }
+                _ => {
+                    let missing = format_exception(specifier.missing_argument())?;
+                    return Ok((formatted, Some(missing)));
+                }
+            };
+
+            argument = match (specifier.conversion, &value) {
+                (_, Value::Null) => Argument::Null,
+                ('s', value) => {
+                    let descriptor = "Ljava/lang/Object;";
+                    match string_value_of(descriptor, value, runtime_data_area, call_stack)? {
+                        Ok(chars) => Argument::Text(chars),
+                        Err(thrown) => return Ok((formatted, Some(thrown))),
+                    }
+                }
+                ('h', value) => {
+                    match invoke_virtual(value, "hashCode", "()I", runtime_data_area, call_stack)? {
+                        Ok(Some(Value::Int(hash_code))) => Argument::Int(hash_code),
+                        Ok(other) => return Err(format!("hashCode() returned {:?}", other).into()),
+                        Err(thrown) => return Ok((formatted, Some(thrown))),
+                    }
+                }
+                (_, value) => format_argument(&runtime_data_area.heap, value),
+            };
+        }
+
+        match specifier.format(&argument) {
+            Ok(text) => formatted.extend(text),
+            Err(error) => return Ok((formatted, Some(format_exception(error)?))),
+        }
+    }
+
+    Ok((formatted, None))
+}
+
+/// Converts an argument of a format to what the formatter needs to know about it,
+/// boxed primitives are unboxed
+fn format_argument(heap: &Heap, value: &Value) -> Argument {
+    let class_name = match value {
+        Value::Object(handle) => heap.object(*handle).class.name.clone(),
+        Value::Array(handle) => heap.array(*handle).descriptor.clone(),
+        _ => return Argument::Null,
+    };
+
+    match (boxed_type(&class_name), boxed_value(heap, value)) {
+        (Some("Z"), Value::Int(value)) => Argument::Boolean(value != 0),
+        (Some("C"), Value::Int(value)) => Argument::Char(value as u16),
+        (Some("B"), Value::Int(value)) => Argument::Byte(value as i8),
+        (Some("S"), Value::Int(value)) => Argument::Short(value as i16),
+        (Some("I"), Value::Int(value)) => Argument::Int(value),
+        (Some("J"), Value::Long(value)) => Argument::Long(value),
+        (Some("F"), Value::Float(value)) => Argument::Float(value),
+        (Some("D"), Value::Double(value)) => Argument::Double(value),
+        _ => Argument::Object(class_name.replace('/', ".")),
+    }
+}
+
+/// Throws the `java.util` exception of a format error, unsupported formats fail the VM
+fn format_exception(error: FormatError) -> Result<InstructionCompleted, VmError> {
+    match error {
+        FormatError::Exception(class_name, message) => Ok(InstructionCompleted::ThrowNewException(
+            class_name,
+            Some(message),
+        )),
+        FormatError::Unsupported(message) => Err(message.into()),
+    }
+}
+
+fn objects_require_non_null(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    match (&args[0], args.get(1)) {
+        (Value::Null, None) => Ok(null_pointer_exception()),
+        (Value::Null, Some(message)) => Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/NullPointerException",
+            string::rust_string(&runtime_data_area.heap, message),
+        )),
+        (object, _) => returns(Some(object.clone())),
+    }
+}
+
+/// Constructors of `Throwable` taking an optional message and an optional cause
+fn throwable_init(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let heap = &mut runtime_data_area.heap;
+
+    if let Some(message) = args.get(1) {
+        set_field(heap, &args[0], THROWABLE_DETAIL_MESSAGE, message.clone());
+    }
+    if let Some(cause) = args.get(2) {
+        set_field(heap, &args[0], THROWABLE_CAUSE, cause.clone());
+    }
+
+    returns(None)
+}
+
+fn throwable_init_with_cause(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    // The message of a wrapping throwable is the description of its cause
+    let message = match &args[1] {
+        Value::Null => Value::Null,
+        cause => {
+            let description = throwable_to_string(&runtime_data_area.heap, cause);
+            runtime_data_area.new_string_from(&description, call_stack)?
+        }
+    };
+
+    let heap = &mut runtime_data_area.heap;
+    set_field(heap, &args[0], THROWABLE_DETAIL_MESSAGE, message);
+    set_field(heap, &args[0], THROWABLE_CAUSE, args[1].clone());
+    returns(None)
+}
+
+fn throwable_get_message(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+    returns(Some(get_field(heap, &args[0], THROWABLE_DETAIL_MESSAGE)))
+}
+
+fn throwable_get_cause(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    returns(Some(get_field(
+        &runtime_data_area.heap,
+        &args[0],
+        THROWABLE_CAUSE,
+    )))
+}
+
+fn throwable_init_cause(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let heap = &mut runtime_data_area.heap;
+    set_field(heap, &args[0], THROWABLE_CAUSE, args[1].clone());
+    returns(Some(args[0].clone()))
+}
+
+fn throwable_to_string_native(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let description = throwable_to_string(&runtime_data_area.heap, &args[0]);
+    returns(Some(
+        runtime_data_area.new_string_from(&description, call_stack)?,
+    ))
+}
+
+fn throwable_print_stack_trace(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+    let chain = cause_chain(heap, &args[0]);
+    let stack_trace = format_stack_trace(&chain, |throwable| throwable_to_string(heap, throwable));
+
+    runtime_data_area
+        .console
+        .print(console::STDERR, stack_trace.as_bytes());
+    returns(None)
+}
+
+fn string_equals(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+    let equal = match (
+        string::string_chars(heap, &args[0]),
+        string::string_chars(heap, &args[1]),
+    ) {
+        (Some(this), Some(other)) => this == other,
+        _ => false,
+    };
+    returns(Some(Value::Int(equal as i32)))
+}
+
+fn string_hash_code(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let chars = string::string_chars(&runtime_data_area.heap, &args[0]).unwrap_or_default();
+    returns(Some(Value::Int(string::hash_code(chars))))
+}
+
+fn string_intern(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let chars = string_contents(&runtime_data_area.heap, &args[0])?;
+    returns(Some(runtime_data_area.intern_string(chars, call_stack)?))
+}
+
+fn string_length(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let length = string_contents(&runtime_data_area.heap, &args[0])?.len();
+    returns(Some(Value::Int(length as i32)))
+}
+
+fn string_is_empty(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let empty = string_contents(&runtime_data_area.heap, &args[0])?.is_empty();
+    returns(Some(Value::Int(empty as i32)))
+}
+
+fn string_char_at(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let chars = string_contents(&runtime_data_area.heap, &args[0])?;
+    let index = int_arg(args, 1)?;
+
+    match usize::try_from(index)
+        .ok()
+        .and_then(|index| chars.get(index))
+    {
+        Some(&c) => returns(Some(Value::Int(c as i32))),
+        None => Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/StringIndexOutOfBoundsException",
+            Some(format!("String index out of range: {}", index)),
+        )),
+    }
+}
+
+fn string_substring(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let chars = string_contents(&runtime_data_area.heap, &args[0])?;
+    let length = chars.len() as i32;
+    let begin = int_arg(args, 1)?;
+    let end = match args.len() {
+        3 => int_arg(args, 2)?,
+        _ => length,
+    };
+
+    if begin < 0 || begin > end || end > length {
+        return Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/StringIndexOutOfBoundsException",
+            Some(format!("begin {}, end {}, length {}", begin, end, length)),
+        ));
+    }
+
+    // The whole string is the string itself
+    if begin == 0 && end == length {
+        returns(Some(args[0].clone()))
+    } else {
+        let substring = chars[begin as usize..end as usize].to_vec();
+        returns(Some(runtime_data_area.new_string(substring, call_stack)?))
+    }
+}
+
+fn string_index_of_char(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let chars = string_contents(&runtime_data_area.heap, &args[0])?;
+    let from = if args.len() == 3 {
+        int_arg(args, 2)?
+    } else {
+        0
+    };
+    let index = string::index_of_code_point(&chars, int_arg(args, 1)?, from);
+    returns(Some(Value::Int(index)))
+}
+
+fn string_index_of(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+    let chars = string_contents(heap, &args[0])?;
+    let Some(target) = string::string_chars(heap, &args[1]) else {
+        return Ok(null_pointer_exception());
+    };
+    let from = if args.len() == 3 {
+        int_arg(args, 2)?
+    } else {
+        0
+    };
+    returns(Some(Value::Int(string::index_of(&chars, target, from))))
+}
+
+fn string_compare_to(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+    let chars = string_contents(heap, &args[0])?;
+
+    match (&args[1], string::string_chars(heap, &args[1])) {
+        (_, Some(other)) => returns(Some(Value::Int(string::compare(&chars, other)))),
+        (Value::Null, _) => Ok(null_pointer_exception()),
+        // Only reachable through the Comparable bridge method
+        (other, None) => Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/ClassCastException",
+            Some(class_cast_message(
+                &type_name_of(heap, other),
+                string::STRING_CLASS,
+            )),
+        )),
+    }
+}
+
+fn string_split(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+    let chars = string_contents(heap, &args[0])?;
+    let Some(pattern) = string::string_chars(heap, &args[1]) else {
+        return Ok(null_pointer_exception());
+    };
+    let limit = if args.len() == 3 {
+        int_arg(args, 2)?
+    } else {
+        0
+    };
+
+    let regex = match Regex::new(pattern) {
+        Ok(regex) => regex,
+        Err(description) => {
+            return Ok(InstructionCompleted::ThrowNewException(
+                "java/util/regex/PatternSyntaxException",
+                Some(description),
+            ));
+        }
+    };
+
+    // Each part is only reachable from here until the array holds it
+    let mut parts = Vec::new();
+    let mut failure = None;
+
+    for part in regex.split(&chars, limit) {
+        match runtime_data_area.new_string(part, call_stack) {
+            Ok(part) => {
+                runtime_data_area.heap.pin(part.clone());
+                parts.push(part);
+            }
+            Err(error) => {
+                failure = Some(error);
+                break;
+            }
+        }
+    }
+
+    let array = match failure {
+        Some(error) => Err(error),
+        None => runtime_data_area.allocate_array(
+            Array::from_elements("[Ljava/lang/String;".to_string(), parts.clone()),
+            call_stack,
+        ),
+    };
+
+    for part in parts.iter().rev() {
+        runtime_data_area.heap.unpin(part);
+    }
+
+    returns(Some(array?))
+}
+
+fn string_to_upper_case(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let chars = string_contents(&runtime_data_area.heap, &args[0])?;
+    converted_string(
+        &args[0],
+        &chars,
+        string::to_upper_case(&chars),
+        runtime_data_area,
+        call_stack,
+    )
+}
+
+fn string_to_lower_case(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let chars = string_contents(&runtime_data_area.heap, &args[0])?;
+    converted_string(
+        &args[0],
+        &chars,
+        string::to_lower_case(&chars),
+        runtime_data_area,
+        call_stack,
+    )
+}
+
+/// Returns the result of a case conversion, a string without any character to convert
+/// is returned as is
+fn converted_string(
+    this: &Value,
+    chars: &[u16],
+    converted: Vec<u16>,
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &CallStack,
+) -> NativeResult {
+    if converted == chars {
+        returns(Some(this.clone()))
+    } else {
+        returns(Some(runtime_data_area.new_string(converted, call_stack)?))
+    }
+}
+
+fn string_value_of_native(
+    method: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let chars = match string_value_of(
+        descriptor::parameter_types(&method.descriptor)[0],
+        &args[0],
+        runtime_data_area,
+        call_stack,
+    )? {
+        Ok(chars) => chars,
+        Err(thrown) => return Ok(thrown),
+    };
+    returns(Some(runtime_data_area.new_string(chars, call_stack)?))
+}
+
+fn string_format(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    match format(&args[0], &args[1], runtime_data_area, call_stack)? {
+        (_, Some(thrown)) => Ok(thrown),
+        (formatted, None) => returns(Some(runtime_data_area.new_string(formatted, call_stack)?)),
+    }
+}
+
+/// Initializes `java/lang/Boolean`, whose `TRUE` and `FALSE` are the instances `valueOf` returns
+fn boolean_clinit(
+    _: &Method,
+    _: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    for (name, value) in [("TRUE", 1), ("FALSE", 0)] {
+        let boxed = box_value(
+            runtime_data_area,
+            "java/lang/Boolean",
+            Value::Int(value),
+            call_stack,
+        )?;
+        runtime_data_area
+            .static_fields
+            .insert(format!("java/lang/Boolean.{}", name), boxed);
+    }
+    returns(None)
+}
+
+/// `valueOf` of the box classes, like `Integer.valueOf(int)` used by autoboxing
+fn box_value_of(
+    method: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let boxed = box_value(
+        runtime_data_area,
+        &method.class_name,
+        args[0].clone(),
+        call_stack,
+    )?;
+    returns(Some(boxed))
+}
+
+/// The `xxxValue()` methods of the box classes, the boxed value is converted to the return
+/// type with the same conversion as a cast, e.g. `Double.intValue()` like `(int) d`
+fn box_unbox(
+    method: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let value = boxed_value(&runtime_data_area.heap, &args[0]);
+    let return_type = descriptor::return_type(&method.descriptor);
+
+    let int = match value {
+        Value::Int(value) => value,
+        Value::Long(value) => value as i32,
+        Value::Float(value) => value as i32,
+        Value::Double(value) => value as i32,
+        other => return Err(format!("Expected a boxed primitive, got {:?}", other).into()),
+    };
+
+    let converted = match (return_type, value) {
+        ("J", Value::Long(value)) => Value::Long(value),
+        ("J", Value::Float(value)) => Value::Long(value as i64),
+        ("J", Value::Double(value)) => Value::Long(value as i64),
+        ("J", _) => Value::Long(int as i64),
+        ("F", Value::Long(value)) => Value::Float(value as f32),
+        ("F", Value::Float(value)) => Value::Float(value),
+        ("F", Value::Double(value)) => Value::Float(value as f32),
+        ("F", _) => Value::Float(int as f32),
+        ("D", Value::Long(value)) => Value::Double(value as f64),
+        ("D", Value::Float(value)) => Value::Double(value as f64),
+        ("D", Value::Double(value)) => Value::Double(value),
+        ("D", _) => Value::Double(int as f64),
+        ("S", _) => Value::Int(int as i16 as i32),
+        ("B", _) => Value::Int(int as i8 as i32),
+        _ => Value::Int(int),
+    };
+
+    returns(Some(converted))
+}
+
+fn box_to_string(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+    let descriptor = boxed_type(&type_name_of(heap, &args[0])).unwrap_or("I");
+    let value = boxed_value(heap, &args[0]);
+
+    match string_value_of(descriptor, &value, runtime_data_area, call_stack)? {
+        Ok(chars) => returns(Some(runtime_data_area.new_string(chars, call_stack)?)),
+        Err(thrown) => Ok(thrown),
+    }
+}
+
+fn box_hash_code(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+    let bits = boxed_bits(&boxed_value(heap, &args[0]));
+
+    // AS SPECIFIED BY THE SPECS: 1231 for true and 1237 for false, longs and doubles fold
+    // their high half onto their low half
+    let hash_code = match boxed_type(&type_name_of(heap, &args[0])) {
+        Some("Z") if bits != 0 => 1231,
+        Some("Z") => 1237,
+        Some("J" | "D") => (bits ^ (bits as u64 >> 32) as i64) as i32,
+        _ => bits as i32,
+    };
+    returns(Some(Value::Int(hash_code)))
+}
+
+/// `equals` of the box classes, floats and doubles are equal when their bits are, so that
+/// NaN equals NaN but 0.0 does not equal -0.0
+fn box_equals(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+
+    let equal = matches!(args[1], Value::Object(_))
+        && type_name_of(heap, &args[0]) == type_name_of(heap, &args[1])
+        && boxed_bits(&boxed_value(heap, &args[0])) == boxed_bits(&boxed_value(heap, &args[1]));
+    returns(Some(Value::Int(equal as i32)))
+}
+
+fn box_compare_to(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+
+    if let Value::Null = args[1] {
+        return Ok(null_pointer_exception());
+    }
+    let source = type_name_of(heap, &args[1]);
+    let target = type_name_of(heap, &args[0]);
+    if source != target {
+        return Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/ClassCastException",
+            Some(class_cast_message(&source, &target)),
+        ));
+    }
+
+    let (this, other) = (boxed_value(heap, &args[0]), boxed_value(heap, &args[1]));
+
+    // AS SPECIFIED BY THE SPECS: bytes, shorts and chars compare to their difference, floats
+    // and doubles order -0.0 before 0.0 and NaN after every other value
+    let comparison = match (boxed_type(&target), this, other) {
+        (Some("B" | "S" | "C"), Value::Int(this), Value::Int(other)) => this - other,
+        (_, Value::Int(this), Value::Int(other)) => this.cmp(&other) as i32,
+        (_, Value::Long(this), Value::Long(other)) => this.cmp(&other) as i32,
+        (_, this, other) => {
+            let (this_bits, other_bits) = (boxed_bits(&this), boxed_bits(&other));
+            let (this, other) = match (this, other) {
+                (Value::Float(this), Value::Float(other)) => (this as f64, other as f64),
+                (Value::Double(this), Value::Double(other)) => (this, other),
+                _ => return Err("Expected boxed values of the same type".into()),
+            };
+
+            match this.partial_cmp(&other) {
+                Some(ordering) if ordering.is_ne() => ordering as i32,
+                _ => this_bits.cmp(&other_bits) as i32,
+            }
+        }
+    };
+    returns(Some(Value::Int(comparison)))
+}
+
+/// `Integer.sum`, `Integer.max` and `Integer.min`, used as method references on boxed values
+fn box_arithmetic(
+    method: &Method,
+    args: &[Value],
+    _: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let (Value::Int(a), Value::Int(b)) = (&args[0], &args[1]) else {
+        return Err(format!("Expected two ints for {}", method.name).into());
+    };
+
+    let result = match method.name.as_str() {
+        "sum" => a.wrapping_add(*b),
+        "max" => *a.max(b),
+        _ => *a.min(b),
+    };
+    returns(Some(Value::Int(result)))
+}
+
+/// `Integer.parseInt`, `Long.parseLong` and the `valueOf(String)` of both, in radix 10 unless
+/// one is given
+fn box_parse(
+    method: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let Some(chars) = string::string_chars(&runtime_data_area.heap, &args[0]) else {
+        return Ok(number_format_exception(
+            "Cannot parse null string".to_string(),
+        ));
+    };
+    let radix = match args.len() {
+        2 => int_arg(args, 1)?,
+        _ => 10,
+    };
+
+    let long = method.class_name == "java/lang/Long";
+    let parsed = match long {
+        true => number::parse_integer(chars, radix, i64::MIN, i64::MAX),
+        false => number::parse_integer(chars, radix, i32::MIN as i64, i32::MAX as i64),
+    };
+    let value = match parsed {
+        Ok(value) if long => Value::Long(value),
+        Ok(value) => Value::Int(value as i32),
+        Err(message) => return Ok(number_format_exception(message)),
+    };
+
+    match method.name.as_str() {
+        "valueOf" => returns(Some(box_value(
+            runtime_data_area,
+            &method.class_name,
+            value,
+            call_stack,
+        )?)),
+        _ => returns(Some(value)),
+    }
+}
+
+/// `Double.parseDouble`, `Float.parseFloat` and the `valueOf(String)` of both
+fn box_parse_floating(
+    method: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let Some(chars) = string::string_chars(&runtime_data_area.heap, &args[0]) else {
+        // The JDK trims the string first, failing with the helpful message of that call
+        return Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/NullPointerException",
+            Some("Cannot invoke \"String.trim()\" because \"in\" is null".to_string()),
+        ));
+    };
+
+    let single = method.class_name == "java/lang/Float";
+    let value = match number::parse_floating(chars, single) {
+        Ok(value) if single => Value::Float(value as f32),
+        Ok(value) => Value::Double(value),
+        Err(message) => return Ok(number_format_exception(message)),
+    };
+
+    match method.name.as_str() {
+        "valueOf" => returns(Some(box_value(
+            runtime_data_area,
+            &method.class_name,
+            value,
+            call_stack,
+        )?)),
+        _ => returns(Some(value)),
+    }
+}
+
+/// `Integer.toString(int, int)` and `Long.toString(long, int)`
+fn box_to_string_radix(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let value = match args[0] {
+        Value::Int(value) => value as i64,
+        Value::Long(value) => value,
+        ref other => return Err(format!("Expected an int or a long, got {:?}", other).into()),
+    };
+    let string = number::to_string_radix(value, int_arg(args, 1)?);
+    returns(Some(
+        runtime_data_area.new_string_from(&string, call_stack)?,
+    ))
+}
+
+/// `toHexString`, `toOctalString` and `toBinaryString` of `Integer` and `Long`, which format
+/// the value as unsigned
+fn box_to_unsigned_string(
+    method: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let bits = match args[0] {
+        Value::Int(value) => value as u32 as u64,
+        Value::Long(value) => value as u64,
+        ref other => return Err(format!("Expected an int or a long, got {:?}", other).into()),
+    };
+    let bits_per_digit = match method.name.as_str() {
+        "toHexString" => 4,
+        "toOctalString" => 3,
+        _ => 1,
+    };
+    let string = number::to_unsigned_string(bits, bits_per_digit);
+    returns(Some(
+        runtime_data_area.new_string_from(&string, call_stack)?,
+    ))
+}
+
+/// Constructors of `StringBuilder` taking an optional initial capacity
+fn string_builder_init(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let capacity = match args.len() {
+        2 => int_arg(args, 1)?,
+        _ => STRING_BUILDER_CAPACITY as i32,
+    };
+
+    if capacity < 0 {
+        return Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/NegativeArraySizeException",
+            Some(capacity.to_string()),
+        ));
+    }
+
+    // The size is checked before the contents are built
+    let mut reservation =
+        runtime_data_area.reserve(Heap::array_size("C", capacity as usize), call_stack)?;
+    let heap = &mut runtime_data_area.heap;
+    let value = heap.allocate_array(
+        &mut reservation,
+        Array::from_chars(vec![0; capacity as usize]),
+    );
+    set_field(heap, &args[0], STRING_BUILDER_VALUE, value);
+    set_field(heap, &args[0], STRING_BUILDER_COUNT, Value::Int(0));
+    returns(None)
+}
+
+fn string_builder_init_with_string(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+    let Some(chars) = string::string_chars(heap, &args[1]).map(<[u16]>::to_vec) else {
+        return Ok(null_pointer_exception());
+    };
+
+    // AS SPECIFIED BY THE SPECS: the initial capacity is 16 plus the length of the string
+    let mut value = chars.clone();
+    value.resize(chars.len() + STRING_BUILDER_CAPACITY, 0);
+
+    let value = runtime_data_area.allocate_array(Array::from_chars(value), call_stack)?;
+    let heap = &mut runtime_data_area.heap;
+    set_field(heap, &args[0], STRING_BUILDER_VALUE, value);
+    set_field(
+        heap,
+        &args[0],
+        STRING_BUILDER_COUNT,
+        Value::Int(chars.len() as i32),
+    );
+    returns(None)
+}
+
+fn string_builder_append(
+    method: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let chars = match string_value_of(
+        descriptor::parameter_types(&method.descriptor)[0],
+        &args[1],
+        runtime_data_area,
+        call_stack,
+    )? {
+        Ok(chars) => chars,
+        Err(thrown) => return Ok(thrown),
+    };
+
+    let count = builder_count(&runtime_data_area.heap, &args[0])?;
+    builder_insert(runtime_data_area, call_stack, &args[0], count, &chars)?;
+    returns(Some(args[0].clone()))
+}
+
+fn string_builder_insert(
+    method: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let offset = int_arg(args, 1)?;
+    let chars = match string_value_of(
+        descriptor::parameter_types(&method.descriptor)[1],
+        &args[2],
+        runtime_data_area,
+        call_stack,
+    )? {
+        Ok(chars) => chars,
+        Err(thrown) => return Ok(thrown),
+    };
+
+    let count = builder_count(&runtime_data_area.heap, &args[0])?;
+
+    if offset < 0 || offset as usize > count {
+        return Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/StringIndexOutOfBoundsException",
+            Some(format!("offset {}, length {}", offset, count)),
+        ));
+    }
+
+    builder_insert(
+        runtime_data_area,
+        call_stack,
+        &args[0],
+        offset as usize,
+        &chars,
+    )?;
+    returns(Some(args[0].clone()))
+}
+
+fn string_builder_reverse(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let heap = &mut runtime_data_area.heap;
+    let count = builder_count(heap, &args[0])?;
+    let value = builder_value(heap, &args[0])?;
+
+    if let ArrayElements::Char(chars) = &mut heap.array_mut(value).elements {
+        string::reverse(&mut chars[..count]);
+    }
+
+    returns(Some(args[0].clone()))
+}
+
+fn string_builder_length(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let count = builder_count(&runtime_data_area.heap, &args[0])?;
+    returns(Some(Value::Int(count as i32)))
+}
+
+fn string_builder_char_at(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let contents = builder_contents(&runtime_data_area.heap, &args[0])?;
+    let index = int_arg(args, 1)?;
+
+    match usize::try_from(index)
+        .ok()
+        .and_then(|index| contents.get(index))
+    {
+        Some(&c) => returns(Some(Value::Int(c as i32))),
+        None => Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/StringIndexOutOfBoundsException",
+            Some(format!("index {}, length {}", index, contents.len())),
+        )),
+    }
+}
+
+fn string_builder_to_string(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let contents = builder_contents(&runtime_data_area.heap, &args[0])?;
+    returns(Some(runtime_data_area.new_string(contents, call_stack)?))
+}
+
+/// Constructors of `Thread` taking an optional `Runnable` target and an optional name
+fn thread_init(
+    method: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let (target, name) = match method.descriptor.as_str() {
+        "(Ljava/lang/Runnable;)V" => (args[1].clone(), None),
+        "(Ljava/lang/Runnable;Ljava/lang/String;)V" => (args[1].clone(), Some(args[2].clone())),
+        "(Ljava/lang/String;)V" => (Value::Null, Some(args[1].clone())),
+        _ => (Value::Null, None),
+    };
+
+    if let Some(Value::Null) = name {
+        return Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/NullPointerException",
+            Some("'name' is null".to_string()),
+        ));
+    }
+
+    thread::init_thread(&args[0], target, name, runtime_data_area, call_stack)?;
+    returns(None)
+}
+
+fn thread_start(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    if !thread::start_thread(&args[0], runtime_data_area, call_stack)? {
+        return Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/IllegalThreadStateException",
+            None,
+        ));
+    }
+    returns(None)
+}
+
+/// Runs the target given to the constructor, a subclass overrides run() instead
+fn thread_run(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let heap = &runtime_data_area.heap;
+    let target = get_field(heap, &args[0], THREAD_TARGET);
+    let run = match &target {
+        Value::Object(handle) => heap
+            .object(*handle)
+            .class
+            .lookup_instance_method("run", "()V"),
+        _ => None,
+    };
+
+    // The run() method of the target returns to the invoker of this one
+    match run {
+        Some(run) => Ok(InstructionCompleted::InvokeMethod(run, vec![target])),
+        None => returns(None),
+    }
+}
+
+fn thread_join(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let timeout = match args.get(1) {
+        None => None,
+        Some(&Value::Long(millis)) if millis < 0 => {
+            return Ok(InstructionCompleted::ThrowNewException(
+                "java/lang/IllegalArgumentException",
+                Some("timeout value is negative".to_string()),
+            ));
+        }
+        // AS SPECIFIED BY THE SPECS: a timeout of 0 means to wait forever
+        Some(&Value::Long(millis)) => (millis > 0).then_some(millis as u64),
+        Some(other) => return Err(format!("Expected a long argument, got {:?}", other).into()),
+    };
+
+    thread::join(&args[0], timeout, runtime_data_area, call_stack);
+    returns(None)
+}
+
+fn thread_is_alive(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let id = thread::thread_id(runtime_data_area, &args[0]);
+    let alive = call_stack.thread.scheduler.is_alive(id);
+    returns(Some(Value::Int(alive as i32)))
+}
+
+fn thread_get_name(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    returns(Some(get_field(
+        &runtime_data_area.heap,
+        &args[0],
+        THREAD_NAME,
+    )))
+}
+
+fn thread_set_name(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    if matches!(args[1], Value::Null) {
+        return Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/NullPointerException",
+            Some("name cannot be null".to_string()),
+        ));
+    }
+
+    set_field(
+        &mut runtime_data_area.heap,
+        &args[0],
+        THREAD_NAME,
+        args[1].clone(),
+    );
+    returns(None)
+}
+
+fn thread_get_id(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    returns(Some(Value::Long(thread::thread_id(
+        runtime_data_area,
+        &args[0],
+    ))))
+}
+
+fn thread_is_daemon(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    returns(Some(get_field(
+        &runtime_data_area.heap,
+        &args[0],
+        THREAD_DAEMON,
+    )))
+}
+
+fn thread_set_daemon(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let id = thread::thread_id(runtime_data_area, &args[0]);
+
+    // AS SPECIFIED BY THE SPECS: a thread is marked as a daemon before it is started
+    if call_stack.thread.scheduler.is_alive(id) {
+        return Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/IllegalThreadStateException",
+            None,
+        ));
+    }
+
+    set_field(
+        &mut runtime_data_area.heap,
+        &args[0],
+        THREAD_DAEMON,
+        args[1].clone(),
+    );
+    returns(None)
+}
+
+fn thread_get_priority(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    returns(Some(get_field(
+        &runtime_data_area.heap,
+        &args[0],
+        THREAD_PRIORITY,
+    )))
+}
+
+fn thread_set_priority(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    _: &mut CallStack,
+) -> NativeResult {
+    let priority = int_arg(args, 1)?;
+
+    if !(1..=10).contains(&priority) {
+        return Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/IllegalArgumentException",
+            None,
+        ));
+    }
+
+    // Kept for getPriority() only, the scheduler ignores priorities
+    set_field(
+        &mut runtime_data_area.heap,
+        &args[0],
+        THREAD_PRIORITY,
+        Value::Int(priority),
+    );
+    returns(None)
+}
+
+fn thread_to_string(
+    _: &Method,
+    args: &[Value],
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let priority = get_field(&runtime_data_area.heap, &args[0], THREAD_PRIORITY);
+    let description = format!(
+        "Thread[#{},{},{},main]",
+        thread::thread_id(runtime_data_area, &args[0]),
+        thread::thread_name(runtime_data_area, &args[0]),
+        int_arg(&[priority], 0)?,
+    );
+    returns(Some(
+        runtime_data_area.new_string_from(&description, call_stack)?,
+    ))
+}
+
+fn thread_current_thread(
+    _: &Method,
+    _: &[Value],
+    _: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    returns(Some(call_stack.thread.object.clone()))
+}
+
+fn thread_sleep(
+    _: &Method,
+    args: &[Value],
+    _: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    let millis = match args[0] {
+        Value::Long(millis) => millis,
+        ref other => return Err(format!("Expected a long argument, got {:?}", other).into()),
+    };
+
+    if millis < 0 {
+        return Ok(InstructionCompleted::ThrowNewException(
+            "java/lang/IllegalArgumentException",
+            Some("timeout value is negative".to_string()),
+        ));
+    }
+
+    thread::sleep(millis as u64, call_stack);
+    returns(None)
+}
+
+fn thread_yield(
+    _: &Method,
+    _: &[Value],
+    _: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> NativeResult {
+    thread::yield_now(call_stack);
+    returns(None)
+}
+
+/// Describes a throwable the way `Throwable.toString()` does: its class name,
+/// followed by its message if it has one
+pub fn throwable_to_string(heap: &Heap, exception: &Value) -> String {
+    let class_name = match exception {
+        Value::Object(handle) => heap.object(*handle).class.name.replace('/', "."),
+        _ => "java.lang.Throwable".to_string(),
+    };
+
+    let message = get_field(heap, exception, THROWABLE_DETAIL_MESSAGE);
+
+    match string::rust_string(heap, &message) {
+        Some(message) => format!("{}: {}", class_name, message),
+        None => class_name,
+    }
+}
+
+/// A throwable along with the frames captured when it was created
+pub struct ThrowableTrace {
+    pub throwable: Value,
+    pub frames: Vec<String>,
+}
+
+/// Collects a throwable and the chain of its causes, along with their backtraces
+pub fn cause_chain(heap: &Heap, exception: &Value) -> Vec<ThrowableTrace> {
+    let mut chain: Vec<ThrowableTrace> = Vec::new();
+    let mut current = exception.clone();
+
+    loop {
+        chain.push(ThrowableTrace {
+            frames: backtrace(heap, &current),
+            throwable: current.clone(),
+        });
+
+        match get_field(heap, &current, THROWABLE_CAUSE) {
+            // A cause chain may loop back on itself, stop before listing a throwable twice
+            cause @ Value::Object(_)
+                if !chain
+                    .iter()
+                    .any(|known| known.throwable.same_reference(&cause)) =>
+            {
+                current = cause;
+            }
+            _ => break,
+        }
+    }
+
+    chain
+}
+
+/// Formats a chain of throwables collected by `cause_chain` the way
+/// `Throwable.printStackTrace()` does, `describe` provides the first line of each throwable
+///
+/// The frames a cause shares with the throwable it caused are elided as `... n more`.
+pub fn format_stack_trace(
+    chain: &[ThrowableTrace],
+    mut describe: impl FnMut(&Value) -> String,
+) -> String {
+    let mut output = String::new();
+    let mut enclosing_trace: &[String] = &[];
+
+    for (i, ThrowableTrace { throwable, frames }) in chain.iter().enumerate() {
+        // Count the frames in common with the enclosing trace, starting from the bottom
+        let mut frames_in_common = 0;
+        while frames_in_common < frames.len()
+            && frames_in_common < enclosing_trace.len()
+            && frames[frames.len() - 1 - frames_in_common]
+                == enclosing_trace[enclosing_trace.len() - 1 - frames_in_common]
+        {
+            frames_in_common += 1;
+        }
+
+        if i != 0 {
+            output.push_str("Caused by: ");
+        }
+        output.push_str(&describe(throwable));
+        output.push('\n');
+
+        for frame in &frames[..frames.len() - frames_in_common] {
+            output.push_str(&format!("\tat {}\n", frame));
+        }
+
+        if frames_in_common != 0 {
+            output.push_str(&format!("\t... {} more\n", frames_in_common));
+        }
+
+        enclosing_trace = frames;
+    }
+
+    output
+}
+
+/// Reads the frame descriptions captured when a throwable was created
+fn backtrace(heap: &Heap, exception: &Value) -> Vec<String> {
+    match get_field(heap, exception, THROWABLE_BACKTRACE) {
+        Value::Array(frames) => heap
+            .array(frames)
+            .references()
+            .iter()
+            .filter_map(|frame| string::rust_string(heap, frame))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Throws an `IllegalMonitorStateException` for an object method called by a thread
+/// that does not own the monitor of the object
+fn not_monitor_owner() -> InstructionCompleted {
+    InstructionCompleted::ThrowNewException(
+        "java/lang/IllegalMonitorStateException",
+        Some("current thread is not owner".to_string()),
+    )
+}
+
+/// Throws a `NullPointerException` for a null argument of a native method
+fn null_pointer_exception() -> InstructionCompleted {
+    InstructionCompleted::ThrowNewException("java/lang/NullPointerException", None)
+}
+
+/// Throws a `NumberFormatException` for a string that is not a number
+fn number_format_exception(message: String) -> InstructionCompleted {
+    InstructionCompleted::ThrowNewException("java/lang/NumberFormatException", Some(message))
+}
+
+/// Reads an int argument of a native method
+fn int_arg(args: &[Value], index: usize) -> Result<i32, VmError> {
+    match args.get(index) {
+        Some(Value::Int(value)) => Ok(*value),
+        other => Err(format!("Expected an int argument, got {:?}", other).into()),
+    }
+}
+
+/// Copies the UTF-16 code units of the string a `java/lang/String` method is invoked on
+fn string_contents(heap: &Heap, this: &Value) -> Result<Vec<u16>, VmError> {
+    string::string_chars(heap, this)
+        .map(<[u16]>::to_vec)
+        .ok_or_else(|| format!("Expected a string, got {:?}", this).into())
+}
+
+/// Name of the runtime type of a reference the way `CONSTANT_Class` entries name types
+fn type_name_of(heap: &Heap, value: &Value) -> String {
+    match value {
+        Value::Object(handle) => heap.object(*handle).class.name.clone(),
+        Value::Array(handle) => heap.array(*handle).descriptor.clone(),
+        _ => "java/lang/Object".to_string(),
+    }
+}
+
+/// Message of the `ClassCastException` thrown when a reference of the source type is cast to
+/// the target type, both named the way `CONSTANT_Class` entries name types, in the HotSpot
+/// format which tells where each class comes from, e.g. `class java.lang.String cannot be
+/// cast to class Main$Cat (java.lang.String is in module java.base of loader 'bootstrap';
+/// Main$Cat is in unnamed module of loader 'app')`
+pub fn class_cast_message(source: &str, target: &str) -> String {
+    // Classes of the VM stand for the java.base module, arrays are defined where their
+    // element class is, and arrays of primitives are in java.base too
+    let origin = |name: &str| {
+        let element = match name.starts_with('[') {
+            true => array::component_class_name(name.trim_start_matches('[')),
+            false => Some(name),
+        };
+
+        match element {
+            Some(class_name) if find_bootstrap_class(class_name).is_none() => {
+                "in unnamed module of loader 'app'"
+            }
+            _ => "in module java.base of loader 'bootstrap'",
+        }
+    };
+
+    let source_name = array::external_array_name(source);
+    let target_name = array::external_array_name(target);
+
+    let origins = match (origin(source), origin(target)) {
+        (source_origin, target_origin) if source_origin == target_origin => {
+            format!("{} and {} are {}", source_name, target_name, source_origin)
+        }
+        (source_origin, target_origin) => format!(
+            "{} is {}; {} is {}",
+            source_name, source_origin, target_name, target_origin
+        ),
+    };
+
+    format!(
+        "class {} cannot be cast to class {} ({})",
+        source_name, target_name, origins
+    )
+}
+
+/// Converts a value of the given type descriptor to the UTF-16 code units `String.valueOf`
+/// gives for it, objects other than strings are converted by invoking their `toString()`
+/// method, whose exception is returned as `Err` if it throws
+pub fn string_value_of(
+    descriptor: &str,
+    value: &Value,
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> Result<Result<Vec<u16>, InstructionCompleted>, VmError> {
+    let heap = &runtime_data_area.heap;
+
+    let converted = match (descriptor, value) {
+        ("Z", Value::Int(value)) => (if *value != 0 { "true" } else { "false" }).to_string(),
+        ("C", Value::Int(value)) => return Ok(Ok(vec![*value as u16])),
+        (_, Value::Int(value)) => value.to_string(),
+        (_, Value::Long(value)) => value.to_string(),
+        (_, Value::Float(value)) => string::float_to_string(*value),
+        (_, Value::Double(value)) => string::double_to_string(*value),
+        ("[C", Value::Array(handle)) => match &heap.array(*handle).elements {
+            ArrayElements::Char(chars) => return Ok(Ok(chars.clone())),
+            _ => return Err(format!("Expected a char array, got {:?}", value).into()),
+        },
+        ("[C", Value::Null) => return Ok(Err(null_pointer_exception())),
+        (_, Value::Null) => "null".to_string(),
+        (_, reference) => {
+            if let Some(chars) = string::string_chars(heap, reference) {
+                return Ok(Ok(chars.to_vec()));
+            }
+
+            if let Value::Object(handle) = reference
+                && heap.object(*handle).class.name == "java/lang/StringBuilder"
+            {
+                return Ok(Ok(builder_contents(heap, reference)?));
+            }
+
+            return match invoke_virtual(
+                reference,
+                "toString",
+                "()Ljava/lang/String;",
+                runtime_data_area,
+                call_stack,
+            )? {
+                Ok(Some(string)) => Ok(Ok(string::string_chars(&runtime_data_area.heap, &string)
+                    .map_or_else(|| "null".encode_utf16().collect(), <[u16]>::to_vec))),
+                Ok(None) => Err("toString() returned no value".into()),
+                Err(thrown) => Ok(Err(thrown)),
+            };
+        }
+    };
+
+    Ok(Ok(converted.encode_utf16().collect()))
+}
+
+/// Invokes an instance method, selected by the runtime type of the receiver like
+/// `invokevirtual` does, returns the exception it throws as `Err`
+fn invoke_virtual(
+    receiver: &Value,
+    name: &str,
+    descriptor: &str,
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> Result<Result<Option<Value>, InstructionCompleted>, VmError> {
+    let class = match receiver {
+        Value::Object(handle) => runtime_data_area.heap.object(*handle).class.clone(),
+        _ => runtime_data_area
+            .class_loader
+            .load_class("java/lang/Object")?,
+    };
+    let method = class
+        .lookup_method(name, descriptor)
+        .ok_or_else(|| format!("No {}{} method in {}", name, descriptor, class.name))?;
+
+    match InstructionExecutor::new().invoke_method(
+        method,
+        vec![receiver.clone()],
+        runtime_data_area,
+        call_stack,
+    )? {
+        InstructionCompleted::ReturnFromMethod(value) => Ok(Ok(value)),
+        thrown => Ok(Err(thrown)),
+    }
+}
+
+/// Descriptor of the primitive type a box class wraps, e.g. `I` for `java/lang/Integer`
+pub fn boxed_type(class_name: &str) -> Option<&'static str> {
+    match class_name {
+        "java/lang/Boolean" => Some("Z"),
+        "java/lang/Character" => Some("C"),
+        "java/lang/Byte" => Some("B"),
+        "java/lang/Short" => Some("S"),
+        "java/lang/Integer" => Some("I"),
+        "java/lang/Long" => Some("J"),
+        "java/lang/Float" => Some("F"),
+        "java/lang/Double" => Some("D"),
+        _ => None,
+    }
+}
+
+/// Box class of a primitive type, e.g. `java/lang/Integer` for `I`
+pub fn box_class(descriptor: &str) -> Option<&'static str> {
+    match descriptor {
+        "Z" => Some("java/lang/Boolean"),
+        "C" => Some("java/lang/Character"),
+        "B" => Some("java/lang/Byte"),
+        "S" => Some("java/lang/Short"),
+        "I" => Some("java/lang/Integer"),
+        "J" => Some("java/lang/Long"),
+        "F" => Some("java/lang/Float"),
+        "D" => Some("java/lang/Double"),
+        _ => None,
+    }
+}
+
+/// The primitive value of a box instance
+pub fn boxed_value(heap: &Heap, boxed: &Value) -> Value {
+    let class_name = type_name_of(heap, boxed);
+    get_field(heap, boxed, &format!("{}.value", class_name))
+}
+
+/// The bits of a boxed value that `equals` compares, NaNs are all the same NaN
+/// like `Double.doubleToLongBits` gives
+fn boxed_bits(value: &Value) -> i64 {
+    match value {
+        Value::Int(value) => *value as i64,
+        Value::Long(value) => *value,
+        Value::Float(value) if value.is_nan() => 0x7fc0_0000,
+        Value::Float(value) => value.to_bits() as i32 as i64,
+        Value::Double(value) if value.is_nan() => 0x7ff8_0000_0000_0000,
+        Value::Double(value) => value.to_bits() as i64,
+        _ => 0,
+    }
+}
+
+/// Boxes a primitive value into an instance of the given box class
+///
+/// AS SPECIFIED BY THE SPECS: §5.1.7, boxing the same `true`, `false`, `char` up to
+/// `\u007f` or integer from -128 to 127 gives the same instance
+pub fn box_value(
+    runtime_data_area: &mut RuntimeDataArea,
+    class_name: &str,
+    value: Value,
+    call_stack: &CallStack,
+) -> Result<Value, VmError> {
+    let cached = match (boxed_type(class_name), &value) {
+        (Some("Z"), Value::Int(value)) => Some(*value as i64),
+        (Some("C"), Value::Int(value)) if (0..=127).contains(value) => Some(*value as i64),
+        (Some("B" | "S" | "I"), Value::Int(value)) if (-128..=127).contains(value) => {
+            Some(*value as i64)
+        }
+        (Some("J"), Value::Long(value)) if (-128..=127).contains(value) => Some(*value),
+        _ => None,
+    };
+
+    let key = cached.map(|cached| (class_name.to_string(), cached));
+    if let Some(boxed) = key
+        .as_ref()
+        .and_then(|key| runtime_data_area.boxes.get(key))
+    {
+        return Ok(boxed.clone());
+    }
+
+    let class = runtime_data_area.class_loader.load_class(class_name)?;
+    let mut boxed = Object::new(class);
+    boxed.fields.insert(format!("{}.value", class_name), value);
+    let boxed = runtime_data_area.allocate_object(boxed, call_stack)?;
+
+    if let Some(key) = key {
+        runtime_data_area.boxes.insert(key, boxed.clone());
+    }
+    Ok(boxed)
+}
+
+/// Retrieves the array holding the contents of a `StringBuilder`
+fn builder_value(heap: &Heap, this: &Value) -> Result<Handle, VmError> {
+    match get_field(heap, this, STRING_BUILDER_VALUE) {
+        Value::Array(value) => Ok(value),
+        other => Err(format!("Uninitialized StringBuilder value: {:?}", other).into()),
+    }
+}
+
+/// Retrieves the number of characters of a `StringBuilder`
+fn builder_count(heap: &Heap, this: &Value) -> Result<usize, VmError> {
+    match get_field(heap, this, STRING_BUILDER_COUNT) {
+        Value::Int(count) => Ok(count as usize),
+        other => Err(format!("Uninitialized StringBuilder count: {:?}", other).into()),
+    }
+}
+
+/// Copies the contents of a `StringBuilder`
+fn builder_contents(heap: &Heap, this: &Value) -> Result<Vec<u16>, VmError> {
+    let count = builder_count(heap, this)?;
+
+    match &heap.array(builder_value(heap, this)?).elements {
+        ArrayElements::Char(chars) => Ok(chars[..count].to_vec()),
+        _ => Err("StringBuilder value is not a char array".into()),
+    }
+}
+
+/// Inserts characters in a `StringBuilder` at the given offset, the offset must not exceed
+/// the number of characters already in the builder
+fn builder_insert(
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &CallStack,
+    this: &Value,
+    offset: usize,
+    chars: &[u16],
+) -> Result<(), VmError> {
+    let heap = &runtime_data_area.heap;
+    let count = builder_count(heap, this)?;
+    let mut value = builder_value(heap, this)?;
+    let capacity = heap.array(value).length();
+    let new_count = count + chars.len();
+
+    // AS SPECIFIED BY THE SPECS: a full builder grows to twice its capacity plus 2,
+    // or to the needed capacity if that is not enough
+    if new_count > capacity {
+        let new_capacity = (capacity * 2 + 2).max(new_count);
+        let mut reservation =
+            runtime_data_area.reserve(Heap::array_size("C", new_capacity), call_stack)?;
+
+        let heap = &mut runtime_data_area.heap;
+        let mut grown = builder_contents(heap, this)?;
+        grown.resize(new_capacity, 0);
+
+        let grown = heap.allocate_array(&mut reservation, Array::from_chars(grown));
+        set_field(heap, this, STRING_BUILDER_VALUE, grown);
+        value = builder_value(heap, this)?;
+    }
+
+    let heap = &mut runtime_data_area.heap;
+
+    if let ArrayElements::Char(contents) = &mut heap.array_mut(value).elements {
+        contents.copy_within(offset..count, offset + chars.len());
+        contents[offset..offset + chars.len()].copy_from_slice(chars);
+    }
+
+    set_field(
+        heap,
+        this,
+        STRING_BUILDER_COUNT,
+        Value::Int(new_count as i32),
+    );
+    Ok(())
+}
+
+/// Reads a field of an object, `null` if the value is not an object or has no such field
+fn get_field(heap: &Heap, value: &Value, key: &str) -> Value {
+    match value {
+        Value::Object(handle) => heap
+            .object(*handle)
+            .fields
+            .get(key)
+            .cloned()
+            .unwrap_or(Value::Null),
+        _ => Value::Null,
+    }
+}
+
+/// Writes a field of an object, ignored if the value is not an object
+fn set_field(heap: &mut Heap, value: &Value, key: &str, field_value: Value) {
+    if let Value::Object(handle) = value {
+        heap.object_mut(*handle)
+            .fields
+            .insert(key.to_string(), field_value);
+    }
+}
+
+/// Retrieves the identity hash code of a reference, 0 for null
+fn identity_hash_code(heap: &Heap, value: &Value) -> i32 {
+    match value.handle() {
+        Some(handle) => heap.identity_hash_code(handle),
+        None => 0,
+    }
+}
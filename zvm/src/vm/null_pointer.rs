@@ -0,0 +1,457 @@
+use crate::{
+    parser::{
+        class_file::ClassFile,
+        code_attribute::CodeAttribute,
+        descriptor::{is_wide, parameter_types},
+        opcode::{Opcode, instruction_length, opcode_at},
+    },
+    vm::{
+        class::Method,
+        simulation::{self, StackValue, read_i16, read_u16},
+        stack_frame::Frame,
+    },
+};
+
+/// Maximum number of nested expressions described in the cause of a NullPointerException
+const MAX_CAUSE_DETAIL: usize = 5;
+
+/// Operand stack entry of the bytecode simulation
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct StackEntry {
+    /// pc of the instruction that pushed the value, `None` when it depends on the path taken
+    source: Option<usize>,
+    /// Whether the value is a long or a double ( computational type category 2 )
+    wide: bool,
+}
+
+impl StackValue for StackEntry {
+    fn is_wide(&self) -> bool {
+        self.wide
+    }
+}
+
+/// Builds the helpful message of the NullPointerException thrown by the instruction the frame
+/// is executing, like HotSpot does ( JEP 358 ):
+///
+/// Cannot invoke "String.length()" because "<local1>" is null
+///
+/// The failed action is always described, the null expression only when the instruction that
+/// pushed the null reference can be found by simulating the operand stack of the method.
+/// Returns `None` if the instruction never dereferences a reference.
+pub fn null_pointer_message(frame: &Frame) -> Option<String> {
+    let code = frame.method.code.as_ref()?;
+    let mut builder = MessageBuilder::new(&frame.method, code, &frame.class.class_file);
+    let action = builder.failed_action(frame.pc)?;
+
+    if builder.simulate().is_none() {
+        return Some(action);
+    }
+
+    let slot = builder.null_slot(frame.pc)?;
+
+    match builder.cause(frame.pc, slot) {
+        Some(cause) => Some(format!("{} because {}", action, cause)),
+        None => Some(action),
+    }
+}
+
+struct MessageBuilder<'a> {
+    method: &'a Method,
+    code: &'a CodeAttribute,
+    class_file: &'a ClassFile,
+    /// Simulated operand stack before each instruction, `None` for pcs never reached
+    stacks: Vec<Option<Vec<StackEntry>>>,
+    /// Local variable slots written by the method, the others still hold its parameters
+    written: Vec<bool>,
+}
+
+impl<'a> MessageBuilder<'a> {
+    fn new(method: &'a Method, code: &'a CodeAttribute, class_file: &'a ClassFile) -> Self {
+        Self {
+            method,
+            code,
+            class_file,
+            stacks: vec![None; code.code.len()],
+            written: vec![false; code.max_locals as usize],
+        }
+    }
+
+    /// Describes what the instruction at the given pc failed to do with a null reference
+    fn failed_action(&self, pc: usize) -> Option<String> {
+        let action = match opcode_at(&self.code.code, pc)? {
+            Opcode::Iaload => "Cannot load from int array".to_string(),
+            Opcode::Laload => "Cannot load from long array".to_string(),
+            Opcode::Faload => "Cannot load from float array".to_string(),
+            Opcode::Daload => "Cannot load from double array".to_string(),
+            Opcode::Aaload => "Cannot load from object array".to_string(),
+            Opcode::Baload => "Cannot load from byte/boolean array".to_string(),
+            Opcode::Caload => "Cannot load from char array".to_string(),
+            Opcode::Saload => "Cannot load from short array".to_string(),
+            Opcode::Iastore => "Cannot store to int array".to_string(),
+            Opcode::Lastore => "Cannot store to long array".to_string(),
+            Opcode::Fastore => "Cannot store to float array".to_string(),
+            Opcode::Dastore => "Cannot store to double array".to_string(),
+            Opcode::Aastore => "Cannot store to object array".to_string(),
+            Opcode::Bastore => "Cannot store to byte/boolean array".to_string(),
+            Opcode::Castore => "Cannot store to char array".to_string(),
+            Opcode::Sastore => "Cannot store to short array".to_string(),
+            Opcode::Arraylength => "Cannot read the array length".to_string(),
+            Opcode::Athrow => "Cannot throw exception".to_string(),
+            Opcode::Monitorenter => "Cannot enter synchronized block".to_string(),
+            Opcode::Monitorexit => "Cannot exit synchronized block".to_string(),
+            Opcode::Getfield => format!("Cannot read field \"{}\"", self.field_ref(pc)?.1),
+            Opcode::Putfield => format!("Cannot assign field \"{}\"", self.field_ref(pc)?.1),
+            Opcode::Invokevirtual | Opcode::Invokespecial | Opcode::Invokeinterface => {
+                format!("Cannot invoke \"{}\"", self.method_description(pc)?)
+            }
+            _ => return None,
+        };
+
+        Some(action)
+    }
+
+    /// Depth in the operand stack of the reference the instruction at the given pc dereferences
+    fn null_slot(&self, pc: usize) -> Option<usize> {
+        let slot = match opcode_at(&self.code.code, pc)? {
+            Opcode::Iaload
+            | Opcode::Laload
+            | Opcode::Faload
+            | Opcode::Daload
+            | Opcode::Aaload
+            | Opcode::Baload
+            | Opcode::Caload
+            | Opcode::Saload
+            | Opcode::Putfield => 1,
+            Opcode::Iastore
+            | Opcode::Lastore
+            | Opcode::Fastore
+            | Opcode::Dastore
+            | Opcode::Aastore
+            | Opcode::Bastore
+            | Opcode::Castore
+            | Opcode::Sastore => 2,
+            Opcode::Invokevirtual | Opcode::Invokespecial | Opcode::Invokeinterface => {
+                parameter_types(&self.method_ref(pc)?.2).len()
+            }
+            _ => 0,
+        };
+
+        Some(slot)
+    }
+
+    /// Describes the expression that pushed the null reference found at the given depth of
+    /// the operand stack before the instruction at the given pc
+    fn cause(&self, pc: usize, slot: usize) -> Option<String> {
+        let source = self.source_of(pc, slot)?;
+
+        if let Some(
+            Opcode::Invokevirtual
+            | Opcode::Invokespecial
+            | Opcode::Invokestatic
+            | Opcode::Invokeinterface,
+        ) = opcode_at(&self.code.code, source)
+        {
+            return Some(format!(
+                "the return value of \"{}\" is null",
+                self.method_description(source)?
+            ));
+        }
+
+        let description = self.describe(pc, slot, MAX_CAUSE_DETAIL)?;
+
+        Some(format!("\"{}\" is null", description))
+    }
+
+    /// Describes the expression that pushed the value found at the given depth of the
+    /// operand stack before the instruction at the given pc, as it would read in Java
+    fn describe(&self, pc: usize, slot: usize, max_detail: usize) -> Option<String> {
+        if max_detail == 0 {
+            return None;
+        }
+
+        let source = self.source_of(pc, slot)?;
+        let code = &self.code.code;
+
+        let description = match opcode_at(code, source)? {
+            Opcode::Aconst_null => "null".to_string(),
+            Opcode::Iconstm1
+            | Opcode::Iconst0
+            | Opcode::Iconst1
+            | Opcode::Iconst2
+            | Opcode::Iconst3
+            | Opcode::Iconst4
+            | Opcode::Iconst5 => (code[source] as i32 - Opcode::Iconst0 as i32).to_string(),
+            Opcode::Bipush => (*code.get(source + 1)? as i8).to_string(),
+            Opcode::Sipush => read_i16(code, source + 1)?.to_string(),
+            Opcode::Iload | Opcode::Lload | Opcode::Fload | Opcode::Dload | Opcode::Aload => {
+                self.local_variable(source, *code.get(source + 1)? as usize)
+            }
+            Opcode::Iload0 | Opcode::Lload0 | Opcode::Fload0 | Opcode::Dload0 | Opcode::Aload_0 => {
+                self.local_variable(source, 0)
+            }
+            Opcode::Iload1 | Opcode::Lload1 | Opcode::Fload1 | Opcode::Dload1 | Opcode::Aload_1 => {
+                self.local_variable(source, 1)
+            }
+            Opcode::Iload2 | Opcode::Lload2 | Opcode::Fload2 | Opcode::Dload2 | Opcode::Aload_2 => {
+                self.local_variable(source, 2)
+            }
+            Opcode::Iload3 | Opcode::Lload3 | Opcode::Fload3 | Opcode::Dload3 | Opcode::Aload_3 => {
+                self.local_variable(source, 3)
+            }
+            Opcode::Wide => self.local_variable(source, read_u16(code, source + 2)? as usize),
+            Opcode::Getstatic => {
+                let (class_name, field_name, _) = self.field_ref(source)?;
+                format!("{}.{}", external_class_name(&class_name), field_name)
+            }
+            Opcode::Getfield => {
+                let (_, field_name, _) = self.field_ref(source)?;
+                match self.describe(source, 0, max_detail - 1) {
+                    Some(object) => format!("{}.{}", object, field_name),
+                    None => field_name,
+                }
+            }
+            Opcode::Invokevirtual
+            | Opcode::Invokespecial
+            | Opcode::Invokestatic
+            | Opcode::Invokeinterface => self.method_description(source)?,
+            Opcode::Iaload
+            | Opcode::Laload
+            | Opcode::Faload
+            | Opcode::Daload
+            | Opcode::Aaload
+            | Opcode::Baload
+            | Opcode::Caload
+            | Opcode::Saload => {
+                let array = self
+                    .describe(source, 1, max_detail - 1)
+                    .unwrap_or_else(|| "<array>".to_string());
+                let index = self
+                    .describe(source, 0, max_detail - 1)
+                    .unwrap_or_else(|| "...".to_string());
+                format!("{}[{}]", array, index)
+            }
+            _ => return None,
+        };
+
+        Some(description)
+    }
+
+    /// Names the local variable read by the load at the given pc, from the LocalVariableTable when
+    /// the class was compiled with `-g`, otherwise after the parameter it holds or its slot
+    fn local_variable(&self, pc: usize, index: usize) -> String {
+        if let Some(name) = self.code.local_variable_name(index, pc) {
+            return name.to_string();
+        }
+
+        let is_parameter = !self.written.get(index).copied().unwrap_or(false);
+
+        if !self.method.is_static() && index == 0 && is_parameter {
+            return "this".to_string();
+        }
+
+        if is_parameter {
+            let mut slot = if self.method.is_static() { 0 } else { 1 };
+
+            for (i, parameter) in parameter_types(&self.method.descriptor).iter().enumerate() {
+                let size = if is_wide(parameter) { 2 } else { 1 };
+
+                if index >= slot && index < slot + size {
+                    return format!("<parameter{}>", i + 1);
+                }
+
+                slot += size;
+            }
+        }
+
+        format!("<local{}>", index)
+    }
+
+    /// Finds the instruction that pushed the value at the given depth of the operand stack
+    /// before the instruction at the given pc
+    fn source_of(&self, pc: usize, slot: usize) -> Option<usize> {
+        let stack = self.stacks.get(pc)?.as_ref()?;
+        let index = stack.len().checked_sub(slot + 1)?;
+
+        stack[index].source
+    }
+
+    /// Simulates the operand stack of every reachable instruction of the method, recording which
+    /// instruction pushed each value. Values pushed by different instructions depending on the
+    /// path taken have no source.
+    fn simulate(&mut self) -> Option<()> {
+        self.record_writes()?;
+
+        let mut worklist = vec![0];
+        self.stacks[0] = Some(Vec::new());
+
+        // AS SPECIFIED BY THE SPECS: a handler starts with only the caught exception on the stack
+        for entry in &self.code.exception_table {
+            let handler_pc = entry.handler_pc as usize;
+            let stack = vec![StackEntry {
+                source: None,
+                wide: false,
+            }];
+
+            if self.merge(handler_pc, &stack)? {
+                worklist.push(handler_pc);
+            }
+        }
+
+        while let Some(pc) = worklist.pop() {
+            let mut stack = self.stacks[pc].clone()?;
+
+            let pushed = |wide| StackEntry {
+                source: Some(pc),
+                wide,
+            };
+
+            for successor in
+                simulation::execute(&self.code.code, self.class_file, pc, &mut stack, pushed)?
+            {
+                if self.merge(successor, &stack)? {
+                    worklist.push(successor);
+                }
+            }
+        }
+
+        Some(())
+    }
+
+    /// Marks the local variable slots stored to anywhere in the method
+    fn record_writes(&mut self) -> Option<()> {
+        let code = &self.code.code;
+        let mut pc = 0;
+
+        while pc < code.len() {
+            let index = match opcode_at(code, pc)? {
+                Opcode::Istore
+                | Opcode::Lstore
+                | Opcode::Fstore
+                | Opcode::Dstore
+                | Opcode::Astore
+                | Opcode::Iinc => Some(*code.get(pc + 1)? as usize),
+                Opcode::Wide => match opcode_at(code, pc + 1)? {
+                    Opcode::Iload
+                    | Opcode::Lload
+                    | Opcode::Fload
+                    | Opcode::Dload
+                    | Opcode::Aload => None,
+                    _ => Some(read_u16(code, pc + 2)? as usize),
+                },
+                Opcode::Istore_0
+                | Opcode::Lstore_0
+                | Opcode::Fstore_0
+                | Opcode::Dstore_0
+                | Opcode::Astore_0 => Some(0),
+                Opcode::Istore_1
+                | Opcode::Lstore_1
+                | Opcode::Fstore_1
+                | Opcode::Dstore_1
+                | Opcode::Astore_1 => Some(1),
+                Opcode::Istore_2
+                | Opcode::Lstore_2
+                | Opcode::Fstore_2
+                | Opcode::Dstore_2
+                | Opcode::Astore_2 => Some(2),
+                Opcode::Istore_3
+                | Opcode::Lstore_3
+                | Opcode::Fstore_3
+                | Opcode::Dstore_3
+                | Opcode::Astore_3 => Some(3),
+                _ => None,
+            };
+
+            if let Some(written) = index.and_then(|index| self.written.get_mut(index)) {
+                *written = true;
+            }
+
+            pc += instruction_length(code, pc)?;
+        }
+
+        Some(())
+    }
+
+    /// Merges the given stack into the stack recorded before the instruction at the given pc,
+    /// returns whether the recorded stack changed and the instruction must be simulated again
+    fn merge(&mut self, pc: usize, stack: &[StackEntry]) -> Option<bool> {
+        let recorded = match self.stacks.get_mut(pc)? {
+            Some(recorded) => recorded,
+            unreached => {
+                *unreached = Some(stack.to_vec());
+                return Some(true);
+            }
+        };
+
+        // Stack heights always agree in verifiable code
+        if recorded.len() != stack.len() {
+            return None;
+        }
+
+        let mut changed = false;
+
+        for (recorded, entry) in recorded.iter_mut().zip(stack) {
+            if recorded.source.is_some() && recorded.source != entry.source {
+                recorded.source = None;
+                changed = true;
+            }
+        }
+
+        Some(changed)
+    }
+
+    /// Retrieves the class name, name and descriptor of the field referenced by the instruction at the given pc
+    fn field_ref(&self, pc: usize) -> Option<(String, String, String)> {
+        simulation::field_ref(&self.code.code, self.class_file, pc)
+    }
+
+    /// Retrieves the class name, name and descriptor of the method invoked by the instruction at the given pc
+    fn method_ref(&self, pc: usize) -> Option<(String, String, String)> {
+        simulation::method_ref(&self.code.code, self.class_file, pc)
+    }
+
+    /// Describes the method invoked by the instruction at the given pc, e.g. `Main.make(int, String)`
+    fn method_description(&self, pc: usize) -> Option<String> {
+        let (class_name, method_name, descriptor) = self.method_ref(pc)?;
+        let parameters: Vec<String> = parameter_types(&descriptor)
+            .iter()
+            .map(|parameter| type_name(parameter))
+            .collect();
+
+        Some(format!(
+            "{}.{}({})",
+            external_class_name(&class_name),
+            method_name,
+            parameters.join(", ")
+        ))
+    }
+}
+
+/// Converts a field descriptor to the type name used in messages, e.g. `[I` to `int[]`
+fn type_name(descriptor: &str) -> String {
+    let element = descriptor.trim_start_matches('[');
+    let dimensions = descriptor.len() - element.len();
+
+    let name = match element {
+        "B" => "byte".to_string(),
+        "C" => "char".to_string(),
+        "D" => "double".to_string(),
+        "F" => "float".to_string(),
+        "I" => "int".to_string(),
+        "J" => "long".to_string(),
+        "S" => "short".to_string(),
+        "Z" => "boolean".to_string(),
+        _ => external_class_name(element.trim_start_matches('L').trim_end_matches(';')),
+    };
+
+    name + &"[]".repeat(dimensions)
+}
+
+/// Converts a binary class name to the name used in messages, `Object` and `String`
+/// are common enough to be printed without their package
+fn external_class_name(binary_name: &str) -> String {
+    match binary_name {
+        "java/lang/Object" => "Object".to_string(),
+        "java/lang/String" => "String".to_string(),
+        _ => binary_name.replace('/', "."),
+    }
+}
@@ -0,0 +1,338 @@
+/// Smallest and largest radix of the number conversions, `Character.MIN_RADIX` and
+/// `Character.MAX_RADIX`
+pub const MIN_RADIX: i32 = 2;
+pub const MAX_RADIX: i32 = 36;
+
+/// Bound of the exponents read, far past the exponent of any finite non zero value even
+/// written with a billion digits
+const MAX_EXPONENT: i64 = 1 << 40;
+
+/// The characters of the digits zero of the Unicode decimal digit ranges in the BMP,
+/// each followed by the nine other digits
+const DECIMAL_ZEROS: &[u16] = &[
+    0x0030, 0x0660, 0x06F0, 0x07C0, 0x0966, 0x09E6, 0x0A66, 0x0AE6, 0x0B66, 0x0BE6, 0x0C66, 0x0CE6,
+    0x0D66, 0x0DE6, 0x0E50, 0x0ED0, 0x0F20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80,
+    0x1A90, 0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0,
+    0xFF10,
+];
+
+/// The value of a character as a digit in a radix like `Character.digit`, which also
+/// accepts the decimal digits of other scripts, e.g. `١` or `１`, and the fullwidth Latin
+/// letters
+pub fn digit(c: u16, radix: u32) -> Option<u32> {
+    let value = match c {
+        0x0041..=0x005A => c - 0x0041 + 10,
+        0x0061..=0x007A => c - 0x0061 + 10,
+        0xFF21..=0xFF3A => c - 0xFF21 + 10,
+        0xFF41..=0xFF5A => c - 0xFF41 + 10,
+        _ => DECIMAL_ZEROS
+            .iter()
+            .find(|zero| (**zero..**zero + 10).contains(&c))
+            .map(|zero| c - zero)?,
+    };
+    (u32::from(value) < radix).then_some(u32::from(value))
+}
+
+/// Parses an integer in the given radix like `Long.parseLong`: an optional sign followed by
+/// at least one digit, nothing else, not even spaces, and a value from `min` to `max`
+///
+/// Fails with the message of the `NumberFormatException`, e.g. `For input string: "12a"` or
+/// `For input string: "zz" under radix 16`
+pub fn parse_integer(chars: &[u16], radix: i32, min: i64, max: i64) -> Result<i64, String> {
+    if radix < MIN_RADIX {
+        return Err(format!("radix {} less than Character.MIN_RADIX", radix));
+    }
+    if radix > MAX_RADIX {
+        return Err(format!("radix {} greater than Character.MAX_RADIX", radix));
+    }
+
+    let for_input_string = || match radix {
+        10 => format!("For input string: \"{}\"", String::from_utf16_lossy(chars)),
+        _ => format!(
+            "For input string: \"{}\" under radix {}",
+            String::from_utf16_lossy(chars),
+            radix
+        ),
+    };
+
+    let (negative, digits) = match chars.split_first() {
+        Some((&sign, digits)) if sign == b'-' as u16 => (true, digits),
+        Some((&sign, digits)) if sign == b'+' as u16 => (false, digits),
+        _ => (false, chars),
+    };
+    if digits.is_empty() {
+        return Err(for_input_string());
+    }
+
+    // The magnitude of the most negative value is one more than the largest value
+    let limit = match negative {
+        true => -(min as i128),
+        false => max as i128,
+    };
+
+    let mut magnitude: i128 = 0;
+    for c in digits {
+        let digit = digit(*c, radix as u32).ok_or_else(for_input_string)?;
+        magnitude = magnitude * radix as i128 + digit as i128;
+        if magnitude > limit {
+            return Err(for_input_string());
+        }
+    }
+
+    Ok(match negative {
+        true => (-magnitude) as i64,
+        false => magnitude as i64,
+    })
+}
+
+/// Parses a floating point number like `Double.parseDouble`, or `Float.parseFloat` for
+/// `single`, which gives a value that converts exactly to a float
+///
+/// Leading and trailing characters up to the space are ignored, the rest is an optional sign
+/// followed by `NaN`, `Infinity`, a decimal like `1.5`, `.5` or `1e-3`, or a hexadecimal like
+/// `0x1.8p1`, the last two with an optional `f`, `F`, `d` or `D` suffix. Fails with the
+/// message of the `NumberFormatException`, e.g. `empty String`
+pub fn parse_floating(chars: &[u16], single: bool) -> Result<f64, String> {
+    let start = chars.iter().position(|c| *c > b' ' as u16);
+    let end = chars.iter().rposition(|c| *c > b' ' as u16);
+    let trimmed = match (start, end) {
+        (Some(start), Some(end)) => &chars[start..=end],
+        _ => return Err("empty String".to_string()),
+    };
+
+    // The input of the other characters is not needed, they all fail the same way
+    let text: String = trimmed
+        .iter()
+        .map(|c| {
+            char::from_u32(u32::from(*c))
+                .filter(char::is_ascii)
+                .unwrap_or('\u{7f}')
+        })
+        .collect();
+    let for_input_string = || {
+        format!(
+            "For input string: \"{}\"",
+            String::from_utf16_lossy(trimmed)
+        )
+    };
+
+    let (negative, unsigned) = match text.as_bytes()[0] {
+        b'-' => (true, &text[1..]),
+        b'+' => (false, &text[1..]),
+        _ => (false, &text[..]),
+    };
+
+    let magnitude = match unsigned {
+        "NaN" => Some(f64::NAN),
+        "Infinity" => Some(f64::INFINITY),
+        _ if unsigned.starts_with("0x") || unsigned.starts_with("0X") => {
+            parse_hexadecimal(&unsigned[2..], single)
+        }
+        _ => parse_decimal(unsigned, single)?,
+    }
+    .ok_or_else(for_input_string)?;
+
+    Ok(if negative { -magnitude } else { magnitude })
+}
+
+/// Parses the digits of a decimal after its sign, `None` if they are malformed
+fn parse_decimal(text: &str, single: bool) -> Result<Option<f64>, String> {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    let mut digits = 0;
+    let mut point = false;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'0'..=b'9' => digits += 1,
+            b'.' if point => return Err("multiple points".to_string()),
+            b'.' => point = true,
+            _ => break,
+        }
+        i += 1;
+    }
+    if digits == 0 {
+        return Ok(None);
+    }
+    let mantissa = &text[..i];
+
+    let mut exponent: i64 = 0;
+    if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+        i += 1;
+        let negative = bytes.get(i) == Some(&b'-');
+        if matches!(bytes.get(i), Some(b'-' | b'+')) {
+            i += 1;
+        }
+
+        let digits = bytes[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 {
+            return Ok(None);
+        }
+
+        for c in &bytes[i..i + digits] {
+            exponent = (exponent * 10 + i64::from(c - b'0')).min(MAX_EXPONENT);
+        }
+        if negative {
+            exponent = -exponent;
+        }
+        i += digits;
+    }
+
+    if !valid_suffix(&bytes[i..]) {
+        return Ok(None);
+    }
+
+    // The standard library rounds the exact value to the nearest like the JDK does
+    let decimal = format!("{}e{}", mantissa, exponent);
+    Ok(match single {
+        true => decimal.parse::<f32>().ok().map(f64::from),
+        false => decimal.parse::<f64>().ok(),
+    })
+}
+
+/// Parses the digits of a hexadecimal after its `0x`, the exponent being required as in
+/// `0x1p3`, `None` if they are malformed
+fn parse_hexadecimal(text: &str, single: bool) -> Option<f64> {
+    let (significand, rest) = text.split_once(['p', 'P'])?;
+    let (integer, fraction) = significand.split_once('.').unwrap_or((significand, ""));
+    if integer.len() + fraction.len() == 0
+        || !integer
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|c| c.is_ascii_hexdigit())
+    {
+        return None;
+    }
+
+    let (negative, rest) = match rest.as_bytes().first() {
+        Some(b'-') => (true, &rest[1..]),
+        Some(b'+') => (false, &rest[1..]),
+        _ => (false, rest),
+    };
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    if digits == 0 || !valid_suffix(&rest.as_bytes()[digits..]) {
+        return None;
+    }
+    let mut exponent: i64 = 0;
+    for c in rest[..digits].bytes() {
+        exponent = (exponent * 10 + i64::from(c - b'0')).min(MAX_EXPONENT);
+    }
+    if negative {
+        exponent = -exponent;
+    }
+
+    // The value is significand * 2^exponent, the significand keeping its first 60 bits
+    // and whether any bit after them is set
+    let mut significand: u64 = 0;
+    let mut sticky = false;
+    exponent -= 4 * fraction.len() as i64;
+    for c in integer.bytes().chain(fraction.bytes()) {
+        let digit = (c as char).to_digit(16)? as u64;
+        if significand >> 56 == 0 {
+            significand = significand << 4 | digit;
+        } else {
+            sticky |= digit != 0;
+            exponent += 4;
+        }
+    }
+
+    Some(round_binary(significand, sticky, exponent, single))
+}
+
+/// Whether the characters after a decimal or hexadecimal are nothing or a single suffix
+fn valid_suffix(suffix: &[u8]) -> bool {
+    matches!(suffix, [] | [b'f' | b'F' | b'd' | b'D'])
+}
+
+/// Rounds significand * 2^exponent to the nearest double, or float for `single`, ties to
+/// the even one, with sticky telling whether bits were already dropped from the significand
+fn round_binary(significand: u64, sticky: bool, exponent: i64, single: bool) -> f64 {
+    if significand == 0 {
+        return 0.0;
+    }
+    let (precision, min_exponent) = match single {
+        true => (24, -126),
+        false => (53, -1022),
+    };
+
+    // Subnormal values have fewer bits, down to none for values from a half to the smallest
+    // value, and values below are zero
+    let bits = 64 - significand.leading_zeros() as i64;
+    let top = bits - 1 + exponent;
+    let kept = precision - (min_exponent - top).max(0);
+    if kept < 0 {
+        return 0.0;
+    }
+    let dropped = bits - kept;
+
+    let (mut rounded, mut scale) = (significand, exponent);
+    if dropped > 0 {
+        let half = 1u64 << (dropped - 1);
+        let remainder = significand & ((half << 1) - 1);
+        rounded = significand.checked_shr(dropped as u32).unwrap_or(0);
+        let above_half = remainder > half || (remainder == half && sticky);
+        if above_half || (remainder == half && rounded & 1 == 1) {
+            rounded += 1;
+        }
+        scale += dropped;
+    }
+
+    // The rounded value is exact in the wider double, scaling it by powers of two in steps
+    // keeps it exact until it overflows to infinity
+    let mut value = rounded as f64;
+    let step = if scale < 0 { -512 } else { 512 };
+    while scale != 0 {
+        let power = if scale.abs() < 512 { scale } else { step };
+        value *= 2f64.powi(power as i32);
+        scale -= power;
+        if value.is_infinite() || value == 0.0 {
+            break;
+        }
+    }
+
+    match single {
+        true => value as f32 as f64,
+        false => value,
+    }
+}
+
+/// Formats an integer in a radix like `Long.toString(long, int)`, with lower case letters
+/// and a minus sign, a radix out of range being replaced by 10
+pub fn to_string_radix(value: i64, radix: i32) -> String {
+    let radix = match radix {
+        MIN_RADIX..=MAX_RADIX => radix as u64,
+        _ => 10,
+    };
+
+    let mut magnitude = value.unsigned_abs();
+    let mut digits = Vec::new();
+    loop {
+        digits.push(char::from_digit((magnitude % radix) as u32, radix as u32).unwrap_or('0'));
+        magnitude /= radix;
+        if magnitude == 0 {
+            break;
+        }
+    }
+    if value < 0 {
+        digits.push('-');
+    }
+
+    digits.iter().rev().collect()
+}
+
+/// Formats the bits of an integer as an unsigned number in a power of two radix, like
+/// `Integer.toHexString` with 4 bits per digit or `Long.toBinaryString` with 1
+pub fn to_unsigned_string(bits: u64, bits_per_digit: u32) -> String {
+    let radix = 1u64 << bits_per_digit;
+    let mut remaining = bits;
+    let mut digits = Vec::new();
+    loop {
+        digits.push(char::from_digit((remaining % radix) as u32, radix as u32).unwrap_or('0'));
+        remaining >>= bits_per_digit;
+        if remaining == 0 {
+            break;
+        }
+    }
+
+    digits.iter().rev().collect()
+}
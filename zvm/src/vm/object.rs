@@ -0,0 +1,44 @@
+use std::{collections::HashMap, fmt, rc::Rc};
+
+use crate::vm::{class::Class, value::Value};
+
+/// An instance of a class created by the `new` instruction
+pub struct Object {
+    pub class: Rc<Class>,
+    /// Instance fields keyed by `<declaring class>.<field name>`, the same way
+    /// static fields are keyed in the runtime data area
+    pub fields: HashMap<String, Value>,
+}
+
+impl Object {
+    /// Creates a new instance with every instance field of the class hierarchy
+    /// set to its default value
+    pub fn new(class: Rc<Class>) -> Self {
+        let mut fields = HashMap::new();
+        let mut current = Some(class.clone());
+
+        while let Some(current_class) = current {
+            for field in current_class
+                .fields
+                .iter()
+                .filter(|field| !field.is_static())
+            {
+                fields.insert(
+                    format!("{}.{}", current_class.name, field.name),
+                    Value::default_for(&field.descriptor),
+                );
+            }
+
+            current = current_class.super_class.clone();
+        }
+
+        Self { class, fields }
+    }
+}
+
+impl fmt::Debug for Object {
+    // Only print the class name, printing the fields could recurse forever on cyclic objects
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Object({})", self.class.name)
+    }
+}
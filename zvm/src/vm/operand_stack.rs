@@ -31,6 +31,11 @@ impl OperandStack {
         self.stack.get(index)
     }
 
+    /// Iterates over the values of the stack, from the bottom to the top
+    pub fn iter(&self) -> impl Iterator<Item = &Value> {
+        self.stack.iter()
+    }
+
     pub fn len(&self) -> usize {
         self.stack.len()
     }
@@ -0,0 +1,635 @@
+/// A small backtracking regular expression engine, covering the part of `java.util.regex`
+/// syntax that `String.split` is used with in practice
+///
+/// Supported: literals, escapes ( `\t`, `\n`, `\r`, `\f`, `\e`, `\0`, `\xhh`, `\uhhhh` and escaped
+/// punctuation ), `.`, character classes with ranges and negation, the predefined classes
+/// `\d \D \s \S \w \W`, anchors `^ $ \b \B`, groups `( )` and `(?: )`, alternation `|`
+/// and the greedy and reluctant quantifiers `* + ? {n} {n,} {n,m}`.
+///
+/// Input and patterns are UTF-16 code units, a surrogate pair is matched as one code point
+/// like Java does.
+pub struct Regex {
+    alternatives: Vec<Vec<Node>>,
+}
+
+enum Node {
+    Char(u32),
+    /// `.`, any code point except line terminators
+    Any,
+    Class(Class),
+    /// `^`
+    Start,
+    /// `$`
+    End,
+    /// `\b` and `\B`
+    WordBoundary(bool),
+    /// A group, matching any of its alternatives
+    Group(Vec<Vec<Node>>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: usize,
+        greedy: bool,
+    },
+}
+
+/// A character class, e.g. `[a-z_]`, `[a-z&&[^aeiou]]` or `\d`
+struct Class {
+    negated: bool,
+    items: Vec<ClassItem>,
+    /// Operands of `&&`, a code point must also match all of them
+    intersections: Vec<Class>,
+}
+
+enum ClassItem {
+    Range(u32, u32),
+    Class(Class),
+}
+
+impl Class {
+    fn matches(&self, c: u32) -> bool {
+        let found = self.items.iter().any(|item| match item {
+            ClassItem::Range(low, high) => (*low..=*high).contains(&c),
+            ClassItem::Class(class) => class.matches(c),
+        }) && self.intersections.iter().all(|class| class.matches(c));
+
+        found != self.negated
+    }
+
+    /// The class of a predefined character class escape like `\d`
+    fn predefined(escape: char) -> Option<Self> {
+        let (negated, items) = match escape {
+            'd' | 'D' => (
+                escape == 'D',
+                vec![ClassItem::Range('0' as u32, '9' as u32)],
+            ),
+            's' | 'S' => (
+                escape == 'S',
+                // AS SPECIFIED BY THE SPECS: [ \t\n\x0B\f\r]
+                vec![
+                    ClassItem::Range(' ' as u32, ' ' as u32),
+                    ClassItem::Range(0x09, 0x0D),
+                ],
+            ),
+            'w' | 'W' => (
+                escape == 'W',
+                vec![
+                    ClassItem::Range('a' as u32, 'z' as u32),
+                    ClassItem::Range('A' as u32, 'Z' as u32),
+                    ClassItem::Range('0' as u32, '9' as u32),
+                    ClassItem::Range('_' as u32, '_' as u32),
+                ],
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            negated,
+            items,
+            intersections: Vec::new(),
+        })
+    }
+}
+
+/// Checks if a code point is matched by `\w`, used for word boundaries
+fn is_word(c: u32) -> bool {
+    Class::predefined('w').is_some_and(|class| class.matches(c))
+}
+
+/// Reads the code point starting at the given index, along with its length in code units,
+/// an unpaired surrogate is a code point of its own
+fn code_point_at(chars: &[u16], index: usize) -> Option<(u32, usize)> {
+    let high = *chars.get(index)? as u32;
+
+    if let Some(&low) = chars.get(index + 1) {
+        let low = low as u32;
+        if (0xD800..0xDC00).contains(&high) && (0xDC00..0xE000).contains(&low) {
+            return Some((0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00), 2));
+        }
+    }
+
+    Some((high, 1))
+}
+
+/// Reads the code point ending right before the given index
+fn code_point_before(chars: &[u16], index: usize) -> Option<u32> {
+    if index == 0 {
+        return None;
+    }
+
+    if let Some((code_point, 2)) = index.checked_sub(2).and_then(|i| code_point_at(chars, i)) {
+        return Some(code_point);
+    }
+
+    code_point_at(chars, index - 1).map(|(code_point, _)| code_point)
+}
+
+struct Parser<'a> {
+    pattern: &'a [u16],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error<T>(&self, description: &str) -> Result<T, String> {
+        Err(format!(
+            "{} near index {} in regular expression \"{}\"",
+            description,
+            self.position,
+            String::from_utf16_lossy(self.pattern)
+        ))
+    }
+
+    fn peek(&self) -> Option<u32> {
+        code_point_at(self.pattern, self.position).map(|(code_point, _)| code_point)
+    }
+
+    fn next(&mut self) -> Option<u32> {
+        let (code_point, length) = code_point_at(self.pattern, self.position)?;
+        self.position += length;
+        Some(code_point)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c as u32) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// alternatives := sequence ( '|' sequence )*
+    fn parse_alternatives(&mut self) -> Result<Vec<Vec<Node>>, String> {
+        let mut alternatives = vec![self.parse_sequence()?];
+
+        while self.eat('|') {
+            alternatives.push(self.parse_sequence()?);
+        }
+
+        Ok(alternatives)
+    }
+
+    /// sequence := ( atom quantifier? )*
+    fn parse_sequence(&mut self) -> Result<Vec<Node>, String> {
+        let mut nodes = Vec::new();
+
+        while let Some(c) = self.peek() {
+            if c == '|' as u32 || c == ')' as u32 {
+                break;
+            }
+
+            let atom = self.parse_atom()?;
+            nodes.push(self.parse_quantifier(atom)?);
+        }
+
+        Ok(nodes)
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, String> {
+        let Some(c) = self.next() else {
+            return self.error("Unexpected end of pattern");
+        };
+
+        let node = match char::from_u32(c) {
+            Some('.') => Node::Any,
+            Some('^') => Node::Start,
+            Some('$') => Node::End,
+            Some('[') => Node::Class(self.parse_class()?),
+            Some('(') => {
+                if self.eat('?') && !self.eat(':') {
+                    return self.error("Unsupported group construct");
+                }
+
+                let alternatives = self.parse_alternatives()?;
+
+                if !self.eat(')') {
+                    return self.error("Unclosed group");
+                }
+
+                Node::Group(alternatives)
+            }
+            Some(')') => return self.error("Unmatched closing ')'"),
+            Some('*') | Some('+') | Some('?') | Some('{') => {
+                return self.error("Dangling meta character");
+            }
+            Some('\\') => match self.peek().and_then(char::from_u32) {
+                Some(boundary @ ('b' | 'B')) => {
+                    self.position += 1;
+                    Node::WordBoundary(boundary == 'b')
+                }
+                Some(escape) => match Class::predefined(escape) {
+                    Some(class) => {
+                        self.position += 1;
+                        Node::Class(class)
+                    }
+                    None => Node::Char(self.parse_escape()?),
+                },
+                None => return self.error("Unexpected internal error"),
+            },
+            _ => Node::Char(c),
+        };
+
+        Ok(node)
+    }
+
+    /// Parses the escape following a `\` that stands for a single code point
+    fn parse_escape(&mut self) -> Result<u32, String> {
+        let Some(c) = self.next() else {
+            return self.error("Unexpected internal error");
+        };
+
+        let code_point = match char::from_u32(c) {
+            Some('t') => 0x09,
+            Some('n') => 0x0A,
+            Some('r') => 0x0D,
+            Some('f') => 0x0C,
+            Some('a') => 0x07,
+            Some('e') => 0x1B,
+            Some('0') => self.parse_number(8, 1, 3)?,
+            Some('x') => self.parse_number(16, 2, 2)?,
+            Some('u') => self.parse_number(16, 4, 4)?,
+            // AS SPECIFIED BY THE SPECS: any non-alphabetic character can be escaped
+            Some(other) if !other.is_ascii_alphanumeric() => c,
+            _ => return self.error("Illegal/unsupported escape sequence"),
+        };
+
+        Ok(code_point)
+    }
+
+    fn parse_number(&mut self, radix: u32, min: usize, max: usize) -> Result<u32, String> {
+        let mut value = 0;
+        let mut digits = 0;
+
+        while digits < max {
+            match self
+                .peek()
+                .and_then(char::from_u32)
+                .and_then(|c| c.to_digit(radix))
+            {
+                Some(digit) => {
+                    value = value * radix + digit;
+                    digits += 1;
+                    self.position += 1;
+                }
+                None => break,
+            }
+        }
+
+        if digits < min {
+            return self.error("Illegal escape sequence");
+        }
+
+        Ok(value)
+    }
+
+    /// Parses a character class, after its opening `[`
+    fn parse_class(&mut self) -> Result<Class, String> {
+        let negated = self.eat('^');
+        self.parse_class_body(negated, true)
+    }
+
+    /// Parses the items of a character class up to its closing `]`, a `]` right at the start
+    /// of a class is a literal
+    fn parse_class_body(&mut self, negated: bool, mut first: bool) -> Result<Class, String> {
+        let mut items = Vec::new();
+        let mut intersections = Vec::new();
+
+        loop {
+            let Some(c) = self.peek() else {
+                return self.error("Unclosed character class");
+            };
+
+            // A ']' right after the opening bracket is a literal
+            if c == ']' as u32 && !first {
+                self.position += 1;
+                break;
+            }
+            first = false;
+
+            if c == '[' as u32 {
+                self.position += 1;
+                items.push(ClassItem::Class(self.parse_class()?));
+                continue;
+            }
+
+            // The rest of the class after `&&` is intersected with what precedes it
+            if c == '&' as u32 && self.pattern.get(self.position + 1) == Some(&('&' as u16)) {
+                self.position += 2;
+                intersections.push(self.parse_class_body(false, false)?);
+                break;
+            }
+
+            let low = match self.parse_class_char()? {
+                Ok(low) => low,
+                Err(class) => {
+                    items.push(ClassItem::Class(class));
+                    continue;
+                }
+            };
+
+            // A '-' is a range unless it is the last character of the class
+            let is_range = self.peek() == Some('-' as u32)
+                && code_point_at(self.pattern, self.position + 1)
+                    .is_some_and(|(next, _)| next != ']' as u32);
+
+            if !is_range {
+                items.push(ClassItem::Range(low, low));
+                continue;
+            }
+
+            self.position += 1;
+
+            let high = match self.parse_class_char()? {
+                Ok(high) => high,
+                Err(_) => return self.error("Illegal character range"),
+            };
+
+            if high < low {
+                return self.error("Illegal character range");
+            }
+
+            items.push(ClassItem::Range(low, high));
+        }
+
+        Ok(Class {
+            negated,
+            items,
+            intersections,
+        })
+    }
+
+    /// Parses a single code point of a character class, or a predefined class like `\d`
+    fn parse_class_char(&mut self) -> Result<Result<u32, Class>, String> {
+        let Some(c) = self.next() else {
+            return self.error("Unclosed character class");
+        };
+
+        if c != '\\' as u32 {
+            return Ok(Ok(c));
+        }
+
+        if let Some(class) = self
+            .peek()
+            .and_then(char::from_u32)
+            .and_then(Class::predefined)
+        {
+            self.position += 1;
+            return Ok(Err(class));
+        }
+
+        Ok(Ok(self.parse_escape()?))
+    }
+
+    fn parse_quantifier(&mut self, atom: Node) -> Result<Node, String> {
+        let (min, max) = match self.peek().and_then(char::from_u32) {
+            Some('*') => (0, usize::MAX),
+            Some('+') => (1, usize::MAX),
+            Some('?') => (0, 1),
+            Some('{') => {
+                self.position += 1;
+                let min = self.parse_count()?;
+                let max = if self.eat(',') {
+                    match self.peek() {
+                        Some(c) if c == '}' as u32 => usize::MAX,
+                        _ => self.parse_count()?,
+                    }
+                } else {
+                    min
+                };
+
+                if self.peek() != Some('}' as u32) {
+                    return self.error("Unclosed counted closure");
+                }
+
+                if max < min {
+                    return self.error("Illegal repetition range");
+                }
+
+                (min, max)
+            }
+            _ => return Ok(atom),
+        };
+
+        self.position += 1;
+
+        if matches!(atom, Node::Start | Node::End | Node::WordBoundary(_)) && max > 1 {
+            return self.error("Dangling meta character");
+        }
+
+        let greedy = !self.eat('?');
+
+        if self.peek() == Some('+' as u32) {
+            return self.error("Possessive quantifiers are not supported");
+        }
+
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+            greedy,
+        })
+    }
+
+    fn parse_count(&mut self) -> Result<usize, String> {
+        let start = self.position;
+        let mut count: usize = 0;
+
+        while let Some(digit) = self
+            .peek()
+            .and_then(char::from_u32)
+            .and_then(|c| c.to_digit(10))
+        {
+            count = count.saturating_mul(10).saturating_add(digit as usize);
+            self.position += 1;
+        }
+
+        if self.position == start {
+            return self.error("Illegal repetition");
+        }
+
+        Ok(count)
+    }
+}
+
+impl Regex {
+    /// Compiles a pattern, the error describes why the pattern is invalid or unsupported
+    pub fn new(pattern: &[u16]) -> Result<Self, String> {
+        let mut parser = Parser {
+            pattern,
+            position: 0,
+        };
+
+        let alternatives = parser.parse_alternatives()?;
+
+        if parser.position < pattern.len() {
+            return parser.error("Unmatched closing ')'");
+        }
+
+        Ok(Self { alternatives })
+    }
+
+    /// Finds the first match starting at or after the given index, as the range of code units
+    /// it spans
+    pub fn find(&self, input: &[u16], from: usize) -> Option<(usize, usize)> {
+        let mut start = from;
+
+        while start <= input.len() {
+            let mut end = None;
+
+            if self.match_alternatives(&self.alternatives, input, start, &mut |matched| {
+                end = Some(matched);
+                true
+            }) {
+                return end.map(|end| (start, end));
+            }
+
+            start += code_point_at(input, start).map_or(1, |(_, length)| length);
+        }
+
+        None
+    }
+
+    /// Splits the input around the matches of the pattern the way `String.split` does
+    pub fn split(&self, input: &[u16], limit: i32) -> Vec<Vec<u16>> {
+        let mut parts: Vec<Vec<u16>> = Vec::new();
+        let limited = limit > 0;
+        let mut index = 0;
+        let mut from = 0;
+
+        while let Some((start, end)) = self.find(input, from) {
+            // The next search starts after the match, one code unit further for empty matches
+            from = if start == end { end + 1 } else { end };
+
+            if !limited || parts.len() < (limit - 1) as usize {
+                // AS SPECIFIED BY THE SPECS: a zero-width match at the beginning
+                // never produces an empty leading substring
+                if index == 0 && start == 0 && start == end {
+                    continue;
+                }
+
+                parts.push(input[index..start].to_vec());
+                index = end;
+            } else {
+                break;
+            }
+        }
+
+        // No match, the result has one element, the input itself
+        if index == 0 {
+            return vec![input.to_vec()];
+        }
+
+        parts.push(input[index..].to_vec());
+
+        // A limit of zero discards the trailing empty strings
+        if limit == 0 {
+            while parts.last().is_some_and(|part| part.is_empty()) {
+                parts.pop();
+            }
+        }
+
+        parts
+    }
+
+    fn match_alternatives(
+        &self,
+        alternatives: &[Vec<Node>],
+        input: &[u16],
+        position: usize,
+        next: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        alternatives
+            .iter()
+            .any(|sequence| self.match_sequence(sequence, input, position, next))
+    }
+
+    /// Matches a sequence of nodes, then calls `next` with the end position of each way
+    /// the sequence matches until it returns true ( continuation passing backtracking )
+    fn match_sequence(
+        &self,
+        nodes: &[Node],
+        input: &[u16],
+        position: usize,
+        next: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        match nodes.split_first() {
+            None => next(position),
+            Some((node, rest)) => self.match_node(node, input, position, &mut |end| {
+                self.match_sequence(rest, input, end, next)
+            }),
+        }
+    }
+
+    fn match_node(
+        &self,
+        node: &Node,
+        input: &[u16],
+        position: usize,
+        next: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        match node {
+            Node::Char(expected) => match code_point_at(input, position) {
+                Some((c, length)) if c == *expected => next(position + length),
+                _ => false,
+            },
+            Node::Any => match code_point_at(input, position) {
+                Some((c, length)) if !matches!(c, 0x0A | 0x0D | 0x85 | 0x2028 | 0x2029) => {
+                    next(position + length)
+                }
+                _ => false,
+            },
+            Node::Class(class) => match code_point_at(input, position) {
+                Some((c, length)) if class.matches(c) => next(position + length),
+                _ => false,
+            },
+            Node::Start => position == 0 && next(position),
+            Node::End => position == input.len() && next(position),
+            Node::WordBoundary(expected) => {
+                let before = code_point_before(input, position).is_some_and(is_word);
+                let after = code_point_at(input, position).is_some_and(|(c, _)| is_word(c));
+                (before != after) == *expected && next(position)
+            }
+            Node::Group(alternatives) => {
+                self.match_alternatives(alternatives, input, position, next)
+            }
+            Node::Repeat {
+                node,
+                min,
+                max,
+                greedy,
+            } => self.match_repeat(node, *min, *max, *greedy, 0, input, position, next),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn match_repeat(
+        &self,
+        node: &Node,
+        min: usize,
+        max: usize,
+        greedy: bool,
+        count: usize,
+        input: &[u16],
+        position: usize,
+        next: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        let once_more = |next: &mut dyn FnMut(usize) -> bool| {
+            count < max
+                && self.match_node(node, input, position, &mut |end| {
+                    // An iteration matching the empty string would repeat forever
+                    (end != position || count < min)
+                        && self.match_repeat(node, min, max, greedy, count + 1, input, end, next)
+                })
+        };
+
+        if count < min {
+            return once_more(next);
+        }
+
+        // Greedy quantifiers first try one more iteration, reluctant ones first try the rest
+        match greedy {
+            true => once_more(next) || next(position),
+            false => next(position) || once_more(next),
+        }
+    }
+}
@@ -1,16 +1,194 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
-use crate::vm::value::Value;
+use crate::vm::{
+    array::Array,
+    call_site::{CallSite, LambdaForm},
+    call_stack::CallStack,
+    class::Method,
+    class_loader::ClassLoader,
+    console::Console,
+    error::VmError,
+    heap::{DEFAULT_MAX_HEAP_SIZE, Heap, Reservation},
+    native::NativeRegistry,
+    object::Object,
+    string::{STRING_CLASS, STRING_VALUE},
+    value::Value,
+};
 
 /// Runtime data area
 pub struct RuntimeDataArea {
     pub static_fields: HashMap<String, Value>,
+    /// Method area, holds every loaded and linked class
+    pub class_loader: ClassLoader,
+    /// Classes whose initialization has already started
+    pub initialized_classes: HashSet<String>,
+    /// Classes whose initialization failed, keyed by class name, with the error recording the
+    /// failure that every later use of the class gets as the cause of its `NoClassDefFoundError`
+    pub erroneous_classes: HashMap<String, Value>,
+    /// Holds every object and array instance
+    pub heap: Heap,
+    /// String pool, the interned strings keyed by their UTF-16 code units
+    pub interned_strings: HashMap<Vec<u16>, Value>,
+    /// Linked `invokedynamic` call sites, keyed by the method holding the instruction
+    /// and the pc of the instruction
+    pub call_sites: HashMap<(*const Method, usize), Rc<CallSite>>,
+    /// Last type that passed each checkcast or instanceof instruction, keyed like call sites
+    pub type_checks: HashMap<(*const Method, usize), String>,
+    /// Classes spun for lambda call sites, keyed by class name
+    pub lambda_forms: HashMap<String, Rc<LambdaForm>>,
+    /// Objects whose monitors the static synchronized methods of each class enter,
+    /// keyed by class name ( they stand in for the `java/lang/Class` instances )
+    pub class_monitors: HashMap<String, Value>,
+    /// Rust implementations of native methods and intrinsics
+    pub natives: NativeRegistry,
+    /// Instances `valueOf` of the box classes returns for the values it caches,
+    /// keyed by class name and value
+    pub boxes: HashMap<(String, i64), Value>,
+    /// The standard streams `System.out` and `System.err` write to
+    pub console: Console,
+    /// Whether the parts of the JDK and of the class file format the VM does not implement
+    /// throw an error the program sees, otherwise undefined opcodes and unsupported constants
+    /// are skipped with a warning
+    pub strict: bool,
+    /// Address the nested runs of the dispatch loop must not go past on the host stack,
+    /// see `call_stack::host_stack_end`
+    pub host_stack_end: usize,
 }
 
 impl RuntimeDataArea {
     pub fn new() -> Self {
         Self {
             static_fields: HashMap::new(),
+            class_loader: ClassLoader::new(),
+            initialized_classes: HashSet::new(),
+            erroneous_classes: HashMap::new(),
+            heap: Heap::new(DEFAULT_MAX_HEAP_SIZE),
+            interned_strings: HashMap::new(),
+            call_sites: HashMap::new(),
+            type_checks: HashMap::new(),
+            lambda_forms: HashMap::new(),
+            class_monitors: HashMap::new(),
+            natives: NativeRegistry::new(),
+            boxes: HashMap::new(),
+            console: Console::new(),
+            strict: true,
+            host_stack_end: 0,
         }
     }
+
+    /// Allocates a new `java/lang/String` holding the given UTF-16 code units
+    pub fn new_string(
+        &mut self,
+        chars: Vec<u16>,
+        call_stack: &CallStack,
+    ) -> Result<Value, VmError> {
+        let class = self.class_loader.load_class(STRING_CLASS)?;
+        let mut string = Object::new(class);
+        let value = Array::from_chars(chars);
+
+        // The value is only reachable from the string, both fit in one reservation
+        let mut reservation = self.reserve(
+            Heap::object_size(&string) + Heap::size_of_array(&value),
+            call_stack,
+        )?;
+        let value = self.heap.allocate_array(&mut reservation, value);
+        string.fields.insert(STRING_VALUE.to_string(), value);
+
+        Ok(self.heap.allocate_object(&mut reservation, string))
+    }
+
+    /// Allocates a new `java/lang/String` holding the given Rust string
+    pub fn new_string_from(
+        &mut self,
+        string: &str,
+        call_stack: &CallStack,
+    ) -> Result<Value, VmError> {
+        self.new_string(string.encode_utf16().collect(), call_stack)
+    }
+
+    /// Returns the string of the string pool holding the given UTF-16 code units,
+    /// adding a new one to the pool if there is none yet
+    /// AS SPECIFIED BY THE SPECS: §5.1, string literals with the same contents
+    /// are the same instance
+    pub fn intern_string(
+        &mut self,
+        chars: Vec<u16>,
+        call_stack: &CallStack,
+    ) -> Result<Value, VmError> {
+        if let Some(string) = self.interned_strings.get(&chars) {
+            return Ok(string.clone());
+        }
+
+        let string = self.new_string(chars.clone(), call_stack)?;
+        self.interned_strings.insert(chars, string.clone());
+        Ok(string)
+    }
+
+    /// Returns the object whose monitor the static synchronized methods of the given
+    /// class enter, allocating it on first use
+    pub fn class_monitor(
+        &mut self,
+        class_name: &str,
+        call_stack: &CallStack,
+    ) -> Result<Value, VmError> {
+        if let Some(monitor) = self.class_monitors.get(class_name) {
+            return Ok(monitor.clone());
+        }
+
+        let class = self.class_loader.load_class("java/lang/Object")?;
+        let monitor = self.allocate_object(Object::new(class), call_stack)?;
+        self.class_monitors
+            .insert(class_name.to_string(), monitor.clone());
+        Ok(monitor)
+    }
+
+    /// Allocates an object and returns a reference to it, see `reserve`
+    pub fn allocate_object(
+        &mut self,
+        object: Object,
+        call_stack: &CallStack,
+    ) -> Result<Value, VmError> {
+        let mut reservation = self.reserve(Heap::object_size(&object), call_stack)?;
+        Ok(self.heap.allocate_object(&mut reservation, object))
+    }
+
+    /// Allocates an array and returns a reference to it, see `reserve`
+    pub fn allocate_array(
+        &mut self,
+        array: Array,
+        call_stack: &CallStack,
+    ) -> Result<Value, VmError> {
+        let mut reservation = self.reserve(Heap::size_of_array(&array), call_stack)?;
+        Ok(self.heap.allocate_array(&mut reservation, array))
+    }
+
+    /// Makes room in the heap for allocations of the given total size, or throws an
+    /// OutOfMemoryError if they do not fit even after a collection
+    ///
+    /// The roots of the collection are the static fields, the interned strings, the class
+    /// monitors, the cached boxes, the initialization errors, the operand stacks and local
+    /// variables of every frame of the call stack, the threads kept alive by the scheduler
+    /// along with the frames of the parked ones, and the values pinned in the heap, which the
+    /// callers holding references outside of any frame across several reservations pin
+    pub fn reserve(&mut self, size: usize, call_stack: &CallStack) -> Result<Reservation, VmError> {
+        self.heap
+            .reserve(size, |heap| {
+                let thread_roots = call_stack.thread.scheduler.roots();
+
+                heap.collect(
+                    self.static_fields
+                        .values()
+                        .chain(self.interned_strings.values())
+                        .chain(self.class_monitors.values())
+                        .chain(self.boxes.values())
+                        .chain(self.erroneous_classes.values())
+                        .chain(call_stack.roots())
+                        .chain(thread_roots.iter()),
+                )
+            })
+            .map_err(|_| VmError::throw("java/lang/OutOfMemoryError", "Java heap space"))
+    }
 }
@@ -0,0 +1,441 @@
+use crate::parser::{
+    class_file::ClassFile,
+    descriptor::{is_wide, parameter_types, return_type},
+    opcode::{Opcode, instruction_length, opcode_at},
+};
+
+/// Value of a simulated operand stack
+pub trait StackValue: Copy {
+    /// Whether the value is a long or a double ( computational type category 2 )
+    fn is_wide(&self) -> bool;
+}
+
+/// Applies the effect of the instruction at the given pc to a simulated operand stack, where
+/// `pushed` makes the values the instruction pushes from whether they are wide, and returns
+/// the pcs of the instructions that may execute next.
+/// Returns `None` for jsr and ret and when the code is malformed.
+pub fn execute<T: StackValue>(
+    code: &[u8],
+    class_file: &ClassFile,
+    pc: usize,
+    stack: &mut Vec<T>,
+    pushed: impl Fn(bool) -> T,
+) -> Option<Vec<usize>> {
+    let opcode = opcode_at(code, pc)?;
+
+    match opcode {
+        // checkcast leaves the checked reference, and thus its source, on the stack
+        Opcode::Nop
+        | Opcode::Iinc
+        | Opcode::Goto
+        | Opcode::Goto_w
+        | Opcode::Return
+        | Opcode::Checkcast => {}
+        Opcode::Aconst_null
+        | Opcode::Iconstm1
+        | Opcode::Iconst0
+        | Opcode::Iconst1
+        | Opcode::Iconst2
+        | Opcode::Iconst3
+        | Opcode::Iconst4
+        | Opcode::Iconst5
+        | Opcode::Fconst0
+        | Opcode::Fconst1
+        | Opcode::Fconst2
+        | Opcode::Bipush
+        | Opcode::Sipush
+        | Opcode::Ldc
+        | Opcode::Ldc_w
+        | Opcode::Iload
+        | Opcode::Fload
+        | Opcode::Aload
+        | Opcode::Iload0
+        | Opcode::Iload1
+        | Opcode::Iload2
+        | Opcode::Iload3
+        | Opcode::Fload0
+        | Opcode::Fload1
+        | Opcode::Fload2
+        | Opcode::Fload3
+        | Opcode::Aload_0
+        | Opcode::Aload_1
+        | Opcode::Aload_2
+        | Opcode::Aload_3
+        | Opcode::New => stack.push(pushed(false)),
+        Opcode::Lconst0
+        | Opcode::Lconst1
+        | Opcode::Dconst0
+        | Opcode::Dconst1
+        | Opcode::Ldc2_w
+        | Opcode::Lload
+        | Opcode::Dload
+        | Opcode::Lload0
+        | Opcode::Lload1
+        | Opcode::Lload2
+        | Opcode::Lload3
+        | Opcode::Dload0
+        | Opcode::Dload1
+        | Opcode::Dload2
+        | Opcode::Dload3 => stack.push(pushed(true)),
+        Opcode::Iaload
+        | Opcode::Faload
+        | Opcode::Aaload
+        | Opcode::Baload
+        | Opcode::Caload
+        | Opcode::Saload
+        | Opcode::Iadd
+        | Opcode::Fadd
+        | Opcode::Isub
+        | Opcode::Fsub
+        | Opcode::Imul
+        | Opcode::Fmul
+        | Opcode::Idiv
+        | Opcode::Fdiv
+        | Opcode::Irem
+        | Opcode::Frem
+        | Opcode::Ishl
+        | Opcode::Ishr
+        | Opcode::Iushr
+        | Opcode::Iand
+        | Opcode::Ior
+        | Opcode::Ixor
+        | Opcode::Lcmp
+        | Opcode::Fcmpl
+        | Opcode::Fcmpg
+        | Opcode::Dcmpl
+        | Opcode::Dcmpg => {
+            pop(stack, 2)?;
+            stack.push(pushed(false));
+        }
+        Opcode::Laload
+        | Opcode::Daload
+        | Opcode::Ladd
+        | Opcode::Dadd
+        | Opcode::Lsub
+        | Opcode::Dsub
+        | Opcode::Lmul
+        | Opcode::Dmul
+        | Opcode::Ldiv
+        | Opcode::Ddiv
+        | Opcode::Lrem
+        | Opcode::Drem
+        | Opcode::Lshl
+        | Opcode::Lshr
+        | Opcode::Lushr
+        | Opcode::Land
+        | Opcode::Lor
+        | Opcode::Lxor => {
+            pop(stack, 2)?;
+            stack.push(pushed(true));
+        }
+        Opcode::Ineg
+        | Opcode::Fneg
+        | Opcode::L2i
+        | Opcode::L2f
+        | Opcode::F2i
+        | Opcode::D2i
+        | Opcode::D2f
+        | Opcode::I2f
+        | Opcode::I2b
+        | Opcode::I2c
+        | Opcode::I2s
+        | Opcode::Newarray
+        | Opcode::Anewarray
+        | Opcode::Arraylength
+        | Opcode::Instanceof => {
+            pop(stack, 1)?;
+            stack.push(pushed(false));
+        }
+        Opcode::Lneg
+        | Opcode::Dneg
+        | Opcode::I2l
+        | Opcode::I2d
+        | Opcode::L2d
+        | Opcode::F2l
+        | Opcode::F2d
+        | Opcode::D2l => {
+            pop(stack, 1)?;
+            stack.push(pushed(true));
+        }
+        Opcode::Istore
+        | Opcode::Lstore
+        | Opcode::Fstore
+        | Opcode::Dstore
+        | Opcode::Astore
+        | Opcode::Istore_0
+        | Opcode::Istore_1
+        | Opcode::Istore_2
+        | Opcode::Istore_3
+        | Opcode::Lstore_0
+        | Opcode::Lstore_1
+        | Opcode::Lstore_2
+        | Opcode::Lstore_3
+        | Opcode::Fstore_0
+        | Opcode::Fstore_1
+        | Opcode::Fstore_2
+        | Opcode::Fstore_3
+        | Opcode::Dstore_0
+        | Opcode::Dstore_1
+        | Opcode::Dstore_2
+        | Opcode::Dstore_3
+        | Opcode::Astore_0
+        | Opcode::Astore_1
+        | Opcode::Astore_2
+        | Opcode::Astore_3
+        | Opcode::Pop
+        | Opcode::Ifeq
+        | Opcode::Ifne
+        | Opcode::Iflt
+        | Opcode::Ifge
+        | Opcode::Ifgt
+        | Opcode::Ifle
+        | Opcode::Ifnull
+        | Opcode::Ifnonnull
+        | Opcode::Tableswitch
+        | Opcode::Lookupswitch
+        | Opcode::Ireturn
+        | Opcode::Lreturn
+        | Opcode::Freturn
+        | Opcode::Dreturn
+        | Opcode::Areturn
+        | Opcode::Athrow
+        | Opcode::Putstatic
+        | Opcode::Monitorenter
+        | Opcode::Monitorexit => pop(stack, 1)?,
+        Opcode::If_icmpeq
+        | Opcode::If_icmpne
+        | Opcode::If_icmplt
+        | Opcode::If_icmpge
+        | Opcode::If_icmpgt
+        | Opcode::If_icmple
+        | Opcode::If_acmpeq
+        | Opcode::If_acmpne
+        | Opcode::Putfield => pop(stack, 2)?,
+        Opcode::Iastore
+        | Opcode::Lastore
+        | Opcode::Fastore
+        | Opcode::Dastore
+        | Opcode::Aastore
+        | Opcode::Bastore
+        | Opcode::Castore
+        | Opcode::Sastore => pop(stack, 3)?,
+        Opcode::Pop2 => {
+            let count = if stack.last()?.is_wide() { 1 } else { 2 };
+            pop(stack, count)?;
+        }
+        Opcode::Dup => stack.push(*stack.last()?),
+        Opcode::Dup_x1 => {
+            let value1 = stack.pop()?;
+            let value2 = stack.pop()?;
+            stack.extend([value1, value2, value1]);
+        }
+        Opcode::Dup_x2 => {
+            let value1 = stack.pop()?;
+            let value2 = stack.pop()?;
+
+            if value2.is_wide() {
+                stack.extend([value1, value2, value1]);
+            } else {
+                let value3 = stack.pop()?;
+                stack.extend([value1, value3, value2, value1]);
+            }
+        }
+        Opcode::Dup2 => {
+            let value1 = stack.pop()?;
+
+            if value1.is_wide() {
+                stack.extend([value1, value1]);
+            } else {
+                let value2 = stack.pop()?;
+                stack.extend([value2, value1, value2, value1]);
+            }
+        }
+        Opcode::Dup2_x1 => {
+            let value1 = stack.pop()?;
+            let value2 = stack.pop()?;
+
+            if value1.is_wide() {
+                stack.extend([value1, value2, value1]);
+            } else {
+                let value3 = stack.pop()?;
+                stack.extend([value2, value1, value3, value2, value1]);
+            }
+        }
+        Opcode::Dup2_x2 => {
+            let value1 = stack.pop()?;
+            let value2 = stack.pop()?;
+
+            match (value1.is_wide(), value2.is_wide()) {
+                (true, true) => stack.extend([value1, value2, value1]),
+                (true, false) => {
+                    let value3 = stack.pop()?;
+                    stack.extend([value1, value3, value2, value1]);
+                }
+                (false, _) => {
+                    let value3 = stack.pop()?;
+
+                    if value3.is_wide() {
+                        stack.extend([value2, value1, value3, value2, value1]);
+                    } else {
+                        let value4 = stack.pop()?;
+                        stack.extend([value2, value1, value4, value3, value2, value1]);
+                    }
+                }
+            }
+        }
+        Opcode::Swap => {
+            let value1 = stack.pop()?;
+            let value2 = stack.pop()?;
+            stack.extend([value1, value2]);
+        }
+        Opcode::Getstatic => stack.push(pushed(is_wide(&field_ref(code, class_file, pc)?.2))),
+        Opcode::Getfield => {
+            pop(stack, 1)?;
+            stack.push(pushed(is_wide(&field_ref(code, class_file, pc)?.2)));
+        }
+        Opcode::Invokevirtual
+        | Opcode::Invokespecial
+        | Opcode::Invokestatic
+        | Opcode::Invokeinterface
+        | Opcode::Invokedynamic => {
+            let descriptor = if let Opcode::Invokedynamic = opcode {
+                class_file
+                    .get_invoke_dynamic_info(read_u16(code, pc + 1)?)?
+                    .2
+            } else {
+                method_ref(code, class_file, pc)?.2
+            };
+            let receiver = match opcode {
+                Opcode::Invokestatic | Opcode::Invokedynamic => 0,
+                _ => 1,
+            };
+
+            pop(stack, parameter_types(&descriptor).len() + receiver)?;
+
+            match return_type(&descriptor) {
+                "V" => {}
+                returned => stack.push(pushed(is_wide(returned))),
+            }
+        }
+        Opcode::Multianewarray => {
+            pop(stack, *code.get(pc + 3)? as usize)?;
+            stack.push(pushed(false));
+        }
+        Opcode::Wide => match opcode_at(code, pc + 1)? {
+            Opcode::Iinc => {}
+            Opcode::Iload | Opcode::Fload | Opcode::Aload => stack.push(pushed(false)),
+            Opcode::Lload | Opcode::Dload => stack.push(pushed(true)),
+            Opcode::Istore | Opcode::Lstore | Opcode::Fstore | Opcode::Dstore | Opcode::Astore => {
+                pop(stack, 1)?
+            }
+            _ => return None,
+        },
+        // Subroutines are not simulated, they only appear in classes older than version 50
+        Opcode::Jsr | Opcode::Jsr_w | Opcode::Ret => return None,
+    }
+
+    let next = pc + instruction_length(code, pc)?;
+
+    let successors = match opcode {
+        Opcode::Goto => vec![branch_target(pc, read_i16(code, pc + 1)? as i32)?],
+        Opcode::Goto_w => vec![branch_target(pc, read_i32(code, pc + 1)?)?],
+        Opcode::Ifeq
+        | Opcode::Ifne
+        | Opcode::Iflt
+        | Opcode::Ifge
+        | Opcode::Ifgt
+        | Opcode::Ifle
+        | Opcode::If_icmpeq
+        | Opcode::If_icmpne
+        | Opcode::If_icmplt
+        | Opcode::If_icmpge
+        | Opcode::If_icmpgt
+        | Opcode::If_icmple
+        | Opcode::If_acmpeq
+        | Opcode::If_acmpne
+        | Opcode::Ifnull
+        | Opcode::Ifnonnull => vec![next, branch_target(pc, read_i16(code, pc + 1)? as i32)?],
+        Opcode::Tableswitch | Opcode::Lookupswitch => switch_targets(code, pc)?,
+        Opcode::Ireturn
+        | Opcode::Lreturn
+        | Opcode::Freturn
+        | Opcode::Dreturn
+        | Opcode::Areturn
+        | Opcode::Return
+        | Opcode::Athrow => vec![],
+        _ => vec![next],
+    };
+
+    Some(successors)
+}
+
+/// Retrieves the class name, name and descriptor of the field referenced by the instruction at the given pc
+pub fn field_ref(
+    code: &[u8],
+    class_file: &ClassFile,
+    pc: usize,
+) -> Option<(String, String, String)> {
+    class_file.get_field_info(read_u16(code, pc + 1)?)
+}
+
+/// Retrieves the class name, name and descriptor of the method invoked by the instruction at the given pc
+pub fn method_ref(
+    code: &[u8],
+    class_file: &ClassFile,
+    pc: usize,
+) -> Option<(String, String, String)> {
+    let index = read_u16(code, pc + 1)?;
+
+    class_file
+        .get_method_info(index)
+        .or_else(|| class_file.get_interface_method_info(index))
+}
+
+pub fn switch_targets(code: &[u8], pc: usize) -> Option<Vec<usize>> {
+    let base = pc + 1 + (3 - pc % 4);
+    let mut targets = vec![branch_target(pc, read_i32(code, base)?)?];
+
+    match opcode_at(code, pc)? {
+        Opcode::Tableswitch => {
+            let low = read_i32(code, base + 4)? as i64;
+            let high = read_i32(code, base + 8)? as i64;
+
+            for i in 0..usize::try_from(high - low + 1).ok()? {
+                targets.push(branch_target(pc, read_i32(code, base + 12 + 4 * i)?)?);
+            }
+        }
+        _ => {
+            let npairs = usize::try_from(read_i32(code, base + 4)?).ok()?;
+
+            for i in 0..npairs {
+                targets.push(branch_target(pc, read_i32(code, base + 12 + 8 * i)?)?);
+            }
+        }
+    }
+
+    Some(targets)
+}
+
+/// Computes the target of a branch from the given pc
+pub fn branch_target(pc: usize, offset: i32) -> Option<usize> {
+    usize::try_from(pc as i64 + offset as i64).ok()
+}
+
+fn pop<T>(stack: &mut Vec<T>, count: usize) -> Option<()> {
+    let length = stack.len().checked_sub(count)?;
+    stack.truncate(length);
+
+    Some(())
+}
+
+pub fn read_u16(code: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*code.get(at)?, *code.get(at + 1)?]))
+}
+
+pub fn read_i16(code: &[u8], at: usize) -> Option<i16> {
+    Some(read_u16(code, at)? as i16)
+}
+
+pub fn read_i32(code: &[u8], at: usize) -> Option<i32> {
+    Some(i32::from_be_bytes(code.get(at..at + 4)?.try_into().ok()?))
+}
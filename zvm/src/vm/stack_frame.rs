@@ -1,81 +1,50 @@
-use crate::{
-    debug_log,
-    parser::{class_file::ClassFile, opcode::Opcode},
-    vm::{
-        call_stack::CallStack,
-        instruction_exec::{InstructionCompleted, InstructionExecutor},
-        local::LocalVariables,
-        operand_stack::OperandStack,
-        runtime::RuntimeDataArea,
-        value::Value,
-    },
+use std::rc::Rc;
+
+use crate::vm::{
+    call_site::LambdaReturn,
+    class::{Class, Method},
+    local::LocalVariables,
+    operand_stack::OperandStack,
+    value::Value,
 };
 
 /// Method execution stack call frame
 #[derive(Clone)]
 pub struct Frame {
-    pub method_name: Option<String>,
+    /// Class declaring the executed method, its constant pool resolves the frame's symbolic references
+    pub class: Rc<Class>,
+    /// Executed method, its Code attribute holds the exception table and line numbers
+    pub method: Rc<Method>,
     pub operand_stack: OperandStack,
     pub local_variables: LocalVariables,
     pub pc: usize,
     pub bytecode: Vec<u8>,
+    /// Subroutines entered with `jsr` and not yet returned from, as the pc of their first
+    /// instruction and their return address, innermost last
+    pub subroutines: Vec<(usize, usize)>,
+    /// The pc execution continues at once the method invoked by the instruction at `pc`
+    /// returns, the pc itself stays on the invoke instruction for stack traces and handlers
+    pub return_pc: usize,
+    /// Monitor entered for a synchronized method, exited when the method completes
+    pub monitor: Option<Value>,
+    /// Functional interface methods forwarded to this frame's method, innermost first, the
+    /// value it returns is handed through each of them to the invoker
+    pub lambda_returns: Vec<LambdaReturn>,
 }
 
 impl Frame {
-    pub fn new(method_name: Option<String>, max_locals: usize, bytecode: Vec<u8>) -> Self {
+    pub fn new(class: Rc<Class>, method: Rc<Method>, max_locals: usize, bytecode: Vec<u8>) -> Self {
         Self {
-            method_name: Some(method_name.unwrap_or("".to_string())),
+            class,
+            method,
             operand_stack: OperandStack::new(),
             local_variables: LocalVariables::new(max_locals),
             pc: 0,
             bytecode: bytecode,
+            subroutines: Vec::new(),
+            return_pc: 0,
+            monitor: None,
+            lambda_returns: Vec::new(),
         }
     }
-
-    pub fn execute_frame(
-        &mut self,
-        class_file: &ClassFile,
-        runtime_data_area: &mut RuntimeDataArea,
-        call_stack: &mut CallStack,
-    ) -> Result<Option<Value>, String> {
-        let name = self.method_name.clone().expect("Failed to get method name");
-
-        debug_log!("\n\nEXECUTING FRAME: {}\n\n", name);
-
-        let mut current_pc = self.pc;
-        let bytecode = self.bytecode.clone();
-
-        let instruction_executor = InstructionExecutor::new();
-
-        while current_pc < bytecode.len() {
-            let opcode = Opcode::from(bytecode[current_pc]);
-            debug_log!("Executing opcode: {:?} at pc: {}", opcode, current_pc);
-
-            match instruction_executor.execute_instruction(
-                opcode,
-                self,
-                class_file,
-                runtime_data_area,
-                call_stack,
-                &mut current_pc,
-            ) {
-                Ok(instruction_completed) => match instruction_completed {
-                    InstructionCompleted::ReturnFromMethod(returned) => {
-                        return Ok(returned);
-                    }
-                    InstructionCompleted::ContinueMethodExecution => {
-                        // do nothing, complete bytecode processing loop
-                    }
-                },
-                Err(e) => {
-                    eprintln!("Error executing instruction: {}", e);
-                    return Err(e);
-                }
-            }
-
-            current_pc += 1;
-        }
-
-        Ok(None)
-    }
 }
@@ -0,0 +1,267 @@
+use crate::vm::{array::ArrayElements, heap::Heap, value::Value};
+use std::{fmt::LowerExp, str::FromStr};
+
+/// Name of the class of string objects
+pub const STRING_CLASS: &str = "java/lang/String";
+
+/// Key of the `java/lang/String` field holding the UTF-16 code units of a string,
+/// a `char[]` that is never modified once the string is created
+pub const STRING_VALUE: &str = "java/lang/String.value";
+
+/// Borrows the UTF-16 code units of a string, `None` if the value is not a string
+pub fn string_chars<'a>(heap: &'a Heap, value: &Value) -> Option<&'a [u16]> {
+    let Value::Object(handle) = value else {
+        return None;
+    };
+
+    let object = heap.object(*handle);
+    if object.class.name != STRING_CLASS {
+        return None;
+    }
+
+    match object.fields.get(STRING_VALUE) {
+        Some(Value::Array(chars)) => match &heap.array(*chars).elements {
+            ArrayElements::Char(chars) => Some(chars),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Converts a string to a Rust string, unpaired surrogates are replaced with U+FFFD
+/// ( `None` if the value is not a string )
+pub fn rust_string(heap: &Heap, value: &Value) -> Option<String> {
+    string_chars(heap, value).map(String::from_utf16_lossy)
+}
+
+/// Hashes UTF-16 code units the way `String.hashCode()` does
+/// AS SPECIFIED BY THE SPECS: s[0]*31^(n-1) + s[1]*31^(n-2) + ... + s[n-1], with int overflow
+pub fn hash_code(chars: &[u16]) -> i32 {
+    chars.iter().fold(0i32, |hash_code, &c| {
+        hash_code.wrapping_mul(31).wrapping_add(c as i32)
+    })
+}
+
+/// Compares two strings lexicographically by UTF-16 code unit, the way `String.compareTo` does:
+/// the difference of the first code units that differ, otherwise the difference of the lengths
+pub fn compare(chars: &[u16], other: &[u16]) -> i32 {
+    chars
+        .iter()
+        .zip(other)
+        .find(|(c, o)| c != o)
+        .map(|(&c, &o)| c as i32 - o as i32)
+        .unwrap_or(chars.len() as i32 - other.len() as i32)
+}
+
+/// Index of the first occurrence of `target` at or after `from`, -1 if there is none,
+/// like `String.indexOf(String, int)`
+pub fn index_of(chars: &[u16], target: &[u16], from: i32) -> i32 {
+    let from = from.max(0) as usize;
+
+    if from >= chars.len() {
+        return if target.is_empty() {
+            chars.len() as i32
+        } else {
+            -1
+        };
+    }
+
+    if target.is_empty() {
+        return from as i32;
+    }
+
+    chars[from..]
+        .windows(target.len())
+        .position(|window| window == target)
+        .map_or(-1, |index| (from + index) as i32)
+}
+
+/// Index of the first occurrence of a code point at or after `from`, -1 if there is none,
+/// like `String.indexOf(int, int)`, a supplementary code point is searched as its surrogate pair
+pub fn index_of_code_point(chars: &[u16], code_point: i32, from: i32) -> i32 {
+    match char::from_u32(code_point as u32) {
+        Some(c) if c.len_utf16() == 2 => {
+            let mut pair = [0; 2];
+            c.encode_utf16(&mut pair);
+            index_of(chars, &pair, from)
+        }
+        // A BMP code point, including lone surrogates that char::from_u32 rejects
+        _ if (0..=0xFFFF).contains(&code_point) => index_of(chars, &[code_point as u16], from),
+        _ => -1,
+    }
+}
+
+/// Maps every code point of a string with a Unicode case mapping, which may change the length
+/// ( e.g. `ß` becomes `SS` ), unpaired surrogates are kept as they are
+fn map_code_points<I: Iterator<Item = char>>(
+    chars: &[u16],
+    mapping: impl Fn(char) -> I,
+) -> Vec<u16> {
+    let mut mapped = Vec::with_capacity(chars.len());
+    let mut buffer = [0; 2];
+
+    for decoded in char::decode_utf16(chars.iter().copied()) {
+        match decoded {
+            Ok(c) => {
+                for m in mapping(c) {
+                    mapped.extend_from_slice(m.encode_utf16(&mut buffer));
+                }
+            }
+            Err(unpaired) => mapped.push(unpaired.unpaired_surrogate()),
+        }
+    }
+
+    mapped
+}
+
+/// Converts a string to upper case like `String.toUpperCase()` in an English locale
+pub fn to_upper_case(chars: &[u16]) -> Vec<u16> {
+    map_code_points(chars, char::to_uppercase)
+}
+
+/// Converts a string to lower case like `String.toLowerCase()` in an English locale
+pub fn to_lower_case(chars: &[u16]) -> Vec<u16> {
+    match String::from_utf16(chars) {
+        // Converting the whole string handles the context sensitive final sigma
+        Ok(string) => string.to_lowercase().encode_utf16().collect(),
+        Err(_) => map_code_points(chars, char::to_lowercase),
+    }
+}
+
+/// Reverses a string like `StringBuilder.reverse()`: surrogate pairs stay in their order
+/// so that supplementary characters survive the reversal
+pub fn reverse(chars: &mut [u16]) {
+    chars.reverse();
+
+    let mut i = 0;
+    while i + 1 < chars.len() {
+        let is_low = (0xDC00..0xE000).contains(&chars[i]);
+        let is_high = (0xD800..0xDC00).contains(&chars[i + 1]);
+
+        if is_low && is_high {
+            chars.swap(i, i + 1);
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Converts a float to a string the way `Float.toString` does, see `double_to_string`
+pub fn float_to_string(value: f32) -> String {
+    match value {
+        value if value.is_nan() => "NaN".to_string(),
+        f32::INFINITY => "Infinity".to_string(),
+        f32::NEG_INFINITY => "-Infinity".to_string(),
+        // The digits are those of the float itself, not of the widened double
+        value => java_decimal(
+            value.is_sign_negative(),
+            &shortest(value.abs()),
+            &format!("{:.160e}", value.abs()),
+        ),
+    }
+}
+
+/// Converts a double to a string the way `Double.toString` does, used by `String.valueOf`,
+/// string concatenation and printing
+///
+/// The digits are the fewest that uniquely distinguish the value from its neighbours, but at
+/// least two when two give a closer value, the closest to the value with an even last digit
+/// on a tie. Magnitudes from 10^-3 up to 10^7
+/// are written as a decimal with at least one digit after the point, e.g. `100.0` or `0.001`,
+/// the others in computerized scientific notation, e.g. `1.0E7` or `1.0E-4`
+pub fn double_to_string(value: f64) -> String {
+    match value {
+        value if value.is_nan() => "NaN".to_string(),
+        f64::INFINITY => "Infinity".to_string(),
+        f64::NEG_INFINITY => "-Infinity".to_string(),
+        value => java_decimal(
+            value.is_sign_negative(),
+            &shortest(value.abs()),
+            &format!("{:.1100e}", value.abs()),
+        ),
+    }
+}
+
+/// Formats a finite magnitude in scientific notation with the fewest digits that parse back
+/// to it, or the two closest digits when a single one is enough, e.g. `4.9e-324` for
+/// `Double.MIN_VALUE` where the shortest is `5e-324`
+fn shortest<T: LowerExp + FromStr + PartialEq>(value: T) -> String {
+    let shortest = format!("{:e}", value);
+    if decimal_digits(&shortest).0.len() == 1 {
+        let two = format!("{:.1e}", value);
+        if two.parse::<T>().is_ok_and(|parsed| parsed == value) {
+            return two;
+        }
+    }
+    shortest
+}
+
+/// The decimal digits of a finite magnitude formatted by Rust in scientific notation, which
+/// uses the shortest digits, e.g. `1.5e2` gives `15` with 2 as the exponent of the first digit
+pub fn decimal_digits(scientific: &str) -> (Vec<u8>, i32) {
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((scientific, "0"));
+    let digits = mantissa.bytes().filter(u8::is_ascii_digit).collect();
+    (digits, exponent.parse().unwrap_or(0))
+}
+
+/// Lays out the shortest digits of a finite magnitude the way `Double.toString` does, given
+/// in scientific notation next to the exact expansion of the magnitude
+fn java_decimal(negative: bool, shortest: &str, exact: &str) -> String {
+    let (mut digits, exponent) = decimal_digits(shortest);
+    let (mut exact, _) = decimal_digits(exact);
+    while exact.len() > 1 && exact.last() == Some(&b'0') {
+        exact.pop();
+    }
+
+    // Rust rounds a magnitude exactly halfway between two shortest candidates up, Java picks
+    // the one with an even last digit
+    let last = digits.len() - 1;
+    if exact.len() == digits.len() + 1
+        && exact[last] + 1 == digits[last]
+        && exact[last] % 2 == 0
+        && exact[digits.len()] == b'5'
+        && exact[..last] == digits[..last]
+    {
+        digits[last] -= 1;
+    }
+    while digits.len() > 1 && digits.last() == Some(&b'0') {
+        digits.pop();
+    }
+
+    let digit = |index: i32| {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| digits.get(index))
+            .map_or('0', |digit| *digit as char)
+    };
+    let fraction = |from: i32| match from < digits.len() as i32 {
+        true => (from..digits.len() as i32).map(digit).collect(),
+        false => "0".to_string(),
+    };
+
+    let mut string = String::from(if negative { "-" } else { "" });
+
+    if (-3..7).contains(&exponent) {
+        // The integer part has exponent + 1 digits, padded with zeros past the known ones
+        match exponent >= 0 {
+            true => string.extend((0..=exponent).map(digit)),
+            false => string.push('0'),
+        }
+        string.push('.');
+        match exponent >= 0 {
+            true => string.push_str(&fraction(exponent + 1)),
+            false => {
+                string.extend((exponent + 1..0).map(|_| '0'));
+                string.extend(digits.iter().map(|digit| *digit as char));
+            }
+        }
+    } else {
+        string.push(digit(0));
+        string.push('.');
+        string.push_str(&fraction(1));
+        string.push_str(&format!("E{}", exponent));
+    }
+
+    string
+}
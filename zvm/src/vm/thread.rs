@@ -0,0 +1,1164 @@
+use std::{
+    cell::{RefCell, RefMut},
+    collections::{BTreeMap, HashMap},
+    io::{self, Write},
+    mem, process,
+    rc::Rc,
+    thread,
+    time::Duration,
+};
+
+use crate::vm::{
+    array,
+    call_stack::{CallStack, DEFAULT_STACK_SIZE},
+    console,
+    error::VmError,
+    heap::{Handle, Heap},
+    instruction_exec::{InstructionCompleted, InstructionExecutor},
+    native,
+    runtime::RuntimeDataArea,
+    string,
+    value::Value,
+};
+
+/// Keys of the `java/lang/Thread` fields in the fields of an object
+pub const THREAD_NAME: &str = "java/lang/Thread.name";
+pub const THREAD_TARGET: &str = "java/lang/Thread.target";
+pub const THREAD_ID: &str = "java/lang/Thread.tid";
+pub const THREAD_DAEMON: &str = "java/lang/Thread.daemon";
+pub const THREAD_PRIORITY: &str = "java/lang/Thread.priority";
+
+/// Id of the thread running `main`, the JDK gives it the first id as well
+pub const MAIN_THREAD_ID: i64 = 1;
+
+/// Priority of every thread, zvm does not schedule by priority
+pub const NORM_PRIORITY: i32 = 5;
+
+/// Instructions a thread executes before the scheduler switches to another runnable thread
+pub const DEFAULT_QUANTUM: usize = 1000;
+
+/// Rate of the virtual clock `Thread.sleep` waits on, it advances with the executed
+/// instructions so that the scheduling only depends on the program and the seed
+const INSTRUCTIONS_PER_MILLISECOND: u64 = 10_000;
+
+/// State of a Java thread as seen by the scheduler
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ThreadStatus {
+    /// Started and ready to run
+    Runnable,
+    /// In `Thread.sleep` until the given virtual time
+    Sleeping(u64),
+    /// In `Thread.join` of the given thread, until the given virtual time if any
+    Joining(i64, Option<u64>),
+    /// Waiting to enter the monitor of the given object
+    Blocked(Handle),
+    /// In `Object.wait` on the given object, until the given virtual time if any
+    Waiting(Handle, Option<u64>),
+    /// Returned from `main`, waiting for every non daemon thread to terminate
+    Exiting,
+    Terminated,
+}
+
+/// A started Java thread
+struct ThreadRecord {
+    /// The `java/lang/Thread` instance
+    object: Value,
+    status: ThreadStatus,
+    daemon: bool,
+    /// Call stack of the thread while another thread runs, the running thread holds its own
+    stack: Option<CallStack>,
+}
+
+/// Monitor of an object, entered by `monitorenter` and synchronized methods
+/// AS SPECIFIED BY THE SPECS: §2.11.10, a thread may enter a monitor it already owns,
+/// and releases it once it exited as many times as it entered
+struct Monitor {
+    /// The object the monitor belongs to, kept alive while the monitor is in use
+    object: Value,
+    /// Id of the thread owning the monitor, if any
+    owner: Option<i64>,
+    /// Number of times the owner entered the monitor without exiting it
+    count: usize,
+    /// Threads in `Object.wait` on the object, in the order they started waiting
+    waiters: Vec<i64>,
+}
+
+impl Monitor {
+    /// Checks if no thread owns or waits on the monitor, it can be dropped then
+    fn is_unused(&self) -> bool {
+        self.owner.is_none() && self.waiters.is_empty()
+    }
+}
+
+struct SchedulerState {
+    /// Started threads by id, iterated in id order so that scheduling is deterministic
+    threads: BTreeMap<i64, ThreadRecord>,
+    next_id: i64,
+    /// Number of the next thread created without a name, `Thread-N`
+    next_number: usize,
+    quantum: usize,
+    /// Size in bytes of the call stack of every thread started
+    stack_size: usize,
+    /// State of the random generator when scheduling with a seed, round robin otherwise
+    random: Option<u64>,
+    /// Instructions executed by every thread so far
+    instructions: u64,
+    /// Milliseconds waited while every thread was sleeping
+    idle: u64,
+    /// Threads suspended in a nested run of the dispatch loop, innermost last, see `switch`
+    suspended: Vec<i64>,
+    /// Class of the exception `main` completed abruptly with
+    uncaught: Option<String>,
+    /// Monitors in use, keyed by the handle of their object
+    monitors: HashMap<Handle, Monitor>,
+}
+
+impl SchedulerState {
+    /// Virtual time in milliseconds
+    fn clock(&self) -> u64 {
+        self.instructions / INSTRUCTIONS_PER_MILLISECOND + self.idle
+    }
+
+    /// Length of the next time slice, random around the quantum when scheduling with a seed
+    fn slice(&mut self) -> usize {
+        match self.next_random() {
+            Some(random) => 1 + (random % (2 * self.quantum as u64)) as usize,
+            None => self.quantum,
+        }
+    }
+
+    /// xorshift64*, reproducible from the seed
+    fn next_random(&mut self) -> Option<u64> {
+        let mut x = self.random?;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.random = Some(x);
+        Some(x.wrapping_mul(0x2545_F491_4F6C_DD1D))
+    }
+
+    /// Wakes up the threads whose wait is over, then picks the thread to run after the
+    /// given one, the next runnable thread by id or a random one when scheduling with a seed.
+    /// If no thread is runnable the VM really waits until the earliest wake up time, which the
+    /// clock then advances to, and if every thread waits forever the deadlock is reported and
+    /// the VM exits
+    ///
+    /// A thread suspended in a nested run can only run again once the threads suspended
+    /// after it did, their nested runs are above its own on the host stack
+    fn pick_next(&mut self, current: i64, runtime_data_area: &RuntimeDataArea) -> i64 {
+        loop {
+            let now = self.clock();
+            let terminated = |threads: &BTreeMap<i64, ThreadRecord>, id: &i64| {
+                threads
+                    .get(id)
+                    .is_none_or(|thread| thread.status == ThreadStatus::Terminated)
+            };
+            let only_daemons = self.threads.values().all(|thread| {
+                thread.daemon
+                    || matches!(
+                        thread.status,
+                        ThreadStatus::Exiting | ThreadStatus::Terminated
+                    )
+            });
+
+            let woken: Vec<i64> = self
+                .threads
+                .iter()
+                .filter(|(_, thread)| match thread.status {
+                    ThreadStatus::Sleeping(until) => until <= now,
+                    ThreadStatus::Joining(target, until) => {
+                        terminated(&self.threads, &target) || until.is_some_and(|t| t <= now)
+                    }
+                    ThreadStatus::Blocked(object) => self
+                        .monitors
+                        .get(&object)
+                        .is_none_or(|monitor| monitor.owner.is_none()),
+                    ThreadStatus::Waiting(_, until) => until.is_some_and(|t| t <= now),
+                    // Main exits from the outermost dispatch loop only
+                    ThreadStatus::Exiting => only_daemons && self.suspended.is_empty(),
+                    _ => false,
+                })
+                .map(|(id, _)| *id)
+                .collect();
+
+            for id in woken {
+                if let Some(thread) = self.threads.get_mut(&id) {
+                    // A wait that timed out leaves the wait set
+                    if let ThreadStatus::Waiting(object, _) = thread.status
+                        && let Some(monitor) = self.monitors.get_mut(&object)
+                    {
+                        monitor.waiters.retain(|waiter| *waiter != id);
+                    }
+
+                    thread.status = ThreadStatus::Runnable;
+                }
+            }
+
+            let buried = &self.suspended[..self.suspended.len().saturating_sub(1)];
+            let runnable: Vec<i64> = self
+                .threads
+                .iter()
+                .filter(|(id, thread)| {
+                    thread.status == ThreadStatus::Runnable && !buried.contains(id)
+                })
+                .map(|(id, _)| *id)
+                .collect();
+
+            if !runnable.is_empty() {
+                return match self.next_random() {
+                    Some(random) => runnable[(random % runnable.len() as u64) as usize],
+                    None => runnable
+                        .iter()
+                        .find(|id| **id > current)
+                        .copied()
+                        .unwrap_or(runnable[0]),
+                };
+            }
+
+            let wake_up = self
+                .threads
+                .values()
+                .filter_map(|thread| match thread.status {
+                    ThreadStatus::Sleeping(until) => Some(until),
+                    ThreadStatus::Joining(_, until) | ThreadStatus::Waiting(_, until) => until,
+                    _ => None,
+                })
+                .min();
+
+            match wake_up {
+                // Every thread waits, really wait for the first of them to wake up
+                Some(until) => {
+                    let waited = until.saturating_sub(now);
+                    thread::sleep(Duration::from_millis(waited));
+                    self.idle += waited.max(1);
+                }
+                None => self.report_deadlock(&runtime_data_area.heap),
+            }
+        }
+    }
+
+    /// Prints the threads waiting on each other in a cycle, the way a thread dump does,
+    /// or what every thread waits for when there is no cycle, then exits the VM since
+    /// no thread can ever run again
+    fn report_deadlock(&self, heap: &Heap) -> ! {
+        let name = |id: i64| {
+            self.threads
+                .get(&id)
+                .map(|thread| object_thread_name(heap, &thread.object))
+                .unwrap_or_default()
+        };
+        let describe = |object: Handle| {
+            self.monitors
+                .get(&object)
+                .map(|monitor| describe_object(heap, &monitor.object))
+                .unwrap_or_default()
+        };
+
+        // A blocked thread waits for the owner of the monitor, a joining one for the thread it joins
+        let waits_for = |id: i64| match self.threads.get(&id)?.status {
+            ThreadStatus::Blocked(object) => self.monitors.get(&object)?.owner,
+            ThreadStatus::Joining(target, None) => Some(target),
+            _ => None,
+        };
+
+        let cycle = self.threads.keys().find_map(|start| {
+            let mut path = vec![*start];
+
+            while let Some(next) = waits_for(*path.last()?) {
+                if let Some(position) = path.iter().position(|id| *id == next) {
+                    return Some(path.split_off(position));
+                }
+                path.push(next);
+            }
+
+            None
+        });
+
+        let mut report = String::new();
+
+        match cycle {
+            Some(cycle) => {
+                report.push_str("Found one Java-level deadlock:\n");
+                report.push_str("=============================\n");
+
+                for id in cycle {
+                    report.push_str(&format!("\"{}\":\n", name(id)));
+
+                    match self.threads[&id].status {
+                        ThreadStatus::Blocked(object) => report.push_str(&format!(
+                            "  waiting to lock monitor {},\n  which is held by \"{}\"\n",
+                            describe(object),
+                            waits_for(id).map(name).unwrap_or_default()
+                        )),
+                        ThreadStatus::Joining(target, _) => {
+                            report.push_str(&format!("  waiting to join \"{}\"\n", name(target)))
+                        }
+                        _ => {}
+                    }
+                }
+
+                report.push_str("\nFound 1 deadlock.\n");
+            }
+            None => {
+                report.push_str("Every thread is waiting forever:\n");
+
+                for (id, thread) in &self.threads {
+                    let waiting = match thread.status {
+                        ThreadStatus::Blocked(object) => {
+                            format!("waiting to lock monitor {}", describe(object))
+                        }
+                        ThreadStatus::Waiting(object, _) => {
+                            format!("waiting on {}", describe(object))
+                        }
+                        ThreadStatus::Joining(target, _) => {
+                            format!("waiting to join \"{}\"", name(target))
+                        }
+                        ThreadStatus::Exiting => {
+                            "waiting for the non daemon threads to terminate".to_string()
+                        }
+                        _ => continue,
+                    };
+                    report.push_str(&format!("\"{}\": {}\n", name(*id), waiting));
+                }
+            }
+        }
+
+        let _ = io::stdout().flush();
+        eprint!("{}", report);
+        process::exit(1);
+    }
+}
+
+/// Cooperative scheduler of the Java threads, each of them has its own call stack but they
+/// all run in the dispatch loop of the host thread, one at a time: the running thread hands
+/// the loop to the next one when its time slice is over or when it blocks, and the loop
+/// goes on with the frames of the call stack swapped in
+pub struct Scheduler {
+    state: RefCell<SchedulerState>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self {
+            state: RefCell::new(SchedulerState {
+                threads: BTreeMap::new(),
+                next_id: MAIN_THREAD_ID + 1,
+                next_number: 0,
+                quantum: DEFAULT_QUANTUM,
+                stack_size: DEFAULT_STACK_SIZE,
+                random: None,
+                instructions: 0,
+                idle: 0,
+                suspended: Vec::new(),
+                uncaught: None,
+                monitors: HashMap::new(),
+            }),
+        }
+    }
+
+    fn state(&self) -> RefMut<'_, SchedulerState> {
+        self.state.borrow_mut()
+    }
+
+    /// Sets the number of instructions of a time slice ( -XX:ThreadQuantum )
+    pub fn set_quantum(&self, quantum: usize) {
+        self.state().quantum = quantum.max(1);
+    }
+
+    /// Sets the size in bytes of the call stack of the threads started from now on ( -Xss )
+    pub fn set_stack_size(&self, stack_size: usize) {
+        self.state().stack_size = stack_size;
+    }
+
+    /// Schedules the threads in a random order reproducible from the seed ( -XX:ThreadSeed ),
+    /// the time slices vary randomly around the quantum as well
+    pub fn set_seed(&self, seed: u64) {
+        // xorshift never leaves the zero state
+        self.state().random = Some(seed.max(1));
+    }
+
+    /// Allocates the id of a new thread
+    pub fn new_thread_id(&self) -> i64 {
+        let mut state = self.state();
+        state.next_id += 1;
+        state.next_id - 1
+    }
+
+    /// Allocates the number of a new thread created without a name
+    pub fn next_thread_number(&self) -> usize {
+        let mut state = self.state();
+        state.next_number += 1;
+        state.next_number - 1
+    }
+
+    /// Checks if the thread of the given id has been started
+    pub fn is_started(&self, id: i64) -> bool {
+        self.state().threads.contains_key(&id)
+    }
+
+    /// Checks if the thread of the given id has been started and has not terminated yet
+    pub fn is_alive(&self, id: i64) -> bool {
+        self.state()
+            .threads
+            .get(&id)
+            .is_some_and(|thread| thread.status != ThreadStatus::Terminated)
+    }
+
+    /// References the scheduler keeps alive: the objects of the threads that have not
+    /// terminated, the frames of the parked threads and the objects of the monitors in use
+    pub fn roots(&self) -> Vec<Value> {
+        let state = self.state();
+
+        state
+            .threads
+            .values()
+            .filter(|thread| thread.status != ThreadStatus::Terminated)
+            .flat_map(|thread| {
+                std::iter::once(&thread.object)
+                    .chain(thread.stack.iter().flat_map(CallStack::roots))
+            })
+            .chain(state.monitors.values().map(|monitor| &monitor.object))
+            .cloned()
+            .collect()
+    }
+
+    /// Virtual time at which a wait of the given number of milliseconds started now is over,
+    /// the instructions of the current time slice count as well
+    fn deadline(&self, millis: u64, thread: &mut JavaThread) -> u64 {
+        let mut state = self.state();
+        state.instructions += thread.executed() as u64;
+        thread.start_slice(thread.slice);
+        state.clock().saturating_add(millis)
+    }
+
+    /// Registers the thread running `main`, which runs first
+    fn register_main(&self, object: Value) {
+        self.state().threads.insert(
+            MAIN_THREAD_ID,
+            ThreadRecord {
+                object,
+                status: ThreadStatus::Runnable,
+                daemon: false,
+                stack: None,
+            },
+        );
+    }
+
+    /// Status of the thread of the given id
+    fn status(&self, id: i64) -> ThreadStatus {
+        self.state()
+            .threads
+            .get(&id)
+            .map_or(ThreadStatus::Runnable, |thread| thread.status)
+    }
+
+    /// Sets the status of the thread of the given id, a thread blocking keeps running until
+    /// the dispatch loop switches to the next thread before its next instruction
+    fn set_status(&self, id: i64, status: ThreadStatus) {
+        if let Some(thread) = self.state().threads.get_mut(&id) {
+            thread.status = status;
+        }
+    }
+
+    /// Parks the current thread with the status it was given, and hands the call stack to
+    /// the next thread, swapping in its call stack in place of the current one
+    ///
+    /// A thread in a nested run of the dispatch loop, e.g. a `toString()` invoked by a
+    /// native, cannot leave the host frames of the run: it is suspended, the other threads
+    /// run in a dispatch loop above them until the scheduler picks it again, and it carries
+    /// on in its nested run
+    ///
+    /// Returns false when the thread now on the call stack carries on outside of the
+    /// current dispatch loop, a thread suspended in a nested run or main done waiting for
+    /// the other threads
+    fn switch(
+        &self,
+        runtime_data_area: &mut RuntimeDataArea,
+        call_stack: &mut CallStack,
+    ) -> Result<bool, VmError> {
+        let current = call_stack.thread.id;
+        let suspended = call_stack.is_nested();
+
+        let mut state = self.state();
+        state.instructions += call_stack.thread.executed() as u64;
+
+        if suspended {
+            state.suspended.push(current);
+        }
+
+        let next = state.pick_next(current, runtime_data_area);
+        let slice = state.slice();
+
+        if next == current {
+            if suspended {
+                state.suspended.pop();
+            }
+            call_stack.thread.start_slice(slice);
+            return Ok(suspended || !call_stack.is_empty());
+        }
+
+        let stack = state
+            .threads
+            .get_mut(&next)
+            .and_then(|thread| thread.stack.take())
+            .ok_or_else(|| format!("No call stack for thread {}", next))?;
+        let parked = mem::replace(call_stack, stack);
+
+        // The call stack of a terminated thread is dropped
+        if let Some(thread) = state.threads.get_mut(&current)
+            && thread.status != ThreadStatus::Terminated
+        {
+            thread.stack = Some(parked);
+        }
+
+        call_stack.thread.start_slice(slice);
+        drop(state);
+
+        if !suspended {
+            return Ok(!call_stack.is_empty() && !call_stack.is_nested());
+        }
+
+        run_threads(runtime_data_area, call_stack)?;
+        self.state().suspended.pop();
+
+        Ok(true)
+    }
+
+    /// Marks the given thread as terminated, releasing the monitors it still owns
+    fn terminate(&self, id: i64) {
+        let mut state = self.state();
+
+        if let Some(thread) = state.threads.get_mut(&id) {
+            thread.status = ThreadStatus::Terminated;
+        }
+
+        // A thread failing with a VM error may still own monitors, release them
+        for monitor in state.monitors.values_mut() {
+            if monitor.owner == Some(id) {
+                monitor.owner = None;
+                monitor.count = 0;
+            }
+        }
+        state.monitors.retain(|_, monitor| !monitor.is_unused());
+    }
+
+    /// Makes the current thread own the monitor of the given object, entered the given
+    /// number of times. Returns false if another thread owns it
+    fn try_enter(&self, object: &Value, handle: Handle, count: usize, current: i64) -> bool {
+        let mut state = self.state();
+        let monitor = state.monitors.entry(handle).or_insert_with(|| Monitor {
+            object: object.clone(),
+            owner: None,
+            count: 0,
+            waiters: Vec::new(),
+        });
+
+        match monitor.owner {
+            None => {
+                monitor.owner = Some(current);
+                monitor.count = count;
+                true
+            }
+            Some(owner) if owner == current => {
+                monitor.count += count;
+                true
+            }
+            Some(_) => false,
+        }
+    }
+}
+
+/// The Java thread a call stack belongs to
+pub struct JavaThread {
+    pub id: i64,
+    /// The `java/lang/Thread` instance, returned by `Thread.currentThread()`
+    pub object: Value,
+    pub scheduler: Rc<Scheduler>,
+    /// Length of the current time slice
+    slice: usize,
+    /// Instructions left in the current time slice
+    budget: usize,
+    /// Monitor the thread enters before its next instruction and how many times, it
+    /// stays blocked until the owner releases it
+    entering: Option<(Value, usize)>,
+}
+
+impl JavaThread {
+    pub fn new(id: i64, object: Value, scheduler: Rc<Scheduler>) -> Self {
+        Self {
+            id,
+            object,
+            scheduler,
+            slice: DEFAULT_QUANTUM,
+            budget: DEFAULT_QUANTUM,
+            entering: None,
+        }
+    }
+
+    fn start_slice(&mut self, slice: usize) {
+        self.slice = slice;
+        self.budget = slice;
+    }
+
+    /// Ends the current time slice, before its next instruction the thread blocks or lets
+    /// the other runnable threads run
+    fn end_slice(&mut self) {
+        self.slice -= self.budget;
+        self.budget = 0;
+    }
+
+    /// Counts an executed instruction against the current time slice, returns whether the
+    /// thread keeps running without the scheduler, see `schedule`
+    ///
+    /// The status of the current thread only changes when it blocks, which ends its slice
+    pub fn tick(&mut self) -> bool {
+        self.budget = self.budget.saturating_sub(1);
+        self.budget > 0 && self.entering.is_none()
+    }
+
+    /// Instructions executed in the current time slice
+    fn executed(&self) -> usize {
+        self.slice - self.budget
+    }
+
+    /// References the thread keeps alive, its `java/lang/Thread` instance and the object
+    /// of the monitor it is entering
+    pub fn roots(&self) -> impl Iterator<Item = &Value> {
+        std::iter::once(&self.object).chain(self.entering.iter().map(|(object, _)| object))
+    }
+}
+
+/// Switches to the next runnable thread once the time slice of the current thread is over
+/// or it blocked, see `JavaThread::tick`, and lets the thread that runs next enter the
+/// monitor it is entering
+///
+/// Returns false when the thread now on the call stack carries on outside of the current
+/// dispatch loop, see `Scheduler::switch`
+pub fn schedule(
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> Result<bool, VmError> {
+    let scheduler = call_stack.thread.scheduler.clone();
+
+    loop {
+        let id = call_stack.thread.id;
+
+        if call_stack.thread.budget == 0 || scheduler.status(id) != ThreadStatus::Runnable {
+            if !scheduler.switch(runtime_data_area, call_stack)? {
+                return Ok(false);
+            }
+            continue;
+        }
+
+        let Some((object, count)) = call_stack.thread.entering.take() else {
+            return Ok(true);
+        };
+
+        if let Some(handle) = object.handle()
+            && !scheduler.try_enter(&object, handle, count, id)
+        {
+            scheduler.set_status(id, ThreadStatus::Blocked(handle));
+            call_stack.thread.entering = Some((object, count));
+        }
+    }
+}
+
+/// Runs the frames on the call stack, the ones of the current thread and of the threads
+/// the scheduler switches to, ending the threads whose frames all completed, until the
+/// call stack is handed to a thread that carries on outside of this loop
+fn run_threads(
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> Result<(), VmError> {
+    loop {
+        match call_stack.execute_frames_above(0, runtime_data_area) {
+            Ok(InstructionCompleted::ContinueMethodExecution) => return Ok(()),
+            completed => {
+                if !end_thread(completed, runtime_data_area, call_stack)? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Ends the current thread once its frames completed, reporting the exception it
+/// completed abruptly with, and switches to the next thread. Main waits for every non
+/// daemon thread to terminate instead, the virtual machine exits then
+///
+/// Returns false when the thread now on the call stack carries on outside of the current
+/// dispatch loop, see `Scheduler::switch`
+fn end_thread(
+    completed: Result<InstructionCompleted, VmError>,
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> Result<bool, VmError> {
+    let id = call_stack.thread.id;
+    let scheduler = call_stack.thread.scheduler.clone();
+
+    match completed {
+        Ok(InstructionCompleted::ThrowException(exception)) => {
+            // The class is read before the other threads run, the exception is not
+            // reachable anymore once reported
+            if id == MAIN_THREAD_ID {
+                scheduler.state().uncaught = exception
+                    .handle()
+                    .map(|handle| runtime_data_area.heap.object(handle).class.name.clone());
+            }
+            report_uncaught_exception(&exception, runtime_data_area, call_stack);
+        }
+        Ok(_) => {}
+        Err(error) if id == MAIN_THREAD_ID => return Err(error),
+        Err(error) => eprintln!("Error executing thread {}: {}", id, error),
+    }
+
+    // AS SPECIFIED BY THE SPECS: §5.7, the virtual machine exits once every
+    // non daemon thread has terminated
+    match id {
+        MAIN_THREAD_ID => scheduler.set_status(id, ThreadStatus::Exiting),
+        _ => scheduler.terminate(id),
+    }
+
+    scheduler.switch(runtime_data_area, call_stack)
+}
+
+/// Creates the `java/lang/Thread` instance of the main thread
+pub fn start_main_thread(
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> Result<(), VmError> {
+    let class = runtime_data_area
+        .class_loader
+        .load_class("java/lang/Thread")?;
+    let name = runtime_data_area.new_string_from("main", call_stack)?;
+
+    let mut thread = crate::vm::object::Object::new(class);
+    thread.fields.insert(THREAD_NAME.to_string(), name.clone());
+    thread
+        .fields
+        .insert(THREAD_ID.to_string(), Value::Long(MAIN_THREAD_ID));
+    thread
+        .fields
+        .insert(THREAD_PRIORITY.to_string(), Value::Int(NORM_PRIORITY));
+
+    // The name is only reachable from the thread
+    runtime_data_area.heap.pin(name.clone());
+    let object = runtime_data_area.allocate_object(thread, call_stack);
+    runtime_data_area.heap.unpin(&name);
+    let object = object?;
+
+    call_stack.thread.object = object.clone();
+    call_stack.thread.scheduler.register_main(object);
+
+    Ok(())
+}
+
+/// Runs the frames of `main`, and of the threads it starts, until every non daemon thread
+/// terminated, the frame of `main` is on the call stack unless the class initializer of
+/// the main class threw the given exception
+///
+/// Returns the class of the exception `main` completed abruptly with, reported already
+pub fn run_main_thread(
+    exception: Option<Value>,
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> Result<Option<String>, VmError> {
+    let carries_on = match exception {
+        Some(exception) => end_thread(
+            Ok(InstructionCompleted::ThrowException(exception)),
+            runtime_data_area,
+            call_stack,
+        )?,
+        None => true,
+    };
+
+    if carries_on {
+        run_threads(runtime_data_area, call_stack)?;
+    }
+
+    let uncaught = call_stack.thread.scheduler.state().uncaught.take();
+    Ok(uncaught)
+}
+
+/// Initializes a new `java/lang/Thread` instance, a thread without a name is named
+/// `Thread-N` and a new thread is a daemon thread if the current thread is one
+pub fn init_thread(
+    thread: &Value,
+    target: Value,
+    name: Option<Value>,
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &CallStack,
+) -> Result<(), VmError> {
+    let scheduler = &call_stack.thread.scheduler;
+
+    let name = match name {
+        Some(name) => name,
+        None => runtime_data_area.new_string_from(
+            &format!("Thread-{}", scheduler.next_thread_number()),
+            call_stack,
+        )?,
+    };
+    let daemon = thread_field(runtime_data_area, &call_stack.thread.object, THREAD_DAEMON);
+
+    let Value::Object(handle) = thread else {
+        return Err(format!("Thread.<init>: expected object, got {:?}", thread).into());
+    };
+
+    let fields = &mut runtime_data_area.heap.object_mut(*handle).fields;
+    fields.insert(THREAD_NAME.to_string(), name);
+    fields.insert(THREAD_TARGET.to_string(), target);
+    fields.insert(
+        THREAD_ID.to_string(),
+        Value::Long(scheduler.new_thread_id()),
+    );
+    fields.insert(THREAD_DAEMON.to_string(), daemon);
+    fields.insert(THREAD_PRIORITY.to_string(), Value::Int(NORM_PRIORITY));
+
+    Ok(())
+}
+
+/// Reads a field of a `java/lang/Thread` instance
+pub fn thread_field(runtime_data_area: &RuntimeDataArea, thread: &Value, key: &str) -> Value {
+    match thread {
+        Value::Object(handle) => runtime_data_area
+            .heap
+            .object(*handle)
+            .fields
+            .get(key)
+            .cloned()
+            .unwrap_or(Value::Null),
+        _ => Value::Null,
+    }
+}
+
+/// Id of a `java/lang/Thread` instance
+pub fn thread_id(runtime_data_area: &RuntimeDataArea, thread: &Value) -> i64 {
+    match thread_field(runtime_data_area, thread, THREAD_ID) {
+        Value::Long(id) => id,
+        _ => 0,
+    }
+}
+
+/// Name of a `java/lang/Thread` instance
+pub fn thread_name(runtime_data_area: &RuntimeDataArea, thread: &Value) -> String {
+    object_thread_name(&runtime_data_area.heap, thread)
+}
+
+/// Name of a `java/lang/Thread` instance, read from the heap only
+fn object_thread_name(heap: &Heap, thread: &Value) -> String {
+    let name = match thread {
+        Value::Object(handle) => heap.object(*handle).fields.get(THREAD_NAME).cloned(),
+        _ => None,
+    };
+
+    name.and_then(|name| string::rust_string(heap, &name))
+        .unwrap_or_default()
+}
+
+/// Describes an object the way thread dumps describe the objects of monitors,
+/// e.g. `<0x1b6d3586> (a java.lang.Object)`
+fn describe_object(heap: &Heap, object: &Value) -> String {
+    let (handle, type_name) = match object {
+        Value::Object(handle) => (*handle, heap.object(*handle).class.name.replace('/', ".")),
+        Value::Array(handle) => (
+            *handle,
+            array::external_array_name(&heap.array(*handle).descriptor),
+        ),
+        _ => return "null".to_string(),
+    };
+
+    format!(
+        "<0x{:08x}> (a {})",
+        heap.identity_hash_code(handle),
+        type_name
+    )
+}
+
+/// Starts a new thread running the `run()` method of the given `java/lang/Thread` instance
+/// on a call stack of its own, it runs once the scheduler picks it.
+/// Returns false if the thread has already been started
+pub fn start_thread(
+    thread: &Value,
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &CallStack,
+) -> Result<bool, VmError> {
+    let scheduler = call_stack.thread.scheduler.clone();
+    let id = thread_id(runtime_data_area, thread);
+
+    if scheduler.is_started(id) {
+        return Ok(false);
+    }
+
+    let daemon = matches!(
+        thread_field(runtime_data_area, thread, THREAD_DAEMON),
+        Value::Int(1)
+    );
+
+    scheduler.state().threads.insert(
+        id,
+        ThreadRecord {
+            object: thread.clone(),
+            status: ThreadStatus::Runnable,
+            daemon,
+            stack: None,
+        },
+    );
+
+    let stack_size = scheduler.state().stack_size;
+    let mut stack = CallStack::new(
+        Some(stack_size),
+        JavaThread::new(id, thread.clone(), scheduler.clone()),
+    );
+
+    let run = match thread {
+        Value::Object(handle) => runtime_data_area
+            .heap
+            .object(*handle)
+            .class
+            .lookup_instance_method("run", "()V"),
+        _ => None,
+    };
+
+    // The frame of run() waits on the call stack of the thread, `Thread.run()` without
+    // a target completes right away
+    let completed = match run {
+        Some(run) => InstructionExecutor::new().invoke(
+            run,
+            vec![thread.clone()],
+            runtime_data_area,
+            &mut stack,
+        )?,
+        None => Some(InstructionCompleted::ReturnFromMethod(None)),
+    };
+
+    match completed {
+        None => {
+            if let Some(record) = scheduler.state().threads.get_mut(&id) {
+                record.stack = Some(stack);
+            }
+        }
+        Some(completed) => {
+            if let InstructionCompleted::ThrowException(exception) = completed {
+                report_uncaught_exception(&exception, runtime_data_area, &mut stack);
+            }
+            scheduler.terminate(id);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Blocks the current thread until the given thread terminates, or until the timeout
+/// in milliseconds elapses when there is one
+pub fn join(
+    thread: &Value,
+    timeout: Option<u64>,
+    runtime_data_area: &RuntimeDataArea,
+    call_stack: &mut CallStack,
+) {
+    let id = thread_id(runtime_data_area, thread);
+    let scheduler = call_stack.thread.scheduler.clone();
+
+    // AS SPECIFIED BY THE SPECS: joining a thread that is not alive returns immediately
+    if !scheduler.is_alive(id) {
+        return;
+    }
+
+    let until = timeout.map(|timeout| scheduler.deadline(timeout, &mut call_stack.thread));
+    scheduler.set_status(call_stack.thread.id, ThreadStatus::Joining(id, until));
+    call_stack.thread.end_slice();
+}
+
+/// Blocks the current thread for the given number of milliseconds of the virtual clock
+pub fn sleep(millis: u64, call_stack: &mut CallStack) {
+    let scheduler = call_stack.thread.scheduler.clone();
+    let until = scheduler.deadline(millis, &mut call_stack.thread);
+
+    scheduler.set_status(call_stack.thread.id, ThreadStatus::Sleeping(until));
+    call_stack.thread.end_slice();
+}
+
+/// Lets the other runnable threads run before the current one continues
+pub fn yield_now(call_stack: &mut CallStack) {
+    call_stack.thread.end_slice();
+}
+
+/// Enters the monitor of the given object, blocking while another thread owns it
+pub fn monitor_enter(object: &Value, call_stack: &mut CallStack) {
+    enter_monitor_times(object, 1, call_stack);
+}
+
+/// Enters the monitor of the given object the given number of times at once, the
+/// thread blocks before its next instruction while another thread owns it
+fn enter_monitor_times(object: &Value, count: usize, call_stack: &mut CallStack) {
+    let Some(handle) = object.handle() else {
+        return;
+    };
+    let scheduler = call_stack.thread.scheduler.clone();
+
+    if !scheduler.try_enter(object, handle, count, call_stack.thread.id) {
+        scheduler.set_status(call_stack.thread.id, ThreadStatus::Blocked(handle));
+        call_stack.thread.entering = Some((object.clone(), count));
+        call_stack.thread.end_slice();
+    }
+}
+
+/// Exits the monitor of the given object once, releasing it when the current thread
+/// exited it as many times as it entered it
+///
+/// Returns false if the current thread does not own the monitor
+pub fn monitor_exit(object: &Value, call_stack: &CallStack) -> bool {
+    let Some(handle) = object.handle() else {
+        return false;
+    };
+    let mut state = call_stack.thread.scheduler.state();
+
+    let Some(monitor) = state.monitors.get_mut(&handle) else {
+        return false;
+    };
+
+    if monitor.owner != Some(call_stack.thread.id) {
+        return false;
+    }
+
+    monitor.count -= 1;
+
+    if monitor.count == 0 {
+        monitor.owner = None;
+
+        if monitor.is_unused() {
+            state.monitors.remove(&handle);
+        }
+    }
+
+    true
+}
+
+/// Releases the monitor of the given object and waits until another thread notifies it,
+/// or until the timeout in milliseconds elapses when there is one, then enters the
+/// monitor again as many times as it was entered
+///
+/// Returns false if the current thread does not own the monitor
+pub fn wait(object: &Value, timeout: Option<u64>, call_stack: &mut CallStack) -> bool {
+    let Some(handle) = object.handle() else {
+        return false;
+    };
+    let current = call_stack.thread.id;
+    let scheduler = call_stack.thread.scheduler.clone();
+    let until = timeout.map(|timeout| scheduler.deadline(timeout, &mut call_stack.thread));
+
+    let count = {
+        let mut state = scheduler.state();
+
+        let Some(monitor) = state
+            .monitors
+            .get_mut(&handle)
+            .filter(|monitor| monitor.owner == Some(current))
+        else {
+            return false;
+        };
+
+        let count = monitor.count;
+        monitor.owner = None;
+        monitor.count = 0;
+        monitor.waiters.push(current);
+        count
+    };
+
+    // Once notified, the thread enters the monitor again before its next instruction
+    scheduler.set_status(current, ThreadStatus::Waiting(handle, until));
+    call_stack.thread.entering = Some((object.clone(), count));
+    call_stack.thread.end_slice();
+    true
+}
+
+/// Wakes up the thread that has waited the longest on the given object, or every waiting
+/// thread, they enter the monitor again once the current thread releases it
+///
+/// Returns false if the current thread does not own the monitor
+pub fn notify(object: &Value, all: bool, call_stack: &CallStack) -> bool {
+    let Some(handle) = object.handle() else {
+        return false;
+    };
+    let mut state = call_stack.thread.scheduler.state();
+
+    let Some(monitor) = state
+        .monitors
+        .get_mut(&handle)
+        .filter(|monitor| monitor.owner == Some(call_stack.thread.id))
+    else {
+        return false;
+    };
+
+    let notified: Vec<i64> = match all {
+        true => monitor.waiters.drain(..).collect(),
+        false if monitor.waiters.is_empty() => Vec::new(),
+        false => vec![monitor.waiters.remove(0)],
+    };
+
+    for id in notified {
+        if let Some(thread) = state.threads.get_mut(&id) {
+            thread.status = ThreadStatus::Blocked(handle);
+        }
+    }
+
+    true
+}
+
+/// Prints an exception no frame caught along with its stack trace, the way the default
+/// uncaught exception handler does, e.g. `Exception in thread "main" java.lang.Error`
+pub fn report_uncaught_exception(
+    exception: &Value,
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) {
+    // Overridden toString() methods may allocate, keep the exception and its causes alive
+    runtime_data_area.heap.pin(exception.clone());
+
+    let chain = native::cause_chain(&runtime_data_area.heap, exception);
+    let stack_trace = native::format_stack_trace(&chain, |throwable| {
+        describe_throwable(throwable, runtime_data_area, call_stack)
+    });
+
+    runtime_data_area.heap.unpin(exception);
+
+    let name = thread_name(runtime_data_area, &call_stack.thread.object);
+    let report = format!("Exception in thread \"{}\" {}", name, stack_trace);
+    runtime_data_area
+        .console
+        .print(console::STDERR, report.as_bytes());
+}
+
+/// Describes a throwable by invoking its `toString()` method, which may be overridden
+fn describe_throwable(
+    throwable: &Value,
+    runtime_data_area: &mut RuntimeDataArea,
+    call_stack: &mut CallStack,
+) -> String {
+    let to_string = match throwable {
+        Value::Object(handle) => runtime_data_area
+            .heap
+            .object(*handle)
+            .class
+            .lookup_method("toString", "()Ljava/lang/String;"),
+        _ => None,
+    };
+
+    let described = to_string.map(|method| {
+        InstructionExecutor::new().invoke_method(
+            method,
+            vec![throwable.clone()],
+            runtime_data_area,
+            call_stack,
+        )
+    });
+
+    match described {
+        Some(Ok(InstructionCompleted::ReturnFromMethod(Some(description))))
+            if string::string_chars(&runtime_data_area.heap, &description).is_some() =>
+        {
+            string::rust_string(&runtime_data_area.heap, &description).unwrap_or_default()
+        }
+        _ => native::throwable_to_string(&runtime_data_area.heap, throwable),
+    }
+}
@@ -1,13 +1,47 @@
-use std::{cell::RefCell, rc::Rc};
+use crate::vm::heap::Handle;
 
 /// JVM Value types
 #[derive(Debug, Clone)]
 pub enum Value {
-    Int(i32),                       // Represent integer values
-    Long(i64),                      // Represent long values
-    Float(f32),                     // Represent float values
-    Double(f64),                    // Represent double values
-    Reference(String),              // For object references
-    Array(Rc<RefCell<Vec<Value>>>), // Support arrays
+    Int(i32),       // Represent integer values
+    Long(i64),      // Represent long values
+    Float(f32),     // Represent float values
+    Double(f64),    // Represent double values
+    Array(Handle),  // Support arrays, allocated in the heap
+    Object(Handle), // Class instances, allocated in the heap
     Null,
+    /// Pc of the instruction following a `jsr`, which its subroutine returns to with `ret`
+    ReturnAddress(usize),
+}
+
+impl Value {
+    /// Returns the default value of a field or array component of the given descriptor
+    /// AS SPECIFIED BY THE SPECS: §2.3 and §2.4
+    pub fn default_for(descriptor: &str) -> Self {
+        match descriptor.chars().next() {
+            Some('J') => Value::Long(0),
+            Some('F') => Value::Float(0.0),
+            Some('D') => Value::Double(0.0),
+            Some('L') | Some('[') => Value::Null,
+            // boolean, byte, char, short and int are all stored as int
+            _ => Value::Int(0),
+        }
+    }
+
+    /// Returns the heap handle of an object or array reference
+    pub fn handle(&self) -> Option<Handle> {
+        match self {
+            Value::Array(handle) | Value::Object(handle) => Some(*handle),
+            _ => None,
+        }
+    }
+
+    /// Checks if two values refer to the same object or array, or are both null
+    pub fn same_reference(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Object(a), Value::Object(b)) | (Value::Array(a), Value::Array(b)) => a == b,
+            (Value::Null, Value::Null) => true,
+            _ => false,
+        }
+    }
 }
@@ -0,0 +1,417 @@
+use std::collections::{HashMap, hash_map::Entry};
+
+use crate::{
+    parser::{
+        class_file::ClassFile,
+        code_attribute::CodeAttribute,
+        opcode::{Opcode, instruction_length, opcode_at},
+    },
+    vm::{
+        class::Method,
+        error::VmError,
+        simulation::{self, StackValue, branch_target, read_i16, read_i32, read_u16},
+    },
+};
+
+/// Maximum number of states the subroutine check explores in a method, past it the
+/// subroutines of the method are only checked as they run
+const MAX_STATES: usize = 1 << 16;
+
+/// Checks the subroutines of a method of a class file older than version 51, the versions
+/// that may contain `jsr` and `ret`, like the old verifier of HotSpot does when the class
+/// is linked, so that a subroutine broken on a path the program never takes is rejected too
+///
+/// AS SPECIFIED BY THE SPECS: §4.10.2.5, a subroutine cannot call itself, even indirectly,
+/// a return address can only be used by `ret`, and once, by the subroutine it was pushed for
+pub fn verify_subroutines(class_file: &ClassFile, method: &Method) -> Result<(), VmError> {
+    let Some(code) = &method.code else {
+        return Ok(());
+    };
+
+    let mut verifier = SubroutineVerifier {
+        code,
+        class_file,
+        states: HashMap::new(),
+        worklist: Vec::new(),
+    };
+
+    match verifier.verify() {
+        Err(Failure::Error(message)) => Err(VmError::throw(
+            "java/lang/VerifyError",
+            format!(
+                "(class: {}, method: {} signature: {}) {}",
+                method.class_name, method.name, method.descriptor, message
+            ),
+        )),
+        // Malformed code is left to the checks of the instructions
+        Err(Failure::GaveUp) | Ok(()) => Ok(()),
+    }
+}
+
+/// Why the subroutine check of a method stopped
+enum Failure {
+    /// The code is out of the reach of the check, e.g. malformed or with paths whose
+    /// operand stack heights disagree
+    GaveUp,
+    /// A subroutine breaks a rule, with the message of the VerifyError
+    Error(String),
+}
+
+/// Operand stack entry of the subroutine check
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct StackEntry {
+    /// Whether the value is a long or a double ( computational type category 2 )
+    wide: bool,
+    /// The return address the value is, pushed by a jsr
+    return_address: Option<usize>,
+}
+
+impl StackValue for StackEntry {
+    fn is_wide(&self) -> bool {
+        self.wide
+    }
+}
+
+/// Operand stack and return addresses held by the local variables before an instruction
+#[derive(Debug, Clone)]
+struct State {
+    stack: Vec<StackEntry>,
+    locals: Vec<Option<usize>>,
+}
+
+/// Subroutines entered and not returned from yet, the entry and the return address of each,
+/// like `Frame::subroutines`
+type Context = Vec<(usize, usize)>;
+
+/// How an instruction uses a local variable
+enum LocalAccess {
+    Load,
+    Increment,
+    Store { reference: bool, wide: bool },
+}
+
+struct SubroutineVerifier<'a> {
+    code: &'a CodeAttribute,
+    class_file: &'a ClassFile,
+    /// State before each instruction reached, keyed by pc and by the subroutines it runs in,
+    /// a subroutine is checked once for each chain of jsr that calls it
+    states: HashMap<(usize, Context), State>,
+    /// Instructions whose state changed and must be checked again
+    worklist: Vec<(usize, Context)>,
+}
+
+impl<'a> SubroutineVerifier<'a> {
+    fn verify(&mut self) -> Result<(), Failure> {
+        let initial = State {
+            stack: Vec::new(),
+            locals: vec![None; self.code.max_locals as usize],
+        };
+        self.merge(0, Vec::new(), initial)?;
+
+        while let Some((pc, context)) = self.worklist.pop() {
+            if self.states.len() > MAX_STATES {
+                return Err(Failure::GaveUp);
+            }
+
+            let state = self.states[&(pc, context.clone())].clone();
+
+            // The subroutines entered by a jsr the handler covers are left along with the
+            // code that threw, like the dispatch loop does
+            for entry in &self.code.exception_table {
+                let covered = entry.start_pc as usize..entry.end_pc as usize;
+                if !covered.contains(&pc) {
+                    continue;
+                }
+
+                let mut context = context.clone();
+                if let Some(depth) = context
+                    .iter()
+                    .position(|(_, return_address)| covered.contains(&(return_address - 1)))
+                {
+                    context.truncate(depth);
+                }
+
+                // AS SPECIFIED BY THE SPECS: a handler starts with only the caught exception
+                // on the stack
+                let handler = State {
+                    stack: vec![StackEntry {
+                        wide: false,
+                        return_address: None,
+                    }],
+                    locals: state.locals.clone(),
+                };
+                self.merge(entry.handler_pc as usize, context, handler)?;
+            }
+
+            for (successor, context, state) in self.execute(pc, context, state)? {
+                self.merge(successor, context, state)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Merges the given state into the state recorded before the instruction at the given pc
+    /// in the given subroutines, the instruction is checked again if the recorded state changed
+    fn merge(&mut self, pc: usize, context: Context, state: State) -> Result<(), Failure> {
+        if pc >= self.code.code.len() {
+            return Err(Failure::GaveUp);
+        }
+
+        let recorded = match self.states.entry((pc, context.clone())) {
+            Entry::Occupied(recorded) => recorded.into_mut(),
+            Entry::Vacant(unreached) => {
+                unreached.insert(state);
+                self.worklist.push((pc, context));
+                return Ok(());
+            }
+        };
+
+        if recorded.stack.len() != state.stack.len() {
+            return Err(Failure::GaveUp);
+        }
+
+        // Values that are different return addresses on two paths are none of them
+        let mut changed = false;
+
+        for (value, entry) in recorded.stack.iter_mut().zip(&state.stack) {
+            if value.return_address.is_some() && value.return_address != entry.return_address {
+                value.return_address = None;
+                changed = true;
+            }
+        }
+
+        for (recorded, local) in recorded.locals.iter_mut().zip(&state.locals) {
+            if recorded.is_some() && recorded != local {
+                *recorded = None;
+                changed = true;
+            }
+        }
+
+        if changed {
+            self.worklist.push((pc, context));
+        }
+
+        Ok(())
+    }
+
+    /// Applies the effect of the instruction at the given pc to the given state and returns
+    /// the instructions that may execute next, with the subroutines they run in and their state
+    fn execute(
+        &self,
+        pc: usize,
+        mut context: Context,
+        mut state: State,
+    ) -> Result<Vec<(usize, Context, State)>, Failure> {
+        let code = &self.code.code;
+        let opcode = opcode_at(code, pc).ok_or(Failure::GaveUp)?;
+
+        match opcode {
+            Opcode::Jsr | Opcode::Jsr_w => {
+                let offset = match opcode {
+                    Opcode::Jsr => read_i16(code, pc + 1).map(i32::from),
+                    _ => read_i32(code, pc + 1),
+                };
+                let target = offset
+                    .and_then(|offset| branch_target(pc, offset))
+                    .ok_or(Failure::GaveUp)?;
+
+                if context.iter().any(|(entry, _)| *entry == target) {
+                    return Err(Failure::Error("Recursive call to jsr entry".to_string()));
+                }
+
+                let return_address = pc + instruction_length(code, pc).ok_or(Failure::GaveUp)?;
+                context.push((target, return_address));
+                state.stack.push(StackEntry {
+                    wide: false,
+                    return_address: Some(return_address),
+                });
+
+                return Ok(vec![(target, context, state)]);
+            }
+            Opcode::Ret => {
+                let index = *code.get(pc + 1).ok_or(Failure::GaveUp)? as usize;
+                return Self::return_from_subroutine(index, context, state);
+            }
+            Opcode::Wide if matches!(opcode_at(code, pc + 1), Some(Opcode::Ret)) => {
+                let index = read_u16(code, pc + 2).ok_or(Failure::GaveUp)? as usize;
+                return Self::return_from_subroutine(index, context, state);
+            }
+            _ => {}
+        }
+
+        let access = local_access(code, pc);
+        let mut stored = None;
+
+        if let Some((access, index)) = &access {
+            let local = *state.locals.get(*index).ok_or(Failure::GaveUp)?;
+
+            match access {
+                LocalAccess::Load if local.is_some() => {
+                    return Err(Failure::Error(format!(
+                        "Cannot load return address from register {}",
+                        index
+                    )));
+                }
+                LocalAccess::Increment if local.is_some() => {
+                    return Err(Failure::Error(format!(
+                        "Register {} contains wrong type",
+                        index
+                    )));
+                }
+                LocalAccess::Store {
+                    reference: true, ..
+                } => stored = state.stack.last().and_then(|value| value.return_address),
+                _ => {}
+            }
+        }
+
+        let pushed = |wide| StackEntry {
+            wide,
+            return_address: None,
+        };
+        let successors = simulation::execute(code, self.class_file, pc, &mut state.stack, pushed)
+            .ok_or(Failure::GaveUp)?;
+
+        if let Some((LocalAccess::Store { wide, .. }, index)) = access {
+            state.locals[index] = stored;
+
+            if wide {
+                *state.locals.get_mut(index + 1).ok_or(Failure::GaveUp)? = None;
+            }
+        }
+
+        Ok(successors
+            .into_iter()
+            .map(|successor| (successor, context.clone(), state.clone()))
+            .collect())
+    }
+
+    /// Continues at the return address in the local variable at an index, leaving the
+    /// subroutine it was pushed for along with any subroutine it called that did not return
+    fn return_from_subroutine(
+        index: usize,
+        mut context: Context,
+        state: State,
+    ) -> Result<Vec<(usize, Context, State)>, Failure> {
+        let Some(return_address) = *state.locals.get(index).ok_or(Failure::GaveUp)? else {
+            return Err(Failure::Error(format!(
+                "Register {} contains wrong type",
+                index
+            )));
+        };
+
+        let Some(depth) = context
+            .iter()
+            .rposition(|(_, address)| *address == return_address)
+        else {
+            return Err(Failure::Error("Illegal return from subroutine".to_string()));
+        };
+        context.truncate(depth);
+
+        Ok(vec![(return_address, context, state)])
+    }
+}
+
+/// Returns how the instruction at the given pc uses a local variable and the index of the
+/// variable, if it uses one
+fn local_access(code: &[u8], pc: usize) -> Option<(LocalAccess, usize)> {
+    let opcode = opcode_at(code, pc)?;
+
+    let (opcode, index) = match opcode {
+        Opcode::Wide => (opcode_at(code, pc + 1)?, read_u16(code, pc + 2)? as usize),
+        Opcode::Iload
+        | Opcode::Lload
+        | Opcode::Fload
+        | Opcode::Dload
+        | Opcode::Aload
+        | Opcode::Istore
+        | Opcode::Lstore
+        | Opcode::Fstore
+        | Opcode::Dstore
+        | Opcode::Astore
+        | Opcode::Iinc => (opcode, *code.get(pc + 1)? as usize),
+        Opcode::Iload0
+        | Opcode::Lload0
+        | Opcode::Fload0
+        | Opcode::Dload0
+        | Opcode::Aload_0
+        | Opcode::Istore_0
+        | Opcode::Lstore_0
+        | Opcode::Fstore_0
+        | Opcode::Dstore_0
+        | Opcode::Astore_0 => (opcode, 0),
+        Opcode::Iload1
+        | Opcode::Lload1
+        | Opcode::Fload1
+        | Opcode::Dload1
+        | Opcode::Aload_1
+        | Opcode::Istore_1
+        | Opcode::Lstore_1
+        | Opcode::Fstore_1
+        | Opcode::Dstore_1
+        | Opcode::Astore_1 => (opcode, 1),
+        Opcode::Iload2
+        | Opcode::Lload2
+        | Opcode::Fload2
+        | Opcode::Dload2
+        | Opcode::Aload_2
+        | Opcode::Istore_2
+        | Opcode::Lstore_2
+        | Opcode::Fstore_2
+        | Opcode::Dstore_2
+        | Opcode::Astore_2 => (opcode, 2),
+        Opcode::Iload3
+        | Opcode::Lload3
+        | Opcode::Fload3
+        | Opcode::Dload3
+        | Opcode::Aload_3
+        | Opcode::Istore_3
+        | Opcode::Lstore_3
+        | Opcode::Fstore_3
+        | Opcode::Dstore_3
+        | Opcode::Astore_3 => (opcode, 3),
+        _ => return None,
+    };
+
+    let access = match opcode {
+        Opcode::Iinc => LocalAccess::Increment,
+        Opcode::Istore
+        | Opcode::Fstore
+        | Opcode::Istore_0
+        | Opcode::Istore_1
+        | Opcode::Istore_2
+        | Opcode::Istore_3
+        | Opcode::Fstore_0
+        | Opcode::Fstore_1
+        | Opcode::Fstore_2
+        | Opcode::Fstore_3 => LocalAccess::Store {
+            reference: false,
+            wide: false,
+        },
+        Opcode::Lstore
+        | Opcode::Dstore
+        | Opcode::Lstore_0
+        | Opcode::Lstore_1
+        | Opcode::Lstore_2
+        | Opcode::Lstore_3
+        | Opcode::Dstore_0
+        | Opcode::Dstore_1
+        | Opcode::Dstore_2
+        | Opcode::Dstore_3 => LocalAccess::Store {
+            reference: false,
+            wide: true,
+        },
+        Opcode::Astore
+        | Opcode::Astore_0
+        | Opcode::Astore_1
+        | Opcode::Astore_2
+        | Opcode::Astore_3 => LocalAccess::Store {
+            reference: true,
+            wide: false,
+        },
+        _ => LocalAccess::Load,
+    };
+
+    Some((access, index))
+}
@@ -1,9 +1,20 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{path::PathBuf, rc::Rc};
 
 use crate::{
     debug_log,
     parser::class_file::ClassFile,
-    vm::{call_stack::CallStack, runtime::RuntimeDataArea, value::Value},
+    vm::{
+        array::Array,
+        call_stack::{self, CallStack},
+        class::Class,
+        coverage::{self, Gap},
+        error::VmError,
+        instruction_exec::InstructionExecutor,
+        native::{self, NativeMethod},
+        runtime::RuntimeDataArea,
+        thread::{self, JavaThread, MAIN_THREAD_ID, Scheduler},
+        value::Value,
+    },
 };
 
 /// The virtual machine
@@ -12,8 +23,11 @@ pub struct Vm {
     runtime_data: RuntimeDataArea,
     /// Parsed class file currently loaded in the VM
     class_file: ClassFile,
-    /// The call stack to handle stack method frames execution
+    /// The call stack of the running thread to handle stack method frames execution, the
+    /// scheduler swaps in the call stack of the thread it runs next
     call_stack: CallStack,
+    /// Schedules the Java threads, each of them has its own call stack
+    scheduler: Rc<Scheduler>,
 }
 
 impl Vm {
@@ -21,11 +35,35 @@ impl Vm {
     ///
     /// Initializes `runtime_data` and sets `class_file` to default
     pub fn new() -> Self {
-        Self {
+        let scheduler = Rc::new(Scheduler::new());
+        let main_thread = JavaThread::new(MAIN_THREAD_ID, Value::Null, scheduler.clone());
+
+        let mut vm = Self {
             runtime_data: RuntimeDataArea::new(),
             class_file: Default::default(),
-            call_stack: CallStack::new(Some(1000)),
+            call_stack: CallStack::new(None, main_thread),
+            scheduler,
+        };
+
+        for &(class_name, name, descriptor, native) in native::BUILTIN_NATIVES {
+            vm.register_native(class_name, name, descriptor, native);
         }
+
+        vm
+    }
+
+    /// Registers the Rust implementation of a method, invoked in place of the method whether
+    /// it is declared native or has bytecode, replacing the implementation registered before
+    pub fn register_native(
+        &mut self,
+        class_name: &str,
+        name: &str,
+        descriptor: &str,
+        native: NativeMethod,
+    ) {
+        self.runtime_data
+            .natives
+            .register(class_name, name, descriptor, native);
     }
 
     /// Sets the class file to be executed by the VM
@@ -33,104 +71,155 @@ impl Vm {
         self.class_file = class_file;
     }
 
-    /// Executes the `<clinit>` (class initializer) method of the loaded class file
-    pub fn execute_clinit(&mut self) {
-        let clinit_method = match self.class_file.find_method("<clinit>") {
-            Some(method) => method,
-            None => {
-                println!("No <clinit> method found");
-                return;
-            }
-        };
-
-        // I assume that there will be always one attribute and it's the code attribute
-        let attribute_info = &clinit_method.attributes[0];
-        let info_bytes = &attribute_info.info;
+    /// Appends a directory to the class path used to load the classes
+    /// referenced by the executed class file
+    pub fn add_class_path(&mut self, path: PathBuf) {
+        self.runtime_data.class_loader.add_class_path(path);
+    }
 
-        // Extract max_locals (two big-endian bytes) from info_bytes[2..3]
-        let max_locals = u16::from_be_bytes([info_bytes[2], info_bytes[3]]);
+    /// Sets the maximum heap size in bytes ( -Xmx ), beyond which allocations
+    /// throw an OutOfMemoryError
+    pub fn set_max_heap_size(&mut self, max_heap_size: usize) {
+        self.runtime_data.heap.set_max_size(max_heap_size);
+    }
 
-        // Extract code_length (four big-endian bytes) from info_bytes[4..8]
-        let code_length =
-            u32::from_be_bytes([info_bytes[4], info_bytes[5], info_bytes[6], info_bytes[7]])
-                as usize;
+    /// Sets the size in bytes of the call stack of every thread ( -Xss ), beyond which
+    /// invocations throw a StackOverflowError
+    pub fn set_thread_stack_size(&mut self, stack_size: usize) {
+        self.call_stack.set_max_size(stack_size);
+        self.scheduler.set_stack_size(stack_size);
+    }
 
-        // Extract bytecode from info_bytes[8..8+code_length]
-        let bytecode = info_bytes[8..8 + code_length].to_vec();
+    /// Sets the number of instructions a thread executes before the scheduler switches
+    /// to another runnable thread ( -XX:ThreadQuantum )
+    pub fn set_thread_quantum(&mut self, quantum: usize) {
+        self.scheduler.set_quantum(quantum);
+    }
 
-        // TODO: Change the hardcoded max_locals value and handle env args array
-        self.call_stack.push_frame(
-            "<clinit>".to_string(),
-            bytecode,
-            max_locals as usize,
-            vec![],
-        );
+    /// Schedules the threads in a pseudo-random order reproducible from the given seed
+    /// ( -XX:ThreadSeed ), to replay the interleaving that revealed a concurrency bug
+    pub fn set_thread_seed(&mut self, seed: u64) {
+        self.scheduler.set_seed(seed);
     }
 
-    pub fn execute_main(&mut self, args: Vec<String>) {
-        let main_method = match self.class_file.find_method("main") {
-            Some(method) => method,
-            None => {
-                println!("No main method found");
-                return;
-            }
-        };
+    /// Sets whether the parts of the JDK and of the class file format the VM does not
+    /// implement throw an error ( -XX:+Strict, the default ) or are skipped with a warning
+    pub fn set_strict(&mut self, strict: bool) {
+        self.runtime_data.strict = strict;
+    }
 
-        // I assume that there will be always one attribute and it's the code attribute
-        let attribute_info = &main_method.attributes[0];
-        let info_bytes = &attribute_info.info;
+    /// Initializes the main class, which executes its `<clinit>` (class initializer) method
+    ///
+    /// Returns the exception thrown by the class initializer, if any
+    pub fn execute_clinit(&mut self, main_class: &Rc<Class>) -> Result<Option<Value>, VmError> {
+        if main_class.find_method("<clinit>", "()V").is_none() {
+            debug_log!("No <clinit> method found");
+        }
 
-        // Extract max_locals (two big-endian bytes) from info_bytes[2..3]
-        let max_locals = u16::from_be_bytes([info_bytes[2], info_bytes[3]]);
+        let instruction_executor = InstructionExecutor::new();
 
-        // Extract code_length (four big-endian bytes) from info_bytes[4..8]
-        let code_length =
-            u32::from_be_bytes([info_bytes[4], info_bytes[5], info_bytes[6], info_bytes[7]])
-                as usize;
+        instruction_executor.initialize_class(
+            main_class,
+            &mut self.runtime_data,
+            &mut self.call_stack,
+        )
+    }
 
-        // Extract bytecode from info_bytes[8..8+code_length]
-        let bytecode = info_bytes[8..8 + code_length].to_vec();
+    pub fn execute_main(
+        &mut self,
+        main_class: &Rc<Class>,
+        args: Vec<String>,
+    ) -> Result<(), VmError> {
+        let main_method = main_class
+            .find_method("main", "([Ljava/lang/String;)V")
+            .ok_or_else(|| {
+                VmError::linkage(format!(
+                    "Main method not found in class {}, please define the main method as:\n   public static void main(String[] args)",
+                    main_class.name.replace('/', ".")
+                ))
+            })?;
 
         let mut env_args = Vec::new();
         let mut array_values = Vec::new();
 
+        // The arguments are only reachable from here until the array holds them
         for arg in args.iter() {
-            let value = Value::Reference(arg.clone());
+            let value = self.runtime_data.new_string_from(arg, &self.call_stack)?;
+            self.runtime_data.heap.pin(value.clone());
             array_values.push(value);
         }
 
-        let array = Value::Array(Rc::new(RefCell::new(array_values)));
-        env_args.push(array);
+        let array = self.runtime_data.allocate_array(
+            Array::from_elements("[Ljava/lang/String;".to_string(), array_values.clone()),
+            &self.call_stack,
+        );
+
+        for value in array_values.iter().rev() {
+            self.runtime_data.heap.unpin(value);
+        }
+
+        env_args.push(array?);
 
         self.call_stack
-            .push_frame("main".to_string(), bytecode, max_locals as usize, env_args);
+            .push_frame(main_class.clone(), main_method, env_args)
     }
 
-    /// Runs the virtual machine with the given class file
-    pub fn run(&mut self, class_file: ClassFile, args: Vec<String>) {
+    /// Lists the instructions of the given class file, and of the classes it references,
+    /// that the VM cannot execute yet, without running any code
+    pub fn check(&mut self, class_file: ClassFile) -> Result<Vec<Gap>, VmError> {
+        let main_class = self.runtime_data.class_loader.define_class(class_file)?;
+
+        coverage::scan(main_class, &mut self.runtime_data)
+    }
+
+    /// Runs the virtual machine with the given class file, on a host thread whose stack has
+    /// the size `call_stack::host_stack_size` gives for the thread stack size
+    ///
+    /// Fails with `UncaughtException` when the main thread ends with an exception, which
+    /// is reported already, or with the failure of the VM that stopped the program
+    pub fn run(&mut self, class_file: ClassFile, args: Vec<String>) -> Result<(), VmError> {
         debug_log!("Starting JVM execution...\n");
 
+        // The nested runs of the dispatch loop recurse on the host stack from here
+        self.runtime_data.host_stack_end =
+            call_stack::host_stack_end(call_stack::host_stack_size(self.call_stack.max_size()));
+
         // Initialize class file
         self.init_class_file(class_file);
 
-        // Execute the main method
-        self.execute_main(args);
+        // Link the main class and every class it extends
+        let main_class = self
+            .runtime_data
+            .class_loader
+            .define_class(self.class_file.clone())?;
 
-        // Execute class static initializer
-        //TODO: <clinit> execution should not be pushed to the call stack and preprocessed
-        self.execute_clinit();
+        // The main thread exists before any code runs, `<clinit>` may already start threads
+        thread::start_main_thread(&mut self.runtime_data, &mut self.call_stack)?;
 
-        let size = self.call_stack.size();
+        // Execute class static initializer before anything else
+        let clinit_exception = self.execute_clinit(&main_class)?;
 
-        debug_log!("\nCURRENT CALL STACK SIZE? {}", size);
+        if clinit_exception.is_none() {
+            // Execute the main method
+            self.execute_main(&main_class, args)?;
 
-        self.call_stack
-            .execute_frames(&self.class_file, &mut self.runtime_data);
+            let size = self.call_stack.size();
 
-        let flag = self.call_stack.is_empty();
+            debug_log!("\nCURRENT CALL STACK SIZE? {}", size);
+        }
 
-        debug_log!("\nIS THE CALL STACK EMPTY NOW? {}", flag);
+        // The threads started by `main` run in turn with it until they all terminated
+        let uncaught_class = thread::run_main_thread(
+            clinit_exception,
+            &mut self.runtime_data,
+            &mut self.call_stack,
+        )?;
 
         debug_log!("\nJVM execution completed.");
+
+        match uncaught_class {
+            Some(class_name) => Err(VmError::UncaughtException { class_name }),
+            None => Ok(()),
+        }
     }
 }